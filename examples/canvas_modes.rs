@@ -1,5 +1,5 @@
 // Demonstrates the various canvas modes
-// Press Space to request a redraw and M to cycle through the various modes
+// Press Space to request a redraw, M to cycle through the various modes and C to change the clear color
 
 use bevy::prelude::*;
 use bevy_vector_shapes::prelude::*;
@@ -35,9 +35,18 @@ fn update_canvas(keys: Res<ButtonInput<KeyCode>>, mut canvas: Query<&mut Canvas>
         canvas.mode = match canvas.mode {
             CanvasMode::Continuous => CanvasMode::Persistent,
             CanvasMode::Persistent => CanvasMode::OnDemand,
-            CanvasMode::OnDemand => CanvasMode::Continuous,
+            CanvasMode::OnDemand => CanvasMode::OnChange,
+            CanvasMode::OnChange => CanvasMode::Continuous,
         }
     }
+
+    if keys.just_pressed(KeyCode::KeyC) {
+        canvas.clear_color = match canvas.clear_color {
+            ClearColorConfig::Custom(_) => ClearColorConfig::Default,
+            _ => ClearColorConfig::Custom(Color::MIDNIGHT_BLUE),
+        };
+        canvas.redraw();
+    }
 }
 
 fn draw_shapes(time: Res<Time>, mut painter: ShapePainter, canvas: Query<(Entity, &Canvas)>) {
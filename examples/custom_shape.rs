@@ -0,0 +1,214 @@
+// Demonstrates defining a custom shape type with it's own instance data and shader
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{ShaderRef, ShaderType, VertexAttribute},
+};
+use bevy_vector_shapes::{prelude::*, render::tint_color};
+use wgpu::vertex_attr_array;
+
+const CHECKER_HANDLE: Handle<Shader> = Handle::weak_from_u128(98143720955018331716);
+
+const CHECKER_SHADER: &str = "
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::view
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+// Must match the layout of CheckerData
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) alt_color: vec4<f32>,
+    @location(6) size: vec2<f32>,
+    @location(7) cells: f32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) alt_color: vec4<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    let shape = shapes[core::get_instance_index(v.index)];
+    let matrix = mat4x4<f32>(shape.matrix_0, shape.matrix_1, shape.matrix_2, shape.matrix_3);
+
+    // Scale the quad from -1 to 1 up to the size of the shape
+    let local_pos = v.pos.xy * shape.size / 2.0;
+    out.clip_position = view.view_proj * matrix * vec4<f32>(local_pos, 0.0, 1.0);
+
+    out.color = shape.color;
+    out.alt_color = shape.alt_color;
+    out.uv = (v.pos.xy + 1.0) / 2.0 * shape.cells;
+    return out;
+}
+
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: VertexOutput) -> @location(0) vec4<f32> {
+    let cell = vec2<i32>(floor(f.uv));
+    var color = select(f.color, f.alt_color, ((cell.x + cell.y) & 1) == 1);
+
+    // Respect the clip masks and blend modes of the shape
+    color.a *= core::clip_mask(f.clip_position);
+    if color.a < 0.0001 {
+        discard;
+    }
+    return core::color_output(color);
+}
+#endif
+";
+
+/// Component for retained checkerboards, colored with the shape's color and `alt_color`.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct CheckerComponent {
+    size: Vec2,
+    cells: f32,
+    alt_color: Color,
+}
+
+impl ShapeComponent for CheckerComponent {
+    type Data = CheckerData;
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> CheckerData {
+        CheckerData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+            color: fill.color.as_linear_rgba_f32(),
+            alt_color: self.alt_color.as_linear_rgba_f32(),
+            size: self.size,
+            cells: self.cells,
+            padding: 0.0,
+        }
+    }
+}
+
+/// Instance data sent to the checkerboard shader.
+#[derive(Clone, Copy, Default, ShaderType)]
+#[repr(C)]
+struct CheckerData {
+    transform: [[f32; 4]; 4],
+    color: [f32; 4],
+    alt_color: [f32; 4],
+    size: Vec2,
+    cells: f32,
+    padding: f32,
+}
+
+impl CheckerData {
+    fn new(config: &ShapeConfig, size: Vec2, cells: f32, alt_color: Color) -> Self {
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+            color: config.faded_color().as_linear_rgba_f32(),
+            alt_color: config.fade(alt_color).as_linear_rgba_f32(),
+            size,
+            cells,
+            padding: 0.0,
+        }
+    }
+}
+
+impl ShapeData for CheckerData {
+    type Component = CheckerComponent;
+
+    fn vertex_layout() -> Vec<VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32x4,
+            6 => Float32x2,
+            7 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        CHECKER_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+        self.alt_color = tint_color(self.alt_color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, _constant_screen_size: bool) {}
+}
+
+/// Extension trait to draw checkerboards with a painter, like the built in shapes.
+trait CheckerPainter {
+    fn checker(&mut self, size: Vec2, cells: f32, alt_color: Color) -> &mut Self;
+}
+
+impl<P: ShapeSender> CheckerPainter for P {
+    fn checker(&mut self, size: Vec2, cells: f32, alt_color: Color) -> &mut Self {
+        self.send(CheckerData::new(self.config(), size, cells, alt_color))
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
+        .add_plugins(Shape2dPlugin::default())
+        .add_plugins(ShapeTypePlugin::<CheckerComponent>::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, draw);
+
+    app.world
+        .resource_mut::<Assets<Shader>>()
+        .insert(CHECKER_HANDLE, Shader::from_wgsl(CHECKER_SHADER, file!()));
+
+    app.run();
+}
+
+fn setup(mut commands: Commands, mut shapes: ShapeCommands) {
+    commands.spawn(Camera2dBundle::default());
+
+    shapes.color = Color::WHITE;
+    shapes.transform = Transform::from_xyz(150.0, 0.0, 0.0);
+    let bundle = ShapeBundle::new(
+        shapes.config(),
+        CheckerComponent {
+            size: Vec2::splat(200.0),
+            cells: 8.0,
+            alt_color: Color::BLACK,
+        },
+    );
+    shapes.spawn_shape(bundle);
+}
+
+fn draw(mut painter: ShapePainter, time: Res<Time>) {
+    painter.color = Color::ORANGE;
+    painter.translate(Vec3::X * -150.0);
+    painter.rotate_z(time.elapsed_seconds());
+    painter.checker(Vec2::splat(200.0), 4.0, Color::MIDNIGHT_BLUE);
+}
@@ -27,6 +27,8 @@ fn setup(mut commands: Commands) {
 }
 
 fn draw_health_bar(painter: &mut ShapePainter, hp: f32) {
+    // Keep health bars upright as the camera looks down on them
+    painter.alignment = Alignment::BillboardY;
     painter.translate(Vec3::Y * 0.7);
     painter.corner_radii = Vec4::splat(0.3);
 
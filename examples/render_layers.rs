@@ -112,9 +112,9 @@ fn draw_shapes(time: Res<Time>, mut painter: ShapePainter) {
     painter.arc(0.8, start_angle, -start_angle);
 
     let offset = Quat::from_rotation_z(start_angle) * Vec3::Y * 1.1;
-    painter.translate(offset);
-    painter.arc(0.5, start_angle + PI * 1.5, start_angle + 2.5 * PI);
-    painter.translate(-offset);
+    painter.with_translation(offset, |painter| {
+        painter.arc(0.5, start_angle + PI * 1.5, start_angle + 2.5 * PI);
+    });
 
     painter.translate(Quat::from_rotation_z(-start_angle) * Vec3::Y * 1.1);
     painter.arc(0.5, start_angle + PI, start_angle + 2.0 * PI);
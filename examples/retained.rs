@@ -1,8 +1,8 @@
-// Demonstrates using ShapeCommands to spawn entity backed shapes
+// Demonstrates using ShapeCommands to spawn entity backed shapes and animating them with components
 
 use std::f32::consts::PI;
 
-use bevy::prelude::*;
+use bevy::{prelude::*, sprite::Anchor};
 use bevy_vector_shapes::prelude::*;
 
 fn main() {
@@ -12,7 +12,6 @@ fn main() {
         .insert_resource(ClearColor(Color::DARK_GRAY))
         .insert_resource(Msaa::Off)
         .add_systems(Startup, setup)
-        .add_systems(Update, rotate_circle)
         .run();
 }
 
@@ -23,16 +22,24 @@ fn setup(mut commands: Commands, mut shapes: ShapeCommands) {
     });
 
     // The ShapeCommands API is identical to the ShapePainter API so can be used almost interchangably
-    shapes.circle(1.0).with_children(|parent| {
-        for _ in 0..4 {
-            parent.rotate_z(PI / 2.0);
-            parent.line(Vec3::ZERO, Vec3::Y * 2.0);
-        }
-    });
-}
+    shapes
+        .circle(1.0)
+        .with_children(|parent| {
+            for _ in 0..4 {
+                parent.rotate_z(PI / 2.0);
+                parent.line(Vec3::ZERO, Vec3::Y * 2.0);
+            }
+        })
+        .insert((ShapeSpin::new(1.0), ShapePulse::new(1.0, 1.2, 2.0)));
+
+    shapes.transform.translation = Vec3::X * 4.0;
+    shapes
+        .circle(0.5)
+        .insert(ShapeBlink::new(1.0, 0.2, 1.5).with_easing(ShapeEasing::QuadraticInOut));
 
-fn rotate_circle(time: Res<Time>, mut circle: Query<&mut Transform, With<DiscComponent>>) {
-    circle
-        .iter_mut()
-        .for_each(|mut tf| tf.rotation *= Quat::from_rotation_z(time.delta_seconds()))
+    // Keep a shape in the top left corner of the window as it is resized
+    shapes.transform = Transform::IDENTITY;
+    shapes
+        .rect(Vec2::splat(1.0))
+        .insert(ScreenAnchor::new(Anchor::TopLeft, Vec2::new(48.0, -48.0)));
 }
@@ -0,0 +1,73 @@
+// Demonstrates customizing the fill and edge of shapes with WGSL shader hooks
+
+use bevy::prelude::*;
+use bevy_vector_shapes::prelude::*;
+
+const SCANLINES: &str = "
+#define_import_path shader_hooks::scanlines
+
+fn fill(color: vec4<f32>, pos: vec2<f32>, half_size: vec2<f32>, frag_coord: vec4<f32>, user_data: vec4<f32>) -> vec4<f32> {
+    // Darken every other pair of rows of pixels
+    let row = u32(frag_coord.y) / 2u;
+    return vec4<f32>(color.rgb * select(1.0, 0.4, row % 2u == 0u), color.a);
+}
+";
+
+const WOBBLE: &str = "
+#define_import_path shader_hooks::wobble
+
+fn distance(dist: f32, pos: vec2<f32>, half_size: vec2<f32>, user_data: vec4<f32>) -> f32 {
+    // Push the edge in and out around the shape, the user data holds the phase and number of waves
+    let angle = atan2(pos.y, pos.x);
+    return dist + sin(angle * user_data.y + user_data.x) * 0.05 * min(half_size.x, half_size.y);
+}
+";
+
+#[derive(Resource)]
+struct Hooks {
+    scanlines: ShapeShaderHook,
+    wobble: ShapeShaderHook,
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(Shape2dPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, draw)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut shaders: ResMut<Assets<Shader>>,
+    mut hooks: ResMut<ShapeShaderHooks>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    let scanlines = shaders.add(Shader::from_wgsl(SCANLINES, "scanlines.wgsl"));
+    let wobble = shaders.add(Shader::from_wgsl(WOBBLE, "wobble.wgsl"));
+    commands.insert_resource(Hooks {
+        scanlines: hooks
+            .add(ShapeHookShader::new(scanlines, "shader_hooks::scanlines").with_fill()),
+        wobble: hooks.add(ShapeHookShader::new(wobble, "shader_hooks::wobble").with_distance()),
+    });
+}
+
+fn draw(mut painter: ShapePainter, hooks: Res<Hooks>, time: Res<Time>) {
+    painter.color = Color::CYAN;
+    painter.shader_hook = Some(hooks.scanlines);
+    painter.translate(Vec3::X * -150.0);
+    painter.rect(Vec2::splat(200.0));
+
+    painter.color = Color::ORANGE;
+    painter.shader_hook = Some(hooks.wobble);
+    painter.user_data = Vec4::new(time.elapsed_seconds() * 2.0, 8.0, 0.0, 0.0);
+    painter.translate(Vec3::new(300.0, 100.0, 0.0));
+    painter.circle(80.0);
+
+    // Shapes drawn with the same hook share a batch while their user data differs
+    painter.user_data = Vec4::new(-time.elapsed_seconds() * 3.0, 5.0, 0.0, 0.0);
+    painter.translate(Vec3::Y * -200.0);
+    painter.circle(80.0);
+}
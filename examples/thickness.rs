@@ -34,7 +34,7 @@ fn draw_gallery(
     let line_vec = Vec3::new(0.4, 2.0, 0.0);
 
     painter.color = Color::MIDNIGHT_BLUE;
-    painter.translate(Vec3::NEG_X * (between_lines * 3.0 + between_sets));
+    painter.translate(Vec3::NEG_X * (between_lines * 4.0 + between_sets * 1.5));
     painter.thickness_type = ThicknessType::Pixels;
 
     painter.thickness = 1.0;
@@ -77,4 +77,19 @@ fn draw_gallery(
     painter.thickness = 5.0;
     painter.translate(Vec3::X * between_lines);
     painter.line(-line_vec, line_vec);
+
+    painter.color = Color::GOLD;
+    painter.translate(Vec3::X * between_sets);
+    painter.thickness_type = ThicknessType::ViewportHeight;
+
+    painter.thickness = 0.001;
+    painter.line(-line_vec, line_vec);
+
+    painter.thickness = 0.0025;
+    painter.translate(Vec3::X * between_lines);
+    painter.line(-line_vec, line_vec);
+
+    painter.thickness = 0.005;
+    painter.translate(Vec3::X * between_lines);
+    painter.line(-line_vec, line_vec);
 }
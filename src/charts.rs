@@ -0,0 +1,311 @@
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::prelude::*;
+
+/// Plot area of a chart mapping data values to positions in the local space of a [`ShapePainter`].
+///
+/// The bottom left corner of the plot area is placed at the origin of the painter's transform. Series drawn by
+/// [`ChartPainter`] place the value at index `i` of their data at an x value of `i`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Chart {
+    /// Size of the plot area.
+    pub size: Vec2,
+    /// Data values at the left and right edges of the plot area.
+    pub x_range: Vec2,
+    /// Data values at the bottom and top edges of the plot area.
+    pub y_range: Vec2,
+}
+
+impl Chart {
+    pub fn new(size: Vec2, x_range: Vec2, y_range: Vec2) -> Self {
+        Self {
+            size,
+            x_range,
+            y_range,
+        }
+    }
+
+    /// Create a chart spanning every index of `data` whose y range covers both it's values and 0.
+    pub fn fit(size: Vec2, data: &[f32]) -> Self {
+        let (min, max) = data.iter().fold((0.0f32, 0.0f32), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+        let max_index = data.len().saturating_sub(1).max(1) as f32;
+        Self::new(
+            size,
+            Vec2::new(0.0, max_index),
+            Vec2::new(min, if max > min { max } else { min + 1.0 }),
+        )
+    }
+
+    /// Position of a data value in the painter's local space.
+    pub fn position(&self, value: Vec2) -> Vec2 {
+        let min = Vec2::new(self.x_range.x, self.y_range.x);
+        let max = Vec2::new(self.x_range.y, self.y_range.y);
+        (value - min) / (max - min) * self.size
+    }
+
+    /// Height in the painter's local space of the line where y is 0, clamped to the plot area.
+    pub fn baseline(&self) -> f32 {
+        self.position(Vec2::ZERO).y.clamp(0.0, self.size.y)
+    }
+
+    fn points(&self, data: &[f32]) -> Vec<Vec2> {
+        data.iter()
+            .enumerate()
+            .map(|(index, &value)| self.position(Vec2::new(index as f32, value)))
+            .collect()
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw charts, requires the `charts` feature.
+///
+/// Axes and line series use the painter's thickness, while bars and areas are filled unless the painter is hollow.
+pub trait ChartPainter {
+    /// Draw the left and bottom axes of a chart with the given number of evenly spaced divisions between ticks.
+    ///
+    /// Ticks are half of the painter's font size in length.
+    fn chart_axes(&mut self, chart: &Chart, x_ticks: u32, y_ticks: u32) -> &mut Self;
+    /// Draw the axes of a chart as with [`ChartPainter::chart_axes`] with the value of each tick written beside it.
+    fn chart_axes_labeled(
+        &mut self,
+        chart: &Chart,
+        x_ticks: u32,
+        y_ticks: u32,
+        font: &Handle<Font>,
+    ) -> &mut Self;
+    /// Draw a series as a polyline through each value.
+    fn line_series(&mut self, chart: &Chart, data: &[f32]) -> &mut Self;
+    /// Draw a series as bars from the baseline to each value, `width` is the fraction of the space between values each bar fills.
+    fn bar_series(&mut self, chart: &Chart, data: &[f32], width: f32) -> &mut Self;
+    /// Fill the area between the baseline and a polyline through each value.
+    fn area_series(&mut self, chart: &Chart, data: &[f32]) -> &mut Self;
+    /// Draw an annular sector for each value centered on the painter's transform, see [`PieChart`].
+    fn pie_chart(&mut self, pie: &PieChart, values: &[f32]) -> &mut Self;
+}
+
+impl<'w, 's> ChartPainter for ShapePainter<'w, 's> {
+    fn chart_axes(&mut self, chart: &Chart, x_ticks: u32, y_ticks: u32) -> &mut Self {
+        let tick_length = self.font_size / 2.0;
+        self.line(Vec3::ZERO, Vec3::new(chart.size.x, 0.0, 0.0));
+        self.line(Vec3::ZERO, Vec3::new(0.0, chart.size.y, 0.0));
+
+        for (_, x) in ticks(chart.x_range, chart.size.x, x_ticks) {
+            self.line(Vec3::new(x, 0.0, 0.0), Vec3::new(x, -tick_length, 0.0));
+        }
+        for (_, y) in ticks(chart.y_range, chart.size.y, y_ticks) {
+            self.line(Vec3::new(0.0, y, 0.0), Vec3::new(-tick_length, y, 0.0));
+        }
+        self
+    }
+
+    fn chart_axes_labeled(
+        &mut self,
+        chart: &Chart,
+        x_ticks: u32,
+        y_ticks: u32,
+        font: &Handle<Font>,
+    ) -> &mut Self {
+        self.chart_axes(chart, x_ticks, y_ticks);
+
+        // Text is centered on it's position so offset labels by an estimate of their size
+        let font_size = self.font_size;
+        let tick_length = font_size / 2.0;
+        for (value, x) in ticks(chart.x_range, chart.size.x, x_ticks) {
+            let label = tick_label(value, chart.x_range, x_ticks);
+            self.with_translation(
+                Vec3::new(x, -tick_length - font_size * 0.75, 0.0),
+                |painter| {
+                    painter.text(&label, font);
+                },
+            );
+        }
+        for (value, y) in ticks(chart.y_range, chart.size.y, y_ticks) {
+            let label = tick_label(value, chart.y_range, y_ticks);
+            let half_width = label.chars().count() as f32 * font_size * 0.3;
+            self.with_translation(
+                Vec3::new(-tick_length - font_size * 0.25 - half_width, y, 0.0),
+                |painter| {
+                    painter.text(&label, font);
+                },
+            );
+        }
+        self
+    }
+
+    fn line_series(&mut self, chart: &Chart, data: &[f32]) -> &mut Self {
+        if data.len() >= 2 {
+            let points: Vec<Vec3> = chart.points(data).iter().map(|p| p.extend(0.0)).collect();
+            self.polyline(&points);
+        }
+        self
+    }
+
+    fn bar_series(&mut self, chart: &Chart, data: &[f32], width: f32) -> &mut Self {
+        let baseline = chart.baseline();
+        let spacing = (chart.position(Vec2::X).x - chart.position(Vec2::ZERO).x).abs();
+        let bar_width = spacing * width;
+
+        self.with_children(|painter| {
+            painter.anchor = Anchor::Center;
+            for point in chart.points(data) {
+                let height = point.y - baseline;
+                if height == 0.0 {
+                    continue;
+                }
+                painter.with_translation(
+                    Vec3::new(point.x, baseline + height / 2.0, 0.0),
+                    |painter| {
+                        painter.rect(Vec2::new(bar_width, height.abs()));
+                    },
+                );
+            }
+        });
+        self
+    }
+
+    fn area_series(&mut self, chart: &Chart, data: &[f32]) -> &mut Self {
+        let baseline = chart.baseline();
+        let points = chart.points(data);
+
+        if points.len() < 2 {
+            return self;
+        }
+
+        // Split the area wherever the series crosses the baseline so that each polygon is simple
+        let mut area = vec![Vec2::new(points[0].x, baseline), points[0]];
+        for pair in points.windows(2) {
+            let (previous, point) = (pair[0], pair[1]);
+            if (previous.y - baseline) * (point.y - baseline) < 0.0 {
+                let t = (baseline - previous.y) / (point.y - previous.y);
+                let crossing = Vec2::new(previous.x + (point.x - previous.x) * t, baseline);
+                area.push(crossing);
+                self.polygon(&area);
+                area = vec![crossing];
+            }
+            area.push(point);
+        }
+        area.push(Vec2::new(points[points.len() - 1].x, baseline));
+        self.polygon(&area);
+        self
+    }
+
+    fn pie_chart(&mut self, pie: &PieChart, values: &[f32]) -> &mut Self {
+        let color = self.color;
+        for (index, angles) in pie.slice_angles(values).into_iter().enumerate() {
+            let Some((start_angle, end_angle)) = angles else {
+                continue;
+            };
+            self.color = pie.color(index).unwrap_or(color);
+            self.sector(pie.inner_radius, pie.radius, start_angle, end_angle);
+        }
+        self.color = color;
+        self
+    }
+}
+
+/// Layout and colors of a pie or donut chart drawn by [`ChartPainter::pie_chart`] or spawned by [`PieChartSpawner::pie_chart`].
+///
+/// Angles are in radians, an angle of 0 points up and angles increase clockwise as with [`DiscPainter::arc`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PieChart {
+    /// Outer radius of each slice.
+    pub radius: f32,
+    /// Radius of the hole in the center, 0 for a pie chart and greater than 0 for a donut chart.
+    pub inner_radius: f32,
+    /// Angle left empty between neighbouring slices.
+    pub gap_angle: f32,
+    /// Angle at which the first slice starts.
+    pub start_angle: f32,
+    /// Colors of each slice, repeating when there are more values than colors.
+    ///
+    /// When empty the color of the painter or spawner is used.
+    pub palette: Vec<Color>,
+}
+
+impl PieChart {
+    pub fn new(radius: f32, palette: Vec<Color>) -> Self {
+        Self {
+            radius,
+            inner_radius: 0.0,
+            gap_angle: 0.0,
+            start_angle: 0.0,
+            palette,
+        }
+    }
+
+    /// Returns the start and end angle of the slice for each value, proportional to it's share of the total.
+    ///
+    /// Negative values are treated as 0, slices too small to fit outside of their gap are None.
+    pub fn slice_angles(&self, values: &[f32]) -> Vec<Option<(f32, f32)>> {
+        let total: f32 = values.iter().map(|value| value.max(0.0)).sum();
+        if total <= 0.0 {
+            return vec![None; values.len()];
+        }
+
+        let mut angle = self.start_angle;
+        values
+            .iter()
+            .map(|value| {
+                let sweep = value.max(0.0) / total * TAU;
+                let (start, end) = (
+                    angle + self.gap_angle / 2.0,
+                    angle + sweep - self.gap_angle / 2.0,
+                );
+                angle += sweep;
+                (end > start).then_some((start, end))
+            })
+            .collect()
+    }
+
+    fn color(&self, index: usize) -> Option<Color> {
+        (!self.palette.is_empty()).then(|| self.palette[index % self.palette.len()])
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of pie and donut charts, requires the `charts` feature.
+pub trait PieChartSpawner<'w> {
+    /// Spawn an annular sector for each value and return their entities, see [`PieChart`].
+    fn pie_chart(&mut self, pie: &PieChart, values: &[f32]) -> Vec<Entity>;
+}
+
+impl<'w, T: ShapeSpawner<'w>> PieChartSpawner<'w> for T {
+    fn pie_chart(&mut self, pie: &PieChart, values: &[f32]) -> Vec<Entity> {
+        let config = self.config().clone();
+        let mut entities = Vec::new();
+        for (index, angles) in pie.slice_angles(values).into_iter().enumerate() {
+            let Some((start_angle, end_angle)) = angles else {
+                continue;
+            };
+            self.color = pie.color(index).unwrap_or(config.color);
+            entities.push(
+                self.sector(pie.inner_radius, pie.radius, start_angle, end_angle)
+                    .id(),
+            );
+        }
+        self.set_config(config);
+        entities
+    }
+}
+
+/// Returns each tick value and it's position along an axis of the given length.
+fn ticks(range: Vec2, length: f32, divisions: u32) -> impl Iterator<Item = (f32, f32)> {
+    let divisions = divisions.max(1);
+    (0..=divisions).map(move |i| {
+        let t = i as f32 / divisions as f32;
+        (range.x + (range.y - range.x) * t, length * t)
+    })
+}
+
+/// Format a tick value with enough decimal places to distinguish neighbouring ticks.
+fn tick_label(value: f32, range: Vec2, divisions: u32) -> String {
+    let step = ((range.y - range.x) / divisions.max(1) as f32).abs();
+    let decimals = if step >= 1.0 || step == 0.0 {
+        0
+    } else {
+        (-step.log10()).ceil() as usize
+    };
+    format!("{value:.decimals$}")
+}
@@ -41,25 +41,75 @@ use shapes::*;
 
 /// Rendering specific traits and structs.
 pub mod render;
-use render::{ShapeRenderPlugin, ShapeType3dPlugin, ShapeTypePlugin};
+#[cfg(feature = "shape_3d")]
+use render::{oit::ShapeOitPlugin, ShapeType3dPlugin};
+use render::{readback::CanvasReadbackPlugin, ShapeRenderPlugin, ShapeShaders, ShapeTypePlugin};
 
 /// Structs and components used by the [`ShapePainter`], [`ShapeCommands`] and [`Canvas`] APIs.
 pub mod painter;
 use painter::*;
 
+/// Helpers for drawing charts with the [`ShapePainter`], enabled by the `charts` feature.
+#[cfg(feature = "charts")]
+pub mod charts;
+
+/// Picking backend for retained shapes, enabled by the `picking` feature.
+#[cfg(feature = "picking")]
+pub mod picking;
+
+/// Debug drawing of 2D physics colliders, enabled by the `rapier2d` or `xpbd_2d` features.
+#[cfg(any(feature = "rapier2d", feature = "xpbd_2d"))]
+pub mod physics;
+
+/// Lenses for animating retained shapes with bevy_tweening, enabled by the `tweening` feature.
+#[cfg(feature = "tweening")]
+pub mod tweening;
+
+/// Serde definitions for foreign types stored on shapes, enabled by the `serialize` feature.
+#[cfg(feature = "serialize")]
+mod serde_remote;
+
 /// `use bevy_vector_shapes::prelude::*` to import commonly used items.
 pub mod prelude {
     pub use crate::painter::{
-        BuildShapeChildren, Canvas, CanvasCommands, CanvasConfig, CanvasMode, ShapeChildBuilder,
-        ShapeCommands, ShapeConfig, ShapeEntityCommands, ShapePainter, ShapeSpawner,
+        BakedShape, BakedShapes, BuildShapeChildren, Canvas, CanvasCommands, CanvasConfig,
+        CanvasFit, CanvasImage, CanvasMode, CanvasReadback, CanvasSpriteBundle,
+        ParallelShapePainter, Persistence, ScreenAnchor, ShapeChildBuilder, ShapeCommands,
+        ShapeConfig, ShapeEntityCommands, ShapeGizmos, ShapeHit, ShapeHitTest, ShapePainter,
+        ShapeRecording, ShapeSender, ShapeSpawner, ShapeSymbol, ShapeSymbolBundle, ShapeSymbols,
+        ThreadShapePainter,
     };
-    pub use crate::{shapes::*, BaseShapeConfig, Shape2dPlugin, ShapePlugin};
+    pub use crate::render::{
+        NoShapeRendering, ShapeComponent, ShapeData, ShapeDiagnosticsPlugin, ShapeHookShader,
+        ShapeShaderHook, ShapeShaderHooks, ShapeShaders, ShapeTypePlugin,
+    };
+    pub use crate::{shapes::*, BaseShapeConfig, Shape2dPlugin};
+
+    #[cfg(feature = "shape_3d")]
+    pub use crate::render::ShapeType3dPlugin;
+    #[cfg(feature = "shape_3d")]
+    pub use crate::ShapePlugin;
+
+    #[cfg(feature = "bevy_ui")]
+    pub use crate::painter::CanvasUiBundle;
+    #[cfg(feature = "bevy_ui")]
+    pub use crate::render::{NodeShape, ShapeNode, ShapeNodeBundle};
+
+    #[cfg(feature = "serialize")]
+    pub use crate::painter::{ShapeSheet, ShapeSheetBundle};
+
+    #[cfg(feature = "charts")]
+    pub use crate::charts::{Chart, ChartPainter, PieChart, PieChartSpawner};
+
+    #[cfg(feature = "tweening")]
+    pub use crate::tweening::{ArcAnglesLens, ShapeColorLens, ShapeRadiusLens, ShapeThicknessLens};
 }
 
 /// Resource that represents the default shape config to be used by [`ShapePainter`] and [`ShapeCommands`] APIs.
 ///
 /// When a [`ShapePainter`] is cleared it will have it's config reset to the current value of this resource.
-#[derive(Resource, Clone)]
+#[derive(Resource, Clone, Reflect)]
+#[reflect(Resource)]
 pub struct BaseShapeConfig(pub ShapeConfig);
 
 /// Plugin that contains all necessary functionality to draw shapes with a 2D camera.
@@ -68,36 +118,101 @@ pub struct Shape2dPlugin {
     ///
     /// Available as a resource [`BaseShapeConfig`].
     pub base_config: ShapeConfig,
+    /// Shaders to draw shape types with in place of their embedded shaders.
+    ///
+    /// Available as a resource [`ShapeShaders`], which is read once when the plugin is finished.
+    pub shaders: ShapeShaders,
 }
 
 impl Default for Shape2dPlugin {
     fn default() -> Self {
         Self {
             base_config: ShapeConfig::default_2d(),
+            shaders: default(),
         }
     }
 }
 
 impl Shape2dPlugin {
     pub fn new(base_config: ShapeConfig) -> Self {
-        Self { base_config }
+        Self {
+            base_config,
+            ..default()
+        }
+    }
+
+    pub fn with_shaders(mut self, shaders: ShapeShaders) -> Self {
+        self.shaders = shaders;
+        self
     }
 }
 
 impl Plugin for Shape2dPlugin {
     fn build(&self, app: &mut App) {
+        app.world
+            .get_resource_or_insert_with(ShapeShaders::default)
+            .extend(&self.shaders);
         app.insert_resource(BaseShapeConfig(self.base_config.clone()))
+            .register_type::<BaseShapeConfig>()
             .add_plugins(PainterPlugin)
+            .add_plugins(ShapeAnimationPlugin)
             .add_plugins(ShapeRenderPlugin)
+            .add_plugins(CanvasReadbackPlugin)
             .add_plugins(ShapeTypePlugin::<LineComponent>::default())
             .add_plugins(ShapeTypePlugin::<DiscComponent>::default())
             .add_plugins(ShapeTypePlugin::<RectangleComponent>::default())
-            .add_plugins(ShapeTypePlugin::<RegularPolygonComponent>::default())
-            .add_plugins(ShapeTypePlugin::<TriangleComponent>::default());
+            .add_plugins(ShapeTypePlugin::<PolylineComponent>::default())
+            .add_plugins(ShapeTypePlugin::<PolygonComponent>::default())
+            .add_plugins(ShapeTypePlugin::<CapsuleComponent>::default())
+            .add_plugins(ShapeTypePlugin::<ArrowComponent>::default())
+            .add_plugins(ShapeTypePlugin::<SplineComponent>::default())
+            .add_plugins(ShapeTypePlugin::<WireBoxComponent>::default())
+            .add_plugins(ShapeTypePlugin::<WireSphereComponent>::default())
+            .add_plugins(ShapeTypePlugin::<WaveComponent>::default());
+
+        #[cfg(feature = "ngon")]
+        app.add_plugins(ShapeTypePlugin::<RegularPolygonComponent>::default());
+
+        #[cfg(feature = "triangle")]
+        app.add_plugins(ShapeTypePlugin::<TriangleComponent>::default());
+
+        #[cfg(feature = "bezier")]
+        app.add_plugins(ShapeTypePlugin::<BezierComponent>::default());
+
+        #[cfg(feature = "ellipse")]
+        app.add_plugins(ShapeTypePlugin::<EllipseComponent>::default());
+
+        #[cfg(feature = "star")]
+        app.add_plugins(ShapeTypePlugin::<StarComponent>::default());
+
+        #[cfg(feature = "superellipse")]
+        app.add_plugins(ShapeTypePlugin::<SuperellipseComponent>::default());
+
+        #[cfg(feature = "grid")]
+        app.add_plugins(ShapeTypePlugin::<GridComponent>::default())
+            .add_plugins(ShapeTypePlugin::<ReferenceGridComponent>::default());
+
+        #[cfg(feature = "point")]
+        app.add_plugins(ShapeTypePlugin::<PointComponent>::default());
+
+        #[cfg(feature = "text")]
+        app.add_plugins(ShapeTypePlugin::<GlyphComponent>::default());
+
+        #[cfg(feature = "bevy_ui")]
+        app.add_plugins(render::ShapeNodePlugin);
+
+        #[cfg(feature = "picking")]
+        app.add_plugins(picking::ShapePickingPlugin);
+
+        #[cfg(feature = "tweening")]
+        app.add_plugins(tweening::ShapeTweeningPlugin);
     }
 }
 
 /// Plugin that contains all necessary functionality to draw shapes with a 3D or 2D camera.
+///
+/// Enabled by the `shape_3d` feature.
+#[cfg(feature = "shape_3d")]
 pub struct ShapePlugin {
     /// Default config that will be used for all [`ShapePainter`]s.
     ///
@@ -107,8 +222,11 @@ pub struct ShapePlugin {
     ///
     /// Useful if you want to add the 3d functionality when another plugin has already added the 2d plugin.
     pub exclude_2d: bool,
+    /// Shaders to draw shape types with in place of their embedded shaders, see [`Shape2dPlugin::shaders`].
+    pub shaders: ShapeShaders,
 }
 
+#[cfg(feature = "shape_3d")]
 impl ShapePlugin {
     pub fn new(base_config: ShapeConfig) -> Self {
         Self {
@@ -118,24 +236,68 @@ impl ShapePlugin {
     }
 }
 
+#[cfg(feature = "shape_3d")]
 impl Default for ShapePlugin {
     fn default() -> Self {
         Self {
             base_config: ShapeConfig::default_3d(),
             exclude_2d: false,
+            shaders: default(),
         }
     }
 }
 
+#[cfg(feature = "shape_3d")]
 impl Plugin for ShapePlugin {
     fn build(&self, app: &mut App) {
-        if !self.exclude_2d {
-            app.add_plugins(Shape2dPlugin::new(self.base_config.clone()));
+        if self.exclude_2d {
+            app.world
+                .get_resource_or_insert_with(ShapeShaders::default)
+                .extend(&self.shaders);
+        } else {
+            app.add_plugins(
+                Shape2dPlugin::new(self.base_config.clone()).with_shaders(self.shaders.clone()),
+            );
         }
-        app.add_plugins(ShapeType3dPlugin::<LineComponent>::default())
+        app.add_plugins(ShapeOitPlugin)
+            .add_plugins(ShapeType3dPlugin::<LineComponent>::default())
             .add_plugins(ShapeType3dPlugin::<DiscComponent>::default())
             .add_plugins(ShapeType3dPlugin::<RectangleComponent>::default())
-            .add_plugins(ShapeType3dPlugin::<RegularPolygonComponent>::default())
-            .add_plugins(ShapeType3dPlugin::<TriangleComponent>::default());
+            .add_plugins(ShapeType3dPlugin::<PolylineComponent>::default())
+            .add_plugins(ShapeType3dPlugin::<PolygonComponent>::default())
+            .add_plugins(ShapeType3dPlugin::<CapsuleComponent>::default())
+            .add_plugins(ShapeType3dPlugin::<ArrowComponent>::default())
+            .add_plugins(ShapeType3dPlugin::<SplineComponent>::default())
+            .add_plugins(ShapeType3dPlugin::<WireBoxComponent>::default())
+            .add_plugins(ShapeType3dPlugin::<WireSphereComponent>::default())
+            .add_plugins(ShapeType3dPlugin::<WaveComponent>::default());
+
+        #[cfg(feature = "ngon")]
+        app.add_plugins(ShapeType3dPlugin::<RegularPolygonComponent>::default());
+
+        #[cfg(feature = "triangle")]
+        app.add_plugins(ShapeType3dPlugin::<TriangleComponent>::default());
+
+        #[cfg(feature = "bezier")]
+        app.add_plugins(ShapeType3dPlugin::<BezierComponent>::default());
+
+        #[cfg(feature = "ellipse")]
+        app.add_plugins(ShapeType3dPlugin::<EllipseComponent>::default());
+
+        #[cfg(feature = "star")]
+        app.add_plugins(ShapeType3dPlugin::<StarComponent>::default());
+
+        #[cfg(feature = "superellipse")]
+        app.add_plugins(ShapeType3dPlugin::<SuperellipseComponent>::default());
+
+        #[cfg(feature = "grid")]
+        app.add_plugins(ShapeType3dPlugin::<GridComponent>::default())
+            .add_plugins(ShapeType3dPlugin::<ReferenceGridComponent>::default());
+
+        #[cfg(feature = "point")]
+        app.add_plugins(ShapeType3dPlugin::<PointComponent>::default());
+
+        #[cfg(feature = "text")]
+        app.add_plugins(ShapeType3dPlugin::<GlyphComponent>::default());
     }
 }
@@ -0,0 +1,111 @@
+use std::any::TypeId;
+
+use bevy::{prelude::*, render::view::RenderLayers};
+
+use crate::{
+    prelude::*,
+    render::{retained_instances, ShapeComponent, ShapePipelineMaterial, ShapePipelineType},
+};
+
+use super::ShapeStorage;
+
+/// Shapes baked into instance data that is uploaded to the GPU once and drawn each frame without being gathered again.
+///
+/// Insert on the root of a hierarchy of retained shapes to bake the root and each of it's descendants on the frame it is
+/// added, or create from a [`ShapeRecording`] with [`BakedShapes::from_recording`] to bake immediate mode shapes.
+/// Baked shapes are frozen with the transform, colors and alpha they had when baked and are drawn while the entity
+/// holding them is visible. Retained shapes that have been baked are marked with [`BakedShape`] and no longer drawn individually.
+///
+/// Each type of shape with the same material is drawn as a single batch, batches are sorted as a whole so baked
+/// transparent shapes are drawn in the order they were baked in rather than by depth.
+#[derive(Component, Default)]
+pub struct BakedShapes {
+    pub(crate) storage: ShapeStorage,
+    len: usize,
+}
+
+impl BakedShapes {
+    /// Bake each shape in the recording relative to the transform of the entity it was recorded with.
+    pub fn from_recording(recording: &ShapeRecording) -> Self {
+        let mut storage = ShapeStorage::default();
+        recording.send(&mut storage, Mat4::IDENTITY, Color::WHITE);
+        Self {
+            storage,
+            len: recording.len(),
+        }
+    }
+
+    /// Number of shape instances that have been baked.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Marker added to retained shapes that have been baked into a [`BakedShapes`], they are no longer drawn individually.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct BakedShape;
+
+/// Bakes each retained shape of type `T` in the hierarchy of a newly added [`BakedShapes`], skipping hidden shapes.
+#[allow(clippy::type_complexity)]
+pub fn bake_retained_shapes<T: ShapeComponent>(
+    mut commands: Commands,
+    mut roots: Query<(Entity, &mut BakedShapes), Added<BakedShapes>>,
+    children: Query<&Children>,
+    shapes: Query<
+        (
+            &T,
+            &ShapeFill,
+            &GlobalTransform,
+            Option<&Visibility>,
+            Option<&ShapeMaterial>,
+            Option<&RenderLayers>,
+            Has<Shape3d>,
+        ),
+        Without<BakedShape>,
+    >,
+    alphas: Query<(Option<&ShapeAlpha>, Option<&Parent>)>,
+) {
+    for (root, mut baked) in &mut roots {
+        let baked = baked.as_mut();
+        for entity in std::iter::once(root).chain(children.iter_descendants(root)) {
+            let Ok((shape, fill, tf, visibility, flags, rl, is_3d)) = shapes.get(entity) else {
+                continue;
+            };
+            if visibility == Some(&Visibility::Hidden) {
+                continue;
+            }
+
+            let material = ShapePipelineMaterial::new(flags, rl);
+            let (pipeline, occluded_tint) = match is_3d {
+                true => (
+                    ShapePipelineType::Shape3d,
+                    flags
+                        .filter(|flags| !flags.always_on_top)
+                        .and_then(|flags| flags.occluded_tint),
+                ),
+                false => (ShapePipelineType::Shape2d, None),
+            };
+            let key = (TypeId::of::<T::Data>(), pipeline);
+            let alpha = inherited_alpha(entity, &alphas);
+            retained_instances(
+                shape,
+                fill,
+                tf,
+                alpha,
+                &material,
+                occluded_tint,
+                flags.is_some_and(|flags| flags.constant_screen_size),
+                |_, material, data| {
+                    baked.storage.push(key, (material, data));
+                    baked.len += 1;
+                },
+            );
+            commands.entity(entity).insert(BakedShape);
+        }
+    }
+}
@@ -6,9 +6,99 @@ use bevy::{
         texture::{BevyDefault, ImageSampler},
         view::{RenderLayers, ViewTarget},
     },
+    window::PrimaryWindow,
 };
 use wgpu::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages};
 
+use crate::prelude::*;
+
+/// Resizes each canvas to match the size of the target given by it's [`CanvasFit`].
+///
+/// Sprites and retained shapes that display the canvas are updated to use the replacement image.
+pub fn fit_canvases(
+    mut canvases: Query<&mut Canvas>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<&Camera>,
+    mut images: ResMut<Assets<Image>>,
+    mut sprites: Query<&mut Handle<Image>>,
+    mut shapes: Query<&mut ShapeMaterial>,
+) {
+    for mut canvas in canvases.iter_mut() {
+        let size = match canvas.fit {
+            CanvasFit::Fixed => continue,
+            CanvasFit::PrimaryWindow => windows
+                .get_single()
+                .ok()
+                .map(|window| UVec2::new(window.physical_width(), window.physical_height())),
+            CanvasFit::Camera(entity) => cameras
+                .get(entity)
+                .ok()
+                .and_then(Camera::physical_viewport_size),
+        };
+
+        // Minimized windows report a size of zero, keep the previous image until they are restored
+        let Some(size) = size.filter(|size| size.x > 0 && size.y > 0) else {
+            continue;
+        };
+        if size == UVec2::new(canvas.width, canvas.height) {
+            continue;
+        }
+
+        let old = canvas.image.clone();
+        let new = canvas.resize(&mut images, size.x, size.y);
+        canvas.redraw();
+
+        for mut handle in sprites.iter_mut().filter(|handle| **handle == old) {
+            *handle = new.clone();
+        }
+        for mut material in shapes
+            .iter_mut()
+            .filter(|material| material.texture.as_ref() == Some(&old))
+        {
+            material.texture = Some(new.clone());
+        }
+    }
+}
+
+/// Marks canvases in [`CanvasMode::OnChange`] to be redrawn when the retained shapes drawn to them change.
+pub fn redraw_changed_canvases<T: Component>(
+    shapes: Query<
+        &ShapeMaterial,
+        Or<(
+            Changed<T>,
+            Changed<ShapeFill>,
+            Changed<ShapeMaterial>,
+            Changed<GlobalTransform>,
+            Changed<InheritedVisibility>,
+        )>,
+    >,
+    mut canvases: Query<&mut Canvas>,
+) {
+    for canvas in shapes.iter().filter_map(|material| material.canvas) {
+        if let Ok(mut canvas) = canvases.get_mut(canvas) {
+            if canvas.mode == CanvasMode::OnChange {
+                canvas.redraw();
+            }
+        }
+    }
+}
+
+/// Marks every canvas in [`CanvasMode::OnChange`] to be redrawn when any retained shape is despawned,
+/// as the canvas it was drawn to is no longer known.
+pub fn redraw_canvases_on_removal(
+    mut removed: RemovedComponents<ShapeMaterial>,
+    mut canvases: Query<&mut Canvas>,
+) {
+    if removed.read().count() == 0 {
+        return;
+    }
+    for mut canvas in canvases.iter_mut() {
+        if canvas.mode == CanvasMode::OnChange {
+            canvas.redraw();
+        }
+    }
+}
+
 /// Prepares the camera assosciated with each canvas.
 ///
 /// Replaces the image handle when the canvas is resized and applies [`CanvasMode`] behaviours.
@@ -25,6 +115,7 @@ pub fn update_canvases(
                 }
             }
 
+            // Each mode sets both fields so that the mode of a canvas can be changed at any time
             match canvas.mode {
                 CanvasMode::Continuous => {
                     camera.clear_color = canvas.clear_color.clone();
@@ -36,32 +127,71 @@ pub fn update_canvases(
                     } else {
                         camera.clear_color = ClearColorConfig::None;
                     }
+                    camera.is_active = true;
                 }
-                CanvasMode::OnDemand => {
+                CanvasMode::OnDemand | CanvasMode::OnChange => {
+                    camera.clear_color = canvas.clear_color.clone();
                     camera.is_active = canvas.redraw;
                 }
             }
 
             canvas.redraw = false;
+            canvas.reading_back = std::mem::take(&mut canvas.readback);
         })
 }
 
 /// Enum that determines when canvases are cleared and redrawn.
-#[derive(Default)]
+///
+/// Each canvas has it's own mode and clear color, both of which can be changed at any time.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum CanvasMode {
     /// Always clear and draw each frame
     #[default]
     Continuous,
-    /// Always draw but don't clear until a call to Canvas::redraw
+    /// Always draw but don't clear until a call to Canvas::redraw, accumulating shapes across frames like a paint program
     Persistent,
     /// Don't draw or clear until a call to Canvas::redraw
     OnDemand,
+    /// Don't draw or clear until the retained shapes drawn to the canvas change or a call to Canvas::redraw
+    ///
+    /// Useful for static canvases such as backgrounds, shapes drawn to the canvas are not extracted on other frames
+    /// so immediate mode shapes can still be drawn each frame at little cost.
+    OnChange,
+}
+
+/// Event sent once the texture of a canvas has been read back to the CPU, see [`Canvas::request_readback`].
+///
+/// Images can be converted with [`Image::try_into_dynamic`] to be saved in any format supported by the `image` crate.
+#[derive(Event, Clone, Debug)]
+pub struct CanvasReadback {
+    /// The canvas entity that was read back.
+    pub canvas: Entity,
+    /// Copy of the canvas' texture, in the same format as the texture.
+    pub image: Image,
+}
+
+/// Enum that determines whether a canvas is resized to follow the size of another render target.
+///
+/// Canvases that follow a target are recreated at it's physical size whenever it changes so that shapes stay crisp,
+/// see [`fit_canvases`].
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum CanvasFit {
+    /// Keep the size the canvas was created with
+    #[default]
+    Fixed,
+    /// Match the size of the primary window
+    PrimaryWindow,
+    /// Match the size of the viewport of the given camera
+    Camera(Entity),
 }
 
 /// Component containing data and methods for a given canvas.
 ///
 /// Can be spawned as part of a [`CanvasBundle`] with [`CanvasCommands::spawn_canvas`].
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 pub struct Canvas {
     /// Handle to the canvas' target texture.
     pub image: Handle<Image>,
@@ -71,9 +201,13 @@ pub struct Canvas {
     pub height: u32,
     /// Determines when the canvas is cleared and drawn to, see [`CanvasMode`].
     pub mode: CanvasMode,
-    /// Clear mode to revert to for [`CanvasMode::OnDemand`].
+    /// Determines whether the canvas is resized to follow another render target, see [`CanvasFit`].
+    pub fit: CanvasFit,
+    /// Color the canvas is cleared to, see [`CanvasMode`] for when it is cleared.
     pub clear_color: ClearColorConfig,
     redraw: bool,
+    readback: bool,
+    reading_back: bool,
 }
 
 impl Canvas {
@@ -84,6 +218,24 @@ impl Canvas {
         height: u32,
         sampler: ImageSampler,
         hdr: bool,
+    ) -> Handle<Image> {
+        let format = if hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+        Self::create_image_with_format(assets, width, height, sampler, format)
+    }
+
+    /// Create a [`Handle<Image>`] with the given texture format that will function as a render target.
+    ///
+    /// The format must support being rendered to, such as [`TextureFormat::Rgba16Float`] for an hdr canvas.
+    pub fn create_image_with_format(
+        assets: &mut Assets<Image>,
+        width: u32,
+        height: u32,
+        sampler: ImageSampler,
+        format: TextureFormat,
     ) -> Handle<Image> {
         let size = Extent3d {
             width,
@@ -96,15 +248,12 @@ impl Canvas {
                 label: None,
                 size,
                 dimension: TextureDimension::D2,
-                format: if hdr {
-                    ViewTarget::TEXTURE_FORMAT_HDR
-                } else {
-                    TextureFormat::bevy_default()
-                },
+                format,
                 mip_level_count: 1,
                 sample_count: 1,
                 usage: TextureUsages::TEXTURE_BINDING
                     | TextureUsages::COPY_DST
+                    | TextureUsages::COPY_SRC
                     | TextureUsages::RENDER_ATTACHMENT,
                 view_formats: &[],
             },
@@ -141,6 +290,18 @@ impl Canvas {
     pub fn redraw(&mut self) {
         self.redraw = true;
     }
+
+    /// Read the canvas' texture back to the CPU once this frame has been drawn.
+    ///
+    /// The image is sent as a [`CanvasReadback`] event, usually a few frames later.
+    pub fn request_readback(&mut self) {
+        self.readback = true;
+    }
+
+    /// Whether the canvas' texture is read back to the CPU after being drawn this frame.
+    pub(crate) fn is_reading_back(&self) -> bool {
+        self.reading_back
+    }
 }
 
 /// Configuration to be used when creating a [`CanvasBundle`]
@@ -150,6 +311,10 @@ pub struct CanvasConfig {
     pub clear_color: ClearColorConfig,
     /// Determines when the canvas is cleared and drawn to, see [`CanvasMode`].
     pub mode: CanvasMode,
+    /// Determines whether the canvas is resized to follow another render target, see [`CanvasFit`].
+    ///
+    /// The width and height are only used until the canvas is first fitted to it's target.
+    pub fit: CanvasFit,
     /// Width of the canvas' target texture in pixels.
     pub width: u32,
     /// Height of the canvas' target texture in pixels.
@@ -160,6 +325,11 @@ pub struct CanvasConfig {
     pub sampler: ImageSampler,
     /// Whether to enable hdr for the assosciated camera and texture.
     pub hdr: bool,
+    /// Format of the target texture, overriding the format chosen by `hdr`.
+    ///
+    /// Allows an hdr camera to be tonemapped into an 8 bit texture or an 8 bit camera to write to a float texture.
+    /// Multisampling is controlled by the global [`Msaa`] resource for all cameras, including canvases.
+    pub format: Option<TextureFormat>,
 }
 
 impl CanvasConfig {
@@ -167,11 +337,13 @@ impl CanvasConfig {
         Self {
             clear_color: ClearColorConfig::Default,
             mode: CanvasMode::default(),
+            fit: CanvasFit::default(),
             width,
             height,
             order: -1,
             sampler: ImageSampler::Default,
             hdr: false,
+            format: None,
         }
     }
 }
@@ -207,14 +379,139 @@ impl CanvasBundle {
                 height: config.height,
 
                 mode: config.mode,
+                fit: config.fit,
                 clear_color: config.clear_color,
                 redraw: true,
+                readback: false,
+                reading_back: false,
             },
             render_layers: RenderLayers::none(),
         }
     }
 }
 
+/// Component that displays the texture of a canvas on a sprite or UI image, see [`CanvasSpriteBundle`].
+///
+/// The displayed texture is kept up to date as the canvas is resized and the aspect ratio of the canvas is preserved.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct CanvasImage {
+    /// Canvas entity to display.
+    pub canvas: Entity,
+    /// Height of sprites in world units, their width follows the aspect ratio of the canvas.
+    ///
+    /// When not set sprites are the size of the canvas in pixels, ignored for UI images.
+    pub height: Option<f32>,
+}
+
+impl CanvasImage {
+    pub fn new(canvas: Entity) -> Self {
+        Self {
+            canvas,
+            height: None,
+        }
+    }
+
+    /// Size of a sprite displaying the given canvas.
+    fn sprite_size(&self, canvas: &Canvas) -> Option<Vec2> {
+        self.height.map(|height| {
+            let aspect = canvas.width as f32 / canvas.height.max(1) as f32;
+            Vec2::new(height * aspect, height)
+        })
+    }
+}
+
+/// Bundle that displays a canvas in the world as a sprite.
+#[derive(Bundle)]
+pub struct CanvasSpriteBundle {
+    pub sprite: SpriteBundle,
+    pub canvas_image: CanvasImage,
+}
+
+impl CanvasSpriteBundle {
+    /// Create a sprite displaying the given canvas entity and it's image.
+    pub fn new(canvas: Entity, image: Handle<Image>) -> Self {
+        Self {
+            sprite: SpriteBundle {
+                texture: image,
+                ..default()
+            },
+            canvas_image: CanvasImage::new(canvas),
+        }
+    }
+
+    /// Create a sprite with the given height in world units, see [`CanvasImage::height`].
+    pub fn with_height(canvas: Entity, image: Handle<Image>, height: f32) -> Self {
+        let mut bundle = Self::new(canvas, image);
+        bundle.canvas_image.height = Some(height);
+        bundle
+    }
+}
+
+/// Bundle that displays a canvas in bevy_ui as an image node, requires the `bevy_ui` feature.
+///
+/// The node keeps the aspect ratio of the canvas, so only one of it's width or height should be set in it's [`Style`].
+#[cfg(feature = "bevy_ui")]
+#[derive(Bundle)]
+pub struct CanvasUiBundle {
+    pub image: ImageBundle,
+    pub canvas_image: CanvasImage,
+}
+
+#[cfg(feature = "bevy_ui")]
+impl CanvasUiBundle {
+    /// Create an image node displaying the given canvas entity and it's image with the given style.
+    pub fn new(canvas: Entity, image: Handle<Image>, style: Style) -> Self {
+        Self {
+            image: ImageBundle {
+                image: UiImage::new(image),
+                style,
+                ..default()
+            },
+            canvas_image: CanvasImage::new(canvas),
+        }
+    }
+}
+
+/// Keeps the texture and size of sprites displaying a canvas in sync with it.
+pub fn update_canvas_sprites(
+    canvases: Query<&Canvas>,
+    mut sprites: Query<(&CanvasImage, &mut Handle<Image>, &mut Sprite)>,
+) {
+    for (canvas_image, mut image, mut sprite) in sprites.iter_mut() {
+        let Ok(canvas) = canvases.get(canvas_image.canvas) else {
+            continue;
+        };
+        if *image != canvas.image {
+            *image = canvas.image.clone();
+        }
+        let size = canvas_image.sprite_size(canvas);
+        if sprite.custom_size != size {
+            sprite.custom_size = size;
+        }
+    }
+}
+
+/// Keeps the texture and aspect ratio of UI images displaying a canvas in sync with it.
+#[cfg(feature = "bevy_ui")]
+pub fn update_canvas_ui_images(
+    canvases: Query<&Canvas>,
+    mut images: Query<(&CanvasImage, &mut UiImage, &mut Style)>,
+) {
+    for (canvas_image, mut image, mut style) in images.iter_mut() {
+        let Ok(canvas) = canvases.get(canvas_image.canvas) else {
+            continue;
+        };
+        if image.texture != canvas.image {
+            image.texture = canvas.image.clone();
+        }
+        let aspect_ratio = Some(canvas.width as f32 / canvas.height.max(1) as f32);
+        if style.aspect_ratio != aspect_ratio {
+            style.aspect_ratio = aspect_ratio;
+        }
+    }
+}
+
 /// Extension trait for [`Commands`] to allow spawning of [`CanvasBundle`] entities.
 pub trait CanvasCommands<'w> {
     /// Spawns a [`CanvasBundle`] according to the given [`CanvasConfig`].
@@ -233,13 +530,22 @@ impl<'w, 's> CanvasCommands<'w> for Commands<'w, 's> {
         assets: &mut Assets<Image>,
         config: CanvasConfig,
     ) -> (Handle<Image>, EntityCommands) {
-        let handle = Canvas::create_image(
-            assets,
-            config.width,
-            config.height,
-            config.sampler.clone(),
-            config.hdr,
-        );
+        let handle = match config.format {
+            Some(format) => Canvas::create_image_with_format(
+                assets,
+                config.width,
+                config.height,
+                config.sampler.clone(),
+                format,
+            ),
+            None => Canvas::create_image(
+                assets,
+                config.width,
+                config.height,
+                config.sampler.clone(),
+                config.hdr,
+            ),
+        };
         (
             handle.clone(),
             self.spawn(CanvasBundle::new(handle, config)),
@@ -1,4 +1,7 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
 
 use bevy::{
     ecs::system::{Command, EntityCommands},
@@ -35,7 +38,9 @@ impl<'w, 's> ShapeEntityCommands<'w, 's> {
         &mut self,
         spawn_children: impl FnOnce(&mut ShapeChildBuilder),
     ) -> &mut Self {
-        let config = self.config.without_transform();
+        // Children inherit the transform and alpha of their parent through the hierarchy
+        let mut config = self.config.without_transform();
+        config.alpha = 1.0;
         let parent = self.id();
         let mut painter = ShapeChildBuilder {
             commands: self.commands(),
@@ -51,6 +56,12 @@ impl<'w, 's> ShapeEntityCommands<'w, 's> {
         self.commands().add(children);
         self
     }
+
+    /// Despawn this entity and it's children after the given number of seconds, see [`ShapeLifetime`].
+    pub fn with_lifetime(&mut self, seconds: f32) -> &mut Self {
+        self.insert(ShapeLifetime(Duration::from_secs_f32(seconds)));
+        self
+    }
 }
 
 impl<'w, 's> Deref for ShapeEntityCommands<'w, 's> {
@@ -116,6 +127,9 @@ impl<'w> ShapeSpawner<'w> for ShapeChildBuilder<'w> {
         if let ShapePipelineType::Shape3d = config.pipeline {
             e.insert(Shape3d);
         }
+        if config.alpha != 1.0 {
+            e.insert(ShapeAlpha(config.alpha));
+        }
 
         ShapeEntityCommands {
             commands: e,
@@ -1,43 +1,157 @@
 use bevy::ecs::component::Tick;
 use bevy::ecs::system::{SystemMeta, SystemParam};
 use bevy::ecs::world::unsafe_world_cell::UnsafeWorldCell;
+use bevy::math::primitives::Plane3d;
 use bevy::prelude::*;
 use bevy::render::view::RenderLayers;
+use bevy::sprite::Anchor;
 use bevy::utils::synccell::SyncCell;
 
 use crate::prelude::*;
 use crate::render::ShapePipelineType;
 
+/// Distance in front of the camera at which shapes are drawn after [`ShapeConfig::set_screen_space`].
+pub const SCREEN_SPACE_DEPTH: f32 = 1.0;
+
+/// How long an immediate mode shape remains visible after the frame in which it was drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Persistence {
+    Frames(u32),
+    Seconds(f32),
+}
+
 /// Describes a configuration that can be applied to a spawned shape.
 #[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShapeConfig {
     /// Transform with which the shape will be spawned.
     pub transform: Transform,
     pub alignment: Alignment,
+    /// Keep shapes the same size on screen regardless of camera distance or projection, like the handles of editor gizmos.
+    ///
+    /// One unit in the shape's local space covers one pixel, measured separately for each camera in the vertex shader.
+    /// Thicknesses in world units are scaled along with the shape so are also measured in pixels.
+    /// Shapes with a constant screen size have no [`Aabb`](bevy::render::primitives::Aabb) so are never culled.
+    pub constant_screen_size: bool,
 
     pub color: Color,
+    /// Multiplier applied to the alpha of every color a shape is drawn with,
+    /// retained shapes are spawned with it as a [`ShapeAlpha`] so that it can be changed later.
+    pub alpha: f32,
+    /// Multiplier applied to the linear rgb of every color a shape is drawn with,
+    /// values above 1.0 push colors past white to be picked up by bloom with an hdr camera.
+    pub emissive: f32,
 
     /// If true spawned shape will have a [`ShapeFill`] with [`FillType::Stroke`], taking into account thickness and thickness_type.
     /// This doesn't use [`FillType`] directly in order to avoid unnecessary shuffling of thickness values when using both types of shape.
     pub hollow: bool,
     pub thickness: f32,
     pub thickness_type: ThicknessType,
+    /// Thickness at the end of lines and arcs, when set strokes taper linearly from thickness to this value.
+    pub thickness_end: Option<f32>,
+    /// Placement of outlines relative to the edge of discs, rectangles and regular polygons.
+    pub stroke_alignment: StrokeAlignment,
 
     pub cap: Cap,
+    pub join: Join,
+    /// Ratio of miter length to half thickness past which [`Join::Miter`] joins are drawn as [`Join::Bevel`] joins.
+    pub miter_limit: f32,
     pub roundness: f32,
+    /// Point on rectangles and regular polygons that is placed at the origin of their transform.
+    #[cfg_attr(feature = "serialize", serde(with = "crate::serde_remote::AnchorDef"))]
+    pub anchor: Anchor,
+    /// Corner radii for rectangles ordered top-right, top-left, bottom-left, bottom-right, see [`rounded_corners`].
     pub corner_radii: Vec4,
+    /// Whether the scale of the transform stretches only the size of rectangles, keeping their corner radii and
+    /// outline thickness unscaled like a 9-slice panel.
+    pub nine_slice: bool,
+    pub arrow_head: ArrowHead,
+    /// Length of arrow heads as a multiple of thickness.
+    pub arrow_head_size: f32,
+    /// Dash pattern applied to lines, discs and rectangles.
+    pub dash_pattern: Option<DashPattern>,
+    /// Gradient applied to the fill of discs, rectangles and regular polygons, blending from color.
+    pub gradient: Option<Gradient>,
+    /// Outline drawn over filled discs, rectangles and regular polygons, ignored when hollow.
+    pub stroke: Option<Stroke>,
+    /// Soft shadow drawn beneath filled discs, rectangles and regular polygons, ignored when hollow.
+    pub shadow: Option<Shadow>,
+    /// Soft halo drawn around filled discs, rectangles and regular polygons, ignored when hollow.
+    pub glow: Option<Glow>,
+    /// Height of a line of text in world units.
+    pub font_size: f32,
+    /// Number of straight segments drawn between each pair of control points of a spline,
+    /// also used for each curve and quarter turn of an arc in a path.
+    pub spline_subdivisions: u32,
 
     #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
     pub render_layers: Option<RenderLayers>,
+    #[cfg_attr(
+        feature = "serialize",
+        serde(with = "crate::serde_remote::AlphaModeDef")
+    )]
     pub alpha_mode: AlphaMode,
+    /// Blend shapes with a transparent alpha mode using screen blending instead, brightening whatever is behind them.
+    pub screen_blend: bool,
+    /// Composite overlapping 3D shapes with order independent transparency rather than sorting them by distance.
+    ///
+    /// Applies to shapes with a [`AlphaMode::Blend`] or [`AlphaMode::Premultiplied`] alpha mode, overlapping shapes
+    /// are blended as a weighted average of their colors so that they don't pop as the camera moves.
+    /// Falls back to sorting on adapters that can't blend into float render targets, such as some WebGL2 browsers.
+    pub oit: bool,
     /// Forcibly disables local anti-aliasing.
     pub disable_laa: bool,
+    /// Width in pixels over which the edges of shapes are smoothed by local anti-aliasing.
+    ///
+    /// Rounded to the nearest quarter of a pixel between 0.25 and 2.0, see [`ShapeConfig::disable_laa`] for hard edges.
+    pub aa_width: f32,
+    /// Constant bias added to the depth of 3D shapes, positive values draw shapes in front of coplanar geometry.
+    ///
+    /// Useful for decals such as selection rings drawn on top of terrain, rounded to a whole number between -16384 and 16383 and ignored in 2D.
+    pub depth_bias: f32,
+    /// Draw order of 2D shapes in place of the z coordinate of their transform, higher values are drawn on top.
+    ///
+    /// Sorted alongside other 2D items such as sprites, which use their z coordinate,
+    /// shapes with the same z index are drawn in an unspecified order.
+    pub z_index: Option<f32>,
+    /// Disables depth testing of 3D shapes so that they are drawn on top of other geometry, useful for debug visualization.
+    pub always_on_top: bool,
+    /// When set 3D shapes are drawn a second time where they are hidden behind other geometry, tinted by this color.
+    ///
+    /// For example `Color::rgba(1.0, 1.0, 1.0, 0.25)` shows occluded parts of shapes at a quarter of their alpha.
+    pub occluded_tint: Option<Color>,
+    /// Mask that shapes are clipped to, see [`ShapeConfig::mask_rect`] and [`ShapeConfig::mask_circle`].
+    pub clip: Option<ClipMask>,
+    /// Rectangle in pixels from the top left of the viewport or canvas that shapes are clipped to, see [`ShapeConfig::clip_rect`].
+    pub scissor: Option<Rect>,
     /// [`Canvas`] to draw the shape to.
     pub canvas: Option<Entity>,
     /// Texture to apply to the shape, color is determined as color * sample.
+    #[cfg_attr(feature = "serialize", serde(skip))]
     pub texture: Option<Handle<Image>>,
+    /// Custom WGSL hook to fill and shape discs, rectangles and regular polygons with, see [`ShapeShaderHooks`].
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub shader_hook: Option<ShapeShaderHook>,
+    /// Arbitrary values passed to shader hooks with each shape, such as the progress of an effect or a random seed.
+    ///
+    /// Currently supported by discs, rectangles and regular polygons.
+    pub user_data: Vec4,
+    /// Number of times the texture repeats across the shape on each axis.
+    ///
+    /// Repeating requires the image's sampler to use [`ImageAddressMode::Repeat`](bevy::render::texture::ImageAddressMode::Repeat),
+    /// currently supported by discs, rectangles and regular polygons.
+    pub texture_tiling: Vec2,
+    /// Offset added to the texture coordinates after tiling, in texture space where 1.0 is one full repeat of the texture.
+    ///
+    /// Currently supported by discs, rectangles and regular polygons.
+    pub texture_offset: Vec2,
     /// Set with set_2d, set_3d and set_canvas.
     pub pipeline: ShapePipelineType,
+    /// When set shapes drawn with [`ShapePainter`] continue to be drawn in the following frames, see [`Persistence`].
+    pub persistence: Option<Persistence>,
     /// Indicates whether or not the config will be reset after a system is run
     pub reset: bool,
 }
@@ -63,6 +177,13 @@ impl ShapeConfig {
         self.transform.rotation = rotation;
     }
 
+    /// Helper method to rotate the configs transform by a given [`Quat`] around a point in local space.
+    pub fn rotate_around(&mut self, point: Vec3, quat: Quat) {
+        self.translate(point);
+        self.rotate(quat);
+        self.translate(-point);
+    }
+
     /// Helper method to rotate the configs transform around the x axis.
     pub fn rotate_x(&mut self, angle: f32) {
         self.rotate(Quat::from_rotation_x(angle))
@@ -106,12 +227,182 @@ impl ShapeConfig {
         self.pipeline = ShapePipelineType::Shape2d;
     }
 
+    /// Helper method to set the configs transform such that positions and world thickness are measured in logical pixels
+    /// from the bottom left corner of the camera's viewport, with y pointing up.
+    ///
+    /// Shapes are drawn on a plane facing the camera [`SCREEN_SPACE_DEPTH`] in front of it,
+    /// the transform is left unchanged if the camera has no viewport.
+    pub fn set_screen_space(&mut self, camera: &Camera, camera_transform: &GlobalTransform) {
+        let Some(size) = camera.logical_viewport_size() else {
+            return;
+        };
+
+        let forward = camera_transform
+            .affine()
+            .transform_vector3(Vec3::NEG_Z)
+            .normalize();
+        let plane_origin = camera_transform.translation() + forward * SCREEN_SPACE_DEPTH;
+
+        // Viewport coordinates start from the top left so flip our y axis
+        let project = |pixel: Vec2| {
+            let ray =
+                camera.viewport_to_world(camera_transform, Vec2::new(pixel.x, size.y - pixel.y))?;
+            let distance = ray.intersect_plane(plane_origin, Plane3d::new(forward))?;
+            Some(ray.get_point(distance))
+        };
+
+        let (Some(origin), Some(x), Some(y)) =
+            (project(Vec2::ZERO), project(Vec2::X), project(Vec2::Y))
+        else {
+            return;
+        };
+
+        let x_axis = x - origin;
+        let y_axis = y - origin;
+        let z_axis = -forward * x_axis.length();
+        self.transform = Transform::from_matrix(Mat4::from_cols(
+            x_axis.extend(0.0),
+            y_axis.extend(0.0),
+            z_axis.extend(0.0),
+            origin.extend(1.0),
+        ));
+    }
+
+    /// Helper method to keep drawing immediate mode shapes for the given number of seconds after the current frame.
+    pub fn persist_for(&mut self, seconds: f32) {
+        self.persistence = Some(Persistence::Seconds(seconds));
+    }
+
+    /// Helper method to keep drawing immediate mode shapes for the given number of frames after the current frame.
+    pub fn persist_for_frames(&mut self, frames: u32) {
+        self.persistence = Some(Persistence::Frames(frames));
+    }
+
+    /// Helper method to set the configs texture and fit the given region of it to the shape,
+    /// `region` is in pixels from the top left corner of an image of size `image_size`.
+    pub fn set_texture_region(&mut self, image: Handle<Image>, region: Rect, image_size: Vec2) {
+        self.texture = Some(image);
+        self.texture_tiling = region.size() / image_size;
+        self.texture_offset = region.min / image_size;
+    }
+
+    /// Helper method to set the configs texture and fit the sprite at `index` in the given atlas layout to the shape.
+    ///
+    /// The texture is left unchanged if the layout has no sprite at `index`.
+    pub fn set_texture_atlas(
+        &mut self,
+        image: Handle<Image>,
+        layout: &TextureAtlasLayout,
+        index: usize,
+    ) {
+        if let Some(region) = layout.textures.get(index) {
+            self.set_texture_region(image, *region, layout.size);
+        }
+    }
+
+    /// Helper method to clip the following shapes to a rectangle of the given size centered on the configs transform.
+    pub fn mask_rect(&mut self, size: Vec2) {
+        self.clip = Some(ClipMask::rect(self.transform, size));
+    }
+
+    /// Helper method to clip the following shapes to a circle of the given radius centered on the configs transform.
+    pub fn mask_circle(&mut self, radius: f32) {
+        self.clip = Some(ClipMask::circle(self.transform, radius));
+    }
+
+    /// Helper method to clip the following shapes to a rectangle in pixels from the top left of the viewport or canvas.
+    ///
+    /// Clips are stacked, the new rectangle is intersected with any existing scissor rectangle.
+    /// Save and restore the config with [`ShapePainter::push`] and [`ShapePainter::pop`] to undo the clip.
+    pub fn clip_rect(&mut self, min: Vec2, max: Vec2) {
+        let rect = Rect::from_corners(min, max);
+        self.scissor = Some(match self.scissor {
+            Some(scissor) => scissor.intersect(rect),
+            None => rect,
+        });
+    }
+
+    /// Helper method to multiply the alpha of a color by the configs alpha and it's linear rgb by the configs emissive intensity.
+    pub fn fade(&self, color: Color) -> Color {
+        if self.emissive == 1.0 {
+            return color.with_a(color.a() * self.alpha);
+        }
+        let [r, g, b, a] = color.as_linear_rgba_f32();
+        Color::rgba_linear(
+            r * self.emissive,
+            g * self.emissive,
+            b * self.emissive,
+            a * self.alpha,
+        )
+    }
+
+    /// Helper method to get the configs color with the configs alpha and emissive intensity applied, see [`ShapeConfig::fade`].
+    pub fn faded_color(&self) -> Color {
+        self.fade(self.color)
+    }
+
+    /// Helper method to get the configs gradient with the configs alpha and emissive intensity applied to it's color.
+    pub fn faded_gradient(&self) -> Option<Gradient> {
+        self.gradient
+            .map(|gradient| gradient.with_color(self.fade(gradient.color())))
+    }
+
+    /// Helper method to get the outline of filled shapes with the configs alpha and emissive intensity applied to it's color,
+    /// along with the configs thickness type.
+    pub fn outline(&self) -> Option<(Stroke, ThicknessType)> {
+        if self.hollow {
+            return None;
+        }
+        self.stroke.map(|stroke| {
+            (
+                Stroke::new(self.fade(stroke.color), stroke.thickness),
+                self.thickness_type,
+            )
+        })
+    }
+
+    /// Helper method to get the shadow of filled shapes with the configs alpha and emissive intensity applied to it's color,
+    /// along with the configs thickness type.
+    pub fn drop_shadow(&self) -> Option<(Shadow, ThicknessType)> {
+        if self.hollow {
+            return None;
+        }
+        self.shadow.map(|shadow| {
+            (
+                Shadow::new(self.fade(shadow.color), shadow.offset, shadow.blur),
+                self.thickness_type,
+            )
+        })
+    }
+
+    /// Helper method to get the glow of filled shapes with the configs alpha and emissive intensity applied to it's color,
+    /// along with the configs thickness type.
+    pub fn halo(&self) -> Option<(Glow, ThicknessType)> {
+        if self.hollow {
+            return None;
+        }
+        self.glow.map(|glow| {
+            (
+                Glow::new(self.fade(glow.color), glow.radius, glow.falloff),
+                self.thickness_type,
+            )
+        })
+    }
+
     /// Helper method to clone the config without it's transform, useful when parenting.
     pub fn without_transform(&self) -> Self {
         let mut config = self.clone();
         config.transform = Transform::IDENTITY;
         config
     }
+
+    /// Helper method to clone the config without it's alpha, retained shapes are built from this as their alpha is
+    /// applied by the [`ShapeAlpha`] inserted alongside them.
+    pub fn without_alpha(&self) -> Self {
+        let mut config = self.clone();
+        config.alpha = 1.0;
+        config
+    }
 }
 
 impl ShapeConfig {
@@ -121,20 +412,52 @@ impl ShapeConfig {
             transform: default(),
 
             color: Color::GRAY,
+            alpha: 1.0,
+            emissive: 1.0,
             thickness: 0.1,
             thickness_type: default(),
+            thickness_end: None,
+            stroke_alignment: default(),
             alignment: default(),
+            constant_screen_size: false,
             hollow: false,
             cap: default(),
+            join: default(),
+            miter_limit: 4.0,
             roundness: default(),
+            anchor: default(),
             corner_radii: default(),
+            nine_slice: false,
+            arrow_head: default(),
+            arrow_head_size: 4.0,
+            dash_pattern: None,
+            gradient: None,
+            stroke: None,
+            shadow: None,
+            glow: None,
+            font_size: 1.0,
+            spline_subdivisions: 16,
 
             render_layers: None,
             alpha_mode: AlphaMode::Blend,
+            screen_blend: false,
+            oit: false,
             disable_laa: false,
+            aa_width: 1.0,
+            depth_bias: 0.0,
+            z_index: None,
+            always_on_top: false,
+            occluded_tint: None,
+            clip: None,
+            scissor: None,
             canvas: None,
             texture: None,
+            shader_hook: None,
+            user_data: Vec4::ZERO,
+            texture_tiling: Vec2::ONE,
+            texture_offset: Vec2::ZERO,
             pipeline: ShapePipelineType::Shape2d,
+            persistence: None,
             reset: true,
         }
     }
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+use crate::render::ShapeComponent;
+
+use super::ShapeStorage;
+
+/// Shapes drawn with a [`ShapePainter`](super::ShapePainter) during the last two runs of the fixed timestep schedules.
+///
+/// Shapes drawn in [`FixedUpdate`] are kept until the next fixed step rather than cleared each frame, so they neither
+/// disappear on frames without a fixed step nor are drawn twice on frames with several. Each frame the shapes of the
+/// latest step are drawn with their transforms interpolated from the matching shapes of the step before it by
+/// [`Time<Fixed>::overstep_fraction`], so they move smoothly at any frame rate at the cost of a step of latency.
+///
+/// Shapes are matched by the order they were drawn in for each shape type, draw the same shapes in the same order each
+/// step for them to be interpolated. Text and shapes drawn with a [`ParallelShapePainter`](super::ParallelShapePainter)
+/// are drawn for the current frame only.
+#[derive(Resource, Default)]
+pub struct FixedShapeStorage {
+    previous: ShapeStorage,
+    current: ShapeStorage,
+    /// Shapes of the variable rate frame, set aside while the fixed step is running.
+    frame: ShapeStorage,
+}
+
+/// Sets the shapes of the frame aside so that shapes drawn during the fixed step are kept separate.
+pub fn begin_fixed_shapes(mut storage: ResMut<ShapeStorage>, mut fixed: ResMut<FixedShapeStorage>) {
+    std::mem::swap(storage.as_mut(), &mut fixed.frame);
+}
+
+/// Keeps the shapes drawn during this fixed step and restores the shapes of the frame.
+pub fn end_fixed_shapes(mut storage: ResMut<ShapeStorage>, mut fixed: ResMut<FixedShapeStorage>) {
+    let frame = std::mem::take(&mut fixed.frame);
+    let mut step = std::mem::replace(storage.as_mut(), frame);
+    storage.append_persistent(&mut step);
+    fixed.previous = std::mem::replace(&mut fixed.current, step);
+}
+
+/// Draws the shapes of type `T` from the latest fixed step, interpolated towards the current frame.
+pub fn interpolate_fixed_shapes<T: ShapeComponent>(
+    mut storage: ResMut<ShapeStorage>,
+    fixed: Res<FixedShapeStorage>,
+    time: Res<Time<Fixed>>,
+) {
+    storage.interpolate::<T::Data>(&fixed.previous, &fixed.current, time.overstep_fraction());
+}
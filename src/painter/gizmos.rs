@@ -0,0 +1,228 @@
+use std::ops::{Deref, DerefMut};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::prelude::*;
+
+/// A system param mirroring Bevy's `Gizmos` API on top of a [`ShapePainter`].
+///
+/// Replacing `Gizmos` with `ShapeGizmos` in a system's parameters lets existing debug drawing code use the
+/// painter's thickness, caps, render layers and other [`ShapeConfig`] settings, which can be changed by
+/// dereferencing to the underlying painter, e.g. `gizmos.thickness = 2.0`.
+///
+/// Each call draws relative to the painter's transform, which is the identity by default so positions are in
+/// world space as with `Gizmos`. Circles, arcs and spheres are drawn with signed distance fields rather than
+/// line segments so [`ShapeGizmos::segments`] has no effect.
+#[derive(SystemParam)]
+pub struct ShapeGizmos<'w, 's> {
+    painter: ShapePainter<'w, 's>,
+}
+
+impl<'w, 's> ShapeGizmos<'w, 's> {
+    /// Draw a line in 3D from `start` to `end`.
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Color) -> &mut Self {
+        self.draw(color, |painter| {
+            painter.line(start, end);
+        })
+    }
+
+    /// Draw a line in 3D with a color gradient from `start_color` to `end_color`.
+    pub fn line_gradient(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        start_color: Color,
+        end_color: Color,
+    ) -> &mut Self {
+        self.painter
+            .line_gradient(start, end, start_color, end_color);
+        self
+    }
+
+    /// Draw a line in 3D from `start` to `start + vector`.
+    pub fn ray(&mut self, start: Vec3, vector: Vec3, color: Color) -> &mut Self {
+        self.line(start, start + vector, color)
+    }
+
+    /// Draw lines in 3D between each consecutive position.
+    pub fn linestrip(
+        &mut self,
+        positions: impl IntoIterator<Item = Vec3>,
+        color: Color,
+    ) -> &mut Self {
+        let positions: Vec<Vec3> = positions.into_iter().collect();
+        self.draw(color, |painter| {
+            for segment in positions.windows(2) {
+                painter.line(segment[0], segment[1]);
+            }
+        })
+    }
+
+    /// Draw a line in 2D from `start` to `end`.
+    pub fn line_2d(&mut self, start: Vec2, end: Vec2, color: Color) -> &mut Self {
+        self.line(start.extend(0.0), end.extend(0.0), color)
+    }
+
+    /// Draw a line in 2D with a color gradient from `start_color` to `end_color`.
+    pub fn line_gradient_2d(
+        &mut self,
+        start: Vec2,
+        end: Vec2,
+        start_color: Color,
+        end_color: Color,
+    ) -> &mut Self {
+        self.line_gradient(start.extend(0.0), end.extend(0.0), start_color, end_color)
+    }
+
+    /// Draw a line in 2D from `start` to `start + vector`.
+    pub fn ray_2d(&mut self, start: Vec2, vector: Vec2, color: Color) -> &mut Self {
+        self.line_2d(start, start + vector, color)
+    }
+
+    /// Draw a joined polyline in 2D through each position.
+    pub fn linestrip_2d(
+        &mut self,
+        positions: impl IntoIterator<Item = Vec2>,
+        color: Color,
+    ) -> &mut Self {
+        let positions: Vec<Vec3> = positions.into_iter().map(|p| p.extend(0.0)).collect();
+        self.draw(color, |painter| {
+            painter.polyline(&positions);
+        })
+    }
+
+    /// Draw a circle in 3D at `position` facing `normal`.
+    pub fn circle(
+        &mut self,
+        position: Vec3,
+        normal: Direction3d,
+        radius: f32,
+        color: Color,
+    ) -> &mut Self {
+        self.draw(color, |painter| {
+            painter.hollow = true;
+            painter.translate(position);
+            painter.circle_oriented(radius, *normal);
+        })
+    }
+
+    /// Draw a circle in 2D at `position`.
+    pub fn circle_2d(&mut self, position: Vec2, radius: f32, color: Color) -> &mut Self {
+        self.draw(color, |painter| {
+            painter.hollow = true;
+            painter.translate(position.extend(0.0));
+            painter.circle(radius);
+        })
+    }
+
+    /// Draw an arc in 2D centered on `direction_angle` and spanning `arc_angle`.
+    ///
+    /// Angles are in radians, an angle of 0 points up and angles increase clockwise.
+    pub fn arc_2d(
+        &mut self,
+        position: Vec2,
+        direction_angle: f32,
+        arc_angle: f32,
+        radius: f32,
+        color: Color,
+    ) -> &mut Self {
+        self.draw(color, |painter| {
+            painter.hollow = true;
+            painter.translate(position.extend(0.0));
+            painter.arc(
+                radius,
+                direction_angle - arc_angle / 2.0,
+                direction_angle + arc_angle / 2.0,
+            );
+        })
+    }
+
+    /// Draw a wireframe sphere in 3D as three orthogonal rings.
+    pub fn sphere(
+        &mut self,
+        position: Vec3,
+        rotation: Quat,
+        radius: f32,
+        color: Color,
+    ) -> &mut Self {
+        self.draw(color, |painter| {
+            painter.translate(position);
+            painter.rotate(rotation);
+            painter.wire_sphere(radius);
+        })
+    }
+
+    /// Draw the outline of a rectangle in 3D.
+    pub fn rect(&mut self, position: Vec3, rotation: Quat, size: Vec2, color: Color) -> &mut Self {
+        self.draw(color, |painter| {
+            painter.hollow = true;
+            painter.translate(position);
+            painter.rotate(rotation);
+            painter.rect(size);
+        })
+    }
+
+    /// Draw the outline of a rectangle in 2D rotated by `rotation` radians.
+    pub fn rect_2d(
+        &mut self,
+        position: Vec2,
+        rotation: f32,
+        size: Vec2,
+        color: Color,
+    ) -> &mut Self {
+        self.rect(
+            position.extend(0.0),
+            Quat::from_rotation_z(rotation),
+            size,
+            color,
+        )
+    }
+
+    /// Draw the edges of a unit cube transformed by `transform`.
+    pub fn cuboid(&mut self, transform: Transform, color: Color) -> &mut Self {
+        self.draw(color, |painter| {
+            painter.transform = painter.transform * transform;
+            painter.wire_box(Vec3::splat(0.5));
+        })
+    }
+
+    /// Draw an arrow in 3D from `start` to `end`.
+    pub fn arrow(&mut self, start: Vec3, end: Vec3, color: Color) -> &mut Self {
+        self.draw(color, |painter| {
+            painter.arrow(start, end);
+        })
+    }
+
+    /// Draw an arrow in 2D from `start` to `end`.
+    pub fn arrow_2d(&mut self, start: Vec2, end: Vec2, color: Color) -> &mut Self {
+        self.arrow(start.extend(0.0), end.extend(0.0), color)
+    }
+
+    /// Accepted for compatibility with the builders returned by `Gizmos`, shapes are not made of segments.
+    pub fn segments(&mut self, _segments: usize) -> &mut Self {
+        self
+    }
+
+    /// Draw with the given color, restoring the painter's config afterwards.
+    fn draw(&mut self, color: Color, draw: impl FnOnce(&mut ShapePainter)) -> &mut Self {
+        self.painter.with_children(|painter| {
+            painter.color = color;
+            draw(painter);
+        });
+        self
+    }
+}
+
+impl<'w, 's> Deref for ShapeGizmos<'w, 's> {
+    type Target = ShapePainter<'w, 's>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.painter
+    }
+}
+
+impl<'w, 's> DerefMut for ShapeGizmos<'w, 's> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.painter
+    }
+}
@@ -0,0 +1,197 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{prelude::*, render::ShapeComponent};
+
+/// Result of a successful hit test against a retained shape.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapeHit {
+    /// Distance along the ray to the hit.
+    pub distance: f32,
+    /// Position of the hit in world space.
+    pub position: Vec3,
+    /// Normal of the shape's plane facing back towards the ray.
+    pub normal: Vec3,
+}
+
+type HitTestQuery<'w, 's, T> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static T,
+        &'static ShapeFill,
+        &'static GlobalTransform,
+    ),
+>;
+
+#[cfg(feature = "ngon")]
+type NgonHitTestQuery<'w, 's> = HitTestQuery<'w, 's, RegularPolygonComponent>;
+// Stand in for the query when regular polygons are compiled out
+#[cfg(not(feature = "ngon"))]
+type NgonHitTestQuery<'w, 's> = ();
+
+/// A system param that hit tests retained shapes on the CPU using the same signed distance fields as the shaders.
+///
+/// Hollow shapes, arcs and rounded corners are only hit where they are drawn. Discs, rectangles, regular polygons
+/// and lines are supported, other shapes are never hit. Shapes are tested in the xy plane of their transform,
+/// billboarded shapes are tested as if they were flat.
+///
+/// Thickness using [`ThicknessType::Pixels`], [`ThicknessType::Screen`] or [`ThicknessType::ViewportHeight`] depends on the camera viewing the shape,
+/// it is only converted by [`ShapeHitTest::viewport_raycast`] and is otherwise treated as world units.
+#[derive(SystemParam)]
+pub struct ShapeHitTest<'w, 's> {
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+    discs: HitTestQuery<'w, 's, DiscComponent>,
+    rects: HitTestQuery<'w, 's, RectangleComponent>,
+    #[cfg_attr(not(feature = "ngon"), allow(dead_code))]
+    ngons: NgonHitTestQuery<'w, 's>,
+    lines: HitTestQuery<'w, 's, LineComponent>,
+}
+
+impl<'w, 's> ShapeHitTest<'w, 's> {
+    /// Returns whether a point in world space lies within the given shape entity.
+    ///
+    /// The point is projected onto the plane of the shape, returns false if the entity is not a supported shape.
+    pub fn point_in_shape(&self, entity: Entity, point: Vec3) -> bool {
+        let hit = point_in(&self.discs, entity, point)
+            .or_else(|| point_in(&self.rects, entity, point))
+            .or_else(|| point_in(&self.lines, entity, point));
+        #[cfg(feature = "ngon")]
+        let hit = hit.or_else(|| point_in(&self.ngons, entity, point));
+        hit.unwrap_or(false)
+    }
+
+    /// Returns the nearest shape hit by the ray.
+    pub fn raycast(&self, ray: Ray3d) -> Option<(Entity, ShapeHit)> {
+        self.raycast_all(ray).into_iter().next()
+    }
+
+    /// Returns every shape hit by the ray, sorted from nearest to furthest.
+    pub fn raycast_all(&self, ray: Ray3d) -> Vec<(Entity, ShapeHit)> {
+        self.cast(ray, None)
+    }
+
+    /// Returns the nearest shape under a position in the viewport of the given camera, such as the cursor position.
+    ///
+    /// Returns None if the entity is not a camera or the position cannot be converted to a ray.
+    pub fn viewport_raycast(
+        &self,
+        camera: Entity,
+        viewport_position: Vec2,
+    ) -> Option<(Entity, ShapeHit)> {
+        let (camera, camera_tf) = self.cameras.get(camera).ok()?;
+        let ray = camera.viewport_to_world(camera_tf, viewport_position)?;
+        self.cast(ray, Some((camera, camera_tf))).into_iter().next()
+    }
+
+    fn cast(
+        &self,
+        ray: Ray3d,
+        camera: Option<(&Camera, &GlobalTransform)>,
+    ) -> Vec<(Entity, ShapeHit)> {
+        let mut hits = Vec::new();
+        cast_all(&self.discs, ray, camera, &mut hits);
+        cast_all(&self.rects, ray, camera, &mut hits);
+        #[cfg(feature = "ngon")]
+        cast_all(&self.ngons, ray, camera, &mut hits);
+        cast_all(&self.lines, ray, camera, &mut hits);
+        hits.sort_by(|a, b| a.1.distance.total_cmp(&b.1.distance));
+        hits
+    }
+}
+
+fn point_in<T: ShapeComponent>(
+    query: &HitTestQuery<T>,
+    entity: Entity,
+    point: Vec3,
+) -> Option<bool> {
+    let (_, shape, fill, tf) = query.get(entity).ok()?;
+    let local = tf.affine().inverse().transform_point3(point).truncate();
+    Some(shape.contains(local, thickness(fill, tf, point, None)))
+}
+
+fn cast_all<T: ShapeComponent>(
+    query: &HitTestQuery<T>,
+    ray: Ray3d,
+    camera: Option<(&Camera, &GlobalTransform)>,
+    hits: &mut Vec<(Entity, ShapeHit)>,
+) {
+    for (entity, shape, fill, tf) in query {
+        if let Some(hit) = ray_hit(shape, fill, tf, ray, camera) {
+            hits.push((entity, hit));
+        }
+    }
+}
+
+/// Intersect a ray with the xy plane of a shape and test whether the intersection lies within it.
+///
+/// When `camera` is None thickness is treated as world units regardless of it's type.
+pub(crate) fn ray_hit<T: ShapeComponent>(
+    shape: &T,
+    fill: &ShapeFill,
+    tf: &GlobalTransform,
+    ray: Ray3d,
+    camera: Option<(&Camera, &GlobalTransform)>,
+) -> Option<ShapeHit> {
+    let normal = Vec3::from(tf.affine().matrix3.z_axis).normalize_or_zero();
+    let facing = ray.direction.dot(normal);
+    if facing.abs() <= f32::EPSILON {
+        return None;
+    }
+    let distance = (tf.translation() - ray.origin).dot(normal) / facing;
+    if distance < 0.0 {
+        return None;
+    }
+
+    let position = ray.get_point(distance);
+    let local = tf.affine().inverse().transform_point3(position).truncate();
+    shape
+        .contains(local, thickness(fill, tf, position, camera))
+        .then(|| ShapeHit {
+            distance,
+            position,
+            normal: if facing < 0.0 { normal } else { -normal },
+        })
+}
+
+/// Thickness of a hollow shape in the units of it's transform, matching the conversion performed by the shaders.
+fn thickness(
+    fill: &ShapeFill,
+    tf: &GlobalTransform,
+    position: Vec3,
+    camera: Option<(&Camera, &GlobalTransform)>,
+) -> Option<f32> {
+    let FillType::Stroke(thickness, thickness_type) = fill.ty else {
+        return None;
+    };
+    let converted = camera.and_then(|(camera, camera_tf)| {
+        pixel_thickness(camera, camera_tf, tf, position, thickness, thickness_type)
+    });
+    Some(converted.unwrap_or(thickness))
+}
+
+fn pixel_thickness(
+    camera: &Camera,
+    camera_tf: &GlobalTransform,
+    tf: &GlobalTransform,
+    position: Vec3,
+    thickness: f32,
+    thickness_type: ThicknessType,
+) -> Option<f32> {
+    let pixels = match thickness_type {
+        ThicknessType::World => return Some(thickness),
+        ThicknessType::Pixels => thickness,
+        ThicknessType::Screen => {
+            let size = camera.physical_viewport_size()?;
+            size.min_element() as f32 * thickness / 100.0
+        }
+        ThicknessType::ViewportHeight => camera.physical_viewport_size()?.y as f32 * thickness,
+    };
+
+    // Pixels per unit along the shape's y axis at the hit position, as in the shaders
+    let up = Vec3::from(tf.affine().matrix3.y_axis);
+    let a = camera.world_to_viewport(camera_tf, position)?;
+    let b = camera.world_to_viewport(camera_tf, position + up)?;
+    let scale_factor = camera.target_scaling_factor().unwrap_or(1.0);
+    Some(pixels / (a.distance(b) * scale_factor).max(f32::EPSILON))
+}
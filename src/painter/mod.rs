@@ -1,6 +1,11 @@
 use std::ops::DerefMut;
 
-use bevy::{prelude::*, render::camera::CameraUpdateSystem};
+use bevy::{
+    prelude::*,
+    render::{camera::CameraUpdateSystem, view::VisibilitySystems},
+};
+
+use crate::shapes::*;
 
 mod config;
 pub use config::*;
@@ -17,6 +22,38 @@ pub use shape_painter::*;
 mod canvas;
 pub use canvas::*;
 
+mod gizmos;
+pub use gizmos::*;
+
+mod hit_test;
+pub(crate) use hit_test::ray_hit;
+pub use hit_test::{ShapeHit, ShapeHitTest};
+
+mod bake;
+pub use bake::*;
+
+mod symbols;
+pub use symbols::*;
+
+mod parallel;
+pub use parallel::*;
+
+mod fixed;
+pub use fixed::*;
+
+mod screen_anchor;
+pub use screen_anchor::*;
+
+#[cfg(feature = "serialize")]
+mod sheet;
+#[cfg(feature = "serialize")]
+pub use sheet::*;
+
+#[cfg(feature = "svg")]
+mod svg;
+#[cfg(feature = "svg")]
+pub use svg::*;
+
 /// Trait that contains logic for spawning shape entities by type.
 ///
 /// Implemented by [`ShapeCommands`] and [`ShapeChildBuilder`].
@@ -26,7 +63,7 @@ pub trait ShapeSpawner<'w>: DerefMut<Target = ShapeConfig> {
     fn set_config(&mut self, config: ShapeConfig);
 
     /// Note: [`ShapeBundle`](crate::ShapeBundle) does not include [`RenderLayers`](bevy::render::view::RenderLayers) as there is no support for optional components
-    /// so instead it is inserted in this function conditionally depending on the [`ShapeConfig`] in `self`
+    /// so instead it is inserted in this function conditionally depending on the [`ShapeConfig`] in `self`, as is [`ShapeAlpha`]
     /// Prefer the function for the shape you want over [`ShapeSpawner::spawn_shape`], e.g. `commands.rect(...)`
     fn spawn_shape(&mut self, bundle: impl Bundle) -> ShapeEntityCommands;
 }
@@ -37,7 +74,86 @@ pub struct PainterPlugin;
 impl Plugin for PainterPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ShapeStorage>()
-            .add_systems(First, clear_storage)
-            .add_systems(PostUpdate, update_canvases.before(CameraUpdateSystem));
+            .init_resource::<ShapeSymbols>()
+            .init_resource::<ParallelShapeStorage>()
+            .init_resource::<FixedShapeStorage>()
+            .register_type::<ShapeLifetime>()
+            .register_type::<ShapeAlpha>()
+            .register_type::<BakedShape>()
+            .register_type::<ShapeSymbol>()
+            .register_type::<ScreenAnchor>()
+            .register_type::<ShapeFill>()
+            .register_type::<ShapeMaterial>()
+            .register_type::<Shape3d>()
+            .register_type::<ShapeConfig>()
+            .register_type::<Canvas>()
+            .register_type::<CanvasImage>()
+            .register_type::<CanvasMode>()
+            .register_type::<CanvasFit>()
+            .register_type::<Persistence>()
+            .register_type::<FillType>()
+            .register_type::<ThicknessType>()
+            .register_type::<Alignment>()
+            .register_type::<StrokeAlignment>()
+            .register_type::<Cap>()
+            .register_type::<Join>()
+            .register_type::<ArrowHead>()
+            .register_type::<WaveType>()
+            .register_type::<SplineType>()
+            .register_type::<DashPattern>()
+            .register_type::<Stroke>()
+            .register_type::<Shadow>()
+            .register_type::<Glow>()
+            .register_type::<Gradient>()
+            .register_type::<ClipMask>()
+            .add_systems(First, (clear_storage, despawn_expired_shapes))
+            .add_systems(Last, clear_parallel_shapes)
+            .add_systems(FixedFirst, begin_fixed_shapes)
+            .add_systems(FixedLast, end_fixed_shapes)
+            .add_systems(
+                PostUpdate,
+                update_screen_anchors.before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(
+                PostUpdate,
+                draw_shape_symbols
+                    .after(TransformSystem::TransformPropagate)
+                    .after(VisibilitySystems::VisibilityPropagate)
+                    .before(update_canvases),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    (fit_canvases, redraw_canvases_on_removal),
+                    update_canvases,
+                    update_canvas_sprites,
+                )
+                    .chain()
+                    .before(CameraUpdateSystem),
+            );
+
+        #[cfg(feature = "grid")]
+        app.register_type::<ReferenceGrid>();
+
+        #[cfg(feature = "text")]
+        app.init_resource::<TextStorage>()
+            .init_resource::<SdfFontAtlases>()
+            .add_systems(PostUpdate, draw_text);
+
+        #[cfg(feature = "bevy_ui")]
+        app.add_systems(
+            PostUpdate,
+            update_canvas_ui_images
+                .after(fit_canvases)
+                .before(bevy::ui::UiSystem::Layout),
+        );
+
+        #[cfg(feature = "serialize")]
+        app.init_asset::<ShapeSheet>()
+            .init_asset_loader::<ShapeSheetLoader>()
+            .add_systems(Update, spawn_shape_sheets);
+
+        #[cfg(feature = "svg")]
+        app.init_asset_loader::<SvgLoader>();
     }
 }
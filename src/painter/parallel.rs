@@ -0,0 +1,123 @@
+use std::ops::{Deref, DerefMut};
+
+use bevy::{ecs::system::SystemParam, prelude::*, utils::Parallel};
+
+use crate::{
+    prelude::*,
+    render::{ShapeComponent, ShapeData},
+};
+
+use super::ShapeStorage;
+
+/// Shapes sent by each thread through a [`ParallelShapePainter`] this frame.
+#[derive(Resource, Default)]
+pub struct ParallelShapeStorage(Parallel<ShapeStorage>);
+
+/// A system param for drawing immediate mode shapes from parallel iterators or from systems running concurrently.
+///
+/// Unlike [`ShapePainter`] this param only reads resources, so any number of systems using it may run in parallel.
+/// Shapes are sent to a queue owned by the thread drawing them, see [`ParallelShapePainter::scope`], and each queue
+/// is merged into the shapes of the frame in the [`Last`] schedule.
+///
+/// Text, paths and recordings are not supported, draw them with a [`ShapePainter`] instead.
+#[derive(SystemParam)]
+pub struct ParallelShapePainter<'w> {
+    storage: Res<'w, ParallelShapeStorage>,
+    default_config: Res<'w, BaseShapeConfig>,
+}
+
+impl<'w> ParallelShapePainter<'w> {
+    /// Run `draw` with a painter that sends shapes to the queue of the current thread.
+    ///
+    /// The painter starts with the config of the [`BaseShapeConfig`] resource each time this is called.
+    pub fn scope<R>(&self, draw: impl FnOnce(&mut ThreadShapePainter) -> R) -> R {
+        self.storage.0.scope(|shapes| {
+            let mut painter = ThreadShapePainter {
+                config: self.default_config.0.clone(),
+                shapes,
+            };
+            draw(&mut painter)
+        })
+    }
+}
+
+/// Painter sending shapes to the queue of a single thread, created by [`ParallelShapePainter::scope`].
+///
+/// Supports the same shape methods as [`ShapePainter`] through [`ShapeSender`].
+pub struct ThreadShapePainter<'a> {
+    config: ShapeConfig,
+    shapes: &'a mut ShapeStorage,
+}
+
+impl<'a> ThreadShapePainter<'a> {
+    pub fn set_config(&mut self, config: ShapeConfig) {
+        self.config = config;
+    }
+
+    /// Takes a closure which draws shapes with a copy of the painter's config, restoring it afterwards.
+    pub fn with_children(&mut self, draw: impl FnOnce(&mut ThreadShapePainter)) -> &mut Self {
+        let config = self.config.clone();
+        draw(self);
+        self.config = config;
+        self
+    }
+}
+
+impl<'a> ShapeSender for ThreadShapePainter<'a> {
+    fn config(&self) -> &ShapeConfig {
+        &self.config
+    }
+
+    fn send<T: ShapeData>(&mut self, data: T) -> &mut Self {
+        self.shapes.send(&self.config, data);
+        self
+    }
+
+    fn send_with_config<T: ShapeData>(&mut self, config: &ShapeConfig, data: T) -> &mut Self {
+        self.shapes.send(config, data);
+        self
+    }
+
+    fn send_many<T: ShapeData>(
+        &mut self,
+        data: T,
+        positions: impl IntoIterator<Item = Vec3>,
+    ) -> &mut Self {
+        self.shapes.send_many(&self.config, data, positions);
+        self
+    }
+}
+
+impl<'a> Deref for ThreadShapePainter<'a> {
+    type Target = ShapeConfig;
+
+    fn deref(&self) -> &Self::Target {
+        &self.config
+    }
+}
+
+impl<'a> DerefMut for ThreadShapePainter<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.config
+    }
+}
+
+/// Copies the shapes of type `T` sent by each thread this frame into the [`ShapeStorage`].
+pub fn merge_parallel_shapes<T: ShapeComponent>(
+    mut storage: ResMut<ShapeStorage>,
+    mut parallel: ResMut<ParallelShapeStorage>,
+) {
+    for shapes in parallel.0.iter_mut() {
+        storage.merge::<T::Data>(shapes);
+    }
+}
+
+/// Clears the queue of each thread once every shape type has been merged, keeping their persistent shapes.
+pub fn clear_parallel_shapes(
+    mut storage: ResMut<ShapeStorage>,
+    mut parallel: ResMut<ParallelShapeStorage>,
+) {
+    for shapes in parallel.0.iter_mut() {
+        storage.finish_merge(shapes);
+    }
+}
@@ -0,0 +1,107 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::prelude::*;
+
+/// Component that keeps a retained shape at a fixed position on screen relative to the viewport of a camera.
+///
+/// Each frame the entity's translation is moved to the point of the viewport at `anchor`, offset by `offset` logical
+/// pixels, keeping it's depth from the camera. This keeps HUD shapes in their corner of the screen as the window is
+/// resized or the camera moves. The translation is taken to be in world space, so anchored entities should not have a
+/// parent, to anchor shapes with [`ShapePainter`] see [`ShapeConfig::set_screen_anchor`].
+#[derive(Component, Reflect, Clone, Copy, Debug, Default)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScreenAnchor {
+    /// Point of the viewport the shape is anchored to.
+    #[cfg_attr(feature = "serialize", serde(with = "crate::serde_remote::AnchorDef"))]
+    pub anchor: Anchor,
+    /// Offset from the anchor in logical pixels, with y pointing up.
+    pub offset: Vec2,
+    /// Camera whose viewport the shape is anchored to, the first active camera if `None`.
+    pub camera: Option<Entity>,
+}
+
+impl ScreenAnchor {
+    pub fn new(anchor: Anchor, offset: Vec2) -> Self {
+        Self {
+            anchor,
+            offset,
+            camera: None,
+        }
+    }
+
+    pub fn with_camera(mut self, camera: Entity) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+}
+
+/// Returns the world position of the viewport point at `anchor` offset by `offset` logical pixels, at the same depth
+/// from the camera as `position`.
+///
+/// Returns `None` if the camera's viewport size is not yet known.
+pub fn screen_anchor_position(
+    (camera, camera_tf): (&Camera, &GlobalTransform),
+    anchor: Anchor,
+    offset: Vec2,
+    position: Vec3,
+) -> Option<Vec3> {
+    let size = camera.logical_viewport_size()?;
+    // Anchors go from -0.5 to 0.5 with y up while viewport coordinates go from the top left corner with y down
+    let fraction = anchor.as_vec() + Vec2::splat(0.5);
+    let point = Vec2::new(
+        fraction.x * size.x + offset.x,
+        (1.0 - fraction.y) * size.y - offset.y,
+    );
+
+    let ray = camera.viewport_to_world(camera_tf, point)?;
+    let forward = camera_tf.compute_transform().rotation * Vec3::NEG_Z;
+    let distance = (position - ray.origin).dot(forward) / ray.direction.dot(forward);
+    Some(ray.get_point(distance))
+}
+
+impl ShapeConfig {
+    /// Moves the translation of the config to the viewport point at `anchor` offset by `offset` logical pixels,
+    /// keeping it's depth from the camera.
+    ///
+    /// The translation is left unchanged if the camera's viewport size is not yet known.
+    pub fn set_screen_anchor(
+        &mut self,
+        camera: (&Camera, &GlobalTransform),
+        anchor: Anchor,
+        offset: Vec2,
+    ) {
+        if let Some(translation) =
+            screen_anchor_position(camera, anchor, offset, self.transform.translation)
+        {
+            self.transform.translation = translation;
+        }
+    }
+}
+
+pub fn update_screen_anchors(
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut anchored: Query<(&ScreenAnchor, &mut Transform)>,
+) {
+    let default_camera = cameras.iter().find(|(camera, _)| camera.is_active);
+    for (screen_anchor, mut tf) in &mut anchored {
+        let camera = match screen_anchor.camera {
+            Some(entity) => cameras.get(entity).ok(),
+            None => default_camera,
+        };
+        let Some(translation) = camera.and_then(|camera| {
+            screen_anchor_position(
+                camera,
+                screen_anchor.anchor,
+                screen_anchor.offset,
+                tf.translation,
+            )
+        }) else {
+            continue;
+        };
+        // Avoid triggering change detection every frame for shapes that haven't moved
+        if tf.translation != translation {
+            tf.translation = translation;
+        }
+    }
+}
@@ -36,6 +36,9 @@ impl<'w, 's> ShapeSpawner<'w> for ShapeCommands<'w, 's> {
         if let ShapePipelineType::Shape3d = config.pipeline {
             entity.insert(Shape3d);
         }
+        if config.alpha != 1.0 {
+            entity.insert(ShapeAlpha(config.alpha));
+        }
 
         ShapeEntityCommands {
             commands: entity,
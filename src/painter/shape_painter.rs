@@ -2,29 +2,271 @@ use std::{
     any::TypeId,
     ops::{Deref, DerefMut},
     slice::Iter,
+    sync::Arc,
+    time::Duration,
 };
 
-use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy::{
+    ecs::{
+        component::Tick,
+        system::{SystemChangeTick, SystemParam},
+    },
+    prelude::*,
+    utils::HashMap,
+};
 
 use any_vec::AnyVec;
+use smallvec::SmallVec;
 
 use crate::{
     prelude::*,
-    render::{ShapeData, ShapeInstance, ShapePipelineMaterial, ShapePipelineType},
+    render::{
+        ShapeComponent, ShapeData, ShapeInstance, ShapePipelineMaterial, ShapePipelineType,
+        SvgElement,
+    },
 };
 
+/// An immediate mode shape that is sent again each frame until it's [`Persistence`] runs out.
+struct PersistentShape {
+    remaining: Persistence,
+    send: Box<dyn Fn(&mut ShapeStorage) + Send + Sync>,
+}
+
+/// A shape captured by [`ShapePainter::record`].
+#[derive(Clone)]
+struct RecordedShape {
+    /// Sends the shape to storage with a base transform and tint.
+    send: Arc<dyn Fn(&mut ShapeStorage, Mat4, Color) + Send + Sync>,
+    /// Converts the shape to an SVG element, see [`ShapeRecording::to_svg`].
+    svg: Arc<dyn Fn() -> Option<SvgElement> + Send + Sync>,
+}
+
+/// A reusable list of shapes recorded with [`ShapePainter::record`] that can be drawn any number of times
+/// with [`ShapePainter::replay`].
+///
+/// Each shape keeps the config it was drawn with, with it's transform relative to the painter's transform.
+#[derive(Clone, Default)]
+pub struct ShapeRecording {
+    shapes: Vec<RecordedShape>,
+}
+
+impl ShapeRecording {
+    /// Number of shape instances in the recording.
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    /// Send each shape in the recording to `storage` relative to `transform` with it's colors multiplied by `tint`.
+    pub(crate) fn send(&self, storage: &mut ShapeStorage, transform: Mat4, tint: Color) {
+        for shape in &self.shapes {
+            (shape.send)(storage, transform, tint);
+        }
+    }
+
+    /// Export the recording as an SVG document showing the given rectangle of the recording's xy plane.
+    ///
+    /// Recorded discs, arcs, rectangles and lines are exported in the order they were drawn with their solid colors,
+    /// gradients, outlines, textures and effects such as shadows are left out. Thickness is exported in world units
+    /// regardless of [`ThicknessType`] and shapes in 3D are projected onto the xy plane.
+    pub fn to_svg(&self, view: Rect) -> String {
+        let size = view.size();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">\n",
+            size.x, size.y, view.min.x, -view.max.y, size.x, size.y
+        );
+        // Shapes are in y up coordinates so flip them to match the y down coordinates of the document
+        svg.push_str("<g transform=\"scale(1 -1)\">\n");
+        for element in self.shapes.iter().filter_map(|shape| (shape.svg)()) {
+            svg.push_str(&element.to_string());
+            svg.push('\n');
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+}
+
 /// A system param for type erased storage of [`ShapeInstance`].
 ///
 /// Generally should only be consumed as part of [`ShapePainter`] and not used directly.
 #[derive(Resource, Default)]
 pub struct ShapeStorage {
     shapes: HashMap<(TypeId, ShapePipelineType), AnyVec<dyn Send + Sync>>,
+    persistent: Vec<PersistentShape>,
+    recording: Option<Vec<RecordedShape>>,
 }
 
 impl ShapeStorage {
-    fn send<T: ShapeData>(&mut self, config: &ShapeConfig, data: T) {
+    pub(crate) fn send<T: ShapeData>(&mut self, config: &ShapeConfig, data: T) {
         let key = (TypeId::of::<T>(), config.pipeline);
-        let entry = (ShapePipelineMaterial::from(config), data);
+        for entry in Self::instances(config, data) {
+            self.send_instance(config, key, entry);
+        }
+    }
+
+    /// Send a copy of the shape at each position, relative to the config's transform.
+    ///
+    /// The instances of the shape and it's effects are built once and only their transforms change for each copy.
+    pub(crate) fn send_many<T: ShapeData>(
+        &mut self,
+        config: &ShapeConfig,
+        data: T,
+        positions: impl IntoIterator<Item = Vec3>,
+    ) {
+        let key = (TypeId::of::<T>(), config.pipeline);
+        let instances = Self::instances(config, data);
+        let base = config.transform.compute_matrix();
+        for position in positions {
+            let offset = Mat4::from_translation(base.transform_vector3(position));
+            for (material, data) in &instances {
+                let mut data = data.clone();
+                data.set_transform(offset * data.transform());
+                self.send_instance(config, key, (material.clone(), data));
+            }
+        }
+    }
+
+    /// Copy the instances of type `T` sent to `other` into this storage, see [`ParallelShapePainter`].
+    pub(crate) fn merge<T: ShapeData>(&mut self, other: &ShapeStorage) {
+        for pipeline in [ShapePipelineType::Shape2d, ShapePipelineType::Shape3d] {
+            let Some(iter) = other.get::<T>(pipeline) else {
+                continue;
+            };
+            let key = (TypeId::of::<T>(), pipeline);
+            for entry in iter {
+                self.push(key, entry.clone());
+            }
+        }
+    }
+
+    /// Take the persistent shapes of `other` and clear it's instances once every shape type has been merged.
+    pub(crate) fn finish_merge(&mut self, other: &mut ShapeStorage) {
+        self.append_persistent(other);
+        other.shapes = HashMap::new();
+    }
+
+    /// Take the persistent shapes of `other` so they are sent again each frame by this storage.
+    pub(crate) fn append_persistent(&mut self, other: &mut ShapeStorage) {
+        self.persistent.append(&mut other.persistent);
+    }
+
+    /// Send each instance of type `T` in `current`, with it's transform interpolated from the instance at the same
+    /// index in `previous` by `t`, see [`FixedShapeStorage`].
+    ///
+    /// Instances without a counterpart in `previous` are sent as they are.
+    pub(crate) fn interpolate<T: ShapeData>(
+        &mut self,
+        previous: &ShapeStorage,
+        current: &ShapeStorage,
+        t: f32,
+    ) {
+        for pipeline in [ShapePipelineType::Shape2d, ShapePipelineType::Shape3d] {
+            let Some(iter) = current.get::<T>(pipeline) else {
+                continue;
+            };
+            let key = (TypeId::of::<T>(), pipeline);
+            let mut previous = previous.get::<T>(pipeline).into_iter().flatten();
+            for (material, data) in iter {
+                let mut data = data.clone();
+                if let Some((_, from)) = previous.next() {
+                    data.set_transform(interpolate_transform(
+                        from.transform(),
+                        data.transform(),
+                        t,
+                    ));
+                }
+                self.push(key, (material.clone(), data));
+            }
+        }
+    }
+
+    /// Instances drawn for a shape in the order they are drawn in: it's shadow and glow, the copy drawn where it is
+    /// occluded and the shape itself.
+    fn instances<T: ShapeData>(
+        config: &ShapeConfig,
+        mut data: T,
+    ) -> SmallVec<[ShapeInstance<T>; 4]> {
+        let mut instances = SmallVec::new();
+        if config.constant_screen_size {
+            data.set_constant_screen_size(true);
+        }
+        let material = ShapePipelineMaterial::from(config);
+
+        // Draw the shadow of filled shapes beneath them
+        if let Some(shadow) = config
+            .drop_shadow()
+            .and_then(|(shadow, thickness_type)| data.shadow(&shadow, thickness_type))
+        {
+            instances.push((material.underlay(), shadow));
+        }
+
+        // Glows are also drawn beneath the shape, fading out from it's edge
+        if let Some(glow) = config
+            .halo()
+            .and_then(|(glow, thickness_type)| data.glow(&glow, thickness_type))
+        {
+            instances.push((material.underlay(), glow));
+        }
+
+        // Draw 3D shapes again where they are hidden behind other geometry
+        if let (Some(tint), ShapePipelineType::Shape3d, false) =
+            (config.occluded_tint, config.pipeline, config.always_on_top)
+        {
+            let mut data = data.clone();
+            data.tint(tint);
+            instances.push((material.occluded(), data));
+        }
+
+        instances.push((material, data));
+        instances
+    }
+
+    fn send_instance<T: ShapeData>(
+        &mut self,
+        config: &ShapeConfig,
+        key: (TypeId, ShapePipelineType),
+        entry: ShapeInstance<T>,
+    ) {
+        if let (Some(remaining), None) = (config.persistence, &self.recording) {
+            let entry = entry.clone();
+            self.persistent.push(PersistentShape {
+                remaining,
+                send: Box::new(move |storage| storage.push(key, entry.clone())),
+            });
+        }
+
+        self.push(key, entry);
+    }
+
+    pub(crate) fn push<T: ShapeData>(
+        &mut self,
+        key: (TypeId, ShapePipelineType),
+        entry: ShapeInstance<T>,
+    ) {
+        if let Some(recording) = &mut self.recording {
+            let (material, data) = entry.clone();
+            recording.push(RecordedShape {
+                send: Arc::new(move |storage, transform, tint| {
+                    let (material, mut data) = entry.clone();
+                    data.set_transform(transform * data.transform());
+                    data.tint(tint);
+                    storage.push(key, (material, data));
+                }),
+                // Only the shape itself is exported, not the copy drawn where it is occluded
+                svg: Arc::new(move || {
+                    if material.is_occluded() {
+                        None
+                    } else {
+                        data.svg()
+                    }
+                }),
+            });
+            return;
+        }
+
         let vec = self
             .shapes
             .entry(key)
@@ -46,16 +288,55 @@ impl ShapeStorage {
             .map(|vec| unsafe { vec.downcast_ref_unchecked::<ShapeInstance<T>>().iter() })
     }
 
-    fn clear(&mut self) {
+    fn clear(&mut self, delta: Duration) {
         self.shapes = HashMap::new();
+
+        // Send any persistent shapes again, dropping those that have run out of time
+        let mut persistent = std::mem::take(&mut self.persistent);
+        persistent.retain_mut(|shape| {
+            // Frames count the redraws remaining, seconds count down the time since the shape was drawn
+            let alive = match &mut shape.remaining {
+                Persistence::Frames(frames) => {
+                    let alive = *frames > 0;
+                    *frames = frames.saturating_sub(1);
+                    alive
+                }
+                Persistence::Seconds(seconds) => {
+                    *seconds -= delta.as_secs_f32();
+                    *seconds > 0.0
+                }
+            };
+            if alive {
+                (shape.send)(self);
+            }
+            alive
+        });
+        self.persistent = persistent;
     }
 }
 
-/// Clears the [`ShapeStorage`] resource each frame.
-pub fn clear_storage(mut storage: ResMut<ShapeStorage>) {
-    storage.clear();
+/// Interpolate the translation, rotation and scale of two transforms.
+fn interpolate_transform(from: Mat4, to: Mat4, t: f32) -> Mat4 {
+    let (from_scale, from_rotation, from_translation) = from.to_scale_rotation_translation();
+    let (to_scale, to_rotation, to_translation) = to.to_scale_rotation_translation();
+    Mat4::from_scale_rotation_translation(
+        from_scale.lerp(to_scale, t),
+        from_rotation.slerp(to_rotation, t),
+        from_translation.lerp(to_translation, t),
+    )
 }
 
+/// Clears the [`ShapeStorage`] resource each frame, keeping only shapes that are still persisting.
+pub fn clear_storage(mut storage: ResMut<ShapeStorage>, time: Res<Time>) {
+    storage.clear(time.delta());
+}
+
+#[cfg(feature = "text")]
+type TextStorageParam<'w> = ResMut<'w, TextStorage>;
+// Stand in for the text queue when text is compiled out
+#[cfg(not(feature = "text"))]
+type TextStorageParam<'w> = ();
+
 /// A system param that allows ergonomic drawing of immediate mode shapes.
 ///
 /// The [`ShapeConfig`] used is initially extracted from the [`BaseShapeConfig`] resource.
@@ -66,7 +347,13 @@ pub fn clear_storage(mut storage: ResMut<ShapeStorage>) {
 pub struct ShapePainter<'w, 's> {
     config: &'s mut ShapeConfig,
     shapes: ResMut<'w, ShapeStorage>,
+    #[cfg_attr(not(feature = "text"), allow(dead_code))]
+    texts: TextStorageParam<'w>,
     default_config: Res<'w, BaseShapeConfig>,
+    symbols: Res<'w, ShapeSymbols>,
+    /// Configs saved with [`ShapePainter::push`] along with the run of the system they were saved in.
+    stack: Local<'s, (Option<Tick>, Vec<ShapeConfig>)>,
+    ticks: SystemChangeTick,
 }
 
 impl<'w, 's> ShapePainter<'w, 's> {
@@ -88,11 +375,43 @@ impl<'w, 's> ShapePainter<'w, 's> {
         self
     }
 
+    /// Send a copy of `data` at each position relative to the painter's transform, sharing the current config.
+    ///
+    /// Cheaper than moving the painter and sending each copy separately when drawing large numbers of the same shape.
+    pub fn send_many<T: ShapeData>(
+        &mut self,
+        data: T,
+        positions: impl IntoIterator<Item = Vec3>,
+    ) -> &mut Self {
+        let Self { config, shapes, .. } = self;
+        shapes.send_many(config, data, positions);
+        self
+    }
+
     pub fn send_with_config<T: ShapeData>(&mut self, config: &ShapeConfig, data: T) -> &mut Self {
         self.shapes.send(config, data);
         self
     }
 
+    /// Draw the shape `t` of the way from `from` to `to`, where 0.0 is `from` and 1.0 is `to`, see [`Morph`].
+    ///
+    /// The shape is drawn with the transform, color and thickness of the current config rather than those of `from` and `to`.
+    pub fn morph<T: Morph + ShapeComponent>(&mut self, from: &T, to: &T, t: f32) -> &mut Self {
+        let Self { config, shapes, .. } = self;
+        let tf = GlobalTransform::from(config.transform);
+        let fill = ShapeFill::new(config);
+        from.morph(to, t)
+            .get_instances(&tf, &fill, |data| shapes.send(config, data));
+        self
+    }
+
+    /// Queue text to be laid out and drawn with the current config, see [`TextPainter`].
+    #[cfg(feature = "text")]
+    pub fn send_text(&mut self, text: &str, font: &Handle<Font>) -> &mut Self {
+        self.texts.send(self.config, text, font);
+        self
+    }
+
     /// Takes a closure which builds children for this shape.
     ///
     /// While event based shapes don't have the parent child relationship that entities have,
@@ -104,9 +423,165 @@ impl<'w, 's> ShapePainter<'w, 's> {
         self
     }
 
+    /// Takes a closure which draws shapes translated by `dir`, see [`ShapeConfig::translate`].
+    ///
+    /// The painter's config is restored afterwards as with [`ShapePainter::with_children`].
+    pub fn with_translation(
+        &mut self,
+        dir: Vec3,
+        draw: impl FnOnce(&mut ShapePainter),
+    ) -> &mut Self {
+        self.with_children(|painter| {
+            painter.translate(dir);
+            draw(painter);
+        })
+    }
+
+    /// Takes a closure which draws shapes rotated by `quat`, see [`ShapeConfig::rotate`].
+    ///
+    /// The painter's config is restored afterwards as with [`ShapePainter::with_children`].
+    pub fn with_rotation(&mut self, quat: Quat, draw: impl FnOnce(&mut ShapePainter)) -> &mut Self {
+        self.with_children(|painter| {
+            painter.rotate(quat);
+            draw(painter);
+        })
+    }
+
+    /// Takes a closure which draws shapes rotated by `quat` around `point`, see [`ShapeConfig::rotate_around`].
+    ///
+    /// The painter's config is restored afterwards as with [`ShapePainter::with_children`].
+    pub fn with_rotation_around(
+        &mut self,
+        point: Vec3,
+        quat: Quat,
+        draw: impl FnOnce(&mut ShapePainter),
+    ) -> &mut Self {
+        self.with_children(|painter| {
+            painter.rotate_around(point, quat);
+            draw(painter);
+        })
+    }
+
+    /// Takes a closure which draws shapes scaled by `scale`, see [`ShapeConfig::scale`].
+    ///
+    /// The painter's config is restored afterwards as with [`ShapePainter::with_children`].
+    pub fn with_scale(&mut self, scale: Vec3, draw: impl FnOnce(&mut ShapePainter)) -> &mut Self {
+        self.with_children(|painter| {
+            painter.scale(scale);
+            draw(painter);
+        })
+    }
+
+    /// Takes a closure which draws shapes to be recorded rather than drawn, see [`ShapeRecording`].
+    ///
+    /// Shapes are recorded relative to the painter's transform, text is drawn immediately and not recorded.
+    pub fn record(&mut self, draw: impl FnOnce(&mut ShapePainter)) -> ShapeRecording {
+        let previous = self.shapes.recording.replace(Vec::new());
+        self.with_children(|painter| {
+            painter.transform = Transform::IDENTITY;
+            draw(painter);
+        });
+        let shapes = std::mem::replace(&mut self.shapes.recording, previous).unwrap_or_default();
+        ShapeRecording { shapes }
+    }
+
+    /// Draw each shape in the recording relative to the painter's transform.
+    pub fn replay(&mut self, recording: &ShapeRecording) -> &mut Self {
+        self.replay_tinted(recording, Color::WHITE)
+    }
+
+    /// Draw each shape in the recording relative to the painter's transform with it's colors multiplied by `tint`.
+    ///
+    /// The painter's alpha is also applied to the tint, see [`ShapeConfig::alpha`].
+    pub fn replay_tinted(&mut self, recording: &ShapeRecording, tint: Color) -> &mut Self {
+        let transform = self.config.transform.compute_matrix();
+        let tint = self.config.fade(tint);
+        recording.send(&mut self.shapes, transform, tint);
+        self
+    }
+
+    /// Draw the symbol `name` from [`ShapeSymbols`] relative to the painter's transform, see [`ShapePainter::replay`].
+    ///
+    /// Does nothing if no symbol of that name has been defined.
+    pub fn stamp(&mut self, name: &str) -> &mut Self {
+        self.stamp_tinted(name, Color::WHITE)
+    }
+
+    /// Draw the symbol `name` from [`ShapeSymbols`] with it's colors multiplied by `tint`, see [`ShapePainter::replay_tinted`].
+    pub fn stamp_tinted(&mut self, name: &str, tint: Color) -> &mut Self {
+        let Self {
+            config,
+            shapes,
+            symbols,
+            ..
+        } = self;
+        if let Some(recording) = symbols.get(name) {
+            recording.send(shapes, config.transform.compute_matrix(), config.fade(tint));
+        }
+        self
+    }
+
+    /// Save a copy of the painter's [`ShapeConfig`] to be restored by a matching call to [`ShapePainter::pop`].
+    ///
+    /// Pushes may be nested, see [`ShapePainter::with_children`] for a closure based alternative.
+    pub fn push(&mut self) -> &mut Self {
+        let config = self.config.clone();
+        self.stack().push(config);
+        self
+    }
+
+    /// Restore the [`ShapeConfig`] saved by the most recent call to [`ShapePainter::push`].
+    ///
+    /// Does nothing if there are no saved configs.
+    pub fn pop(&mut self) -> &mut Self {
+        if let Some(config) = self.stack().pop() {
+            *self.config = config;
+        }
+        self
+    }
+
     /// Set the painter's [`ShapeConfig`] to the current value of the [`BaseShapeConfig`] resource.
+    ///
+    /// Also discards any configs saved with [`ShapePainter::push`].
     pub fn reset(&mut self) {
         *self.config = self.default_config.0.clone();
+        self.stack.1.clear();
+    }
+
+    /// Configs saved with [`ShapePainter::push`], discarding those saved in an earlier run of the system when the
+    /// config has since been reset to the [`BaseShapeConfig`].
+    fn stack(&mut self) -> &mut Vec<ShapeConfig> {
+        let this_run = self.ticks.this_run();
+        let (run, stack) = &mut *self.stack;
+        if *run != Some(this_run) {
+            if self.config.reset {
+                stack.clear();
+            }
+            *run = Some(this_run);
+        }
+        stack
+    }
+}
+
+impl<'w, 's> ShapeSender for ShapePainter<'w, 's> {
+    fn config(&self) -> &ShapeConfig {
+        ShapePainter::config(self)
+    }
+
+    fn send<T: ShapeData>(&mut self, data: T) -> &mut Self {
+        ShapePainter::send(self, data)
+    }
+
+    fn send_with_config<T: ShapeData>(&mut self, config: &ShapeConfig, data: T) -> &mut Self {
+        ShapePainter::send_with_config(self, config, data)
+    }
+
+    fn send_many<T: ShapeData>(
+        &mut self,
+        data: T,
+        positions: impl IntoIterator<Item = Vec3>,
+    ) -> &mut Self {
+        ShapePainter::send_many(self, data, positions)
     }
 }
 
@@ -123,3 +598,89 @@ impl<'w, 's> DerefMut for ShapePainter<'w, 's> {
         self.config
     }
 }
+
+/// Trait implemented by painters that draw immediate mode shapes, [`ShapePainter`] and [`ThreadShapePainter`].
+///
+/// Painter traits such as [`DiscPainter`] are implemented for every type implementing this trait.
+pub trait ShapeSender: DerefMut<Target = ShapeConfig> {
+    fn config(&self) -> &ShapeConfig;
+
+    fn send<T: ShapeData>(&mut self, data: T) -> &mut Self;
+
+    fn send_with_config<T: ShapeData>(&mut self, config: &ShapeConfig, data: T) -> &mut Self;
+
+    /// Send a copy of `data` at each position relative to the painter's transform, see [`ShapePainter::send_many`].
+    fn send_many<T: ShapeData>(
+        &mut self,
+        data: T,
+        positions: impl IntoIterator<Item = Vec3>,
+    ) -> &mut Self;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unbalanced_push() {
+        let mut world = World::new();
+        world.insert_resource(BaseShapeConfig(ShapeConfig::default_2d()));
+        world.init_resource::<ShapeStorage>();
+        #[cfg(feature = "text")]
+        world.init_resource::<TextStorage>();
+        world.init_resource::<ShapeSymbols>();
+
+        // Push without popping in the first run, the pop in the second run has nothing to restore
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            |mut painter: ShapePainter, base: Res<BaseShapeConfig>, mut run: Local<u32>| {
+                if *run == 0 {
+                    painter.color = Color::RED;
+                    painter.push();
+                } else {
+                    painter.pop();
+                    assert_eq!(painter.color, base.0.color);
+                }
+                *run += 1;
+            },
+        );
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+    }
+
+    fn line_count(storage: &ShapeStorage) -> usize {
+        storage
+            .get::<LineData>(ShapePipelineType::Shape2d)
+            .map_or(0, |iter| iter.len())
+    }
+
+    #[test]
+    fn persist_for_frames() {
+        let mut storage = ShapeStorage::default();
+        let mut config = ShapeConfig::default_2d();
+        config.persist_for_frames(2);
+        storage.send(&config, LineData::new(&config, Vec3::ZERO, Vec3::X));
+        assert_eq!(line_count(&storage), 1);
+
+        // Drawn again in each of the two frames after the one it was drawn in, then gone in the third
+        for _ in 0..2 {
+            storage.clear(Duration::ZERO);
+            assert_eq!(line_count(&storage), 1);
+        }
+        storage.clear(Duration::ZERO);
+        assert_eq!(line_count(&storage), 0);
+    }
+
+    #[test]
+    fn persist_for_seconds() {
+        let mut storage = ShapeStorage::default();
+        let mut config = ShapeConfig::default_2d();
+        config.persist_for(0.5);
+        storage.send(&config, LineData::new(&config, Vec3::ZERO, Vec3::X));
+
+        storage.clear(Duration::from_secs_f32(0.3));
+        assert_eq!(line_count(&storage), 1);
+        storage.clear(Duration::from_secs_f32(0.3));
+        assert_eq!(line_count(&storage), 0);
+    }
+}
@@ -0,0 +1,412 @@
+use std::fmt;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::{BoxedFuture, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// A named hierarchy of shapes loaded from a `.shapes.ron` file, requires the `serialize` feature.
+///
+/// Spawn it with a [`ShapeSheetBundle`], the shapes are respawned as children of the bundle's entity
+/// whenever the file changes, enable bevy's `file_watcher` feature to hot reload sheets as they are edited.
+///
+/// ```ron
+/// (
+///     shapes: [
+///         (
+///             name: Some("health"),
+///             translation: (0.0, -200.0, 0.0),
+///             style: (color: Some(Rgba(red: 1.0, green: 0.2, blue: 0.2, alpha: 1.0)), roundness: Some(4.0)),
+///             shape: Some(Rect((300.0, 20.0))),
+///             children: [(shape: Some(Circle(8.0)), translation: (-170.0, 0.0, 0.0))],
+///         ),
+///     ],
+/// )
+/// ```
+#[derive(Asset, TypePath, Clone, Default, Serialize, Deserialize)]
+pub struct ShapeSheet {
+    /// Top level nodes of the sheet, spawned with the [`BaseShapeConfig`].
+    #[serde(default)]
+    pub shapes: Vec<SheetNode>,
+}
+
+/// A single node of a [`ShapeSheet`], an empty node without a shape can be used to group and position it's children.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SheetNode {
+    /// Inserted as a [`Name`] on the spawned entity.
+    pub name: Option<String>,
+    pub translation: Vec3,
+    /// Rotation around the z axis in radians.
+    pub rotation: f32,
+    pub scale: Vec3,
+    /// Changes to the style of this node, inherited by it's children.
+    pub style: SheetStyle,
+    pub shape: Option<SheetShape>,
+    pub children: Vec<SheetNode>,
+}
+
+impl Default for SheetNode {
+    fn default() -> Self {
+        Self {
+            name: None,
+            translation: Vec3::ZERO,
+            rotation: 0.0,
+            scale: Vec3::ONE,
+            style: default(),
+            shape: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl SheetNode {
+    /// Transform of the node relative to it's parent.
+    pub fn transform(&self) -> Transform {
+        Transform::from_translation(self.translation)
+            .with_rotation(Quat::from_rotation_z(self.rotation))
+            .with_scale(self.scale)
+    }
+}
+
+/// Overrides applied to the [`ShapeConfig`] of a [`SheetNode`], fields that are left out are inherited from the parent.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SheetStyle {
+    pub color: Option<Color>,
+    pub alpha: Option<f32>,
+    pub emissive: Option<f32>,
+    pub hollow: Option<bool>,
+    pub thickness: Option<f32>,
+    pub thickness_type: Option<ThicknessType>,
+    pub stroke_alignment: Option<StrokeAlignment>,
+    pub cap: Option<Cap>,
+    pub join: Option<Join>,
+    pub roundness: Option<f32>,
+    pub corner_radii: Option<Vec4>,
+    pub nine_slice: Option<bool>,
+    pub arrow_head: Option<ArrowHead>,
+    pub dash_pattern: Option<DashPattern>,
+    pub gradient: Option<Gradient>,
+    pub stroke: Option<Stroke>,
+    pub shadow: Option<Shadow>,
+    pub glow: Option<Glow>,
+    pub z_index: Option<f32>,
+}
+
+impl SheetStyle {
+    /// Apply each field that is set to the given config.
+    pub fn apply(&self, config: &mut ShapeConfig) {
+        if let Some(color) = self.color {
+            config.color = color;
+        }
+        if let Some(alpha) = self.alpha {
+            config.alpha = alpha;
+        }
+        if let Some(emissive) = self.emissive {
+            config.emissive = emissive;
+        }
+        if let Some(hollow) = self.hollow {
+            config.hollow = hollow;
+        }
+        if let Some(thickness) = self.thickness {
+            config.thickness = thickness;
+        }
+        if let Some(thickness_type) = self.thickness_type {
+            config.thickness_type = thickness_type;
+        }
+        if let Some(stroke_alignment) = self.stroke_alignment {
+            config.stroke_alignment = stroke_alignment;
+        }
+        if let Some(cap) = self.cap {
+            config.cap = cap;
+        }
+        if let Some(join) = self.join {
+            config.join = join;
+        }
+        if let Some(roundness) = self.roundness {
+            config.roundness = roundness;
+        }
+        if let Some(corner_radii) = self.corner_radii {
+            config.corner_radii = corner_radii;
+        }
+        if let Some(nine_slice) = self.nine_slice {
+            config.nine_slice = nine_slice;
+        }
+        if let Some(arrow_head) = self.arrow_head {
+            config.arrow_head = arrow_head;
+        }
+        if self.dash_pattern.is_some() {
+            config.dash_pattern = self.dash_pattern;
+        }
+        if self.gradient.is_some() {
+            config.gradient = self.gradient;
+        }
+        if self.stroke.is_some() {
+            config.stroke = self.stroke;
+        }
+        if self.shadow.is_some() {
+            config.shadow = self.shadow;
+        }
+        if self.glow.is_some() {
+            config.glow = self.glow;
+        }
+        if self.z_index.is_some() {
+            config.z_index = self.z_index;
+        }
+    }
+}
+
+/// Shape drawn by a [`SheetNode`], each variant spawns the shape of the same name with [`ShapeCommands`].
+#[derive(Clone, Serialize, Deserialize)]
+pub enum SheetShape {
+    Rect(Vec2),
+    Circle(f32),
+    Arc {
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    },
+    #[cfg(feature = "ellipse")]
+    Ellipse(Vec2),
+    #[cfg(feature = "ngon")]
+    Ngon {
+        sides: f32,
+        radius: f32,
+    },
+    #[cfg(feature = "star")]
+    Star {
+        points: f32,
+        inner_radius: f32,
+        outer_radius: f32,
+    },
+    #[cfg(feature = "superellipse")]
+    Superellipse {
+        size: Vec2,
+        exponent: f32,
+    },
+    Capsule {
+        start: Vec2,
+        end: Vec2,
+        radius: f32,
+    },
+    #[cfg(feature = "triangle")]
+    Triangle(Vec2, Vec2, Vec2),
+    Line(Vec3, Vec3),
+    Arrow(Vec3, Vec3),
+    Polyline(Vec<Vec3>),
+    ClosedPolyline(Vec<Vec3>),
+    Polygon(Vec<Vec2>),
+    Spline {
+        ty: SplineType,
+        points: Vec<Vec2>,
+        closed: bool,
+    },
+    #[cfg(feature = "grid")]
+    Grid {
+        cells_x: u32,
+        cells_y: u32,
+        cell_size: Vec2,
+    },
+}
+
+impl SheetShape {
+    /// Spawn the shape with the spawner's current config.
+    pub fn spawn<'a, 'w>(
+        &self,
+        spawner: &'a mut impl ShapeSpawner<'w>,
+    ) -> ShapeEntityCommands<'a, 'a> {
+        match self {
+            SheetShape::Rect(size) => spawner.rect(*size),
+            SheetShape::Circle(radius) => spawner.circle(*radius),
+            SheetShape::Arc {
+                radius,
+                start_angle,
+                end_angle,
+            } => spawner.arc(*radius, *start_angle, *end_angle),
+            #[cfg(feature = "ellipse")]
+            SheetShape::Ellipse(radii) => spawner.ellipse(*radii),
+            #[cfg(feature = "ngon")]
+            SheetShape::Ngon { sides, radius } => spawner.ngon(*sides, *radius),
+            #[cfg(feature = "star")]
+            SheetShape::Star {
+                points,
+                inner_radius,
+                outer_radius,
+            } => spawner.star(*points, *inner_radius, *outer_radius),
+            #[cfg(feature = "superellipse")]
+            SheetShape::Superellipse { size, exponent } => spawner.superellipse(*size, *exponent),
+            SheetShape::Capsule { start, end, radius } => spawner.capsule(*start, *end, *radius),
+            #[cfg(feature = "triangle")]
+            SheetShape::Triangle(a, b, c) => spawner.triangle(*a, *b, *c),
+            SheetShape::Line(start, end) => spawner.line(*start, *end),
+            SheetShape::Arrow(start, end) => spawner.arrow(*start, *end),
+            SheetShape::Polyline(points) => spawner.polyline(points),
+            SheetShape::ClosedPolyline(points) => spawner.closed_polyline(points),
+            SheetShape::Polygon(points) => spawner.polygon(points),
+            SheetShape::Spline { ty, points, closed } => {
+                if *closed {
+                    spawner.closed_spline(*ty, points)
+                } else {
+                    spawner.spline(*ty, points)
+                }
+            }
+            #[cfg(feature = "grid")]
+            SheetShape::Grid {
+                cells_x,
+                cells_y,
+                cell_size,
+            } => spawner.grid(*cells_x, *cells_y, *cell_size),
+        }
+    }
+}
+
+/// Bundle that spawns the shapes of a [`ShapeSheet`] as it's children once the sheet has loaded.
+///
+/// Any other children of the entity are despawned when the sheet is reloaded,
+/// insert [`Shape3d`] to spawn the sheet's shapes in the 3D pipeline.
+#[derive(Bundle, Default)]
+pub struct ShapeSheetBundle {
+    pub sheet: Handle<ShapeSheet>,
+    pub spatial: SpatialBundle,
+}
+
+impl ShapeSheetBundle {
+    pub fn new(sheet: Handle<ShapeSheet>, transform: Transform) -> Self {
+        Self {
+            sheet,
+            spatial: SpatialBundle::from_transform(transform),
+        }
+    }
+}
+
+/// Errors that can occur while loading a [`ShapeSheet`].
+#[derive(Debug)]
+pub enum ShapeSheetLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for ShapeSheetLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeSheetLoaderError::Io(err) => write!(f, "could not read shape sheet: {err}"),
+            ShapeSheetLoaderError::Ron(err) => write!(f, "could not parse shape sheet: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ShapeSheetLoaderError {}
+
+impl From<std::io::Error> for ShapeSheetLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ShapeSheetLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+/// Loads [`ShapeSheet`] assets from `.shapes.ron` files.
+#[derive(Default)]
+pub struct ShapeSheetLoader;
+
+impl AssetLoader for ShapeSheetLoader {
+    type Asset = ShapeSheet;
+    type Settings = ();
+    type Error = ShapeSheetLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<ShapeSheet, ShapeSheetLoaderError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["shapes.ron"]
+    }
+}
+
+fn spawn_sheet_nodes(spawner: &mut ShapeChildBuilder, nodes: &[SheetNode]) {
+    for node in nodes {
+        spawn_sheet_node(spawner, node);
+    }
+}
+
+fn spawn_sheet_node(spawner: &mut ShapeChildBuilder, node: &SheetNode) {
+    let config = spawner.config().clone();
+    node.style.apply(spawner);
+    spawner.transform = node.transform();
+
+    match &node.shape {
+        Some(shape) => {
+            let mut entity = shape.spawn(spawner);
+            if let Some(name) = &node.name {
+                entity.insert(Name::new(name.clone()));
+            }
+            entity.with_children(|spawner| spawn_sheet_nodes(spawner, &node.children));
+        }
+        None => {
+            let group_config = spawner.config().clone();
+            let mut entity = spawner.spawn(SpatialBundle::from_transform(node.transform()));
+            if let Some(name) = &node.name {
+                entity.insert(Name::new(name.clone()));
+            }
+            entity.with_shape_children(&group_config, |spawner| {
+                spawn_sheet_nodes(spawner, &node.children)
+            });
+        }
+    }
+
+    spawner.set_config(config);
+}
+
+/// Respawns the shapes of each [`ShapeSheet`] whose handle was added or changed, or whose asset was loaded or modified.
+pub fn spawn_shape_sheets(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<ShapeSheet>>,
+    sheets: Res<Assets<ShapeSheet>>,
+    base_config: Res<BaseShapeConfig>,
+    roots: Query<(Entity, Ref<Handle<ShapeSheet>>, Has<Shape3d>)>,
+) {
+    let reloaded: HashSet<AssetId<ShapeSheet>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, handle, is_3d) in &roots {
+        if !handle.is_changed() && !reloaded.contains(&handle.id()) {
+            continue;
+        }
+        let Some(sheet) = sheets.get(handle.id()) else {
+            continue;
+        };
+
+        let mut config = base_config.0.clone();
+        if is_3d {
+            config.set_3d();
+        }
+
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_shape_children(&config, |spawner| spawn_sheet_nodes(spawner, &sheet.shapes));
+    }
+}
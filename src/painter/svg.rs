@@ -0,0 +1,664 @@
+use std::{fmt, str::FromStr};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    math::Affine2,
+    prelude::*,
+    utils::BoxedFuture,
+};
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+use svgtypes::{Paint, PointsParser, SimplePathSegment, SimplifyingPathParser};
+
+use crate::prelude::*;
+
+/// Distance along the z axis between consecutive shapes of an imported SVG so that they are drawn in document order.
+const LAYER_STEP: f32 = 0.001;
+
+/// Settings for [`SvgLoader`].
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SvgLoaderSettings {
+    /// Number of straight segments each curve of a path is flattened into.
+    pub curve_subdivisions: u32,
+}
+
+impl Default for SvgLoaderSettings {
+    fn default() -> Self {
+        Self {
+            curve_subdivisions: 8,
+        }
+    }
+}
+
+/// Errors that can occur while loading an SVG as a [`ShapeSheet`].
+#[derive(Debug)]
+pub enum SvgLoaderError {
+    Io(std::io::Error),
+    Utf8(std::string::FromUtf8Error),
+    Xml(roxmltree::Error),
+}
+
+impl fmt::Display for SvgLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgLoaderError::Io(err) => write!(f, "could not read svg: {err}"),
+            SvgLoaderError::Utf8(err) => write!(f, "svg is not valid utf-8: {err}"),
+            SvgLoaderError::Xml(err) => write!(f, "could not parse svg: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SvgLoaderError {}
+
+impl From<std::io::Error> for SvgLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<std::string::FromUtf8Error> for SvgLoaderError {
+    fn from(err: std::string::FromUtf8Error) -> Self {
+        Self::Utf8(err)
+    }
+}
+
+impl From<roxmltree::Error> for SvgLoaderError {
+    fn from(err: roxmltree::Error) -> Self {
+        Self::Xml(err)
+    }
+}
+
+/// Loads `.svg` files as [`ShapeSheet`] assets, requires the `svg` feature.
+///
+/// Supports a practical subset of SVG, see [`svg_to_shape_sheet`], spawn the result with a [`ShapeSheetBundle`].
+#[derive(Default)]
+pub struct SvgLoader;
+
+impl AssetLoader for SvgLoader {
+    type Asset = ShapeSheet;
+    type Settings = SvgLoaderSettings;
+    type Error = SvgLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        settings: &'a SvgLoaderSettings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<ShapeSheet, SvgLoaderError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            svg_to_shape_sheet(&String::from_utf8(bytes)?, settings)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["svg"]
+    }
+}
+
+/// Convert an SVG document into a flat [`ShapeSheet`] with one node per fill and stroke.
+///
+/// Supports rects, circles, ellipses, lines, polylines, polygons and paths inside of groups with transforms,
+/// solid fills and strokes with opacity, caps and joins, and linear and radial gradients using their first and last stops.
+/// Gradients are only applied to circles and rects, other shapes use the color of the first stop.
+///
+/// The sheet is centered on the document's view box with the y axis flipped to point up, one SVG unit is one world unit.
+/// Subpaths of a filled path are filled separately so holes are not cut out, and skewed shapes lose their skew.
+pub fn svg_to_shape_sheet(
+    svg: &str,
+    settings: &SvgLoaderSettings,
+) -> Result<ShapeSheet, SvgLoaderError> {
+    let doc = Document::parse(svg)?;
+    let root = doc.root_element();
+
+    let view_box = root
+        .attribute("viewBox")
+        .and_then(|view_box| svgtypes::ViewBox::from_str(view_box).ok())
+        .map(|view_box| {
+            Vec2::new(
+                (view_box.x + view_box.w / 2.0) as f32,
+                (view_box.y + view_box.h / 2.0) as f32,
+            )
+        });
+    let center = view_box.unwrap_or_else(|| {
+        Vec2::new(
+            number(root.attribute("width")).unwrap_or(0.0),
+            number(root.attribute("height")).unwrap_or(0.0),
+        ) / 2.0
+    });
+
+    let mut converter = SvgConverter {
+        doc: &doc,
+        settings,
+        shapes: Vec::new(),
+    };
+    converter.visit(
+        root,
+        Affine2::from_translation(-center),
+        &SvgStyle::default(),
+    );
+
+    Ok(ShapeSheet {
+        shapes: converter.shapes,
+    })
+}
+
+#[derive(Clone, Debug)]
+enum SvgPaint {
+    None,
+    Color(Color),
+    Url(String),
+}
+
+/// Presentation attributes inherited from parent elements.
+#[derive(Clone, Debug)]
+struct SvgStyle {
+    fill: SvgPaint,
+    stroke: SvgPaint,
+    stroke_width: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+    opacity: f32,
+    cap: Cap,
+    join: Join,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            fill: SvgPaint::Color(Color::BLACK),
+            stroke: SvgPaint::None,
+            stroke_width: 1.0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            opacity: 1.0,
+            cap: Cap::None,
+            join: Join::Miter,
+        }
+    }
+}
+
+/// Returns the value of a presentation attribute, preferring the element's `style` attribute.
+fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attribute("style")
+        .and_then(|style| {
+            style.split(';').find_map(|declaration| {
+                let (key, value) = declaration.split_once(':')?;
+                (key.trim() == name).then_some(value.trim())
+            })
+        })
+        .or_else(|| node.attribute(name))
+}
+
+fn number(value: Option<&str>) -> Option<f32> {
+    let value = value?.trim();
+    value.strip_suffix("px").unwrap_or(value).parse().ok()
+}
+
+fn color(color: svgtypes::Color) -> Color {
+    Color::rgba_u8(color.red, color.green, color.blue, color.alpha)
+}
+
+fn paint(value: &str, current: &SvgPaint) -> SvgPaint {
+    match Paint::from_str(value) {
+        Ok(Paint::None) => SvgPaint::None,
+        Ok(Paint::Color(c)) => SvgPaint::Color(color(c)),
+        Ok(Paint::FuncIRI(id, _)) => SvgPaint::Url(id.to_string()),
+        _ => current.clone(),
+    }
+}
+
+impl SvgStyle {
+    fn inherit(&self, node: Node) -> Self {
+        let mut style = self.clone();
+        if let Some(fill) = property(node, "fill") {
+            style.fill = paint(fill, &self.fill);
+        }
+        if let Some(stroke) = property(node, "stroke") {
+            style.stroke = paint(stroke, &self.stroke);
+        }
+        if let Some(width) = number(property(node, "stroke-width")) {
+            style.stroke_width = width;
+        }
+        if let Some(opacity) = number(property(node, "fill-opacity")) {
+            style.fill_opacity = opacity;
+        }
+        if let Some(opacity) = number(property(node, "stroke-opacity")) {
+            style.stroke_opacity = opacity;
+        }
+        // Opacity is not inherited but applies to the whole group, multiply it into it's descendants instead
+        if let Some(opacity) = number(property(node, "opacity")) {
+            style.opacity *= opacity;
+        }
+        match property(node, "stroke-linecap") {
+            Some("butt") => style.cap = Cap::None,
+            Some("round") => style.cap = Cap::Round,
+            Some("square") => style.cap = Cap::Square,
+            _ => {}
+        }
+        match property(node, "stroke-linejoin") {
+            Some("miter") | Some("miter-clip") | Some("arcs") => style.join = Join::Miter,
+            Some("round") => style.join = Join::Round,
+            Some("bevel") => style.join = Join::Bevel,
+            _ => {}
+        }
+        style
+    }
+}
+
+/// Convert a point in the local space of an SVG transform to world space, flipping the y axis to point up.
+fn to_world(transform: Affine2, point: Vec2) -> Vec2 {
+    transform.transform_point2(point) * Vec2::new(1.0, -1.0)
+}
+
+/// Returns the world space translation, rotation and scale of a shape centered on `center` in the local space of an SVG transform.
+fn placement(transform: Affine2, center: Vec2) -> (Vec3, f32, Vec2) {
+    let flip = Affine2::from_scale(Vec2::new(1.0, -1.0));
+    let (scale, rotation, _) = (flip * transform * flip).to_scale_angle_translation();
+    (
+        to_world(transform, center).extend(0.0),
+        rotation,
+        scale.abs(),
+    )
+}
+
+struct SvgConverter<'a, 'input> {
+    doc: &'a Document<'input>,
+    settings: &'a SvgLoaderSettings,
+    shapes: Vec<SheetNode>,
+}
+
+impl<'a, 'input> SvgConverter<'a, 'input> {
+    fn visit(&mut self, node: Node, transform: Affine2, style: &SvgStyle) {
+        if property(node, "display") == Some("none") {
+            return;
+        }
+
+        let transform = match node
+            .attribute("transform")
+            .map(svgtypes::Transform::from_str)
+        {
+            Some(Ok(t)) => {
+                transform
+                    * Affine2::from_cols_array(&[t.a, t.b, t.c, t.d, t.e, t.f].map(|v| v as f32))
+            }
+            _ => transform,
+        };
+        let style = style.inherit(node);
+
+        match node.tag_name().name() {
+            "svg" | "g" | "a" => {
+                for child in node.children().filter(Node::is_element) {
+                    self.visit(child, transform, &style);
+                }
+            }
+            "rect" => self.rect(node, transform, &style),
+            "circle" => {
+                let r = number(node.attribute("r")).unwrap_or(0.0);
+                self.ellipse(node, transform, &style, Vec2::splat(r));
+            }
+            "ellipse" => {
+                let rx = number(node.attribute("rx")).unwrap_or(0.0);
+                let ry = number(node.attribute("ry")).unwrap_or(0.0);
+                self.ellipse(node, transform, &style, Vec2::new(rx, ry));
+            }
+            "line" => {
+                let attr = |name| number(node.attribute(name)).unwrap_or(0.0);
+                let subpath = SubPath {
+                    points: vec![
+                        Vec2::new(attr("x1"), attr("y1")),
+                        Vec2::new(attr("x2"), attr("y2")),
+                    ],
+                    closed: false,
+                };
+                // Lines have no interior so are never filled
+                self.stroke_path(node, transform, &style, &[subpath]);
+            }
+            "polyline" | "polygon" => {
+                let points = PointsParser::from(node.attribute("points").unwrap_or_default())
+                    .map(|(x, y)| Vec2::new(x as f32, y as f32))
+                    .collect();
+                let subpath = SubPath {
+                    points,
+                    closed: node.tag_name().name() == "polygon",
+                };
+                self.fill_path(node, transform, &style, std::slice::from_ref(&subpath));
+                self.stroke_path(node, transform, &style, &[subpath]);
+            }
+            "path" => {
+                let subpaths = self.flatten_path(node.attribute("d").unwrap_or_default());
+                self.fill_path(node, transform, &style, &subpaths);
+                self.stroke_path(node, transform, &style, &subpaths);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the fill color of a shape and the gradient that should be applied on top of it, if any.
+    fn fill(&self, style: &SvgStyle, size: Vec2) -> Option<(Color, Option<Gradient>)> {
+        let (mut color, gradient) = match &style.fill {
+            SvgPaint::None => return None,
+            SvgPaint::Color(color) => (*color, None),
+            SvgPaint::Url(id) => self.gradient(id, size)?,
+        };
+
+        let opacity = style.fill_opacity * style.opacity;
+        color.set_a(color.a() * opacity);
+        let gradient = gradient.map(|gradient| match gradient {
+            Gradient::Linear { color, angle } => Gradient::Linear {
+                color: color.with_a(color.a() * opacity),
+                angle,
+            },
+            Gradient::Radial { color, focal } => Gradient::Radial {
+                color: color.with_a(color.a() * opacity),
+                focal,
+            },
+            gradient => gradient,
+        });
+        Some((color, gradient))
+    }
+
+    /// Resolves a gradient by id into the color of it's first stop and a gradient towards it's last stop.
+    fn gradient(&self, id: &str, size: Vec2) -> Option<(Color, Option<Gradient>)> {
+        let find = |id: &str| {
+            self.doc
+                .descendants()
+                .find(|node| node.attribute("id") == Some(id))
+        };
+        let node = find(id)?;
+
+        // Stops may be inherited from another gradient referenced by href
+        let href = node
+            .attribute(("http://www.w3.org/1999/xlink", "href"))
+            .or_else(|| node.attribute("href"))
+            .and_then(|href| href.strip_prefix('#'));
+        let stops_node = match href {
+            Some(href) if !node.children().any(|child| child.has_tag_name("stop")) => {
+                find(href).unwrap_or(node)
+            }
+            _ => node,
+        };
+
+        let mut stops = stops_node
+            .children()
+            .filter(|child| child.has_tag_name("stop"))
+            .map(|stop| {
+                let mut stop_color = property(stop, "stop-color")
+                    .and_then(|value| svgtypes::Color::from_str(value).ok())
+                    .map(color)
+                    .unwrap_or(Color::BLACK);
+                let opacity = number(property(stop, "stop-opacity")).unwrap_or(1.0);
+                stop_color.set_a(stop_color.a() * opacity);
+                stop_color
+            });
+        let first = stops.next()?;
+        let Some(last) = stops.last() else {
+            return Some((first, None));
+        };
+
+        let gradient = match node.tag_name().name() {
+            "linearGradient" => {
+                // Coordinates default to fractions of the bounding box of the shape
+                let scale = if node.attribute("gradientUnits") == Some("userSpaceOnUse") {
+                    Vec2::ONE
+                } else {
+                    size
+                };
+                let attr = |name, default| number(node.attribute(name)).unwrap_or(default);
+                let direction = Vec2::new(
+                    attr("x2", 1.0) - attr("x1", 0.0),
+                    attr("y2", 0.0) - attr("y1", 0.0),
+                ) * scale;
+                Gradient::linear(last, (-direction.y).atan2(direction.x))
+            }
+            "radialGradient" => Gradient::radial(last),
+            _ => return Some((first, None)),
+        };
+        Some((first, Some(gradient)))
+    }
+
+    /// Returns the style of a shape's stroke, `scale` is the scale of the shape's transform.
+    fn stroke(&self, style: &SvgStyle, scale: f32) -> Option<SheetStyle> {
+        let SvgPaint::Color(mut color) = style.stroke else {
+            return None;
+        };
+        color.set_a(color.a() * style.stroke_opacity * style.opacity);
+        Some(SheetStyle {
+            color: Some(color),
+            hollow: Some(true),
+            thickness: Some(style.stroke_width * scale),
+            thickness_type: Some(ThicknessType::World),
+            stroke_alignment: Some(StrokeAlignment::Center),
+            cap: Some(style.cap),
+            join: Some(style.join),
+            ..default()
+        })
+    }
+
+    fn push(&mut self, node: Node, mut sheet_node: SheetNode) {
+        sheet_node.name = node.attribute("id").map(str::to_string);
+        sheet_node.translation.z = self.shapes.len() as f32 * LAYER_STEP;
+        self.shapes.push(sheet_node);
+    }
+
+    /// Push a fill and a stroke of a primitive shape, `size` is the size of the shape's bounding box in it's local space.
+    fn push_primitive(
+        &mut self,
+        node: Node,
+        style: &SvgStyle,
+        primitive: SheetNode,
+        size: Vec2,
+        scale: f32,
+    ) {
+        if let Some((color, gradient)) = self.fill(style, size) {
+            let mut fill = primitive.clone();
+            fill.style.color = Some(color);
+            fill.style.hollow = Some(false);
+            fill.style.gradient = gradient;
+            self.push(node, fill);
+        }
+
+        if let Some(stroke_style) = self.stroke(style, scale) {
+            let mut stroke = primitive;
+            stroke.style = SheetStyle {
+                corner_radii: stroke.style.corner_radii,
+                ..stroke_style
+            };
+            self.push(node, stroke);
+        }
+    }
+
+    fn rect(&mut self, node: Node, transform: Affine2, style: &SvgStyle) {
+        let attr = |name| number(node.attribute(name));
+        let min = Vec2::new(attr("x").unwrap_or(0.0), attr("y").unwrap_or(0.0));
+        let size = Vec2::new(attr("width").unwrap_or(0.0), attr("height").unwrap_or(0.0));
+        if size.cmple(Vec2::ZERO).any() {
+            return;
+        }
+        // A missing radius on one axis takes the value of the other
+        let radius = attr("rx").or(attr("ry")).unwrap_or(0.0);
+
+        let (translation, rotation, scale) = placement(transform, min + size / 2.0);
+        let corner_radius = radius.min(size.min_element() / 2.0) * scale.min_element();
+        let primitive = SheetNode {
+            translation,
+            rotation,
+            style: SheetStyle {
+                corner_radii: Some(Vec4::splat(corner_radius)),
+                ..default()
+            },
+            shape: Some(SheetShape::Rect(size * scale)),
+            ..default()
+        };
+        self.push_primitive(node, style, primitive, size, scale.element_sum() / 2.0);
+    }
+
+    fn ellipse(&mut self, node: Node, transform: Affine2, style: &SvgStyle, radii: Vec2) {
+        let center = Vec2::new(
+            number(node.attribute("cx")).unwrap_or(0.0),
+            number(node.attribute("cy")).unwrap_or(0.0),
+        );
+        if radii.cmple(Vec2::ZERO).any() {
+            return;
+        }
+
+        let (translation, rotation, scale) = placement(transform, center);
+        let radii = radii * scale;
+        // Gradients are only supported by discs so circles are kept as circles
+        let shape = if (radii.x - radii.y).abs() <= radii.x * 1e-4 {
+            SheetShape::Circle(radii.x)
+        } else {
+            SheetShape::Ellipse(radii)
+        };
+        let primitive = SheetNode {
+            translation,
+            rotation,
+            shape: Some(shape),
+            ..default()
+        };
+        self.push_primitive(
+            node,
+            style,
+            primitive,
+            radii * 2.0,
+            scale.element_sum() / 2.0,
+        );
+    }
+
+    /// Flatten path data into subpaths, stopping at the first error as in the SVG spec.
+    fn flatten_path(&self, data: &str) -> Vec<SubPath> {
+        let subdivisions = self.settings.curve_subdivisions.max(1);
+        let mut subpaths: Vec<SubPath> = Vec::new();
+        let mut current = Vec2::ZERO;
+
+        let line_to = |subpaths: &mut Vec<SubPath>, current: &mut Vec2, to: Vec2| {
+            match subpaths.last_mut() {
+                Some(subpath) if !subpath.closed => subpath.points.push(to),
+                // Drawing after a subpath has been closed continues from it's start in a new subpath
+                _ => subpaths.push(SubPath {
+                    points: vec![*current, to],
+                    closed: false,
+                }),
+            }
+            *current = to;
+        };
+        let point = |x: f64, y: f64| Vec2::new(x as f32, y as f32);
+
+        for segment in SimplifyingPathParser::from(data).map_while(Result::ok) {
+            match segment {
+                SimplePathSegment::MoveTo { x, y } => {
+                    current = point(x, y);
+                    subpaths.push(SubPath {
+                        points: vec![current],
+                        closed: false,
+                    });
+                }
+                SimplePathSegment::LineTo { x, y } => {
+                    line_to(&mut subpaths, &mut current, point(x, y))
+                }
+                SimplePathSegment::Quadratic { x1, y1, x, y } => {
+                    let [p0, p1, p2, p3] = quadratic_to_cubic(current, point(x1, y1), point(x, y));
+                    for i in 1..=subdivisions {
+                        let to = cubic_point(p0, p1, p2, p3, i as f32 / subdivisions as f32);
+                        line_to(&mut subpaths, &mut current, to);
+                    }
+                }
+                SimplePathSegment::CurveTo {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    x,
+                    y,
+                } => {
+                    let (p0, p1, p2, p3) = (current, point(x1, y1), point(x2, y2), point(x, y));
+                    for i in 1..=subdivisions {
+                        let to = cubic_point(p0, p1, p2, p3, i as f32 / subdivisions as f32);
+                        line_to(&mut subpaths, &mut current, to);
+                    }
+                }
+                SimplePathSegment::ClosePath => {
+                    if let Some(subpath) = subpaths.last_mut() {
+                        // Drop a final point that already returns to the start to avoid a zero length segment
+                        if subpath.points.len() > 1
+                            && subpath.points.first() == subpath.points.last()
+                        {
+                            subpath.points.pop();
+                        }
+                        subpath.closed = true;
+                        current = subpath.points[0];
+                    }
+                }
+            }
+        }
+
+        subpaths
+    }
+
+    fn fill_path(
+        &mut self,
+        node: Node,
+        transform: Affine2,
+        style: &SvgStyle,
+        subpaths: &[SubPath],
+    ) {
+        let Some((color, _)) = self.fill(style, Vec2::ONE) else {
+            return;
+        };
+
+        for subpath in subpaths.iter().filter(|subpath| subpath.points.len() > 2) {
+            let points = subpath
+                .points
+                .iter()
+                .map(|point| to_world(transform, *point))
+                .collect();
+            let fill = SheetNode {
+                style: SheetStyle {
+                    color: Some(color),
+                    hollow: Some(false),
+                    ..default()
+                },
+                shape: Some(SheetShape::Polygon(points)),
+                ..default()
+            };
+            self.push(node, fill);
+        }
+    }
+
+    fn stroke_path(
+        &mut self,
+        node: Node,
+        transform: Affine2,
+        style: &SvgStyle,
+        subpaths: &[SubPath],
+    ) {
+        let (_, _, scale) = placement(transform, Vec2::ZERO);
+        let Some(stroke_style) = self.stroke(style, scale.element_sum() / 2.0) else {
+            return;
+        };
+
+        for subpath in subpaths.iter().filter(|subpath| subpath.points.len() > 1) {
+            let points = subpath
+                .points
+                .iter()
+                .map(|point| to_world(transform, *point).extend(0.0))
+                .collect();
+            let stroke = SheetNode {
+                style: stroke_style.clone(),
+                shape: Some(if subpath.closed {
+                    SheetShape::ClosedPolyline(points)
+                } else {
+                    SheetShape::Polyline(points)
+                }),
+                ..default()
+            };
+            self.push(node, stroke);
+        }
+    }
+}
+
+fn cubic_point(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, t: f32) -> Vec2 {
+    let u = 1.0 - t;
+    p0 * u * u * u + p1 * 3.0 * u * u * t + p2 * 3.0 * u * t * t + p3 * t * t * t
+}
@@ -0,0 +1,107 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::prelude::*;
+
+use super::ShapeStorage;
+
+/// Resource of named [`ShapeRecording`]s that can be drawn any number of times by name.
+///
+/// Define a composite of shapes once by recording it with [`ShapePainter::record`], then draw it with
+/// [`ShapePainter::stamp`] or by spawning entities with a [`ShapeSymbol`].
+#[derive(Resource, Default)]
+pub struct ShapeSymbols {
+    symbols: HashMap<String, ShapeRecording>,
+}
+
+impl ShapeSymbols {
+    /// Define the symbol `name`, replacing any previous definition.
+    pub fn insert(&mut self, name: impl Into<String>, recording: ShapeRecording) {
+        self.symbols.insert(name.into(), recording);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ShapeRecording> {
+        self.symbols.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<ShapeRecording> {
+        self.symbols.remove(name)
+    }
+}
+
+/// Component that draws the symbol `name` from [`ShapeSymbols`] each frame relative to the entity's transform.
+///
+/// The colors of the symbol are multiplied by `tint` and the inherited [`ShapeAlpha`] of the entity, symbols
+/// are not drawn while the entity is hidden or when no symbol of that name has been defined.
+/// Each instance is gathered every frame like immediate mode shapes, for symbols that never move consider
+/// baking them with [`BakedShapes::from_recording`].
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapeSymbol {
+    pub name: String,
+    pub tint: Color,
+}
+
+impl ShapeSymbol {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..default()
+        }
+    }
+
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+}
+
+impl Default for ShapeSymbol {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            tint: Color::WHITE,
+        }
+    }
+}
+
+/// Bundle for stamping a [`ShapeSymbol`].
+#[derive(Bundle, Default)]
+pub struct ShapeSymbolBundle {
+    pub symbol: ShapeSymbol,
+    pub spatial_bundle: SpatialBundle,
+}
+
+impl ShapeSymbolBundle {
+    pub fn new(symbol: ShapeSymbol, transform: Transform) -> Self {
+        Self {
+            symbol,
+            spatial_bundle: SpatialBundle::from_transform(transform),
+        }
+    }
+}
+
+pub fn draw_shape_symbols(
+    mut storage: ResMut<ShapeStorage>,
+    symbols: Res<ShapeSymbols>,
+    query: Query<(
+        Entity,
+        &ShapeSymbol,
+        &GlobalTransform,
+        Option<&InheritedVisibility>,
+    )>,
+    alphas: Query<(Option<&ShapeAlpha>, Option<&Parent>)>,
+) {
+    for (entity, symbol, tf, visibility) in &query {
+        if !visibility.map_or(true, InheritedVisibility::get) {
+            continue;
+        }
+        let Some(recording) = symbols.get(&symbol.name) else {
+            continue;
+        };
+        let tint = symbol
+            .tint
+            .with_a(symbol.tint.a() * inherited_alpha(entity, &alphas));
+        recording.send(&mut storage, tf.compute_matrix(), tint);
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+#[cfg(feature = "rapier2d")]
+mod rapier;
+
+#[cfg(feature = "xpbd_2d")]
+mod xpbd;
+
+/// Plugin that draws the colliders of 2D physics bodies with a [`ShapePainter`], see [`PhysicsDebugConfig`].
+///
+/// Colliders from `bevy_rapier2d` are drawn with the `rapier2d` feature and colliders from `bevy_xpbd_2d`
+/// with the `xpbd_2d` feature. Balls, cuboids, capsules, segments, triangles, convex polygons and compound
+/// shapes made of them are supported, other collider shapes are skipped.
+pub struct PhysicsDebugPlugin;
+
+impl Plugin for PhysicsDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhysicsDebugConfig>()
+            .register_type::<PhysicsDebugConfig>()
+            .register_type::<PhysicsDebugColor>();
+
+        #[cfg(feature = "rapier2d")]
+        app.add_systems(
+            PostUpdate,
+            rapier::draw_rapier_colliders
+                .run_if(physics_debug_enabled)
+                .after(TransformSystem::TransformPropagate),
+        );
+
+        #[cfg(feature = "xpbd_2d")]
+        app.add_systems(
+            PostUpdate,
+            xpbd::draw_xpbd_colliders
+                .run_if(physics_debug_enabled)
+                .after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Resource controlling how [`PhysicsDebugPlugin`] draws colliders.
+///
+/// A collider's color is chosen by the first matching rule: it's [`PhysicsDebugColor`], then sensors, then sleeping
+/// bodies and finally the type of it's rigid body.
+#[derive(Resource, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct PhysicsDebugConfig {
+    /// Whether colliders are drawn.
+    pub enabled: bool,
+    /// Config used to draw each collider, the color and transform are replaced per collider.
+    pub config: ShapeConfig,
+    /// Color of colliders on dynamic bodies.
+    pub dynamic: Color,
+    /// Color of colliders on kinematic bodies.
+    pub kinematic: Color,
+    /// Color of colliders on fixed bodies and colliders without a body.
+    pub fixed: Color,
+    /// Color of sensor colliders.
+    pub sensor: Color,
+    /// Color of colliders on sleeping bodies, when None sleeping bodies use the color of their body type.
+    pub sleeping: Option<Color>,
+}
+
+impl Default for PhysicsDebugConfig {
+    fn default() -> Self {
+        let mut config = ShapeConfig::default_2d();
+        config.hollow = true;
+        config.thickness = 1.5;
+        config.thickness_type = ThicknessType::Pixels;
+        config.transform.translation.z = 100.0;
+
+        Self {
+            enabled: true,
+            config,
+            dynamic: Color::rgb(0.9, 0.6, 0.2),
+            kinematic: Color::rgb(0.6, 0.4, 0.9),
+            fixed: Color::rgb(0.4, 0.7, 0.9),
+            sensor: Color::rgb(0.4, 0.9, 0.5),
+            sleeping: Some(Color::GRAY),
+        }
+    }
+}
+
+/// Component overriding the color a collider is drawn with by [`PhysicsDebugPlugin`].
+#[derive(Component, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct PhysicsDebugColor(pub Color);
+
+/// Type of rigid body a collider is attached to, shared between physics backends.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BodyKind {
+    Dynamic,
+    Kinematic,
+    Fixed,
+}
+
+impl PhysicsDebugConfig {
+    fn color(
+        &self,
+        color: Option<&PhysicsDebugColor>,
+        body: BodyKind,
+        sensor: bool,
+        sleeping: bool,
+    ) -> Color {
+        if let Some(color) = color {
+            return color.0;
+        }
+        if sensor {
+            return self.sensor;
+        }
+        if let Some(color) = self.sleeping.filter(|_| sleeping) {
+            return color;
+        }
+        match body {
+            BodyKind::Dynamic => self.dynamic,
+            BodyKind::Kinematic => self.kinematic,
+            BodyKind::Fixed => self.fixed,
+        }
+    }
+}
+
+/// Collider shapes supported by [`PhysicsDebugPlugin`] in the local space of the collider.
+enum DebugShape {
+    Circle(f32),
+    Rect(Vec2),
+    Capsule(Vec2, Vec2, f32),
+    Segment(Vec2, Vec2),
+    Polygon(Vec<Vec2>),
+}
+
+impl DebugShape {
+    fn draw(&self, painter: &mut ShapePainter) {
+        match self {
+            DebugShape::Circle(radius) => {
+                painter.circle(*radius);
+            }
+            DebugShape::Rect(half_extents) => {
+                painter.rect(*half_extents * 2.0);
+            }
+            DebugShape::Capsule(start, end, radius) => {
+                painter.capsule(*start, *end, *radius);
+            }
+            DebugShape::Segment(start, end) => {
+                painter.line(start.extend(0.0), end.extend(0.0));
+            }
+            DebugShape::Polygon(points) => {
+                let points: Vec<Vec3> = points.iter().map(|p| p.extend(0.0)).collect();
+                painter.closed_polyline(&points);
+            }
+        }
+    }
+}
+
+fn physics_debug_enabled(config: Res<PhysicsDebugConfig>) -> bool {
+    config.enabled
+}
+
+/// Draw a set of shapes relative to a collider's transform with the color chosen by the config.
+fn draw_collider(
+    painter: &mut ShapePainter,
+    config: &PhysicsDebugConfig,
+    tf: &GlobalTransform,
+    color: Color,
+    shapes: impl IntoIterator<Item = (Vec2, f32, DebugShape)>,
+) {
+    let mut tf = tf.compute_transform();
+    // Keep colliders above the sprites they belong to
+    tf.translation.z += config.config.transform.translation.z;
+
+    painter.set_config(config.config.clone());
+    painter.transform = tf;
+    painter.color = color;
+    for (translation, rotation, shape) in shapes {
+        painter.with_children(|painter| {
+            painter.translate(translation.extend(0.0));
+            painter.rotate_z(rotation);
+            shape.draw(painter);
+        });
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{draw_collider, BodyKind, DebugShape, PhysicsDebugColor, PhysicsDebugConfig};
+use crate::prelude::*;
+
+pub(super) fn draw_rapier_colliders(
+    mut painter: ShapePainter,
+    config: Res<PhysicsDebugConfig>,
+    colliders: Query<(
+        Entity,
+        &Collider,
+        &GlobalTransform,
+        Has<Sensor>,
+        Option<&PhysicsDebugColor>,
+    )>,
+    bodies: Query<(&RigidBody, Option<&Sleeping>)>,
+    parents: Query<&Parent>,
+) {
+    for (entity, collider, tf, sensor, color) in &colliders {
+        // Colliders may be attached directly to a body or to a descendant of it
+        let body = std::iter::once(entity)
+            .chain(parents.iter_ancestors(entity))
+            .find_map(|entity| bodies.get(entity).ok());
+        let (kind, sleeping) = match body {
+            Some((body, sleeping)) => (
+                match body {
+                    RigidBody::Dynamic => BodyKind::Dynamic,
+                    RigidBody::Fixed => BodyKind::Fixed,
+                    RigidBody::KinematicPositionBased | RigidBody::KinematicVelocityBased => {
+                        BodyKind::Kinematic
+                    }
+                },
+                sleeping.is_some_and(|sleeping| sleeping.sleeping),
+            ),
+            None => (BodyKind::Fixed, false),
+        };
+
+        let mut shapes = Vec::new();
+        debug_shapes(collider.as_typed_shape(), Vec2::ZERO, 0.0, &mut shapes);
+        let color = config.color(color, kind, sensor, sleeping);
+        draw_collider(&mut painter, &config, tf, color, shapes);
+    }
+}
+
+fn debug_shapes(
+    view: ColliderView,
+    translation: Vec2,
+    rotation: f32,
+    shapes: &mut Vec<(Vec2, f32, DebugShape)>,
+) {
+    let shape = match view {
+        ColliderView::Ball(ball) => DebugShape::Circle(ball.radius()),
+        ColliderView::Cuboid(cuboid) => DebugShape::Rect(cuboid.half_extents()),
+        ColliderView::Capsule(capsule) => {
+            let segment = capsule.segment();
+            DebugShape::Capsule(segment.a(), segment.b(), capsule.radius())
+        }
+        ColliderView::Segment(segment) => DebugShape::Segment(segment.a(), segment.b()),
+        ColliderView::Triangle(triangle) => {
+            DebugShape::Polygon(vec![triangle.a(), triangle.b(), triangle.c()])
+        }
+        ColliderView::ConvexPolygon(polygon) => DebugShape::Polygon(polygon.points().collect()),
+        ColliderView::Compound(compound) => {
+            for (child_translation, child_rotation, child) in compound.shapes() {
+                debug_shapes(
+                    child,
+                    translation + Vec2::from_angle(rotation).rotate(child_translation),
+                    rotation + child_rotation,
+                    shapes,
+                );
+            }
+            return;
+        }
+        _ => return,
+    };
+    shapes.push((translation, rotation, shape));
+}
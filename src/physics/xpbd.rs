@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use bevy_xpbd_2d::{
+    parry::{
+        math::Point,
+        shape::{SharedShape, TypedShape},
+    },
+    prelude::*,
+};
+
+use super::{draw_collider, BodyKind, DebugShape, PhysicsDebugColor, PhysicsDebugConfig};
+use crate::prelude::*;
+
+pub(super) fn draw_xpbd_colliders(
+    mut painter: ShapePainter,
+    config: Res<PhysicsDebugConfig>,
+    colliders: Query<(
+        &Collider,
+        &GlobalTransform,
+        Option<&ColliderParent>,
+        Has<Sensor>,
+        Option<&PhysicsDebugColor>,
+    )>,
+    bodies: Query<(&RigidBody, Has<Sleeping>)>,
+) {
+    for (collider, tf, parent, sensor, color) in &colliders {
+        // Every collider has a parent, which is it's own entity when attached directly to a body
+        let body = bodies.get(parent.map_or(Entity::PLACEHOLDER, ColliderParent::get));
+        let (kind, sleeping) = match body.ok() {
+            Some((body, sleeping)) => (
+                match body {
+                    RigidBody::Dynamic => BodyKind::Dynamic,
+                    RigidBody::Static => BodyKind::Fixed,
+                    RigidBody::Kinematic => BodyKind::Kinematic,
+                },
+                sleeping,
+            ),
+            None => (BodyKind::Fixed, false),
+        };
+
+        let mut shapes = Vec::new();
+        debug_shapes(collider.shape_scaled(), Vec2::ZERO, 0.0, &mut shapes);
+        let color = config.color(color, kind, sensor, sleeping);
+
+        // Scaled shapes already include the scale of the transform
+        let (_, rotation, translation) = tf.to_scale_rotation_translation();
+        let tf =
+            GlobalTransform::from(Transform::from_translation(translation).with_rotation(rotation));
+        draw_collider(&mut painter, &config, &tf, color, shapes);
+    }
+}
+
+fn debug_shapes(
+    shape: &SharedShape,
+    translation: Vec2,
+    rotation: f32,
+    shapes: &mut Vec<(Vec2, f32, DebugShape)>,
+) {
+    let point = |point: &Point<f32>| Vec2::new(point.x, point.y);
+    let shape = match shape.as_typed_shape() {
+        TypedShape::Ball(ball) => DebugShape::Circle(ball.radius),
+        TypedShape::Cuboid(cuboid) => {
+            DebugShape::Rect(Vec2::new(cuboid.half_extents.x, cuboid.half_extents.y))
+        }
+        TypedShape::Capsule(capsule) => DebugShape::Capsule(
+            point(&capsule.segment.a),
+            point(&capsule.segment.b),
+            capsule.radius,
+        ),
+        TypedShape::Segment(segment) => DebugShape::Segment(point(&segment.a), point(&segment.b)),
+        TypedShape::Triangle(triangle) => DebugShape::Polygon(vec![
+            point(&triangle.a),
+            point(&triangle.b),
+            point(&triangle.c),
+        ]),
+        TypedShape::ConvexPolygon(polygon) => {
+            DebugShape::Polygon(polygon.points().iter().map(point).collect())
+        }
+        TypedShape::Compound(compound) => {
+            for (isometry, child) in compound.shapes() {
+                let child_translation = Vec2::new(isometry.translation.x, isometry.translation.y);
+                debug_shapes(
+                    child,
+                    translation + Vec2::from_angle(rotation).rotate(child_translation),
+                    rotation + isometry.rotation.angle(),
+                    shapes,
+                );
+            }
+            return;
+        }
+        _ => return,
+    };
+    shapes.push((translation, rotation, shape));
+}
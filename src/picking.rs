@@ -0,0 +1,95 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_mod_picking::backend::{prelude::*, ray::RayMap};
+
+use crate::{painter::ray_hit, prelude::*, render::ShapeComponent};
+
+/// Plugin that adds a [`bevy_mod_picking`] backend for retained shapes, enabled by the `picking` feature.
+///
+/// Hit tests are performed with the same logic as [`ShapeHitTest`], respecting [`Pickable`], [`ViewVisibility`]
+/// and [`RenderLayers`].
+///
+/// Added automatically by [`Shape2dPlugin`] when the feature is enabled.
+pub struct ShapePickingPlugin;
+
+impl Plugin for ShapePickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (
+                shape_picking::<DiscComponent>,
+                shape_picking::<RectangleComponent>,
+                shape_picking::<LineComponent>,
+            )
+                .in_set(PickSet::Backend),
+        );
+        #[cfg(feature = "ngon")]
+        app.add_systems(
+            PreUpdate,
+            shape_picking::<RegularPolygonComponent>.in_set(PickSet::Backend),
+        );
+    }
+}
+
+fn shape_picking<T: ShapeComponent>(
+    ray_map: Res<RayMap>,
+    cameras: Query<(&Camera, &GlobalTransform, Option<&RenderLayers>)>,
+    shapes: Query<(
+        Entity,
+        &T,
+        &ShapeFill,
+        &GlobalTransform,
+        &ViewVisibility,
+        Option<&RenderLayers>,
+        Option<&Pickable>,
+    )>,
+    mut output: EventWriter<PointerHits>,
+) {
+    for (ray_id, ray) in ray_map.iter() {
+        let Ok((camera, camera_tf, camera_layers)) = cameras.get(ray_id.camera) else {
+            continue;
+        };
+        if !camera.is_active {
+            continue;
+        }
+        let camera_layers = camera_layers.copied().unwrap_or_default();
+
+        let mut picks: Vec<(Entity, HitData, bool)> = Vec::new();
+        for (entity, shape, fill, tf, visibility, layers, pickable) in &shapes {
+            if !visibility.get() || !camera_layers.intersects(&layers.copied().unwrap_or_default())
+            {
+                continue;
+            }
+            if pickable.is_some_and(|pickable| !pickable.is_hoverable) {
+                continue;
+            }
+
+            let Some(hit) = ray_hit(shape, fill, tf, *ray, Some((camera, camera_tf))) else {
+                continue;
+            };
+
+            let blocks = pickable.map_or(true, |pickable| pickable.should_block_lower);
+            picks.push((
+                entity,
+                HitData::new(
+                    ray_id.camera,
+                    hit.distance,
+                    Some(hit.position),
+                    Some(hit.normal),
+                ),
+                blocks,
+            ));
+        }
+
+        // Shapes behind the first blocking shape are hidden from the pointer
+        picks.sort_by(|a, b| a.1.depth.total_cmp(&b.1.depth));
+        if let Some(blocking) = picks.iter().position(|(_, _, blocks)| *blocks) {
+            picks.truncate(blocking + 1);
+        }
+
+        let picks = picks
+            .into_iter()
+            .map(|(entity, hit, _)| (entity, hit))
+            .collect();
+        output.send(PointerHits::new(ray_id.pointer, picks, camera.order as f32));
+    }
+}
@@ -0,0 +1,256 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::entity::EntityHashMap,
+    prelude::*,
+    render::{
+        render_resource::{encase, BindGroup, BindGroupEntries},
+        renderer::RenderDevice,
+        Extract,
+    },
+    utils::HashMap,
+};
+use wgpu::{util::BufferInitDescriptor, BufferUsages};
+
+use crate::{
+    painter::BakedShapes,
+    render::{
+        render_2d::{Shape2dInstances, Shape2dMaterials},
+        ShapeData, ShapeInstance, ShapeInstanceBuffer, ShapePipeline, ShapePipelineMaterial,
+        ShapePipelineType, ShapeTypeStats,
+    },
+};
+
+#[cfg(feature = "shape_3d")]
+use crate::render::render_3d::{Shape3dInstances, Shape3dMaterials};
+
+/// Baked instances of a single type and material, kept in the render world until their [`BakedShapes`] changes.
+struct BakedBatch<T: ShapeData> {
+    material: ShapePipelineMaterial,
+    /// First instance of the batch, used to sort the batch as a whole.
+    first: T,
+    count: u32,
+    /// Bind group of the batch's instance buffer, None on platforms without storage buffers.
+    bind_group: Option<BindGroup>,
+    /// Instances uploaded each frame on platforms without storage buffers.
+    instances: Vec<T>,
+}
+
+/// Batches of each [`BakedShapes`] in the main world for the shape type `T`.
+#[derive(Resource)]
+pub struct BakedShapeBatches<T: ShapeData> {
+    values: HashMap<(Entity, ShapePipelineType), Vec<BakedBatch<T>>>,
+}
+
+impl<T: ShapeData> Default for BakedShapeBatches<T> {
+    fn default() -> Self {
+        Self {
+            values: Default::default(),
+        }
+    }
+}
+
+/// Component on the render world entity drawing a batch of baked shapes, with the bind group of it's instances.
+#[derive(Component)]
+pub struct BakedShapeBatch<T: ShapeData> {
+    pub count: u32,
+    pub bind_group: BindGroup,
+    _marker: PhantomData<T>,
+}
+
+type BakedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Ref<'static, BakedShapes>,
+        Option<&'static InheritedVisibility>,
+    ),
+>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn extract_baked_shapes_2d<T: ShapeData>(
+    mut commands: Commands,
+    baked: Extract<BakedQuery>,
+    mut batches: ResMut<BakedShapeBatches<T>>,
+    render_device: Res<RenderDevice>,
+    pipeline: Res<ShapePipeline<T>>,
+    instance_buffer: Res<ShapeInstanceBuffer<T>>,
+    mut instance_data: ResMut<Shape2dInstances<T>>,
+    mut materials: ResMut<Shape2dMaterials<T>>,
+    mut stats: ResMut<ShapeTypeStats<T>>,
+) {
+    extract_baked_shapes(
+        ShapePipelineType::Shape2d,
+        &mut commands,
+        &baked,
+        &mut batches,
+        &render_device,
+        &pipeline,
+        &instance_buffer,
+        &mut materials,
+        &mut instance_data,
+        &mut stats,
+    );
+}
+
+#[cfg(feature = "shape_3d")]
+#[allow(clippy::too_many_arguments)]
+pub fn extract_baked_shapes_3d<T: ShapeData>(
+    mut commands: Commands,
+    baked: Extract<BakedQuery>,
+    mut batches: ResMut<BakedShapeBatches<T>>,
+    render_device: Res<RenderDevice>,
+    pipeline: Res<ShapePipeline<T>>,
+    instance_buffer: Res<ShapeInstanceBuffer<T>>,
+    mut instance_data: ResMut<Shape3dInstances<T>>,
+    mut materials: ResMut<Shape3dMaterials<T>>,
+    mut stats: ResMut<ShapeTypeStats<T>>,
+) {
+    extract_baked_shapes(
+        ShapePipelineType::Shape3d,
+        &mut commands,
+        &baked,
+        &mut batches,
+        &render_device,
+        &pipeline,
+        &instance_buffer,
+        &mut materials,
+        &mut instance_data,
+        &mut stats,
+    );
+}
+
+/// Rebuilds the batches of changed [`BakedShapes`] and adds an entity drawing each visible batch to the shapes of this frame.
+#[allow(clippy::too_many_arguments)]
+fn extract_baked_shapes<T: ShapeData>(
+    pipeline_type: ShapePipelineType,
+    commands: &mut Commands,
+    baked: &BakedQuery,
+    batches: &mut BakedShapeBatches<T>,
+    render_device: &RenderDevice,
+    pipeline: &ShapePipeline<T>,
+    instance_buffer: &ShapeInstanceBuffer<T>,
+    materials: &mut HashMap<ShapePipelineMaterial, Vec<Entity>>,
+    instance_data: &mut EntityHashMap<ShapeInstance<T>>,
+    stats: &mut ShapeTypeStats<T>,
+) {
+    // Drop the batches of baked shapes that have been removed
+    batches
+        .values
+        .retain(|(entity, pipeline), _| *pipeline != pipeline_type || baked.contains(*entity));
+
+    let storage = matches!(instance_buffer, ShapeInstanceBuffer::Storage(_));
+    for (entity, shapes, visibility) in baked.iter() {
+        let key = (entity, pipeline_type);
+        if shapes.is_changed() || !batches.values.contains_key(&key) {
+            let values = bake_batches(
+                &shapes,
+                pipeline_type,
+                storage,
+                render_device,
+                pipeline,
+                stats,
+            );
+            batches.values.insert(key, values);
+        }
+
+        if !visibility.map_or(true, InheritedVisibility::get) {
+            continue;
+        }
+
+        for batch in &batches.values[&key] {
+            let Some(bind_group) = &batch.bind_group else {
+                // Platforms without storage buffers upload baked instances each frame like immediate mode shapes
+                for data in &batch.instances {
+                    let entity = commands.spawn_empty().id();
+                    materials
+                        .entry(batch.material.clone())
+                        .or_default()
+                        .push(entity);
+                    instance_data.insert(entity, (batch.material.clone(), data.clone()));
+                }
+                continue;
+            };
+
+            let entity = commands
+                .spawn((
+                    batch.material.clone(),
+                    BakedShapeBatch::<T> {
+                        count: batch.count,
+                        bind_group: bind_group.clone(),
+                        _marker: PhantomData,
+                    },
+                ))
+                .id();
+            materials
+                .entry(batch.material.clone())
+                .or_default()
+                .push(entity);
+            instance_data.insert(entity, (batch.material.clone(), batch.first.clone()));
+        }
+    }
+}
+
+/// Group the baked instances of type `T` by material, uploading each group to it's own buffer when `storage` is true.
+fn bake_batches<T: ShapeData>(
+    shapes: &BakedShapes,
+    pipeline_type: ShapePipelineType,
+    storage: bool,
+    render_device: &RenderDevice,
+    pipeline: &ShapePipeline<T>,
+    stats: &mut ShapeTypeStats<T>,
+) -> Vec<BakedBatch<T>> {
+    let Some(iter) = shapes.storage.get::<T>(pipeline_type) else {
+        return Vec::new();
+    };
+
+    // Keep batches in the order their first instance was baked
+    let mut groups: Vec<(ShapePipelineMaterial, Vec<T>)> = Vec::new();
+    let mut indices = HashMap::new();
+    for (material, data) in iter {
+        let index = *indices.entry(material.clone()).or_insert_with(|| {
+            groups.push((material.clone(), Vec::new()));
+            groups.len() - 1
+        });
+        groups[index].1.push(data.clone());
+    }
+
+    groups
+        .into_iter()
+        .map(|(material, instances)| {
+            let first = instances[0].clone();
+            let count = instances.len() as u32;
+            if !storage {
+                return BakedBatch {
+                    material,
+                    first,
+                    count,
+                    bind_group: None,
+                    instances,
+                };
+            }
+
+            let mut writer = encase::StorageBuffer::new(Vec::new());
+            writer.write(&instances).unwrap();
+            let contents = writer.into_inner();
+            stats.baked_bytes += contents.len() as u64;
+            let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: Some("baked_shape_buffer"),
+                contents: &contents,
+                usage: BufferUsages::STORAGE,
+            });
+            BakedBatch {
+                material,
+                first,
+                count,
+                bind_group: Some(render_device.create_bind_group(
+                    "baked_shape_bind_group",
+                    &pipeline.layout,
+                    &BindGroupEntries::single(buffer.as_entire_binding()),
+                )),
+                instances: Vec::new(),
+            }
+        })
+        .collect()
+}
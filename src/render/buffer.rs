@@ -0,0 +1,142 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{
+            encase, BindingResource, Buffer, BufferDescriptor, BufferUsages, GpuArrayBuffer,
+            GpuArrayBufferIndex,
+        },
+        renderer::{RenderDevice, RenderQueue},
+    },
+    utils::nonmax::NonMaxU32,
+};
+
+use crate::render::ShapeData;
+
+/// Smallest allocation made for a storage instance buffer, in bytes.
+const MIN_CAPACITY: u64 = 64 * 1024;
+
+/// Instance data for a shape type, uploaded once per frame and bound to the shape shaders.
+///
+/// On platforms with storage buffers instances are written into a persistent buffer that grows to the next power of
+/// two when it runs out of space and is otherwise reused each frame, so the buffer and it's bind group are only
+/// recreated when the number of instances outgrows every previous frame. Writes are staged by the render queue so
+/// the same buffer can be rewritten while the previous frame is still in flight.
+///
+/// Platforms without storage buffers fall back to Bevy's [`GpuArrayBuffer`], which batches instances into uniform buffers.
+#[derive(Resource)]
+pub enum ShapeInstanceBuffer<T: ShapeData> {
+    Uniform(GpuArrayBuffer<T>),
+    Storage(StorageInstanceBuffer<T>),
+}
+
+impl<T: ShapeData> ShapeInstanceBuffer<T> {
+    pub fn new(device: &RenderDevice) -> Self {
+        if GpuArrayBuffer::<T>::batch_size(device).is_some() {
+            Self::Uniform(GpuArrayBuffer::new(device))
+        } else {
+            Self::Storage(StorageInstanceBuffer::default())
+        }
+    }
+
+    /// Queue an instance to be uploaded this frame, returning it's index for the draw call.
+    pub fn push(&mut self, value: T) -> GpuArrayBufferIndex<T> {
+        match self {
+            Self::Uniform(buffer) => buffer.push(value),
+            Self::Storage(buffer) => buffer.push(value),
+        }
+    }
+
+    /// Upload every instance pushed this frame and clear them, ready for the next frame.
+    pub fn write_buffer(&mut self, device: &RenderDevice, queue: &RenderQueue) {
+        match self {
+            Self::Uniform(buffer) => {
+                buffer.write_buffer(device, queue);
+                buffer.clear();
+            }
+            Self::Storage(buffer) => buffer.write_buffer(device, queue),
+        }
+    }
+
+    pub fn binding(&self) -> Option<BindingResource> {
+        match self {
+            Self::Uniform(buffer) => buffer.binding(),
+            Self::Storage(buffer) => buffer
+                .buffer
+                .as_ref()
+                .map(|buffer| buffer.as_entire_binding()),
+        }
+    }
+
+    /// Number of times the underlying buffer has been reallocated, bind groups only need to be recreated when it changes.
+    ///
+    /// Uniform buffers are rewritten every frame so this is always None for them.
+    pub fn generation(&self) -> Option<u64> {
+        match self {
+            Self::Uniform(_) => None,
+            Self::Storage(buffer) => Some(buffer.generation),
+        }
+    }
+}
+
+/// Persistent storage buffer of instances, see [`ShapeInstanceBuffer`].
+pub struct StorageInstanceBuffer<T: ShapeData> {
+    values: Vec<T>,
+    scratch: Vec<u8>,
+    buffer: Option<Buffer>,
+    generation: u64,
+}
+
+impl<T: ShapeData> Default for StorageInstanceBuffer<T> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            scratch: Vec::new(),
+            buffer: None,
+            generation: 0,
+        }
+    }
+}
+
+impl<T: ShapeData> StorageInstanceBuffer<T> {
+    fn push(&mut self, value: T) -> GpuArrayBufferIndex<T> {
+        let index = NonMaxU32::new(self.values.len() as u32).unwrap();
+        self.values.push(value);
+        GpuArrayBufferIndex {
+            index,
+            dynamic_offset: None,
+            element_type: PhantomData,
+        }
+    }
+
+    fn write_buffer(&mut self, device: &RenderDevice, queue: &RenderQueue) {
+        if self.values.is_empty() {
+            return;
+        }
+
+        // Reuse the scratch allocation from previous frames to serialize instances into the layout expected by the shaders
+        let mut scratch = std::mem::take(&mut self.scratch);
+        scratch.clear();
+        let mut writer = encase::StorageBuffer::new(scratch);
+        writer.write(&self.values).unwrap();
+        self.scratch = writer.into_inner();
+        self.values.clear();
+
+        let size = self.scratch.len() as u64;
+        let capacity = self.buffer.as_ref().map_or(0, |buffer| buffer.size());
+        if size > capacity {
+            self.buffer = Some(device.create_buffer(&BufferDescriptor {
+                label: Some("shape_instance_buffer"),
+                size: size.next_power_of_two().max(MIN_CAPACITY),
+                usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+            self.generation += 1;
+        }
+
+        if let Some(buffer) = &self.buffer {
+            queue.write_buffer(buffer, 0, &self.scratch);
+        }
+    }
+}
@@ -30,6 +30,7 @@ pub type DrawShapeCommand<T> = (
     SetShapeViewBindGroup<0>,
     SetShapeBindGroup<T, 1>,
     SetShapeTextureBindGroup<2>,
+    SetShapeClipBindGroup,
     DrawShape<T>,
 );
 
@@ -93,9 +94,70 @@ pub fn prepare_shape_texture_bind_groups(
     }
 }
 
+/// Uniform matching the `Clip` struct in core.wgsl.
+#[derive(ShaderType)]
+pub struct ClipUniform {
+    inverse_transform: Mat4,
+    size: Vec2,
+    corner_radius: f32,
+    masked: u32,
+    scissor: Vec4,
+}
+
+impl From<&ClipKey> for ClipUniform {
+    fn from(clip: &ClipKey) -> Self {
+        let (inverse_transform, size, corner_radius) =
+            clip.mask().unwrap_or((Mat4::IDENTITY, Vec2::ZERO, 0.0));
+        let scissor = clip
+            .scissor()
+            .map_or(Vec4::new(f32::MIN, f32::MIN, f32::MAX, f32::MAX), |rect| {
+                rect.min.extend(rect.max.x).extend(rect.max.y)
+            });
+        Self {
+            inverse_transform,
+            size,
+            corner_radius,
+            masked: clip.mask().is_some() as u32,
+            scissor,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ShapeClipBindGroups {
+    values: HashMap<ClipKey, BindGroup>,
+}
+
+pub fn prepare_shape_clip_bind_groups(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    shape_pipelines: Res<ShapePipelines>,
+    batches: Query<&ShapePipelineMaterial>,
+    mut clip_bind_groups: ResMut<ShapeClipBindGroups>,
+) {
+    // Masks and scissor rectangles often move each frame so only bind groups still in use are kept
+    let mut previous = std::mem::take(&mut clip_bind_groups.values);
+    for clip in batches.iter().filter_map(|material| material.clip) {
+        if clip_bind_groups.values.contains_key(&clip) {
+            continue;
+        }
+        let bind_group = previous.remove(&clip).unwrap_or_else(|| {
+            let mut buffer = UniformBuffer::from(ClipUniform::from(&clip));
+            buffer.write_buffer(&render_device, &render_queue);
+            render_device.create_bind_group(
+                "shape_clip_bind_group",
+                &shape_pipelines.clip_layout,
+                &BindGroupEntries::single(buffer.binding().unwrap()),
+            )
+        });
+        clip_bind_groups.values.insert(clip, bind_group);
+    }
+}
+
 #[derive(Resource)]
 pub struct ShapeBindGroup<T: ShapeData> {
     pub value: BindGroup,
+    generation: Option<u64>,
     _marker: PhantomData<T>,
 }
 
@@ -103,8 +165,17 @@ pub fn prepare_shape_bind_group<T: ShapeData + 'static>(
     mut commands: Commands,
     pipeline: Res<ShapePipeline<T>>,
     render_device: Res<RenderDevice>,
-    shape_buffer: Res<GpuArrayBuffer<T>>,
+    shape_buffer: Res<ShapeInstanceBuffer<T>>,
+    bind_group: Option<Res<ShapeBindGroup<T>>>,
 ) {
+    // Persistent buffers keep their bind group until they are reallocated
+    let generation = shape_buffer.generation();
+    if generation.is_some()
+        && bind_group.is_some_and(|bind_group| bind_group.generation == generation)
+    {
+        return;
+    }
+
     if let Some(binding) = shape_buffer.binding() {
         commands.insert_resource(ShapeBindGroup {
             value: render_device.create_bind_group(
@@ -112,6 +183,7 @@ pub fn prepare_shape_bind_group<T: ShapeData + 'static>(
                 &pipeline.layout,
                 &BindGroupEntries::single(binding),
             ),
+            generation,
             _marker: PhantomData::<T>,
         });
     }
@@ -167,6 +239,34 @@ impl<const I: usize, P: PhaseItem> RenderCommand<P> for SetShapeTextureBindGroup
     }
 }
 
+/// Binds the clip mask and scissor rectangle of a batch, which directly follows the texture bind group when the batch is textured.
+pub struct SetShapeClipBindGroup;
+
+impl<P: PhaseItem> RenderCommand<P> for SetShapeClipBindGroup {
+    type ViewQuery = ();
+    type ItemQuery = Read<ShapePipelineMaterial>;
+    type Param = SRes<ShapeClipBindGroups>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        material: Option<&'w ShapePipelineMaterial>,
+        bind_groups: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(material) = material else {
+            return RenderCommandResult::Success;
+        };
+        if let Some(clip) = &material.clip {
+            let index = if material.texture.is_some() { 3 } else { 2 };
+            let bind_groups = bind_groups.into_inner();
+            pass.set_bind_group(index, bind_groups.values.get(clip).unwrap(), &[]);
+        }
+        RenderCommandResult::Success
+    }
+}
+
 pub struct SetShapeBindGroup<T: ShapeData, const I: usize>(PhantomData<T>);
 
 impl<const I: usize, T: ShapeData + 'static, P: PhaseItem> RenderCommand<P>
@@ -174,16 +274,22 @@ impl<const I: usize, T: ShapeData + 'static, P: PhaseItem> RenderCommand<P>
 {
     type Param = SRes<ShapeBindGroup<T>>;
     type ViewQuery = ();
-    type ItemQuery = ();
+    type ItemQuery = Option<Read<BakedShapeBatch<T>>>;
 
     #[inline]
     fn render<'w>(
         item: &P,
         _view: (),
-        _item_query: Option<()>,
+        baked: Option<Option<&'w BakedShapeBatch<T>>>,
         shape_bind_group: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
+        // Baked shapes are drawn from their own buffer
+        if let Some(baked) = baked.flatten() {
+            pass.set_bind_group(I, &baked.bind_group, &[]);
+            return RenderCommandResult::Success;
+        }
+
         let mut dynamic_offsets: [u32; 1] = Default::default();
         let mut offset_count = 0;
         if let Some(dynamic_offset) = item.dynamic_offset() {
@@ -202,7 +308,7 @@ impl<const I: usize, T: ShapeData + 'static, P: PhaseItem> RenderCommand<P>
 pub struct DrawShape<T: ShapeData>(PhantomData<T>);
 
 impl<P: PhaseItem, T: ShapeData> RenderCommand<P> for DrawShape<T> {
-    type Param = SRes<QuadVertices>;
+    type Param = (SRes<QuadVertices>, SRes<ShapeTypeStats<T>>);
     type ViewQuery = ();
     type ItemQuery = ();
 
@@ -211,7 +317,7 @@ impl<P: PhaseItem, T: ShapeData> RenderCommand<P> for DrawShape<T> {
         item: &P,
         _view: (),
         _item_query: Option<()>,
-        quad: SystemParamItem<'w, '_, Self::Param>,
+        (quad, stats): SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
         let batch_range = item.batch_range();
@@ -223,6 +329,7 @@ impl<P: PhaseItem, T: ShapeData> RenderCommand<P> for DrawShape<T> {
         );
         pass.set_vertex_buffer(0, quad.into_inner().buffer.slice(..));
         pass.draw(0..T::VERTICES, batch_range.clone());
+        stats.add_draw_call();
 
         RenderCommandResult::Success
     }
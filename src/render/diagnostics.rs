@@ -0,0 +1,248 @@
+use std::{
+    any::type_name,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticId, DiagnosticMeasurement, DiagnosticsStore, RegisterDiagnostic,
+    },
+    prelude::*,
+    render::{render_resource::ShaderType, Render, RenderApp, RenderSet},
+    utils::Instant,
+};
+
+use crate::render::{ShapeComponent, ShapeData};
+
+/// Counters for the shapes of type `T` rendered this frame, reset once they have been collected.
+#[derive(Resource)]
+pub struct ShapeTypeStats<T: ShapeData> {
+    /// Instances written to each view's render phases, including baked instances.
+    pub instances: u32,
+    /// Instances uploaded to the instance buffer this frame.
+    pub uploaded_instances: u32,
+    /// Batches of instances after merging neighbouring phase items.
+    pub batches: u32,
+    /// Bytes uploaded when baking shapes this frame.
+    pub baked_bytes: u64,
+    /// Draw calls issued, incremented while render passes are encoded.
+    pub draw_calls: AtomicU32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: ShapeData> Default for ShapeTypeStats<T> {
+    fn default() -> Self {
+        Self {
+            instances: 0,
+            uploaded_instances: 0,
+            batches: 0,
+            baked_bytes: 0,
+            draw_calls: AtomicU32::new(0),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ShapeData> ShapeTypeStats<T> {
+    /// Count a draw call, safe to call from render commands encoding passes in parallel.
+    pub fn add_draw_call(&self) {
+        self.draw_calls.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Counters of a single shape type sent to the main world.
+struct ShapeTypeFrameStats {
+    type_name: &'static str,
+    instances: u32,
+    batches: u32,
+    draw_calls: u32,
+    uploaded_bytes: u64,
+}
+
+/// Counters of every shape type for the current frame, only present when [`ShapeDiagnosticsPlugin`] has been added.
+#[derive(Resource, Default)]
+struct ShapeFrameStats(Vec<ShapeTypeFrameStats>);
+
+#[derive(Resource)]
+struct ShapeStatsSender(Sender<ShapeFrameStats>);
+
+#[derive(Resource)]
+struct ShapeStatsReceiver(Mutex<Receiver<ShapeFrameStats>>);
+
+/// Takes the counters of shape type `T` for this frame, adding them to the frame's stats when diagnostics are enabled.
+pub fn collect_shape_type_stats<T: ShapeData>(
+    mut stats: ResMut<ShapeTypeStats<T>>,
+    frame: Option<ResMut<ShapeFrameStats>>,
+) {
+    let stats = std::mem::take(stats.as_mut());
+    if let Some(mut frame) = frame {
+        frame.0.push(ShapeTypeFrameStats {
+            type_name: type_name::<T::Component>(),
+            instances: stats.instances,
+            batches: stats.batches,
+            draw_calls: stats.draw_calls.into_inner(),
+            uploaded_bytes: stats.uploaded_instances as u64 * T::min_size().get()
+                + stats.baked_bytes,
+        });
+    }
+}
+
+pub(crate) fn send_shape_stats(mut frame: ResMut<ShapeFrameStats>, sender: Res<ShapeStatsSender>) {
+    // The main world may have already shut down in which case there is nobody to receive the stats
+    let _ = sender.0.send(std::mem::take(frame.as_mut()));
+}
+
+fn receive_shape_stats(receiver: Res<ShapeStatsReceiver>, mut store: ResMut<DiagnosticsStore>) {
+    let receiver = receiver.0.lock().unwrap();
+    for frame in receiver.try_iter() {
+        let time = Instant::now();
+        let (mut instances, mut batches, mut draw_calls, mut uploaded_bytes) = (0, 0, 0, 0);
+        for stats in &frame.0 {
+            instances += stats.instances;
+            batches += stats.batches;
+            draw_calls += stats.draw_calls;
+            uploaded_bytes += stats.uploaded_bytes;
+        }
+        add_measurement(
+            &mut store,
+            time,
+            ShapeDiagnosticsPlugin::INSTANCES,
+            instances as f64,
+        );
+        add_measurement(
+            &mut store,
+            time,
+            ShapeDiagnosticsPlugin::BATCHES,
+            batches as f64,
+        );
+        add_measurement(
+            &mut store,
+            time,
+            ShapeDiagnosticsPlugin::DRAW_CALLS,
+            draw_calls as f64,
+        );
+        add_measurement(
+            &mut store,
+            time,
+            ShapeDiagnosticsPlugin::UPLOADED_BYTES,
+            uploaded_bytes as f64,
+        );
+
+        for stats in frame.0 {
+            // Shape types are only known once they have been rendered so their diagnostics are registered on demand
+            let id = type_instances_id(stats.type_name);
+            if store.get(id).is_none() {
+                store.add(Diagnostic::new(
+                    id,
+                    format!("shape_instances/{}", shape_name(stats.type_name)),
+                    ShapeDiagnosticsPlugin::HISTORY_LENGTH,
+                ));
+            }
+            add_measurement(&mut store, time, id, stats.instances as f64);
+        }
+    }
+}
+
+fn add_measurement(store: &mut DiagnosticsStore, time: Instant, id: DiagnosticId, value: f64) {
+    if let Some(diagnostic) = store.get_mut(id).filter(|d| d.is_enabled) {
+        diagnostic.add_measurement(DiagnosticMeasurement { time, value });
+    }
+}
+
+/// Derive the id of the per type instance diagnostic from the name of the shape's component.
+fn type_instances_id(type_name: &str) -> DiagnosticId {
+    // FNV-1a, stable across runs unlike the std hasher
+    let hash = type_name.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    DiagnosticId::from_u128(0x8e2f_0b1c_5d4a_4f36_0000_0000_0000_0000 | hash as u128)
+}
+
+/// Convert the type name of a shape component into a short snake case name, `DiscComponent` becomes `disc`.
+fn shape_name(type_name: &str) -> String {
+    let name = type_name.rsplit("::").next().unwrap_or(type_name);
+    let name = name.strip_suffix("Component").unwrap_or(name);
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Plugin that reports counters from the shape renderer to Bevy's [`DiagnosticsStore`].
+///
+/// Registers totals across every shape type under the associated constants of this plugin, as well as the number of
+/// instances of each shape type under `shape_instances/<shape>` once that type is first rendered,
+/// see [`ShapeDiagnosticsPlugin::instances_of`]. Counters are summed across every view, so a shape seen by two cameras
+/// is counted twice. Measurements arrive a frame late when rendering is pipelined.
+pub struct ShapeDiagnosticsPlugin;
+
+impl ShapeDiagnosticsPlugin {
+    /// Shape instances drawn across all shape types.
+    pub const INSTANCES: DiagnosticId =
+        DiagnosticId::from_u128(0x8e2f_0b1c_5d4a_4f35_9a61_2c7e_3b0d_0001);
+    /// Batches of shape instances after merging neighbouring instances with the same material.
+    pub const BATCHES: DiagnosticId =
+        DiagnosticId::from_u128(0x8e2f_0b1c_5d4a_4f35_9a61_2c7e_3b0d_0002);
+    /// Draw calls issued for shapes.
+    pub const DRAW_CALLS: DiagnosticId =
+        DiagnosticId::from_u128(0x8e2f_0b1c_5d4a_4f35_9a61_2c7e_3b0d_0003);
+    /// Bytes of instance data uploaded to the GPU, excluding alignment padding.
+    pub const UPLOADED_BYTES: DiagnosticId =
+        DiagnosticId::from_u128(0x8e2f_0b1c_5d4a_4f35_9a61_2c7e_3b0d_0004);
+
+    /// Number of measurements kept for each diagnostic.
+    pub const HISTORY_LENGTH: usize = 20;
+
+    /// Id of the diagnostic counting instances drawn of the shape type `T`.
+    pub fn instances_of<T: ShapeComponent>() -> DiagnosticId {
+        type_instances_id(type_name::<T>())
+    }
+}
+
+impl Plugin for ShapeDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        app.register_diagnostic(Diagnostic::new(
+            Self::INSTANCES,
+            "shape_instances",
+            Self::HISTORY_LENGTH,
+        ))
+        .register_diagnostic(Diagnostic::new(
+            Self::BATCHES,
+            "shape_batches",
+            Self::HISTORY_LENGTH,
+        ))
+        .register_diagnostic(Diagnostic::new(
+            Self::DRAW_CALLS,
+            "shape_draw_calls",
+            Self::HISTORY_LENGTH,
+        ))
+        .register_diagnostic(
+            Diagnostic::new(
+                Self::UPLOADED_BYTES,
+                "shape_uploaded_bytes",
+                Self::HISTORY_LENGTH,
+            )
+            .with_suffix("B"),
+        )
+        .insert_resource(ShapeStatsReceiver(Mutex::new(receiver)))
+        .add_systems(PreUpdate, receive_shape_stats);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .insert_resource(ShapeStatsSender(sender))
+            .init_resource::<ShapeFrameStats>()
+            .add_systems(Render, send_shape_stats.in_set(RenderSet::Cleanup));
+    }
+}
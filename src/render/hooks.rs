@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+use super::HOOKS_HANDLE;
+
+/// Identifies a [`ShapeHookShader`] registered with [`ShapeShaderHooks`], set on shapes with [`ShapeConfig::shader_hook`](crate::prelude::ShapeConfig::shader_hook).
+///
+/// Ids are only created by [`ShapeShaderHooks::add`] and are only meaningful to the app they were registered in,
+/// so they are neither reflected nor serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ShapeShaderHook(u8);
+
+impl ShapeShaderHook {
+    /// Value of the `SHAPE_HOOK` shader def of shapes using this hook.
+    pub(crate) fn id(&self) -> u8 {
+        self.0
+    }
+}
+
+/// A WGSL module customizing how discs, rectangles and regular polygons are drawn, similar to a material extension.
+///
+/// The module must declare it's import path with `#define_import_path` and define the functions it hooks:
+/// - `fn fill(color: vec4<f32>, pos: vec2<f32>, half_size: vec2<f32>, frag_coord: vec4<f32>, user_data: vec4<f32>) -> vec4<f32>`
+///   returns the fill color of the shape, given the color of it's fill and gradient.
+/// - `fn distance(dist: f32, pos: vec2<f32>, half_size: vec2<f32>, user_data: vec4<f32>) -> f32` returns the signed
+///   distance from the edge of the shape, negative inside it, used for it's edge, outline, shadow and glow.
+///
+/// `pos` is the position within the shape, which spans from `-half_size` to `half_size` in the same units as the
+/// distance, `frag_coord` is the position of the fragment in pixels and `user_data` is the shape's
+/// [`ShapeConfig::user_data`](crate::prelude::ShapeConfig::user_data). Modules may import `bevy_vector_shapes::core`
+/// for shared helpers.
+#[derive(Clone, Debug)]
+pub struct ShapeHookShader {
+    /// Shader asset of the module, kept loaded while the hook is registered.
+    pub shader: Handle<Shader>,
+    /// Import path declared by the module with `#define_import_path`.
+    pub import_path: String,
+    /// Whether the module defines `fill`.
+    pub fill: bool,
+    /// Whether the module defines `distance`.
+    pub distance: bool,
+}
+
+impl ShapeHookShader {
+    pub fn new(shader: Handle<Shader>, import_path: impl Into<String>) -> Self {
+        Self {
+            shader,
+            import_path: import_path.into(),
+            fill: false,
+            distance: false,
+        }
+    }
+
+    pub fn with_fill(mut self) -> Self {
+        self.fill = true;
+        self
+    }
+
+    pub fn with_distance(mut self) -> Self {
+        self.distance = true;
+        self
+    }
+}
+
+/// Resource of the [`ShapeHookShader`]s that shapes can be drawn with.
+///
+/// Each shape drawn with a hook is specialized into it's own pipeline, so hooks are best kept to a handful of effects
+/// such as procedural noise fills or scanlines. Hooks apply to discs, rectangles and regular polygons, other shapes
+/// ignore them.
+#[derive(Resource, Default)]
+pub struct ShapeShaderHooks {
+    pub(crate) hooks: Vec<ShapeHookShader>,
+}
+
+impl ShapeShaderHooks {
+    /// Register a hook, returning the id to draw shapes with it.
+    ///
+    /// # Panics
+    /// Panics if more than 255 hooks are registered.
+    pub fn add(&mut self, hook: ShapeHookShader) -> ShapeShaderHook {
+        // Ids start from 1 so that 0 never refers to a hook
+        let id = u8::try_from(self.hooks.len() + 1)
+            .expect("at most 255 shape shader hooks are supported");
+        self.hooks.push(hook);
+        ShapeShaderHook(id)
+    }
+
+    pub fn get(&self, hook: ShapeShaderHook) -> Option<&ShapeHookShader> {
+        (hook.0 as usize)
+            .checked_sub(1)
+            .and_then(|index| self.hooks.get(index))
+    }
+}
+
+/// Generate the `bevy_vector_shapes::hooks` module, which calls the hook selected by the `SHAPE_HOOK` shader def.
+pub(crate) fn hooks_shader(hooks: &[ShapeHookShader]) -> Shader {
+    let mut imports = String::new();
+    let mut fill = String::new();
+    let mut distance = String::new();
+    for (index, hook) in hooks.iter().enumerate() {
+        let id = index + 1;
+        // Only import the module of the hook in use so that other hooks do not need to be loaded
+        imports.push_str(&format!(
+            "#ifdef SHAPE_HOOK\n#if SHAPE_HOOK == {id}\n#import {} as hook_{id}\n#endif\n#endif\n",
+            hook.import_path
+        ));
+        if hook.fill {
+            fill.push_str(&format!(
+                "#if SHAPE_HOOK == {id}\n    out = hook_{id}::fill(color, pos, half_size, frag_coord, user_data);\n#endif\n"
+            ));
+        }
+        if hook.distance {
+            distance.push_str(&format!(
+                "#if SHAPE_HOOK == {id}\n    out = hook_{id}::distance(dist, pos, half_size, user_data);\n#endif\n"
+            ));
+        }
+    }
+
+    let source = format!(
+        "#define_import_path bevy_vector_shapes::hooks
+{imports}
+fn fill(color: vec4<f32>, pos: vec2<f32>, half_size: vec2<f32>, frag_coord: vec4<f32>, user_data: vec4<f32>) -> vec4<f32> {{
+    var out = color;
+#ifdef SHAPE_HOOK
+{fill}#endif
+    return out;
+}}
+
+fn distance(dist: f32, pos: vec2<f32>, half_size: vec2<f32>, user_data: vec4<f32>) -> f32 {{
+    var out = dist;
+#ifdef SHAPE_HOOK
+{distance}#endif
+    return out;
+}}
+"
+    );
+    Shader::from_wgsl(source, "bevy_vector_shapes/hooks.wgsl")
+}
+
+/// Regenerates the hooks module when hooks are registered, recompiling the shape pipelines that use it.
+pub fn update_shape_hooks(hooks: Res<ShapeShaderHooks>, mut shaders: ResMut<Assets<Shader>>) {
+    shaders.insert(HOOKS_HANDLE, hooks_shader(&hooks.hooks));
+}
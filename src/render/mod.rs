@@ -1,17 +1,16 @@
+use std::any::TypeId;
 use std::hash::Hasher;
 use std::marker::PhantomData;
 use std::{hash::Hash, ops::Deref};
 
 use bevy::{
     asset::load_internal_asset,
-    core_pipeline::{
-        core_2d::Transparent2d,
-        core_3d::{AlphaMask3d, Opaque3d, Transparent3d},
-    },
+    core_pipeline::core_2d::Transparent2d,
     ecs::entity::EntityHashMap,
     prelude::*,
     reflect::GetTypeRegistration,
     render::{
+        primitives::Aabb,
         render_phase::{
             AddRenderCommand, CachedRenderPipelinePhaseItem, DrawFunctionId, RenderPhase,
         },
@@ -20,15 +19,22 @@ use bevy::{
             ShaderRef,
         },
         renderer::{RenderDevice, RenderQueue},
-        view::RenderLayers,
+        view::{RenderLayers, VisibilitySystems},
         Extract, Render, RenderApp, RenderSet,
     },
-    utils::{nonmax::NonMaxU32, FloatOrd},
+    transform::TransformSystem,
+    utils::{nonmax::NonMaxU32, FloatOrd, HashMap, Parallel},
 };
 use bitfield::bitfield;
 use wgpu::{util::BufferInitDescriptor, BufferUsages, VertexAttribute};
 
-use crate::prelude::*;
+use crate::{
+    painter::{
+        bake_retained_shapes, clear_parallel_shapes, interpolate_fixed_shapes,
+        merge_parallel_shapes, redraw_changed_canvases, update_canvases,
+    },
+    prelude::*,
+};
 
 pub(crate) mod pipeline;
 use pipeline::*;
@@ -39,15 +45,49 @@ use commands::*;
 pub(crate) mod render_2d;
 use render_2d::*;
 
+#[cfg(feature = "shape_3d")]
 pub(crate) mod render_3d;
+#[cfg(feature = "shape_3d")]
 use render_3d::*;
 
+#[cfg(feature = "shape_3d")]
+pub(crate) mod oit;
+#[cfg(feature = "shape_3d")]
+use oit::*;
+
+pub(crate) mod readback;
+
+pub(crate) mod buffer;
+use buffer::*;
+
+pub(crate) mod bake;
+use bake::*;
+
+mod diagnostics;
+pub use diagnostics::ShapeDiagnosticsPlugin;
+use diagnostics::*;
+
+mod hooks;
+pub use hooks::*;
+
+mod svg;
+pub use svg::SvgElement;
+pub(crate) use svg::{arc_path, rounded_rect_path, sector_path, stroke_offset};
+
+#[cfg(feature = "bevy_ui")]
+mod ui_node;
+#[cfg(feature = "bevy_ui")]
+pub use ui_node::*;
+
 /// Handler to shader containing shared functionality.
 pub const CORE_HANDLE: Handle<Shader> = Handle::weak_from_u128(13215291696265391738);
 
 /// Handler to shader containing shared constants.
 pub const CONSTANTS_HANDLE: Handle<Shader> = Handle::weak_from_u128(14523762397345674763);
 
+/// Handler to the generated shader calling the registered [`ShapeHookShader`]s.
+pub const HOOKS_HANDLE: Handle<Shader> = Handle::weak_from_u128(11429680274465397811);
+
 /// Handler to shader for drawing discs.
 pub const DISC_HANDLE: Handle<Shader> = Handle::weak_from_u128(12563478638216678166);
 
@@ -55,14 +95,101 @@ pub const DISC_HANDLE: Handle<Shader> = Handle::weak_from_u128(12563478638216678
 pub const LINE_HANDLE: Handle<Shader> = Handle::weak_from_u128(13656934768948239208);
 
 /// Handler to shader for drawing regular polygons.
+#[cfg(feature = "ngon")]
 pub const NGON_HANDLE: Handle<Shader> = Handle::weak_from_u128(17394960287230910395);
 
 /// Handler to shader for drawing rectangles.
 pub const RECT_HANDLE: Handle<Shader> = Handle::weak_from_u128(15069348348279052351);
 
 /// Handler to shader for drawing triangles.
+#[cfg(feature = "triangle")]
 pub const TRIANGLE_HANDLE: Handle<Shader> = Handle::weak_from_u128(12344032791831516511);
 
+/// Handler to shader for drawing bézier curves.
+#[cfg(feature = "bezier")]
+pub const BEZIER_HANDLE: Handle<Shader> = Handle::weak_from_u128(16232398472938104571);
+
+/// Handler to shader for drawing polylines.
+pub const POLYLINE_HANDLE: Handle<Shader> = Handle::weak_from_u128(11873520945871263019);
+
+/// Handler to shader for drawing arbitrary polygons.
+pub const POLYGON_HANDLE: Handle<Shader> = Handle::weak_from_u128(14498312760234409127);
+
+/// Handler to shader for drawing ellipses.
+#[cfg(feature = "ellipse")]
+pub const ELLIPSE_HANDLE: Handle<Shader> = Handle::weak_from_u128(10392851736452019847);
+
+/// Handler to shader for drawing capsules.
+pub const CAPSULE_HANDLE: Handle<Shader> = Handle::weak_from_u128(17760234918264550923);
+
+/// Handler to shader for drawing stars.
+#[cfg(feature = "star")]
+pub const STAR_HANDLE: Handle<Shader> = Handle::weak_from_u128(13928475520674312861);
+
+/// Handler to shader for drawing arrows.
+pub const ARROW_HANDLE: Handle<Shader> = Handle::weak_from_u128(16601348926473025391);
+
+/// Handler to shader for drawing glyphs from an SDF font atlas.
+#[cfg(feature = "text")]
+pub const GLYPH_HANDLE: Handle<Shader> = Handle::weak_from_u128(12086475930183374629);
+
+/// Handler to shader for drawing superellipses.
+#[cfg(feature = "superellipse")]
+pub const SUPERELLIPSE_HANDLE: Handle<Shader> = Handle::weak_from_u128(15513872095826140363);
+
+/// Handler to shader for drawing grids.
+#[cfg(feature = "grid")]
+pub const GRID_HANDLE: Handle<Shader> = Handle::weak_from_u128(10837226459917310467);
+
+/// Handler to shader for drawing infinite reference grids.
+#[cfg(feature = "grid")]
+pub const REFERENCE_GRID_HANDLE: Handle<Shader> = Handle::weak_from_u128(17279573529123831788);
+
+/// Handler to shader for drawing points.
+#[cfg(feature = "point")]
+pub const POINT_HANDLE: Handle<Shader> = Handle::weak_from_u128(13318520467109254853);
+
+/// Handler to shader for drawing waves.
+pub const WAVE_HANDLE: Handle<Shader> = Handle::weak_from_u128(9647213508937266029);
+
+/// Shaders replacing the embedded shader of each shape type, see [`Shape2dPlugin::shaders`](crate::Shape2dPlugin::shaders).
+///
+/// Shaders given as a path are loaded through the asset server, so modified copies of the embedded shaders can be
+/// shipped with a project and hot reloaded with bevy's `file_watcher` feature. A replacement must keep the instance
+/// layout and entry points of the shader it replaces, see [`ShapeData`]. The embedded shader libraries such as
+/// `bevy_vector_shapes::core` remain available to import.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ShapeShaders {
+    shaders: HashMap<TypeId, ShaderRef>,
+}
+
+impl ShapeShaders {
+    /// Draw shapes with the instance data `T`, such as [`DiscData`], using the given shader.
+    pub fn with_shader<T: ShapeData>(mut self, shader: impl Into<ShaderRef>) -> Self {
+        self.insert::<T>(shader);
+        self
+    }
+
+    pub fn insert<T: ShapeData>(&mut self, shader: impl Into<ShaderRef>) {
+        self.shaders.insert(TypeId::of::<T>(), shader.into());
+    }
+
+    /// Returns the replacement shader for `T`, if any.
+    pub fn get<T: ShapeData>(&self) -> Option<&ShaderRef> {
+        self.shaders.get(&TypeId::of::<T>())
+    }
+
+    /// Add the replacements of `other`, overriding any existing replacements of the same type.
+    pub fn extend(&mut self, other: &ShapeShaders) {
+        self.shaders.extend(
+            other
+                .shaders
+                .iter()
+                .map(|(ty, shader)| (*ty, shader.clone())),
+        );
+    }
+}
+
 /// Load the libraries shaders as internal assets.
 pub fn load_shaders(app: &mut App) {
     load_internal_asset!(app, CORE_HANDLE, "shaders/core.wgsl", Shader::from_wgsl);
@@ -72,6 +199,10 @@ pub fn load_shaders(app: &mut App) {
         "shaders/constants.wgsl",
         Shader::from_wgsl
     );
+    let hooks = hooks_shader(&app.world.resource::<ShapeShaderHooks>().hooks);
+    app.world
+        .resource_mut::<Assets<Shader>>()
+        .insert(HOOKS_HANDLE, hooks);
     let defs = DiscData::shader_defs(app);
     load_internal_asset!(
         app,
@@ -80,7 +211,7 @@ pub fn load_shaders(app: &mut App) {
         Shader::from_wgsl_with_defs,
         defs
     );
-    let defs = NgonData::shader_defs(app);
+    let defs = LineData::shader_defs(app);
     load_internal_asset!(
         app,
         LINE_HANDLE,
@@ -88,34 +219,279 @@ pub fn load_shaders(app: &mut App) {
         Shader::from_wgsl_with_defs,
         defs
     );
-    let defs = NgonData::shader_defs(app);
+    #[cfg(feature = "ngon")]
+    {
+        let defs = NgonData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            NGON_HANDLE,
+            "shaders/shapes/ngon.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    let defs = RectData::shader_defs(app);
     load_internal_asset!(
         app,
-        NGON_HANDLE,
-        "shaders/shapes/ngon.wgsl",
+        RECT_HANDLE,
+        "shaders/shapes/rect.wgsl",
         Shader::from_wgsl_with_defs,
         defs
     );
-    let defs = RectData::shader_defs(app);
+    #[cfg(feature = "triangle")]
+    {
+        let defs = TriangleData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            TRIANGLE_HANDLE,
+            "shaders/shapes/tri.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    #[cfg(feature = "bezier")]
+    {
+        let defs = BezierData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            BEZIER_HANDLE,
+            "shaders/shapes/bezier.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    let defs = PolylineData::shader_defs(app);
     load_internal_asset!(
         app,
-        RECT_HANDLE,
-        "shaders/shapes/rect.wgsl",
+        POLYLINE_HANDLE,
+        "shaders/shapes/polyline.wgsl",
         Shader::from_wgsl_with_defs,
         defs
     );
+    let defs = PolygonData::shader_defs(app);
     load_internal_asset!(
         app,
-        TRIANGLE_HANDLE,
-        "shaders/shapes/tri.wgsl",
-        Shader::from_wgsl
+        POLYGON_HANDLE,
+        "shaders/shapes/polygon.wgsl",
+        Shader::from_wgsl_with_defs,
+        defs
+    );
+    #[cfg(feature = "ellipse")]
+    {
+        let defs = EllipseData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            ELLIPSE_HANDLE,
+            "shaders/shapes/ellipse.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    let defs = CapsuleData::shader_defs(app);
+    load_internal_asset!(
+        app,
+        CAPSULE_HANDLE,
+        "shaders/shapes/capsule.wgsl",
+        Shader::from_wgsl_with_defs,
+        defs
+    );
+    #[cfg(feature = "star")]
+    {
+        let defs = StarData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            STAR_HANDLE,
+            "shaders/shapes/star.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    let defs = ArrowData::shader_defs(app);
+    load_internal_asset!(
+        app,
+        ARROW_HANDLE,
+        "shaders/shapes/arrow.wgsl",
+        Shader::from_wgsl_with_defs,
+        defs
+    );
+    #[cfg(feature = "text")]
+    {
+        let defs = GlyphData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            GLYPH_HANDLE,
+            "shaders/shapes/glyph.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    #[cfg(feature = "superellipse")]
+    {
+        let defs = SuperellipseData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            SUPERELLIPSE_HANDLE,
+            "shaders/shapes/superellipse.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    #[cfg(feature = "grid")]
+    {
+        let defs = GridData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            GRID_HANDLE,
+            "shaders/shapes/grid.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    #[cfg(feature = "grid")]
+    {
+        let defs = ReferenceGridData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            REFERENCE_GRID_HANDLE,
+            "shaders/shapes/reference_grid.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+    #[cfg(feature = "point")]
+    {
+        let defs = PointData::shader_defs(app);
+        load_internal_asset!(
+            app,
+            POINT_HANDLE,
+            "shaders/shapes/point.wgsl",
+            Shader::from_wgsl_with_defs,
+            defs
+        );
+    }
+
+    let defs = WaveData::shader_defs(app);
+    load_internal_asset!(
+        app,
+        WAVE_HANDLE,
+        "shaders/shapes/wave.wgsl",
+        Shader::from_wgsl_with_defs,
+        defs
     );
 }
 
 /// A pair of [`ShapePipelineMaterial`] and [`ShapeData`] to be used for rendering.
 pub type ShapeInstance<T> = (ShapePipelineMaterial, T);
 
+/// Instance gathered by one of the threads extracting retained shapes.
+///
+/// Holds the entity of the shape it was created from, the entity it is keyed by if it is the shape's first instance, it's material and data.
+pub type ExtractedInstance<T> = (Entity, Option<Entity>, ShapePipelineMaterial, T);
+
+/// Merge the instances gathered by each thread during extraction, spawning entities for any that are not yet keyed.
+///
+/// Instances are ordered by the entity of their shape so that shapes with the same sort key are drawn in the same order every frame.
+pub(crate) fn insert_extracted_instances<T: ShapeData>(
+    commands: &mut Commands,
+    queue: &mut Parallel<Vec<ExtractedInstance<T>>>,
+    extracted: &mut Vec<ExtractedInstance<T>>,
+    materials: &mut HashMap<ShapePipelineMaterial, Vec<Entity>>,
+    instance_data: &mut EntityHashMap<ShapeInstance<T>>,
+) {
+    extracted.extend(queue.drain());
+    // Sorting is stable so each shape's instances keep the order they were gathered in
+    extracted.sort_by_key(|(shape, ..)| *shape);
+    for (_, entity, material, data) in extracted.drain(..) {
+        let entity = entity.unwrap_or_else(|| commands.spawn_empty().id());
+        materials.entry(material.clone()).or_default().push(entity);
+        instance_data.insert(entity, (material, data));
+    }
+}
+
+/// Passes each instance needed to draw a retained shape to `f` with it's material, including the shadow and glow drawn
+/// beneath it and the tinted copy drawn where it is occluded.
+///
+/// `alpha` is multiplied into each color of the shape, see [`inherited_alpha`]. Instances of the shape itself are
+/// passed with `true`, those drawn beneath or behind it with `false`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn retained_instances<T: ShapeComponent>(
+    shape: &T,
+    fill: &ShapeFill,
+    tf: &GlobalTransform,
+    alpha: f32,
+    material: &ShapePipelineMaterial,
+    occluded_tint: Option<Color>,
+    constant_screen_size: bool,
+    mut f: impl FnMut(bool, ShapePipelineMaterial, T::Data),
+) {
+    // Shadows and glows take their colors from the fill rather than the shape's data
+    let fill = &ShapeFill {
+        shadow: fill.shadow.map(|(shadow, thickness_type)| {
+            let color = shadow.color.with_a(shadow.color.a() * alpha);
+            (
+                Shadow::new(color, shadow.offset, shadow.blur),
+                thickness_type,
+            )
+        }),
+        glow: fill.glow.map(|(glow, thickness_type)| {
+            let color = glow.color.with_a(glow.color.a() * alpha);
+            (Glow::new(color, glow.radius, glow.falloff), thickness_type)
+        }),
+        ..*fill
+    };
+    shape.get_instances(tf, fill, |mut data| {
+        // Tint rather than fading the fill so that gradients and end colors are faded as well
+        if alpha != 1.0 {
+            data.tint(Color::rgba(1.0, 1.0, 1.0, alpha));
+        }
+        if constant_screen_size {
+            data.set_constant_screen_size(true);
+        }
+
+        // Draw the shadow of filled shapes beneath them
+        if let Some(shadow) = fill
+            .drop_shadow()
+            .and_then(|(shadow, thickness_type)| data.shadow(&shadow, thickness_type))
+        {
+            f(false, material.underlay(), shadow);
+        }
+
+        // Glows are also drawn beneath the shape, fading out from it's edge
+        if let Some(glow) = fill
+            .halo()
+            .and_then(|(glow, thickness_type)| data.glow(&glow, thickness_type))
+        {
+            f(false, material.underlay(), glow);
+        }
+
+        // Draw the shape again where it is hidden behind other geometry
+        if let Some(tint) = occluded_tint {
+            let mut data = data.clone();
+            data.tint(tint);
+            f(false, material.occluded(), data);
+        }
+
+        f(true, material.clone(), data);
+    });
+}
+
+/// Multiply a linear color from a shape's data by the given color, see [`ShapeData::tint`].
+pub fn tint_color(color: [f32; 4], tint: Color) -> [f32; 4] {
+    (Vec4::from(color) * Vec4::from(tint.as_linear_rgba_f32())).into()
+}
+
 /// Trait implemented by each shapes shader data, defines common methods used in the rendering pipeline.
+///
+/// Implement it alongside [`ShapeComponent`] to define a custom shape type with it's own instance data and shader,
+/// drawn with the same batching, sorting, clipping and blending as the built in shapes. Register the type with
+/// [`ShapeTypePlugin`] and [`ShapeType3dPlugin`] and draw it with [`ShapeSender::send`] or spawn it in a
+/// [`ShapeBundle`], see the `custom_shape` example.
+///
+/// The shader is drawn as a quad with vertices from -1 to 1 in the xy plane and must provide `vertex` and `fragment`
+/// entry points. Instances are bound as an array of a struct matching [`ShapeData::vertex_layout`] at group 1, which
+/// is a uniform array of `PER_OBJECT_BUFFER_BATCH_SIZE` instances when that def is set and a storage array otherwise.
+/// `bevy_vector_shapes::core` provides the view at group 0, `get_instance_index` to index the array, and
+/// `clip_mask` and `color_output` which fragments should pass through to support clipping and every blend mode.
 pub trait ShapeData: Send + Sync + GpuArrayBufferable + 'static {
     /// Corresponding component representing the given shape.
     type Component: ShapeComponent<Data = Self>;
@@ -131,6 +507,30 @@ pub trait ShapeData: Send + Sync + GpuArrayBufferable + 'static {
     }
     /// Transform of the shape to be used for z-ordering in 3D.
     fn transform(&self) -> Mat4;
+    /// Replace the transform of the shape, used when replaying a [`ShapeRecording`].
+    fn set_transform(&mut self, transform: Mat4);
+    /// Multiply each color of the shape by the given color, used when replaying a [`ShapeRecording`].
+    fn tint(&mut self, color: Color);
+    /// Set whether the shape keeps the same size on screen, see [`ShapeConfig::constant_screen_size`].
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool);
+    /// Returns the data for the shadow drawn beneath the shape, see [`Shadow`].
+    ///
+    /// Only filled discs, rectangles and regular polygons cast a shadow.
+    fn shadow(&self, _shadow: &Shadow, _thickness_type: ThicknessType) -> Option<Self> {
+        None
+    }
+    /// Returns the data for the glow drawn around the shape, see [`Glow`].
+    ///
+    /// Only filled discs, rectangles and regular polygons glow.
+    fn glow(&self, _glow: &Glow, _thickness_type: ThicknessType) -> Option<Self> {
+        None
+    }
+    /// Returns the shape as an SVG element, used when exporting a [`ShapeRecording`] with [`ShapeRecording::to_svg`].
+    ///
+    /// Only discs, arcs, rectangles and lines are exported, as solid fills and strokes.
+    fn svg(&self) -> Option<SvgElement> {
+        None
+    }
 
     fn shader_defs(app: &App) -> Vec<ShaderDefVal> {
         let mut shader_defs = Vec::with_capacity(1);
@@ -154,10 +554,34 @@ pub trait ShapeData: Send + Sync + GpuArrayBufferable + 'static {
 pub trait ShapeComponent: Component + GetTypeRegistration {
     type Data: ShapeData<Component = Self>;
     fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> Self::Data;
+
+    /// Passes each instance required to draw this component to `f`.
+    ///
+    /// Most shapes are drawn with the single instance from [`ShapeComponent::get_data`],
+    /// shapes made up of multiple instances such as [`PolylineComponent`] override this.
+    fn get_instances(&self, tf: &GlobalTransform, fill: &ShapeFill, mut f: impl FnMut(Self::Data)) {
+        f(self.get_data(tf, fill))
+    }
+
+    /// Returns whether a point in the xy plane of the shape's transform lies within the shape, used by [`ShapeHitTest`](crate::painter::ShapeHitTest).
+    ///
+    /// `thickness` is the thickness of outlines in world units, or `None` when the shape is filled.
+    /// Shapes without an accurate hit test are never hit, discs, rectangles, regular polygons and lines are supported.
+    fn contains(&self, _point: Vec2, _thickness: Option<f32>) -> bool {
+        false
+    }
+
+    /// Returns the bounds of the shape in the space of it's transform, used to cull shapes outside of every camera's frustum.
+    ///
+    /// Shapes without bounds are never culled, nor are shapes whose thickness is not measured in world units.
+    fn aabb(&self, _fill: &ShapeFill) -> Option<Aabb> {
+        None
+    }
 }
 
 /// Determines whether the shape is rendered in the 2D or 3D pipelines.
 #[derive(Resource, Copy, Clone, Reflect, Eq, PartialEq, Hash, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShapePipelineType {
     Shape3d,
     Shape2d,
@@ -167,10 +591,21 @@ bitfield! {
     /// Flags consumed in shape shaders
     pub struct Flags(u32);
     pub u32, from into ThicknessType, _, set_thickness_type: 1, 0;
-    pub u32, from into Alignment, _, set_alignment: 2, 2;
-    pub u32, _, set_hollow: 3, 3;
-    pub u32, from into Cap, _, set_cap: 5, 4;
-    pub u32, _, set_arc: 6, 6;
+    pub u32, hollow, set_hollow: 3, 3;
+    pub u32, from into Cap, cap, set_cap: 5, 4;
+    pub u32, arc, set_arc: 6, 6;
+    pub u32, from into Join, _, set_join: 8, 7;
+    pub u32, from into ArrowHead, _, set_arrow_head: 10, 9;
+    pub u32, _, set_double_headed: 11, 11;
+    pub u32, from into WaveType, _, set_wave_type: 12, 12;
+    pub u32, _, set_gradient: 14, 13;
+    pub u32, _, set_outlined: 15, 15;
+    pub u32, from into StrokeAlignment, stroke_alignment, set_stroke_alignment: 17, 16;
+    pub u32, shadow, set_shadow: 18, 18;
+    pub u32, glow, set_glow: 19, 19;
+    pub u32, _, set_nine_slice: 20, 20;
+    pub u32, _, set_constant_screen_size: 21, 21;
+    pub u32, from into Alignment, _, set_alignment: 23, 22;
 }
 
 /// Properties attached to a batch of shapes that are needed for pipeline specialization
@@ -178,8 +613,17 @@ bitfield! {
 pub struct ShapePipelineMaterial {
     render_layers: RenderLayersHash,
     alpha_mode: AlphaModeOrd,
+    screen_blend: bool,
+    oit: bool,
     disable_laa: bool,
+    aa_width: u32,
+    depth_bias: i16,
+    z_index: Option<FloatOrd>,
+    always_on_top: bool,
+    occluded: bool,
+    clip: Option<ClipKey>,
     texture: Option<Handle<Image>>,
+    shader_hook: Option<ShapeShaderHook>,
     canvas: Option<Entity>,
     pipeline: ShapePipelineType,
 }
@@ -190,27 +634,166 @@ impl ShapePipelineMaterial {
         Self {
             render_layers: RenderLayersHash(render_layers.cloned().unwrap_or_default()),
             alpha_mode: AlphaModeOrd(material.alpha_mode),
+            screen_blend: material.screen_blend,
+            oit: material.oit,
             disable_laa: material.disable_laa || material.alpha_mode == AlphaMode::Opaque,
+            aa_width: aa_width_quarters(material.aa_width),
+            depth_bias: depth_bias_units(material.depth_bias),
+            z_index: material.z_index.map(FloatOrd),
+            always_on_top: material.always_on_top,
+            occluded: false,
+            clip: ClipKey::new(material.clip.as_ref(), material.scissor),
             canvas: material.canvas,
             pipeline: material.pipeline,
             texture: material.texture,
+            shader_hook: material.shader_hook,
         }
     }
 }
 
+impl ShapePipelineMaterial {
+    /// Returns the material used to draw the parts of a shape hidden behind other geometry.
+    ///
+    /// Occluded shapes are always blended so that their tint can fade them.
+    pub fn occluded(&self) -> Self {
+        let alpha_mode = match self.alpha_mode.0 {
+            AlphaMode::Opaque | AlphaMode::Mask(_) => AlphaMode::Blend,
+            alpha_mode => alpha_mode,
+        };
+        Self {
+            alpha_mode: AlphaModeOrd(alpha_mode),
+            oit: false,
+            always_on_top: false,
+            occluded: true,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the material used to draw effects beneath a shape such as it's shadow or glow.
+    ///
+    /// These are always blended so that their soft edges can fade out.
+    pub fn underlay(&self) -> Self {
+        let alpha_mode = match self.alpha_mode.0 {
+            AlphaMode::Opaque | AlphaMode::Mask(_) => AlphaMode::Blend,
+            alpha_mode => alpha_mode,
+        };
+        Self {
+            alpha_mode: AlphaModeOrd(alpha_mode),
+            disable_laa: false,
+            ..self.clone()
+        }
+    }
+
+    /// Whether this is the material of the parts of a shape hidden behind other geometry, see [`ShapePipelineMaterial::occluded`].
+    pub(crate) fn is_occluded(&self) -> bool {
+        self.occluded
+    }
+
+    /// Whether the shape is drawn without the usual depth test and so must be drawn after opaque geometry.
+    fn ignores_depth(&self) -> bool {
+        self.always_on_top || self.occluded
+    }
+
+    /// Whether the shape is drawn with order independent transparency, which only replaces alpha blending.
+    fn uses_oit(&self) -> bool {
+        self.oit
+            && !self.screen_blend
+            && !self.ignores_depth()
+            && matches!(
+                self.alpha_mode.0,
+                AlphaMode::Blend | AlphaMode::Premultiplied
+            )
+    }
+}
+
 impl From<&ShapeConfig> for ShapePipelineMaterial {
     fn from(config: &ShapeConfig) -> Self {
         Self {
             render_layers: RenderLayersHash(config.render_layers.unwrap_or_default()),
             alpha_mode: AlphaModeOrd(config.alpha_mode),
+            screen_blend: config.screen_blend,
+            oit: config.oit,
             disable_laa: config.disable_laa || config.alpha_mode == AlphaMode::Opaque,
+            aa_width: aa_width_quarters(config.aa_width),
+            depth_bias: depth_bias_units(config.depth_bias),
+            z_index: config.z_index.map(FloatOrd),
+            always_on_top: config.always_on_top,
+            occluded: false,
+            clip: ClipKey::new(config.clip.as_ref(), config.scissor),
             texture: config.texture.clone(),
+            shader_hook: config.shader_hook,
             pipeline: config.pipeline,
             canvas: config.canvas,
         }
     }
 }
 
+/// Quantize an anti-aliasing width in pixels to quarters of a pixel so that it can be used for pipeline specialization.
+fn aa_width_quarters(width: f32) -> u32 {
+    (width * 4.0).round().clamp(1.0, 8.0) as u32
+}
+
+/// Round a depth bias to a whole number that fits in the bits reserved for it in the pipeline key.
+fn depth_bias_units(bias: f32) -> i16 {
+    (bias.round() as i16).clamp(-(1 << 14), (1 << 14) - 1)
+}
+
+/// A [`ClipMask`] and scissor rectangle stored as bits so that they can be used for batching.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+pub struct ClipKey {
+    mask: Option<[u32; 19]>,
+    scissor: Option<[u32; 4]>,
+}
+
+impl ClipKey {
+    /// Returns [`None`] when shapes are neither masked or scissored.
+    pub fn new(mask: Option<&ClipMask>, scissor: Option<Rect>) -> Option<Self> {
+        if mask.is_none() && scissor.is_none() {
+            return None;
+        }
+
+        let mask = mask.map(|mask| {
+            let inverse = mask.transform.compute_matrix().inverse().to_cols_array();
+            let mut bits = [0; 19];
+            let values = inverse
+                .iter()
+                .chain(&mask.size.to_array())
+                .chain(&[mask.corner_radius]);
+            for (bits, value) in bits.iter_mut().zip(values) {
+                *bits = value.to_bits();
+            }
+            bits
+        });
+        let scissor =
+            scissor.map(|rect| [rect.min.x, rect.min.y, rect.max.x, rect.max.y].map(f32::to_bits));
+
+        Some(Self { mask, scissor })
+    }
+
+    /// Returns the inverse transform, size and corner radius of the mask.
+    pub fn mask(&self) -> Option<(Mat4, Vec2, f32)> {
+        self.mask.map(|bits| {
+            let values = bits.map(f32::from_bits);
+            (
+                Mat4::from_cols_slice(&values[..16]),
+                Vec2::new(values[16], values[17]),
+                values[18],
+            )
+        })
+    }
+
+    /// Returns the scissor rectangle in pixels.
+    pub fn scissor(&self) -> Option<Rect> {
+        self.scissor.map(|bits| {
+            let [min_x, min_y, max_x, max_y] = bits.map(f32::from_bits);
+            Rect {
+                min: Vec2::new(min_x, min_y),
+                max: Vec2::new(max_x, max_y),
+            }
+        })
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Ord, PartialOrd)]
 struct RenderLayersHash(RenderLayers);
 
@@ -299,42 +882,88 @@ pub fn extract_render_layers(
     }
 }
 
+/// Marker component for cameras that should not draw any immediate or retained shapes, regardless of their [`RenderLayers`].
+///
+/// Useful for cameras such as portrait captures that share render layers with cameras that do draw shapes.
+#[derive(Component, Clone, Copy, Default, Reflect, Debug)]
+#[reflect(Component, Default)]
+pub struct NoShapeRendering;
+
+/// System that extracts [`NoShapeRendering`] for each camera.
+pub fn extract_no_shape_rendering(
+    mut commands: Commands,
+    cameras: Extract<Query<Entity, (With<Camera>, With<NoShapeRendering>)>>,
+) {
+    for entity in &cameras {
+        commands.get_or_spawn(entity).insert(NoShapeRendering);
+    }
+}
+
 fn setup_pipeline(app: &mut App) {
+    // Shape pipelines are created in the render world, so they read their replacement shaders from a copy
+    let shaders = app
+        .world
+        .get_resource::<ShapeShaders>()
+        .cloned()
+        .unwrap_or_default();
     app.sub_app_mut(RenderApp)
+        .insert_resource(shaders)
         .init_resource::<ShapePipelines>()
         .init_resource::<ShapeTextureBindGroups>()
+        .init_resource::<ShapeClipBindGroups>()
         .init_resource::<QuadVertices>()
-        .add_systems(ExtractSchedule, extract_render_layers)
+        .add_systems(
+            ExtractSchedule,
+            (extract_render_layers, extract_no_shape_rendering),
+        )
         .add_systems(
             Render,
             prepare_shape_view_bind_groups.in_set(RenderSet::PrepareBindGroups),
         )
         .add_systems(
             Render,
-            prepare_shape_texture_bind_groups.in_set(RenderSet::PrepareBindGroups),
+            (
+                prepare_shape_texture_bind_groups,
+                prepare_shape_clip_bind_groups,
+            )
+                .in_set(RenderSet::PrepareBindGroups),
         );
 }
 
 fn setup_type_pipeline<T: ShapeData + 'static>(app: &mut App) {
     app.sub_app_mut(RenderApp)
         .init_resource::<ShapePipeline<T>>()
+        .init_resource::<ShapeTypeStats<T>>()
         .add_systems(
             Render,
             (
                 write_batched_instance_buffer::<T>.in_set(RenderSet::PrepareResourcesFlush),
                 prepare_shape_bind_group::<T>.in_set(RenderSet::PrepareBindGroups),
+                collect_shape_type_stats::<T>
+                    .in_set(RenderSet::Cleanup)
+                    .before(send_shape_stats),
             ),
         );
 }
 
+#[cfg(feature = "shape_3d")]
 fn setup_type_pipeline_3d<T: ShapeData + 'static>(app: &mut App) {
+    use bevy::core_pipeline::core_3d::{AlphaMask3d, Opaque3d, Transparent3d};
+
     app.sub_app_mut(RenderApp)
         .add_render_command::<Opaque3d, DrawShapeCommand<T>>()
         .add_render_command::<Transparent3d, DrawShapeCommand<T>>()
         .add_render_command::<AlphaMask3d, DrawShapeCommand<T>>()
+        .add_render_command::<ShapeOit3d, DrawShapeCommand<T>>()
         .init_resource::<Shape3dInstances<T>>()
         .init_resource::<Shape3dMaterials<T>>()
-        .add_systems(ExtractSchedule, extract_shapes_3d::<T>)
+        .add_systems(
+            ExtractSchedule,
+            (
+                extract_shapes_3d::<T>,
+                extract_baked_shapes_3d::<T>.after(extract_shapes_3d::<T>),
+            ),
+        )
         .add_systems(
             Render,
             (
@@ -345,6 +974,8 @@ fn setup_type_pipeline_3d<T: ShapeData + 'static>(app: &mut App) {
                     .in_set(RenderSet::PrepareResources),
                 batch_and_prepare_render_phase::<T, Shape3dInstances<T>, Transparent3d>
                     .in_set(RenderSet::PrepareResources),
+                batch_and_prepare_render_phase::<T, Shape3dInstances<T>, ShapeOit3d>
+                    .in_set(RenderSet::PrepareResources),
             ),
         );
 }
@@ -352,13 +983,20 @@ fn setup_type_pipeline_3d<T: ShapeData + 'static>(app: &mut App) {
 fn setup_type_pipeline_2d<T: ShapeData + 'static>(app: &mut App) {
     if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
         render_app
-            .insert_resource(GpuArrayBuffer::<T>::new(
+            .insert_resource(ShapeInstanceBuffer::<T>::new(
                 render_app.world.resource::<RenderDevice>(),
             ))
             .add_render_command::<Transparent2d, DrawShapeCommand<T>>()
             .init_resource::<Shape2dInstances<T>>()
             .init_resource::<Shape2dMaterials<T>>()
-            .add_systems(ExtractSchedule, extract_shapes_2d::<T>)
+            .init_resource::<BakedShapeBatches<T>>()
+            .add_systems(
+                ExtractSchedule,
+                (
+                    extract_shapes_2d::<T>,
+                    extract_baked_shapes_2d::<T>.after(extract_shapes_2d::<T>),
+                ),
+            )
             .add_systems(
                 Render,
                 (
@@ -373,20 +1011,39 @@ fn setup_type_pipeline_2d<T: ShapeData + 'static>(app: &mut App) {
 pub fn write_batched_instance_buffer<T: ShapeData + 'static>(
     render_device: Res<RenderDevice>,
     render_queue: Res<RenderQueue>,
-    gpu_array_buffer: ResMut<GpuArrayBuffer<T>>,
+    mut instance_buffer: ResMut<ShapeInstanceBuffer<T>>,
 ) {
-    let gpu_array_buffer = gpu_array_buffer.into_inner();
-    gpu_array_buffer.write_buffer(&render_device, &render_queue);
-    gpu_array_buffer.clear();
+    instance_buffer.write_buffer(&render_device, &render_queue);
 }
 
 /// Plugin that sets up the 2d render pipeline for the given [`ShapeComponent`].
+///
+/// Add it after [`Shape2dPlugin`](crate::Shape2dPlugin) or [`ShapePlugin`](crate::ShapePlugin) to draw a custom
+/// shape type, see [`ShapeData`].
 #[derive(Default)]
 pub struct ShapeTypePlugin<T: ShapeComponent>(PhantomData<T>);
 
 impl<T: ShapeComponent> Plugin for ShapeTypePlugin<T> {
     fn build(&self, app: &mut App) {
-        app.register_type::<T>();
+        app.register_type::<T>()
+            .add_systems(
+                PostUpdate,
+                (
+                    redraw_changed_canvases::<T>
+                        .after(TransformSystem::TransformPropagate)
+                        .after(VisibilitySystems::VisibilityPropagate)
+                        .before(update_canvases),
+                    update_shape_aabbs::<T>.in_set(VisibilitySystems::CalculateBounds),
+                    bake_retained_shapes::<T>.after(TransformSystem::TransformPropagate),
+                ),
+            )
+            .add_systems(
+                Last,
+                (
+                    merge_parallel_shapes::<T>.before(clear_parallel_shapes),
+                    interpolate_fixed_shapes::<T>,
+                ),
+            );
     }
 
     fn finish(&self, app: &mut App) {
@@ -397,10 +1054,13 @@ impl<T: ShapeComponent> Plugin for ShapeTypePlugin<T> {
 
 /// Plugin that sets up the 3d render pipeline for the given [`ShapeComponent`].
 ///
-/// Requires [`ShapeTypePlugin`] of the same type to have already been built.
+/// Requires [`ShapeTypePlugin`] of the same type and [`ShapePlugin`](crate::ShapePlugin) to have already been built.
+/// Enabled by the `shape_3d` feature.
+#[cfg(feature = "shape_3d")]
 #[derive(Default)]
 pub struct ShapeType3dPlugin<T: ShapeComponent>(PhantomData<T>);
 
+#[cfg(feature = "shape_3d")]
 impl<T: ShapeComponent> Plugin for ShapeType3dPlugin<T> {
     fn build(&self, app: &mut App) {
         setup_type_pipeline_3d::<T::Data>(app);
@@ -411,7 +1071,14 @@ impl<T: ShapeComponent> Plugin for ShapeType3dPlugin<T> {
 pub struct ShapeRenderPlugin;
 
 impl Plugin for ShapeRenderPlugin {
-    fn build(&self, _app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.register_type::<NoShapeRendering>()
+            .init_resource::<ShapeShaderHooks>()
+            .add_systems(
+                PostUpdate,
+                update_shape_hooks.run_if(resource_changed::<ShapeShaderHooks>),
+            );
+    }
 
     fn finish(&self, app: &mut App) {
         load_shaders(app);
@@ -444,19 +1111,44 @@ impl<T: PartialEq> BatchMeta<T> {
     }
 }
 
+/// Writes the instances of each phase item of type `T` and merges neighbouring items with the same material into a single draw.
+///
+/// Batches never span shape types, as each type has it's own instance layout and shader. A combined pipeline sharing one
+/// shader between discs, rectangles, regular polygons and lines was considered and left out:
+/// - Each instance would need to be as large as the largest of the four, raising upload size for every shape.
+/// - The union of their vertex outputs is larger than the 15 vec4 inter-stage components available on WebGL2,
+///   so it would need repacking into shared slots and would make every fragment branch on the shape type.
+/// - Opaque and alpha masked 3D phases are already sorted by pipeline, so only transparent phases interleave types,
+///   and there only between shapes at different depths.
+///
+/// Scenes that interleave many types should give shapes on the same layer a shared [`ShapeConfig::z_index`] so that
+/// each type is drawn together, or bake static shapes with [`BakedShapes`].
 pub fn batch_and_prepare_render_phase<
     T: ShapeData,
     R: Resource + Deref<Target = EntityHashMap<ShapeInstance<T>>>,
     P: CachedRenderPipelinePhaseItem,
 >(
     mut commands: Commands,
-    mut gpu_array_buffer: ResMut<GpuArrayBuffer<T>>,
+    mut instance_buffer: ResMut<ShapeInstanceBuffer<T>>,
     mut views: Query<&mut RenderPhase<P>>,
     instance_data: Res<R>,
+    baked: Query<&BakedShapeBatch<T>>,
+    mut stats: ResMut<ShapeTypeStats<T>>,
 ) {
     let mut process_item = |item: &mut P| {
+        // Baked batches draw every instance in their own buffer
+        if let Ok(baked) = baked.get(item.entity()) {
+            stats.instances += baked.count;
+            stats.batches += 1;
+            *item.batch_range_mut() = 0..baked.count;
+            *item.dynamic_offset_mut() = None;
+            return None;
+        }
+
         let (material, data) = instance_data.get(&item.entity())?;
-        let buffer_index = gpu_array_buffer.push(data.clone());
+        let buffer_index = instance_buffer.push(data.clone());
+        stats.instances += 1;
+        stats.uploaded_instances += 1;
 
         let index = buffer_index.index.get();
         *item.batch_range_mut() = index..index + 1;
@@ -489,5 +1181,6 @@ pub fn batch_and_prepare_render_phase<
         }
     }
 
+    stats.batches += batches.len() as u32;
     commands.insert_or_spawn_batch(batches);
 }
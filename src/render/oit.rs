@@ -0,0 +1,440 @@
+use std::ops::Range;
+
+use bevy::{
+    asset::load_internal_asset,
+    core_pipeline::{
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        camera::ExtractedCamera,
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_phase::{
+            sort_phase_system, CachedRenderPipelinePhaseItem, DrawFunctionId, DrawFunctions,
+            PhaseItem, RenderPhase,
+        },
+        render_resource::*,
+        renderer::{RenderAdapter, RenderContext, RenderDevice},
+        texture::{BevyDefault, CachedTexture, TextureCache},
+        view::{ExtractedView, ViewDepthTexture, ViewTarget},
+        Extract, Render, RenderApp, RenderSet,
+    },
+    utils::nonmax::NonMaxU32,
+};
+
+use super::pipeline::{OIT_ACCUMULATE_FORMAT, OIT_REVEALAGE_FORMAT};
+
+/// Handler to shader for compositing order independent transparency.
+pub const OIT_COMPOSITE_HANDLE: Handle<Shader> = Handle::weak_from_u128(11490238476519083627);
+
+/// Which of the two passes of order independent transparency a [`ShapeOit3d`] is drawn in.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum OitPass {
+    Accumulate,
+    Revealage,
+}
+
+/// Phase item for 3D shapes drawn with order independent transparency, see [`ShapeConfig::oit`](crate::prelude::ShapeConfig::oit).
+///
+/// Each shape is queued once for each [`OitPass`], as order doesn't matter items are sorted by pass and pipeline
+/// to batch as many shapes together as possible.
+pub struct ShapeOit3d {
+    pub pass: OitPass,
+    pub entity: Entity,
+    pub pipeline: CachedRenderPipelineId,
+    pub draw_function: DrawFunctionId,
+    pub batch_range: Range<u32>,
+    pub dynamic_offset: Option<NonMaxU32>,
+}
+
+impl PhaseItem for ShapeOit3d {
+    type SortKey = (OitPass, usize);
+
+    #[inline]
+    fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    fn sort_key(&self) -> Self::SortKey {
+        (self.pass, self.pipeline.id())
+    }
+
+    #[inline]
+    fn draw_function(&self) -> DrawFunctionId {
+        self.draw_function
+    }
+
+    #[inline]
+    fn batch_range(&self) -> &Range<u32> {
+        &self.batch_range
+    }
+
+    #[inline]
+    fn batch_range_mut(&mut self) -> &mut Range<u32> {
+        &mut self.batch_range
+    }
+
+    #[inline]
+    fn dynamic_offset(&self) -> Option<NonMaxU32> {
+        self.dynamic_offset
+    }
+
+    #[inline]
+    fn dynamic_offset_mut(&mut self) -> &mut Option<NonMaxU32> {
+        &mut self.dynamic_offset
+    }
+}
+
+impl CachedRenderPipelinePhaseItem for ShapeOit3d {
+    #[inline]
+    fn cached_pipeline(&self) -> CachedRenderPipelineId {
+        self.pipeline
+    }
+}
+
+/// Whether the adapter can render and blend into the targets used by order independent transparency.
+///
+/// When it can't, such as on WebGL2 without float color buffer extensions, views get no [`ShapeOit3d`] phase and
+/// shapes using order independent transparency fall back to sorted transparency.
+#[derive(Resource, Clone, Copy)]
+pub struct OitSupported(pub bool);
+
+impl FromWorld for OitSupported {
+    fn from_world(world: &mut World) -> Self {
+        let adapter = world.resource::<RenderAdapter>();
+        Self(
+            [OIT_ACCUMULATE_FORMAT, OIT_REVEALAGE_FORMAT]
+                .into_iter()
+                .all(|format| {
+                    let features = adapter.get_texture_format_features(format);
+                    features
+                        .allowed_usages
+                        .contains(TextureUsages::RENDER_ATTACHMENT)
+                        && features
+                            .flags
+                            .contains(TextureFormatFeatureFlags::BLENDABLE)
+                }),
+        )
+    }
+}
+
+/// Add a [`ShapeOit3d`] phase to each active 3D camera.
+pub fn extract_oit_phases(
+    mut commands: Commands,
+    cameras: Extract<Query<(Entity, &Camera), With<Camera3d>>>,
+    supported: Res<OitSupported>,
+) {
+    if !supported.0 {
+        return;
+    }
+
+    for (entity, camera) in &cameras {
+        if camera.is_active {
+            commands
+                .get_or_spawn(entity)
+                .insert(RenderPhase::<ShapeOit3d>::default());
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct OitCompositePipeline {
+    layout: BindGroupLayout,
+    multisampled_layout: BindGroupLayout,
+}
+
+impl OitCompositePipeline {
+    fn layout(&self, multisampled: bool) -> &BindGroupLayout {
+        if multisampled {
+            &self.multisampled_layout
+        } else {
+            &self.layout
+        }
+    }
+}
+
+impl FromWorld for OitCompositePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let create_layout = |label, multisampled| {
+            let entry = |binding| BindGroupLayoutEntry {
+                binding,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled,
+                },
+                count: None,
+            };
+            render_device.create_bind_group_layout(Some(label), &[entry(0), entry(1)])
+        };
+
+        Self {
+            layout: create_layout("shape_oit_composite_layout", false),
+            multisampled_layout: create_layout("shape_oit_composite_multisampled_layout", true),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OitCompositeKey {
+    hdr: bool,
+    msaa_samples: u32,
+}
+
+impl SpecializedRenderPipeline for OitCompositePipeline {
+    type Key = OitCompositeKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let multisampled = key.msaa_samples > 1;
+        let mut shader_defs = Vec::new();
+        if multisampled {
+            shader_defs.push("MULTISAMPLED".into());
+        }
+
+        let format = match key.hdr {
+            true => ViewTarget::TEXTURE_FORMAT_HDR,
+            false => TextureFormat::bevy_default(),
+        };
+
+        RenderPipelineDescriptor {
+            label: Some("shape_oit_composite_pipeline".into()),
+            layout: vec![self.layout(multisampled).clone()],
+            push_constant_ranges: vec![],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: OIT_COMPOSITE_HANDLE,
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: key.msaa_samples,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        }
+    }
+}
+
+/// Targets and composite pipeline of a view with shapes to draw with order independent transparency.
+#[derive(Component)]
+pub struct ViewOitTextures {
+    accumulate: CachedTexture,
+    revealage: CachedTexture,
+    composite_pipeline: CachedRenderPipelineId,
+    composite_bind_group: BindGroup,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_oit_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<OitCompositePipeline>>,
+    render_device: Res<RenderDevice>,
+    pipeline_cache: Res<PipelineCache>,
+    composite_pipeline: Res<OitCompositePipeline>,
+    msaa: Res<Msaa>,
+    views: Query<(
+        Entity,
+        &ExtractedCamera,
+        &ExtractedView,
+        &RenderPhase<ShapeOit3d>,
+    )>,
+) {
+    for (entity, camera, view, phase) in &views {
+        let Some(size) = camera.physical_target_size else {
+            continue;
+        };
+        if phase.items.is_empty() {
+            continue;
+        }
+
+        let mut create_texture = |label, format| {
+            texture_cache.get(
+                &render_device,
+                TextureDescriptor {
+                    label: Some(label),
+                    size: Extent3d {
+                        width: size.x,
+                        height: size.y,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: msaa.samples(),
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+            )
+        };
+        let accumulate = create_texture("shape_oit_accumulate_texture", OIT_ACCUMULATE_FORMAT);
+        let revealage = create_texture("shape_oit_revealage_texture", OIT_REVEALAGE_FORMAT);
+
+        let key = OitCompositeKey {
+            hdr: view.hdr,
+            msaa_samples: msaa.samples(),
+        };
+        let composite_bind_group = render_device.create_bind_group(
+            "shape_oit_composite_bind_group",
+            composite_pipeline.layout(key.msaa_samples > 1),
+            &BindGroupEntries::sequential((&accumulate.default_view, &revealage.default_view)),
+        );
+
+        commands.entity(entity).insert(ViewOitTextures {
+            accumulate,
+            revealage,
+            composite_pipeline: pipelines.specialize(&pipeline_cache, &composite_pipeline, key),
+            composite_bind_group,
+        });
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+pub struct ShapeOitLabel;
+
+/// Draws the accumulation and revealage passes of each view's [`ShapeOit3d`] phase,
+/// then composites the result over the view's main texture.
+#[derive(Default)]
+pub struct ShapeOitNode;
+
+impl ViewNode for ShapeOitNode {
+    type ViewQuery = (
+        &'static ExtractedCamera,
+        &'static RenderPhase<ShapeOit3d>,
+        &'static ViewTarget,
+        &'static ViewDepthTexture,
+        &'static ViewOitTextures,
+    );
+
+    fn run<'w>(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext<'w>,
+        (camera, phase, target, depth, textures): QueryItem<'w, Self::ViewQuery>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        let Some(composite_pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(textures.composite_pipeline)
+        else {
+            return Ok(());
+        };
+        let view_entity = graph.view_entity();
+        let revealage_start = phase
+            .items
+            .partition_point(|item| item.pass == OitPass::Accumulate);
+
+        let passes = [
+            (
+                "shape_oit_accumulate_pass",
+                &textures.accumulate,
+                Color::NONE,
+                0..revealage_start,
+            ),
+            (
+                "shape_oit_revealage_pass",
+                &textures.revealage,
+                Color::WHITE,
+                revealage_start..phase.items.len(),
+            ),
+        ];
+        for (label, texture, clear_color, range) in passes {
+            let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some(label),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &texture.default_view,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(clear_color.into()),
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(depth.get_attachment(StoreOp::Store)),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            if let Some(viewport) = camera.viewport.as_ref() {
+                render_pass.set_camera_viewport(viewport);
+            }
+            phase.render_range(&mut render_pass, world, view_entity, range);
+        }
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("shape_oit_composite_pass"),
+            color_attachments: &[Some(target.get_color_attachment())],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        if let Some(viewport) = camera.viewport.as_ref() {
+            render_pass.set_camera_viewport(viewport);
+        }
+        render_pass.set_render_pipeline(composite_pipeline);
+        render_pass.set_bind_group(0, &textures.composite_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+/// Plugin that sets up order independent transparency for 3D shapes.
+///
+/// Must be added before any [`ShapeType3dPlugin`](super::ShapeType3dPlugin) so that they can register their draw commands.
+pub struct ShapeOitPlugin;
+
+impl Plugin for ShapeOitPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            OIT_COMPOSITE_HANDLE,
+            "shaders/oit_composite.wgsl",
+            Shader::from_wgsl
+        );
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<DrawFunctions<ShapeOit3d>>()
+            .init_resource::<SpecializedRenderPipelines<OitCompositePipeline>>()
+            .add_systems(ExtractSchedule, extract_oit_phases)
+            .add_systems(
+                Render,
+                (
+                    sort_phase_system::<ShapeOit3d>.in_set(RenderSet::PhaseSort),
+                    prepare_oit_textures.in_set(RenderSet::PrepareResources),
+                ),
+            )
+            .add_render_graph_node::<ViewNodeRunner<ShapeOitNode>>(Core3d, ShapeOitLabel)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::MainTransparentPass,
+                    ShapeOitLabel,
+                    Node3d::EndMainPass,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<OitSupported>()
+                .init_resource::<OitCompositePipeline>();
+        }
+    }
+}
@@ -12,12 +12,21 @@ use super::*;
 bitflags::bitflags! {
     #[derive(Eq, PartialEq, Hash, Clone, Copy)]
     #[repr(transparent)]
-    pub struct ShapePipelineKey: u32 {
+    pub struct ShapePipelineKey: u64 {
         const NONE                              = 0;
         const HDR                               = (1 << 0);
+        const BLEND_SCREEN                      = (1 << 1);
         const PIPELINE_2D                       = (1 << 2);
         const LOCAL_AA                          = (1 << 3);
         const TEXTURED                          = (1 << 4);
+        const CLIPPED                           = (1 << 24);
+        const DEPTH_ALWAYS                      = (1 << 25);
+        const DEPTH_OCCLUDED                    = (1 << 26);
+        const OIT_ACCUMULATE                    = (1 << 27);
+        const OIT_REVEALAGE                     = (1 << 28);
+        const AA_WIDTH_RESERVED_BITS            = Self::AA_WIDTH_MASK_BITS << Self::AA_WIDTH_SHIFT_BITS;
+        const SHADER_HOOK_RESERVED_BITS         = Self::SHADER_HOOK_MASK_BITS << Self::SHADER_HOOK_SHIFT_BITS;
+        const DEPTH_BIAS_RESERVED_BITS          = Self::DEPTH_BIAS_MASK_BITS << Self::DEPTH_BIAS_SHIFT_BITS;
         const BLEND_RESERVED_BITS               = Self::BLEND_MASK_BITS << Self::BLEND_SHIFT_BITS;
         const BLEND_OPAQUE                      = (0 << Self::BLEND_SHIFT_BITS);
         const BLEND_ADD                         = (1 << Self::BLEND_SHIFT_BITS);
@@ -28,14 +37,21 @@ bitflags::bitflags! {
 }
 
 impl ShapePipelineKey {
-    const MSAA_MASK_BITS: u32 = 0b111;
-    const MSAA_SHIFT_BITS: u32 = 32 - Self::MSAA_MASK_BITS.count_ones();
-    const BLEND_MASK_BITS: u32 = 0b11;
+    const MSAA_MASK_BITS: u64 = 0b111;
+    const MSAA_SHIFT_BITS: u32 = 64 - Self::MSAA_MASK_BITS.count_ones();
+    const BLEND_MASK_BITS: u64 = 0b11;
     const BLEND_SHIFT_BITS: u32 = Self::MSAA_SHIFT_BITS - Self::BLEND_MASK_BITS.count_ones();
+    const AA_WIDTH_MASK_BITS: u64 = 0b1111;
+    const AA_WIDTH_SHIFT_BITS: u32 = 5;
+    const DEPTH_BIAS_MASK_BITS: u64 = 0x7FFF;
+    const DEPTH_BIAS_SHIFT_BITS: u32 =
+        Self::AA_WIDTH_SHIFT_BITS + Self::AA_WIDTH_MASK_BITS.count_ones();
+    const SHADER_HOOK_MASK_BITS: u64 = 0xFF;
+    const SHADER_HOOK_SHIFT_BITS: u32 = 29;
 
     pub fn from_msaa_samples(msaa_samples: u32) -> Self {
         let msaa_bits =
-            (msaa_samples.trailing_zeros() & Self::MSAA_MASK_BITS) << Self::MSAA_SHIFT_BITS;
+            (msaa_samples.trailing_zeros() as u64 & Self::MSAA_MASK_BITS) << Self::MSAA_SHIFT_BITS;
         Self::from_bits_retain(msaa_bits)
     }
 
@@ -48,7 +64,25 @@ impl ShapePipelineKey {
     }
 
     pub fn msaa_samples(&self) -> u32 {
-        1 << ((self.bits() >> Self::MSAA_SHIFT_BITS) & Self::MSAA_MASK_BITS)
+        1 << ((self.bits() >> Self::MSAA_SHIFT_BITS) & Self::MSAA_MASK_BITS) as u32
+    }
+
+    /// Width of local anti-aliasing in quarters of a pixel.
+    pub fn aa_width(&self) -> u32 {
+        ((self.bits() >> Self::AA_WIDTH_SHIFT_BITS) & Self::AA_WIDTH_MASK_BITS) as u32
+    }
+
+    /// Constant depth bias applied by 3D pipelines.
+    pub fn depth_bias(&self) -> i32 {
+        // Shift our bits to the top and back to sign extend them
+        let unused_bits = 64 - Self::DEPTH_BIAS_MASK_BITS.count_ones();
+        let bits = (self.bits() >> Self::DEPTH_BIAS_SHIFT_BITS) & Self::DEPTH_BIAS_MASK_BITS;
+        (((bits << unused_bits) as i64) >> unused_bits) as i32
+    }
+
+    /// Id of the [`ShapeShaderHook`] the shape is drawn with, 0 when it has none.
+    pub fn shader_hook(&self) -> u32 {
+        ((self.bits() >> Self::SHADER_HOOK_SHIFT_BITS) & Self::SHADER_HOOK_MASK_BITS) as u32
     }
 
     pub fn from_material(material: &ShapePipelineMaterial) -> Self {
@@ -60,18 +94,56 @@ impl ShapePipelineKey {
             AlphaMode::Add => Self::BLEND_ADD,
             AlphaMode::Multiply => Self::BLEND_MULTIPLY,
         };
+        // Screen blending replaces the blending of transparent alpha modes
+        if material.screen_blend
+            && !matches!(
+                material.alpha_mode.0,
+                AlphaMode::Opaque | AlphaMode::Mask(_)
+            )
+        {
+            key |= Self::BLEND_SCREEN;
+        }
         if material.texture.is_some() {
             key |= Self::TEXTURED;
         }
+        if !material.disable_laa {
+            key |= Self::from_bits_retain(
+                (material.aa_width as u64 & Self::AA_WIDTH_MASK_BITS) << Self::AA_WIDTH_SHIFT_BITS,
+            );
+        }
+        if material.clip.is_some() {
+            key |= Self::CLIPPED;
+        }
+        if let Some(hook) = material.shader_hook {
+            key |= Self::from_bits_retain(
+                (hook.id() as u64 & Self::SHADER_HOOK_MASK_BITS) << Self::SHADER_HOOK_SHIFT_BITS,
+            );
+        }
+        if material.always_on_top {
+            key |= Self::DEPTH_ALWAYS;
+        } else if material.occluded {
+            key |= Self::DEPTH_OCCLUDED;
+        }
+        key |= Self::from_bits_retain(
+            (material.depth_bias as u16 as u64 & Self::DEPTH_BIAS_MASK_BITS)
+                << Self::DEPTH_BIAS_SHIFT_BITS,
+        );
 
         key
     }
 }
 
+/// Format of the target that weighted colors and alphas are summed into.
+pub const OIT_ACCUMULATE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+/// Format of the target that the product of one minus each alpha is multiplied into.
+pub const OIT_REVEALAGE_FORMAT: TextureFormat = TextureFormat::R16Float;
+
 #[derive(Resource)]
 pub struct ShapePipelines {
     pub view_layout: BindGroupLayout,
     pub texture_layout: BindGroupLayout,
+    pub clip_layout: BindGroupLayout,
     pipeline_cache: HashMap<(ShapePipelineKey, TypeId), CachedRenderPipelineId>,
 }
 
@@ -115,10 +187,24 @@ impl FromWorld for ShapePipelines {
                 },
             ],
         );
+        let clip_layout = render_device.create_bind_group_layout(
+            Some("shape_clip_layout"),
+            &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: Some(ClipUniform::min_size()),
+                },
+                count: None,
+            }],
+        );
 
         Self {
             view_layout,
             texture_layout,
+            clip_layout,
             pipeline_cache: default(),
         }
     }
@@ -134,14 +220,20 @@ impl ShapePipelines {
         let Self {
             view_layout,
             texture_layout,
+            clip_layout,
             pipeline_cache,
         } = self;
 
         *pipeline_cache
             .entry((key, TypeId::of::<T>()))
             .or_insert_with(|| {
-                let descriptor =
-                    pipeline.specialize(view_layout, texture_layout, &pipeline.layout, key);
+                let descriptor = pipeline.specialize(
+                    view_layout,
+                    texture_layout,
+                    clip_layout,
+                    &pipeline.layout,
+                    key,
+                );
                 cache.queue_render_pipeline(descriptor)
             })
     }
@@ -151,6 +243,8 @@ impl ShapePipelines {
 pub struct ShapePipeline<T: ShapeData> {
     pub shader: Handle<Shader>,
     pub layout: BindGroupLayout,
+    /// Number of instances per uniform buffer batch when storage buffers are unsupported, see [`GpuArrayBuffer`].
+    pub batch_size: Option<u32>,
     _marker: PhantomData<T>,
 }
 
@@ -162,10 +256,18 @@ impl<T: ShapeData> FromWorld for ShapePipeline<T> {
             &[GpuArrayBuffer::<T>::binding_layout(render_device).build(0, ShaderStages::VERTEX)],
         );
 
+        let batch_size = GpuArrayBuffer::<T>::batch_size(render_device);
+        let shader = world
+            .get_resource::<ShapeShaders>()
+            .and_then(ShapeShaders::get::<T>)
+            .cloned()
+            .unwrap_or_else(T::shader);
+
         let asset_server = world.resource_mut::<AssetServer>();
         Self {
             layout,
-            shader: match T::shader() {
+            batch_size,
+            shader: match shader {
                 ShaderRef::Default => RECT_HANDLE,
                 ShaderRef::Handle(handle) => handle,
                 ShaderRef::Path(path) => asset_server.load(path),
@@ -180,15 +282,65 @@ impl<T: ShapeData> ShapePipeline<T> {
         &self,
         view_layout: &BindGroupLayout,
         texture_layout: &BindGroupLayout,
+        clip_layout: &BindGroupLayout,
         shape_layout: &BindGroupLayout,
         key: ShapePipelineKey,
     ) -> RenderPipelineDescriptor {
         let mut shader_defs = Vec::new();
-        let (label, blend, depth_stencil, depth_write_enabled);
+        // Shaders of custom shapes are not loaded with the defs from `ShapeData::shader_defs`, so pass the batch size
+        // to every shape pipeline
+        if let Some(batch_size) = self.batch_size {
+            shader_defs.push(ShaderDefVal::UInt(
+                "PER_OBJECT_BUFFER_BATCH_SIZE".into(),
+                batch_size,
+            ));
+        }
+        let (label, blend, depth_stencil, mut depth_write_enabled);
 
         let pass = key.intersection(ShapePipelineKey::BLEND_RESERVED_BITS);
 
-        if pass == ShapePipelineKey::BLEND_ALPHA {
+        // Order independent transparency blends straight alpha into separate accumulation and revealage targets
+        if key.contains(ShapePipelineKey::OIT_ACCUMULATE) {
+            label = "oit_accumulate_shape_pipeline".into();
+            blend = Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::SrcAlpha,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent {
+                    src_factor: BlendFactor::One,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+            });
+            shader_defs.push("BLEND_ALPHA".into());
+            depth_write_enabled = false;
+        } else if key.contains(ShapePipelineKey::OIT_REVEALAGE) {
+            label = "oit_revealage_shape_pipeline".into();
+            blend = Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Zero,
+                    dst_factor: BlendFactor::OneMinusSrcAlpha,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent::OVER,
+            });
+            shader_defs.push("BLEND_ALPHA".into());
+            depth_write_enabled = false;
+        } else if key.contains(ShapePipelineKey::BLEND_SCREEN) {
+            label = "screen_blend_shape_pipeline".into();
+            blend = Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::One,
+                    dst_factor: BlendFactor::OneMinusSrc,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent::OVER,
+            });
+            shader_defs.push("BLEND_SCREEN".into());
+            depth_write_enabled = false;
+        } else if pass == ShapePipelineKey::BLEND_ALPHA {
             label = "alpha_blend_shape_pipeline".into();
             blend = Some(BlendState::ALPHA_BLENDING);
             shader_defs.push("BLEND_ALPHA".into());
@@ -228,6 +380,17 @@ impl<T: ShapeData> ShapePipeline<T> {
             depth_write_enabled = true;
         }
 
+        // Reverse z, occluded fragments are further away than what is already in the depth buffer
+        let depth_compare = if key.contains(ShapePipelineKey::DEPTH_ALWAYS) {
+            depth_write_enabled = false;
+            CompareFunction::Always
+        } else if key.contains(ShapePipelineKey::DEPTH_OCCLUDED) {
+            depth_write_enabled = false;
+            CompareFunction::Less
+        } else {
+            CompareFunction::Greater
+        };
+
         if key.contains(ShapePipelineKey::PIPELINE_2D) {
             depth_stencil = None;
             shader_defs.push("PIPELINE_2D".into());
@@ -235,7 +398,7 @@ impl<T: ShapeData> ShapePipeline<T> {
             depth_stencil = Some(DepthStencilState {
                 format: TextureFormat::Depth32Float,
                 depth_write_enabled,
-                depth_compare: CompareFunction::Greater,
+                depth_compare,
                 stencil: StencilState {
                     front: StencilFaceState::IGNORE,
                     back: StencilFaceState::IGNORE,
@@ -243,7 +406,7 @@ impl<T: ShapeData> ShapePipeline<T> {
                     write_mask: 0,
                 },
                 bias: DepthBiasState {
-                    constant: 0,
+                    constant: key.depth_bias(),
                     slope_scale: 0.0,
                     clamp: 0.0,
                 },
@@ -253,13 +416,23 @@ impl<T: ShapeData> ShapePipeline<T> {
 
         if key.contains(ShapePipelineKey::LOCAL_AA) {
             shader_defs.push("LOCAL_AA".into());
+            shader_defs.push(ShaderDefVal::UInt("AA_WIDTH".into(), key.aa_width()));
         } else {
             shader_defs.push("DISABLE_LOCAL_AA".into())
         }
 
-        let format = match key.contains(ShapePipelineKey::HDR) {
-            true => bevy::render::view::ViewTarget::TEXTURE_FORMAT_HDR,
-            false => TextureFormat::bevy_default(),
+        if key.shader_hook() > 0 {
+            shader_defs.push(ShaderDefVal::UInt("SHAPE_HOOK".into(), key.shader_hook()));
+        }
+
+        let format = if key.contains(ShapePipelineKey::OIT_ACCUMULATE) {
+            OIT_ACCUMULATE_FORMAT
+        } else if key.contains(ShapePipelineKey::OIT_REVEALAGE) {
+            OIT_REVEALAGE_FORMAT
+        } else if key.contains(ShapePipelineKey::HDR) {
+            bevy::render::view::ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
         };
 
         let mut layout = vec![view_layout.clone(), shape_layout.clone()];
@@ -267,6 +440,22 @@ impl<T: ShapeData> ShapePipeline<T> {
             layout.push(texture_layout.clone());
             shader_defs.push("TEXTURED".into());
         }
+        // The clip mask follows the texture when there is one
+        if key.contains(ShapePipelineKey::CLIPPED) {
+            shader_defs.push(ShaderDefVal::UInt("CLIP_GROUP".into(), layout.len() as u32));
+            layout.push(clip_layout.clone());
+            shader_defs.push("CLIPPED".into());
+        }
+
+        // WebGL2 doesn't support the first instance of a draw call, so it's passed to the shader as a push constant
+        let mut push_constant_ranges = Vec::with_capacity(1);
+        if cfg!(all(feature = "webgl", target_arch = "wasm32")) {
+            shader_defs.push("BASE_INSTANCE_WORKAROUND".into());
+            push_constant_ranges.push(PushConstantRange {
+                stages: ShaderStages::VERTEX,
+                range: 0..4,
+            });
+        }
 
         let mut fragment_defs = shader_defs.clone();
         fragment_defs.push("FRAGMENT".into());
@@ -309,7 +498,7 @@ impl<T: ShapeData> ShapePipeline<T> {
                 alpha_to_coverage_enabled: false,
             },
             label: Some(label),
-            push_constant_ranges: vec![],
+            push_constant_ranges,
         }
     }
 }
@@ -0,0 +1,178 @@
+use std::sync::{
+    mpsc::{channel, Receiver, Sender},
+    Mutex,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::{RenderAssetUsages, RenderAssets},
+        render_resource::Buffer,
+        renderer::{RenderDevice, RenderQueue},
+        Extract, Render, RenderApp, RenderSet,
+    },
+};
+use wgpu::{
+    BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d, ImageCopyBuffer,
+    ImageDataLayout, Maintain, MapMode, TextureDimension, TextureFormat,
+};
+
+use crate::prelude::*;
+
+/// Sends the images read back from canvases in the render world to the main world.
+#[derive(Resource)]
+struct CanvasReadbackSender(Sender<CanvasReadback>);
+
+/// Receives the images read back from canvases in the main world.
+#[derive(Resource)]
+struct CanvasReadbackReceiver(Mutex<Receiver<CanvasReadback>>);
+
+/// Canvases to read back this frame along with their target image.
+#[derive(Resource, Default)]
+struct CanvasReadbackRequests(Vec<(Entity, AssetId<Image>)>);
+
+/// A canvas being copied into a buffer that can be mapped for reading.
+struct PendingReadback {
+    canvas: Entity,
+    buffer: Buffer,
+    size: Extent3d,
+    format: TextureFormat,
+    row_bytes: usize,
+    padded_row_bytes: usize,
+}
+
+fn extract_canvas_readbacks(
+    mut requests: ResMut<CanvasReadbackRequests>,
+    canvases: Extract<Query<(Entity, &Canvas)>>,
+) {
+    requests.0 = canvases
+        .iter()
+        .filter(|(_, canvas)| canvas.is_reading_back())
+        .map(|(e, canvas)| (e, canvas.image.id()))
+        .collect();
+}
+
+/// Copies each requested canvas into a buffer once it has been rendered, sending it to the main world once mapped.
+fn readback_canvases(
+    requests: Res<CanvasReadbackRequests>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    sender: Res<CanvasReadbackSender>,
+) {
+    if !requests.0.is_empty() {
+        let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("canvas_readback_encoder"),
+        });
+
+        let mut pending = Vec::with_capacity(requests.0.len());
+        for &(canvas, image) in &requests.0 {
+            let Some(gpu_image) = images.get(image) else {
+                continue;
+            };
+            let size = gpu_image.texture.size();
+            let format = gpu_image.texture_format;
+            let Some(pixel_bytes) = format.block_copy_size(None) else {
+                continue;
+            };
+
+            // Rows of the buffer must be aligned for the copy, this padding is stripped once it is read
+            let row_bytes = size.width as usize * pixel_bytes as usize;
+            let padded_row_bytes = RenderDevice::align_copy_bytes_per_row(row_bytes);
+            let buffer = render_device.create_buffer(&BufferDescriptor {
+                label: Some("canvas_readback_buffer"),
+                size: (padded_row_bytes * size.height as usize) as u64,
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            encoder.copy_texture_to_buffer(
+                gpu_image.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer: &buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_row_bytes as u32),
+                        rows_per_image: None,
+                    },
+                },
+                size,
+            );
+
+            pending.push(PendingReadback {
+                canvas,
+                buffer,
+                size,
+                format,
+                row_bytes,
+                padded_row_bytes,
+            });
+        }
+
+        render_queue.submit([encoder.finish()]);
+
+        for readback in pending {
+            let sender = sender.0.clone();
+            let buffer = readback.buffer.clone();
+            buffer.slice(..).map_async(MapMode::Read, move |result| {
+                if result.is_err() {
+                    return;
+                }
+
+                let data: Vec<u8> = readback
+                    .buffer
+                    .slice(..)
+                    .get_mapped_range()
+                    .chunks(readback.padded_row_bytes)
+                    .flat_map(|row| &row[..readback.row_bytes])
+                    .copied()
+                    .collect();
+                readback.buffer.unmap();
+
+                // The main world may have already shut down in which case there is nobody to receive the image
+                let _ = sender.send(CanvasReadback {
+                    canvas: readback.canvas,
+                    image: Image::new(
+                        readback.size,
+                        TextureDimension::D2,
+                        data,
+                        readback.format,
+                        RenderAssetUsages::default(),
+                    ),
+                });
+            });
+        }
+    }
+
+    // Run the callbacks of any readbacks that have finished copying without blocking
+    render_device.wgpu_device().poll(Maintain::Poll);
+}
+
+fn receive_canvas_readbacks(
+    receiver: Res<CanvasReadbackReceiver>,
+    mut events: EventWriter<CanvasReadback>,
+) {
+    let receiver = receiver.0.lock().unwrap();
+    events.send_batch(receiver.try_iter());
+}
+
+/// Plugin that reads the textures of canvases back to the CPU, see [`Canvas::request_readback`].
+pub struct CanvasReadbackPlugin;
+
+impl Plugin for CanvasReadbackPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+        app.add_event::<CanvasReadback>()
+            .insert_resource(CanvasReadbackReceiver(Mutex::new(receiver)))
+            .add_systems(PreUpdate, receive_canvas_readbacks);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .insert_resource(CanvasReadbackSender(sender))
+            .init_resource::<CanvasReadbackRequests>()
+            .add_systems(ExtractSchedule, extract_canvas_readbacks)
+            .add_systems(Render, readback_canvases.in_set(RenderSet::Cleanup));
+    }
+}
@@ -1,13 +1,18 @@
-use crate::{painter::ShapeStorage, render::*, shapes::Shape3d};
+use crate::{
+    painter::{Canvas, ShapeStorage},
+    render::*,
+    shapes::Shape3d,
+};
 use bevy::{
     ecs::entity::EntityHashMap,
     render::{
+        primitives::Aabb,
         render_phase::{DrawFunctions, RenderPhase},
         render_resource::*,
         view::{ExtractedView, RenderLayers},
         Extract,
     },
-    utils::{FloatOrd, HashMap},
+    utils::{FloatOrd, HashMap, Parallel},
 };
 
 #[derive(Resource, Deref, DerefMut)]
@@ -41,43 +46,82 @@ pub fn extract_shapes_2d<T: ShapeData>(
                 &ShapeFill,
                 &GlobalTransform,
                 &InheritedVisibility,
+                &ViewVisibility,
+                Has<Aabb>,
                 Option<&ShapeMaterial>,
                 Option<&RenderLayers>,
             ),
-            Without<Shape3d>,
+            (Without<Shape3d>, Without<BakedShape>),
         >,
     >,
+    alphas: Extract<Query<(Option<&ShapeAlpha>, Option<&Parent>)>>,
     storage: Extract<Res<ShapeStorage>>,
+    canvases: Extract<Query<&Camera, With<Canvas>>>,
     mut instance_data: ResMut<Shape2dInstances<T>>,
     mut materials: ResMut<Shape2dMaterials<T>>,
+    mut queue: Local<Parallel<Vec<ExtractedInstance<T>>>>,
+    mut extracted: Local<Vec<ExtractedInstance<T>>>,
 ) {
     instance_data.clear();
     materials.clear();
 
+    // Shapes drawn to canvases that are not being drawn this frame are skipped entirely
+    let is_drawn = |material: &ShapePipelineMaterial| {
+        material.canvas.map_or(true, |canvas| {
+            canvases.get(canvas).map_or(true, |camera| camera.is_active)
+        })
+    };
+
     entities
-        .iter()
-        .filter_map(|(e, cp, fill, tf, vis, flags, rl)| {
-            if vis.get() {
-                Some((
-                    e,
-                    ShapePipelineMaterial::new(flags, rl),
-                    cp.get_data(tf, fill),
-                ))
-            } else {
-                None
+        .par_iter()
+        .for_each(|(e, cp, fill, tf, vis, view_vis, bounded, flags, rl)| {
+            if !vis.get() {
+                return;
             }
-        })
-        .for_each(|(entity, material, data)| {
-            materials.entry(material.clone()).or_default().push(entity);
-            instance_data.insert(entity, (material, data));
+            let material = ShapePipelineMaterial::new(flags, rl);
+            // Shapes with bounds outside of every camera's frustum are culled, canvases are always drawn to
+            if bounded && !view_vis.get() && material.canvas.is_none() {
+                return;
+            }
+            if !is_drawn(&material) {
+                return;
+            }
+            let alpha = inherited_alpha(e, &alphas);
+            // The first instance is keyed by the shape's entity, any others need their own
+            let mut entity = Some(e);
+            queue.scope(|instances| {
+                retained_instances(
+                    cp,
+                    fill,
+                    tf,
+                    alpha,
+                    &material,
+                    None,
+                    flags.is_some_and(|flags| flags.constant_screen_size),
+                    |own, material, data| {
+                        let entity = if own { entity.take() } else { None };
+                        instances.push((e, entity, material, data));
+                    },
+                );
+            });
         });
 
+    insert_extracted_instances(
+        &mut commands,
+        &mut queue,
+        &mut extracted,
+        &mut materials,
+        &mut instance_data,
+    );
+
     if let Some(iter) = storage.get::<T>(ShapePipelineType::Shape2d) {
-        iter.cloned().for_each(|(material, data)| {
-            let entity = commands.spawn_empty().id();
-            materials.entry(material.clone()).or_default().push(entity);
-            instance_data.insert(entity, (material, data));
-        });
+        iter.filter(|(material, _)| is_drawn(material))
+            .cloned()
+            .for_each(|(material, data)| {
+                let entity = commands.spawn_empty().id();
+                materials.entry(material.clone()).or_default().push(entity);
+                instance_data.insert(entity, (material, data));
+            });
     }
 }
 
@@ -90,11 +134,14 @@ pub fn queue_shapes_2d<T: ShapeData>(
     materials: Res<Shape2dMaterials<T>>,
     instance_data: Res<Shape2dInstances<T>>,
     mut shape_pipelines: ResMut<ShapePipelines>,
-    mut views: Query<(
-        &ExtractedView,
-        Option<&RenderLayers>,
-        &mut RenderPhase<Transparent2d>,
-    )>,
+    mut views: Query<
+        (
+            &ExtractedView,
+            Option<&RenderLayers>,
+            &mut RenderPhase<Transparent2d>,
+        ),
+        Without<NoShapeRendering>,
+    >,
 ) {
     let draw_function = transparent_2d_draw_functions
         .read()
@@ -136,7 +183,7 @@ pub fn queue_shapes_2d<T: ShapeData>(
                     entity: *entity,
                     pipeline,
                     draw_function,
-                    sort_key: FloatOrd(data.distance()),
+                    sort_key: material.z_index.unwrap_or(FloatOrd(data.distance())),
                     batch_range: 0..1,
                     dynamic_offset: None,
                 });
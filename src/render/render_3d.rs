@@ -3,12 +3,13 @@ use bevy::{
     ecs::entity::EntityHashMap,
     prelude::*,
     render::{
+        primitives::Aabb,
         render_phase::{DrawFunctions, RenderPhase},
         render_resource::*,
         view::{ExtractedView, RenderLayers},
         Extract,
     },
-    utils::HashMap,
+    utils::{HashMap, Parallel},
 };
 
 use crate::{painter::ShapeStorage, render::*, shapes::Shape3d};
@@ -44,37 +45,66 @@ pub fn extract_shapes_3d<T: ShapeData>(
                 &ShapeFill,
                 &GlobalTransform,
                 &InheritedVisibility,
+                &ViewVisibility,
+                Has<Aabb>,
                 Option<&ShapeMaterial>,
                 Option<&RenderLayers>,
             ),
-            With<Shape3d>,
+            (With<Shape3d>, Without<BakedShape>),
         >,
     >,
+    alphas: Extract<Query<(Option<&ShapeAlpha>, Option<&Parent>)>>,
     storage: Extract<Res<ShapeStorage>>,
     mut instance_data: ResMut<Shape3dInstances<T>>,
     mut materials: ResMut<Shape3dMaterials<T>>,
+    mut queue: Local<Parallel<Vec<ExtractedInstance<T>>>>,
+    mut extracted: Local<Vec<ExtractedInstance<T>>>,
 ) {
     instance_data.clear();
     materials.clear();
 
     entities
-        .iter()
-        .filter_map(|(e, cp, fill, tf, vis, flags, rl)| {
-            if vis.get() {
-                Some((
-                    e,
-                    ShapePipelineMaterial::new(flags, rl),
-                    cp.get_data(tf, fill),
-                ))
-            } else {
-                None
+        .par_iter()
+        .for_each(|(e, cp, fill, tf, vis, view_vis, bounded, flags, rl)| {
+            if !vis.get() {
+                return;
             }
-        })
-        .for_each(|(entity, material, data)| {
-            materials.entry(material.clone()).or_default().push(entity);
-            instance_data.insert(entity, (material, data));
+            let material = ShapePipelineMaterial::new(flags, rl);
+            // Shapes with bounds outside of every camera's frustum are culled, canvases are always drawn to
+            if bounded && !view_vis.get() && material.canvas.is_none() {
+                return;
+            }
+            let occluded_tint = flags
+                .and_then(|flags| flags.occluded_tint)
+                .filter(|_| !material.always_on_top);
+            let alpha = inherited_alpha(e, &alphas);
+            // The first instance is keyed by the shape's entity, any others need their own
+            let mut entity = Some(e);
+            queue.scope(|instances| {
+                retained_instances(
+                    cp,
+                    fill,
+                    tf,
+                    alpha,
+                    &material,
+                    occluded_tint,
+                    flags.is_some_and(|flags| flags.constant_screen_size),
+                    |own, material, data| {
+                        let entity = if own { entity.take() } else { None };
+                        instances.push((e, entity, material, data));
+                    },
+                );
+            });
         });
 
+    insert_extracted_instances(
+        &mut commands,
+        &mut queue,
+        &mut extracted,
+        &mut materials,
+        &mut instance_data,
+    );
+
     if let Some(iter) = storage.get::<T>(ShapePipelineType::Shape3d) {
         iter.cloned().for_each(|(material, data)| {
             let entity = commands.spawn_empty().id();
@@ -89,25 +119,31 @@ pub fn queue_shapes_3d<T: ShapeData>(
     opaque_draw_functions: Res<DrawFunctions<Opaque3d>>,
     alpha_mask_draw_functions: Res<DrawFunctions<AlphaMask3d>>,
     transparent_draw_functions: Res<DrawFunctions<Transparent3d>>,
+    oit_draw_functions: Res<DrawFunctions<ShapeOit3d>>,
     pipeline: Res<ShapePipeline<T>>,
     pipeline_cache: Res<PipelineCache>,
     msaa: Res<Msaa>,
     materials: Res<Shape3dMaterials<T>>,
     instance_data: Res<Shape3dInstances<T>>,
     mut shape_pipelines: ResMut<ShapePipelines>,
-    mut views: Query<(
-        &ExtractedView,
-        Option<&RenderLayers>,
-        &mut RenderPhase<Opaque3d>,
-        &mut RenderPhase<AlphaMask3d>,
-        &mut RenderPhase<Transparent3d>,
-    )>,
+    mut views: Query<
+        (
+            &ExtractedView,
+            Option<&RenderLayers>,
+            &mut RenderPhase<Opaque3d>,
+            &mut RenderPhase<AlphaMask3d>,
+            &mut RenderPhase<Transparent3d>,
+            Option<&mut RenderPhase<ShapeOit3d>>,
+        ),
+        Without<NoShapeRendering>,
+    >,
 ) {
     let draw_opaque = opaque_draw_functions.read().id::<DrawShapeCommand<T>>();
     let draw_alpha_mask = alpha_mask_draw_functions.read().id::<DrawShapeCommand<T>>();
     let draw_transparent = transparent_draw_functions
         .read()
         .id::<DrawShapeCommand<T>>();
+    let draw_oit = oit_draw_functions.read().id::<DrawShapeCommand<T>>();
     let view_count = views.iter().count();
 
     for (material, entities) in materials.iter() {
@@ -131,12 +167,38 @@ pub fn queue_shapes_3d<T: ShapeData>(
                 .for_each(|view| visible_views.push(view))
         };
 
-        for (view, _, mut opaque_phase, mut alpha_mask_phase, mut transparent_phase) in
+        for (view, _, mut opaque_phase, mut alpha_mask_phase, mut transparent_phase, oit_phase) in
             visible_views.into_iter()
         {
             let mut view_key = key;
             view_key |= ShapePipelineKey::from_msaa_samples(msaa.samples());
             view_key |= ShapePipelineKey::from_hdr(view.hdr);
+
+            // Order independent shapes are drawn once into each target of the view's oit phase
+            if let (Some(mut oit_phase), true) = (oit_phase, material.uses_oit()) {
+                for (pass, pass_key) in [
+                    (OitPass::Accumulate, ShapePipelineKey::OIT_ACCUMULATE),
+                    (OitPass::Revealage, ShapePipelineKey::OIT_REVEALAGE),
+                ] {
+                    let pipeline = shape_pipelines.specialize(
+                        &pipeline_cache,
+                        pipeline.as_ref(),
+                        view_key | pass_key,
+                    );
+                    for &entity in entities {
+                        oit_phase.add(ShapeOit3d {
+                            pass,
+                            entity,
+                            pipeline,
+                            draw_function: draw_oit,
+                            batch_range: 0..1,
+                            dynamic_offset: None,
+                        });
+                    }
+                }
+                continue;
+            }
+
             let pipeline = shape_pipelines.specialize(&pipeline_cache, pipeline.as_ref(), view_key);
 
             let rangefinder = view.rangefinder3d();
@@ -145,7 +207,7 @@ pub fn queue_shapes_3d<T: ShapeData>(
                 let (_, data) = unsafe { instance_data.get(&entity).unwrap_unchecked() };
                 let distance = rangefinder.distance(&data.transform());
                 match material.alpha_mode.0 {
-                    AlphaMode::Opaque => {
+                    AlphaMode::Opaque if !material.ignores_depth() => {
                         opaque_phase.add(Opaque3d {
                             asset_id: AssetId::Uuid {
                                 uuid: AssetId::<Mesh>::DEFAULT_UUID,
@@ -157,7 +219,7 @@ pub fn queue_shapes_3d<T: ShapeData>(
                             dynamic_offset: None,
                         });
                     }
-                    AlphaMode::Mask(_) => {
+                    AlphaMode::Mask(_) if !material.ignores_depth() => {
                         alpha_mask_phase.add(AlphaMask3d {
                             entity,
                             draw_function: draw_alpha_mask,
@@ -167,10 +229,7 @@ pub fn queue_shapes_3d<T: ShapeData>(
                             dynamic_offset: None,
                         });
                     }
-                    AlphaMode::Blend
-                    | AlphaMode::Premultiplied
-                    | AlphaMode::Add
-                    | AlphaMode::Multiply => {
+                    _ => {
                         transparent_phase.add(Transparent3d {
                             entity,
                             draw_function: draw_transparent,
@@ -1,5 +1,7 @@
 #define_import_path bevy_vector_shapes::core
 
+#import bevy_vector_shapes::constants::TAU
+
 struct ColorGrading {
     exposure: f32,
     gamma: f32,
@@ -37,6 +39,38 @@ var image_sampler: sampler;
 #endif
 #endif
 
+#ifdef CLIPPED
+#ifdef FRAGMENT
+
+struct Clip {
+    inverse_transform: mat4x4<f32>,
+    size: vec2<f32>,
+    corner_radius: f32,
+    masked: u32,
+    // Scissor rectangle in pixels (min_x, min_y, max_x, max_y)
+    scissor: vec4<f32>,
+};
+
+@group(#{CLIP_GROUP}) @binding(0)
+var<uniform> clip: Clip;
+
+#endif
+#endif
+
+#ifdef BASE_INSTANCE_WORKAROUND
+// WebGL2 ignores the first instance of a draw so it is passed in a push constant instead
+var<push_constant> base_instance: i32;
+#endif
+
+// Index of the current instance in the shape buffer
+fn get_instance_index(instance_index: u32) -> u32 {
+#ifdef BASE_INSTANCE_WORKAROUND
+    return u32(base_instance) + instance_index;
+#else
+    return instance_index;
+#endif
+}
+
 // Calculate pixels per world unit from a given position and up vector
 fn pixels_per_unit(pos: vec3<f32>, dir: vec3<f32>) -> f32 {
     var vp = transpose(view.view_proj);
@@ -60,6 +94,9 @@ fn get_thickness_pixels(thickness: f32, thickness_type: u32, pixels_per_u: f32)
         case 2u: { // SCREEN
             return min(view.viewport.z, view.viewport.w) * (thickness / 100.);
         }
+        case 3u: { // VIEWPORT_HEIGHT
+            return view.viewport.w * thickness;
+        }
     }
 }
 
@@ -80,7 +117,8 @@ fn get_thickness_data(thickness: f32, thickness_type: u32, pos: vec3<f32>, dir:
 
 // Determine thickness of a shape depending on thickness_data and whether it's hollow
 fn calculate_thickness(thickness_data: ThicknessData, uv_scale: f32, flags: u32) -> f32 {
-    var hollow = f_hollow(flags);
+    // Filled shapes with an outline use our thickness for their outline
+    var hollow = f_hollow(flags) | f_outlined(flags) | f_shadow(flags) | f_glow(flags);
     if hollow > 0u {
         // Convert from thickness in pixels to uv space, this requires the same scaling factor as size
         return thickness_data.thickness_p / thickness_data.pixels_per_u / uv_scale;
@@ -112,18 +150,45 @@ fn rotate_vec_a(v: vec2<f32>, a: f32) -> vec2<f32> {
 // bitfield! {
 //     pub struct Flags(u32);
 //     pub u32, from into ThicknessType, _, set_thickness_type: 1, 0;
-//     pub u32, from into Alignment, _, set_alignment: 2, 2;
-//     pub u32, _, set_hollow: 3, 3;
+//     pub u32, hollow, set_hollow: 3, 3;
 //     pub u32, from into Cap, _, set_cap: 5, 4;
-//     pub u32, _, set_arc: 6, 6;
+//     pub u32, arc, set_arc: 6, 6;
+//     pub u32, from into Join, _, set_join: 8, 7;
+//     pub u32, from into ArrowHead, _, set_arrow_head: 10, 9;
+//     pub u32, _, set_double_headed: 11, 11;
+//     pub u32, from into WaveType, _, set_wave_type: 12, 12;
+//     pub u32, _, set_gradient: 14, 13;
+//     pub u32, _, set_outlined: 15, 15;
+//     pub u32, from into StrokeAlignment, _, set_stroke_alignment: 17, 16;
+//     pub u32, _, set_shadow: 18, 18;
+//     pub u32, _, set_glow: 19, 19;
+//     pub u32, _, set_nine_slice: 20, 20;
+//     pub u32, _, set_constant_screen_size: 21, 21;
+//     pub u32, from into Alignment, _, set_alignment: 23, 22;
 // }
 
+// Direction to the camera flattened onto the XZ plane, falling back to the camera's up vector when looking straight down
+fn upright_camera_dir(p: vec3<f32>) -> vec3<f32> {
+    var dir = p_to_camera_dir(p);
+    dir.y = 0.0;
+    if dot(dir, dir) < 1e-6 {
+        dir = (view.view * vec4<f32>(0.0, 1.0, 0.0, 0.0)).xyz;
+        dir.y = 0.0;
+    }
+    return normalize(dir);
+}
+
 fn f_thickness_type(flags: u32) -> u32 {
     return flags & 3u;
 }
 
 fn f_alignment(flags: u32) -> u32 {
-    return (flags >> 2u) & 1u;
+    return (flags >> 22u) & 3u;
+}
+
+// Lines always billboard around their own direction, so any alignment other than flat becomes Alignment::Billboard for lines
+fn f_line_alignment(flags: u32) -> u32 {
+    return select(0u, 3u, f_alignment(flags) != 0u);
 }
 
 fn f_hollow(flags: u32) -> u32 {
@@ -138,6 +203,183 @@ fn f_arc(flags: u32) -> u32 {
     return (flags >> 6u) & 1u;
 }
 
+fn f_join(flags: u32) -> u32 {
+    return (flags >> 7u) & 3u;
+}
+
+fn f_arrow_head(flags: u32) -> u32 {
+    return (flags >> 9u) & 3u;
+}
+
+fn f_double_headed(flags: u32) -> u32 {
+    return (flags >> 11u) & 1u;
+}
+
+fn f_wave_type(flags: u32) -> u32 {
+    return (flags >> 12u) & 1u;
+}
+
+fn f_gradient(flags: u32) -> u32 {
+    return (flags >> 13u) & 3u;
+}
+
+fn f_outlined(flags: u32) -> u32 {
+    return (flags >> 15u) & 1u;
+}
+
+fn f_stroke_alignment(flags: u32) -> u32 {
+    return (flags >> 16u) & 3u;
+}
+
+fn f_shadow(flags: u32) -> u32 {
+    return (flags >> 18u) & 1u;
+}
+
+fn f_glow(flags: u32) -> u32 {
+    return (flags >> 19u) & 1u;
+}
+
+fn f_nine_slice(flags: u32) -> u32 {
+    return (flags >> 20u) & 1u;
+}
+
+fn f_constant_screen_size(flags: u32) -> u32 {
+    return (flags >> 21u) & 1u;
+}
+
+// Fraction of it's thickness that the outline of a shape is moved outwards by to respect it's stroke alignment
+fn stroke_offset(flags: u32) -> f32 {
+    // Shadows use their thickness as the width of their blur, which extends half way outside the shape
+    if f_shadow(flags) > 0u {
+        return 0.5;
+    }
+    // Glows use their thickness as their radius, which extends entirely outside the shape
+    if f_glow(flags) > 0u {
+        return 1.0;
+    }
+    if (f_hollow(flags) | f_outlined(flags)) == 0u {
+        return 0.0;
+    }
+    return f32(f_stroke_alignment(flags)) / 2.0;
+}
+
+fn cross2d(a: vec2<f32>, b: vec2<f32>) -> f32 {
+    return (a.x * b.y) - (a.y * b.x);
+}
+
+// Distance used for points past the end of an open polyline, past_end is the distance along the segment beyond the end
+fn cap_distance(past_end: f32, perp: f32, radius: f32, cap: u32) -> f32 {
+    switch cap {
+        // Cap::None
+        default: {
+            return max(perp, past_end + radius);
+        }
+        // Cap::Square
+        case 1u: {
+            return max(perp, past_end);
+        }
+        // Cap::Round
+        case 2u: {
+            return length(vec2<f32>(past_end, perp));
+        }
+    }
+}
+
+// Distance used for points past a joint between the segment entering with direction dir_in and leaving with direction dir_out
+// Miter joins whose ratio of miter length to radius exceeds miter_limit are drawn as bevel joins
+fn join_distance(to_joint: vec2<f32>, dir_in: vec2<f32>, dir_out: vec2<f32>, perp: f32, radius: f32, join: u32, miter_limit: f32) -> f32 {
+    // Direction from the joint towards the outside corner
+    let outer = dir_in - dir_out;
+    if length(outer) < 0.0001 {
+        return perp;
+    }
+    let outer_dir = normalize(outer);
+
+    // Cosine of half the angle between the segments, the miter length is radius / cos_half
+    let cos_half = abs(cross2d(dir_in, outer_dir));
+
+    // Distance that clips the corner with a straight edge between the two outer edges
+    let bevel = max(perp, dot(to_joint, outer_dir) - radius * cos_half + radius);
+
+    switch join {
+        // Join::Miter
+        default: {
+            if cos_half * miter_limit < 1.0 {
+                return bevel;
+            }
+            return perp;
+        }
+        // Join::Bevel
+        case 1u: {
+            return bevel;
+        }
+        // Join::Round
+        case 2u: {
+            return length(to_joint);
+        }
+    }
+}
+
+struct SegmentDistance {
+    // Distance from the segment taking into account caps and joins
+    dist: f32,
+    // 1.0 if the segment is responsible for drawing this point, 0.0 otherwise
+    owned: f32,
+};
+
+// Calculate the distance between a point and a single segment of a polyline
+// A previous or next point equal to the start or end respectively indicates that there is no neighbouring segment on that side
+fn polyline_segment_distance(pos: vec2<f32>, prev: vec2<f32>, start: vec2<f32>, end: vec2<f32>, next: vec2<f32>, radius: f32, miter_limit: f32, flags: u32) -> SegmentDistance {
+    var out: SegmentDistance;
+    out.owned = 1.0;
+
+    let segment = end - start;
+    let seg_length = length(segment);
+    let dir = segment / seg_length;
+
+    // Position along the segment and distance from the segment's center line
+    let to_start = pos - start;
+    let along = dot(to_start, dir);
+    let perp = abs(cross2d(dir, to_start));
+
+    let has_prev = any(prev != start);
+    let has_next = any(next != end);
+    let prev_dir = normalize(start - prev);
+    let next_dir = normalize(next - end);
+
+    // Each segment owns the area on it's side of the line bisecting each joint
+    // This is a hard cut to ensure no pixel is drawn by both segments
+    if has_prev {
+        let bisector = prev_dir + dir;
+        if length(bisector) > 0.0001 && dot(to_start, bisector) < 0.0 {
+            out.owned = 0.0;
+        }
+    }
+    if has_next {
+        let bisector = dir + next_dir;
+        if length(bisector) > 0.0001 && dot(pos - end, bisector) >= 0.0 {
+            out.owned = 0.0;
+        }
+    }
+
+    out.dist = perp;
+    if along < 0.0 {
+        if has_prev {
+            out.dist = join_distance(to_start, prev_dir, dir, perp, radius, f_join(flags), miter_limit);
+        } else {
+            out.dist = cap_distance(-along, perp, radius, f_cap(flags));
+        }
+    } else if along > seg_length {
+        if has_next {
+            out.dist = join_distance(pos - end, dir, next_dir, perp, radius, f_join(flags), miter_limit);
+        } else {
+            out.dist = cap_distance(along - seg_length, perp, radius, f_cap(flags));
+        }
+    }
+
+    return out;
+}
+
 #ifdef LOCAL_AA
 const AA_PADDING: f32 = 2.0;
 
@@ -148,17 +390,22 @@ fn partial_derivative(v: f32) -> f32 {
     return length(dv);
 }
 
+// Width in pixels over which edges are smoothed, given in quarters of a pixel by the pipeline
+fn aa_width() -> f32 {
+    return f32(#{AA_WIDTH}u) / 4.0;
+}
+
 // Apply local anti aliasing based on the partial derivative of x and y per pixel
 // This is imperfect and is open to improvement 
 fn step_aa(edge: f32, x: f32) -> f32 {
     var value = x - edge;
-    var pd = partial_derivative(value);
+    var pd = partial_derivative(value) * aa_width();
     return 1.0 - saturate(-value / pd);
 }
 
 fn step_aa_pd(edge: f32, x: f32, in: f32) -> f32 {
     var value = x - edge;
-    var pd = partial_derivative(in);
+    var pd = partial_derivative(in) * aa_width();
     return 1.0 - saturate(-value / pd);
 }
 #endif
@@ -176,6 +423,17 @@ fn step_aa_pd(edge: f32, x: f32, pd: f32) -> f32 {
 }
 #endif
 
+// Scale the basis vectors of a shape's matrix such that one unit in it's local space covers one pixel on screen
+// Measured along the camera's up vector so that the size doesn't depend on the shape's orientation
+fn apply_constant_screen_size(matrix: mat4x4<f32>, flags: u32) -> mat4x4<f32> {
+    if f_constant_screen_size(flags) == 0u {
+        return matrix;
+    }
+    let up = normalize((view.view * vec4<f32>(0.0, 1.0, 0.0, 0.0)).xyz);
+    let scale = 1.0 / pixels_per_unit(matrix[3].xyz, up);
+    return mat4x4<f32>(matrix[0] * scale, matrix[1] * scale, matrix[2] * scale, matrix[3]);
+}
+
 // Calculate xy scale by taking it directly from the length of the basis vectors in the matrix
 fn get_scale(matrix: mat4x4<f32>) -> vec2<f32> {
     return vec2<f32>(length(matrix[0].xyz), length(matrix[1].xyz));
@@ -202,8 +460,13 @@ fn get_basis_vectors_from_up(matrix: mat4x4<f32>, origin: vec3<f32>, up: vec3<f3
             y_basis = normalize((view.view * vec4<f32>(0.0, 1.0, 0.0, 0.0)).xyz);
             z_basis = p_to_camera_dir(origin);
         }
-        // Alignment::Billboard for lines
+        // Alignment::BillboardY
         case 2u: {
+            y_basis = vec3<f32>(0.0, 1.0, 0.0);
+            z_basis = upright_camera_dir(origin);
+        }
+        // Alignment::Billboard for lines
+        case 3u: {
             z_basis = p_to_camera_dir(origin);
         }
     }
@@ -266,6 +529,11 @@ fn get_texture_uv(vertex: vec2<f32>) -> vec2<f32> {
     return (vertex + 1.0) / 2.0;
 }
 
+// Texture coordinates scaled by the tiling in xy of texture and then shifted by the offset in zw
+fn get_tiled_texture_uv(vertex: vec2<f32>, texture: vec4<f32>) -> vec2<f32> {
+    return get_texture_uv(vertex) * texture.xy + texture.zw;
+}
+
 #ifdef FRAGMENT
 // Transform our color output to respect the alpha mode set for our shape and combine with our texture if any
 fn color_output(in: vec4<f32>) -> vec4<f32> {
@@ -275,10 +543,137 @@ fn color_output(in: vec4<f32>) -> vec4<f32> {
 #ifdef BLEND_ADD
     var color = vec4<f32>(in.rgb * in.a, 0.0);
 #endif
+#ifdef BLEND_SCREEN
+    var color = vec4<f32>(in.rgb * in.a, in.a);
+#endif
 #ifdef BLEND_ALPHA
     var color = in;
 #endif
 
     return color;
 }
+
+// Determine how much of a fragment lies within the scissor rectangle and clip mask, if any, as seen from the camera
+fn clip_mask(frag_coord: vec4<f32>) -> f32 {
+    var mask = 1.0;
+#ifdef CLIPPED
+#ifdef FRAGMENT
+    // Scissor rectangles are relative to the top left of the viewport
+    var pixel = frag_coord.xy - view.viewport.xy;
+    if any(pixel < clip.scissor.xy) || any(pixel > clip.scissor.zw) {
+        return 0.0;
+    }
+    if clip.masked == 0u {
+        return mask;
+    }
+
+    // Cast a ray through the fragment and into the local space of the mask, reverse z places the near plane at 1
+    var ndc = (frag_coord.xy - view.viewport.xy) / view.viewport.zw * 2.0 - 1.0;
+    ndc.y = -ndc.y;
+    var near = clip.inverse_transform * view.inverse_view_proj * vec4<f32>(ndc, 1.0, 1.0);
+    var far = clip.inverse_transform * view.inverse_view_proj * vec4<f32>(ndc, 0.5, 1.0);
+    var origin = near.xyz / near.w;
+    var dir = far.xyz / far.w - origin;
+
+    // Find where the ray meets the plane of the mask, masks viewed edge on hide everything
+    if abs(dir.z) < 0.000001 {
+        return 0.0;
+    }
+    var p = origin.xy - dir.xy * origin.z / dir.z;
+
+    // Signed distance to the rounded rectangle of the mask
+    var half_size = clip.size / 2.0;
+    var radius = clamp(clip.corner_radius, 0.0, min(half_size.x, half_size.y));
+    var q = abs(p) - half_size + radius;
+    var dist = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+    mask = step_aa(dist, 0.0);
+#endif
+#endif
+    return mask;
+}
+
+// Combine the fill of a shape with it's outline given how much of the outline covers a point,
+// shapes without an outline are only drawn where covered by their outline, which spans the whole shape when filled
+fn outline_fill(fill: vec4<f32>, stroke: vec4<f32>, outline: f32, flags: u32) -> vec4<f32> {
+    if f_outlined(flags) > 0u {
+        return mix(fill, stroke, outline);
+    }
+    return vec4<f32>(fill.rgb, fill.a * outline);
+}
+
+// Coverage of a shadow at the given distance from the edge of it's shape, fading out over the width of it's blur
+fn shadow_mask(dist: f32, blur: f32) -> f32 {
+    if blur <= 0.0 {
+        return step_aa(dist, 0.0);
+    }
+    return 1.0 - smoothstep(-blur / 2.0, blur / 2.0, dist);
+}
+
+// Coverage of a glow at the given distance from the edge of it's shape, fading out over it's radius outside the shape
+fn glow_mask(dist: f32, radius: f32, falloff: f32) -> f32 {
+    if radius <= 0.0 {
+        return 0.0;
+    }
+    var outside = 1.0 - step_aa(dist, 0.0);
+    return outside * pow(saturate(1.0 - dist / radius), falloff);
+}
+
+// Shadows and glows are never textured so ignore the sampled texture color for them
+fn texture_color(sample: vec4<f32>, flags: u32) -> vec4<f32> {
+    return select(sample, vec4<f32>(1.0), (f_shadow(flags) | f_glow(flags)) > 0u);
+}
+
+// Blend from a shape's color to it's gradient color at a position within a shape spanning -half_size to half_size
+fn gradient_color(color: vec4<f32>, end_color: vec4<f32>, gradient: vec4<f32>, pos: vec2<f32>, half_size: vec2<f32>, flags: u32) -> vec4<f32> {
+    var t = 0.0;
+    switch f_gradient(flags) {
+        // No gradient
+        default: {}
+        // Gradient::Linear
+        case 1u: {
+            // Measure along our direction relative to the extent of the shape in that direction
+            var dir = gradient.xy;
+            t = dot(pos, dir) / dot(abs(dir), half_size) * 0.5 + 0.5;
+        }
+        // Gradient::Radial
+        case 2u: {
+            // Work in a space where the edge of the shape is the unit circle
+            var p = pos / half_size;
+            var focal = gradient.xy;
+            if length(focal) > 0.99 {
+                focal = normalize(focal) * 0.99;
+            }
+
+            // Find how far the unit circle is from our focal point in the direction of our position
+            var to_pos = p - focal;
+            var dist = length(to_pos);
+            var dir = to_pos / max(dist, 1e-6);
+            var b = dot(focal, dir);
+            var edge = -b + sqrt(b * b - dot(focal, focal) + 1.0);
+            t = dist / edge;
+        }
+        // Gradient::Conic
+        case 3u: {
+            var angle = (atan2(pos.y, pos.x) - gradient.x) / TAU;
+            t = angle - floor(angle);
+        }
+    }
+    return mix(color, end_color, clamp(t, 0.0, 1.0));
+}
+
+// Mask points by a dash pattern of (on, off, offset) given their position along the outline of a shape
+fn dash_mask(along: f32, dash: vec3<f32>) -> f32 {
+    var period = dash.x + dash.y;
+    if period <= 0.0 {
+        return 1.0;
+    }
+
+    // Position within the current period of the pattern
+    var pos = along - dash.z;
+    pos = pos - period * floor(pos / period);
+
+    // Signed distance to the nearest end of a dash, positive within a dash
+    var dist = max(min(pos, dash.x - pos), pos - period);
+    return step_aa_pd(0.0, dist, along);
+}
 #endif
\ No newline at end of file
@@ -0,0 +1,42 @@
+// Composite the weighted blended order independent transparency of 3D shapes over the view
+
+#ifdef MULTISAMPLED
+@group(0) @binding(0)
+var accumulate_texture: texture_multisampled_2d<f32>;
+
+@group(0) @binding(1)
+var revealage_texture: texture_multisampled_2d<f32>;
+#else
+@group(0) @binding(0)
+var accumulate_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var revealage_texture: texture_2d<f32>;
+#endif
+
+struct FragmentInput {
+    @builtin(position) position: vec4<f32>,
+#ifdef MULTISAMPLED
+    @builtin(sample_index) sample_index: u32,
+#endif
+};
+
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    let coords = vec2<i32>(f.position.xy);
+#ifdef MULTISAMPLED
+    let accumulate = textureLoad(accumulate_texture, coords, i32(f.sample_index));
+    let revealage = textureLoad(revealage_texture, coords, i32(f.sample_index)).r;
+#else
+    let accumulate = textureLoad(accumulate_texture, coords, 0);
+    let revealage = textureLoad(revealage_texture, coords, 0).r;
+#endif
+
+    // Leave pixels without any shapes untouched
+    if revealage >= 1.0 {
+        discard;
+    }
+
+    // Average color of every shape weighted by their alpha, covering the view by how much light they block
+    return vec4<f32>(accumulate.rgb / max(accumulate.a, 0.00001), 1.0 - revealage);
+}
@@ -0,0 +1,209 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) start: vec3<f32>,
+    @location(8) end: vec3<f32>,
+    @location(9) head_size: f32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) length: f32,
+    @location(4) head_length: f32,
+    @location(5) flags: u32,
+#ifdef TEXTURED
+    @location(6) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+
+    // Get our start and end in world space
+    var world_start = (matrix * vec4<f32>(shape.start, 1.0)).xyz;
+    var world_end = (matrix * vec4<f32>(shape.end, 1.0)).xyz;
+
+    // The y basis is the normalized vector along the arrow
+    var y_basis = normalize(world_start - world_end);
+
+    // Choose which point we will work in reference to based on our y position
+    var origin = select(world_end, world_start, vertex.y < 0.0);
+
+    // Calculate the remainder of our basis vectors
+    var basis_vectors = core::get_basis_vectors_from_up(matrix, origin, y_basis, core::f_line_alignment(shape.flags));
+
+    // Calculate thickness data
+    var thickness_type = core::f_thickness_type(shape.flags);
+    var thickness_data = core::get_thickness_data(shape.thickness, thickness_type, origin, basis_vectors[1]);
+
+    let scale = core::get_scale(matrix);
+
+    // If our thickness in pixels is less than 1, clamp to 1 and reduce the alpha instead
+    var out_color = shape.color;
+    if thickness_data.thickness_p * scale.x < 1.0 {
+        out_color.a = out_color.a * thickness_data.thickness_p * scale.x;
+        thickness_data.thickness_p = 1.;
+    }
+
+    // We work in world units with x across the arrow and y along it starting from our start point
+    var thickness = thickness_data.thickness_p / thickness_data.pixels_per_u * scale.x;
+    out.radius = thickness / 2.0;
+    out.length = length(world_end - world_start);
+    out.head_length = shape.head_size * thickness;
+
+    // Our heads are as wide as they are long so pad each end and side by half a head to fit them
+    var extent = out.radius + out.head_length / 2.0;
+    var aa_padding_u = core::AA_PADDING / thickness_data.pixels_per_u;
+
+    // Calculate the offset from our origin point
+    var local_offset = vertex.xy * (extent + aa_padding_u);
+
+    // Determine final world position by offsetting by the origin we chose and rotating by our basis vectors
+    var world_pos = origin + local_offset.x * basis_vectors[0] + local_offset.y * basis_vectors[1];
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+    out.pos = vec2<f32>(local_offset.x, select(out.length + local_offset.y, local_offset.y, vertex.y < 0.0));
+    out.flags = shape.flags;
+
+    out.color = out_color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) length: f32,
+    @location(4) head_length: f32,
+    @location(5) flags: u32,
+#ifdef TEXTURED
+    @location(6) texture_uv: vec2<f32>,
+#endif
+};
+
+// Distance from the body of the arrow between a and b along the y axis, rounding either end when requested
+fn bodySDF(pos: vec2<f32>, a: f32, b: f32, radius: f32, round_a: bool, round_b: bool) -> f32 {
+    var nearest = clamp(pos.y, a, b);
+    if (pos.y < a && round_a) || (pos.y > b && round_b) {
+        return length(vec2<f32>(pos.x, pos.y - nearest)) - radius;
+    }
+    return max(abs(pos.x) - radius, max(a - pos.y, pos.y - b));
+}
+
+// Distance from a head with it's tip at the origin pointing towards negative y
+fn headSDF(position: vec2<f32>, head: u32, head_length: f32, radius: f32) -> f32 {
+    // Heads are symmetrical across the y axis so mirror our point into positive x
+    var pos = vec2<f32>(abs(position.x), position.y);
+    var half_width = head_length / 2.0;
+
+    var dist: f32;
+    switch head {
+        // ArrowHead::Triangle
+        default: {
+            // Signed distance from the slanted side, and from the base
+            var side = (pos.x * head_length - pos.y * half_width) / length(vec2<f32>(head_length, half_width));
+            dist = max(side, pos.y - head_length);
+        }
+        // ArrowHead::Open
+        case 1u: {
+            // Distance from the stroke between the tip and the end of a wing
+            var wing = vec2<f32>(half_width, head_length);
+            var h = clamp(dot(pos, wing) / dot(wing, wing), 0.0, 1.0);
+            dist = length(pos - wing * h) - radius;
+        }
+        // ArrowHead::Dot
+        case 2u: {
+            dist = length(pos) - half_width;
+        }
+    }
+    return dist;
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    var head = core::f_arrow_head(f.flags);
+    var double_headed = core::f_double_headed(f.flags) > 0u;
+    var cap = core::f_cap(f.flags);
+
+    // Triangle heads replace the end of the body, other heads are drawn over it
+    var head_inset = select(0.0, f.head_length, head == 0u);
+    var body_end = f.length - head_inset;
+    var body_start = select(0.0, head_inset, double_headed);
+
+    // Without a head at the start we apply the cap, square caps extend by our radius
+    if !double_headed && cap == 1u {
+        body_start = -f.radius;
+    }
+    var round_start = select(cap == 2u, head == 1u, double_headed);
+
+    var dist = bodySDF(f.pos, body_start, body_end, f.radius, round_start, head == 1u);
+
+    // Reflect our position such that each tip is at the origin pointing towards negative y
+    dist = min(dist, headSDF(vec2<f32>(f.pos.x, f.length - f.pos.y), head, f.head_length, f.radius));
+    if double_headed {
+        dist = min(dist, headSDF(f.pos, head, f.head_length, f.radius));
+    }
+
+    in_shape *= core::step_aa(dist, 0.0);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
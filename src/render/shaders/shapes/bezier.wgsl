@@ -0,0 +1,224 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) p_0: vec2<f32>,
+    @location(8) p_1: vec2<f32>,
+    @location(9) p_2: vec2<f32>,
+    @location(10) p_3: vec2<f32>,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) cap: u32,
+
+    @location(4) p_0: vec2<f32>,
+    @location(5) p_1: vec2<f32>,
+    @location(6) p_2: vec2<f32>,
+    @location(7) p_3: vec2<f32>,
+#ifdef TEXTURED
+    @location(8) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+    let scale = core::get_scale(matrix);
+
+    // A bezier curve always falls within the convex hull of it's control points so we can use their bounds for our quad
+    let bounds_min = min(min(shape.p_0, shape.p_1), min(shape.p_2, shape.p_3));
+    let bounds_max = max(max(shape.p_0, shape.p_1), max(shape.p_2, shape.p_3));
+    let center = (bounds_min + bounds_max) / 2.0;
+    let half_size = (bounds_max - bounds_min) / 2.0;
+
+    // Transform the center of our bounds into world space
+    var origin = (matrix * vec4<f32>(center, 0.0, 1.0)).xyz;
+    var basis_vectors = core::get_basis_vectors(matrix, origin, shape.flags);
+
+    // Calculate thickness data
+    var thickness_type = core::f_thickness_type(shape.flags);
+    var thickness_data = core::get_thickness_data(shape.thickness, thickness_type, origin, basis_vectors[1]);
+
+    // If our thickness in pixels is less than 1, clamp to 1 and reduce the alpha instead
+    var out_color = shape.color;
+    if thickness_data.thickness_p < 1.0 {
+        out_color.a = out_color.a * thickness_data.thickness_p;
+        thickness_data.thickness_p = 1.;
+    }
+
+    // Convert our radius and padding from pixels into the local space of our control points
+    let local_per_p = 1.0 / (thickness_data.pixels_per_u * max(scale.x, scale.y));
+    var radius = thickness_data.thickness_p / 2.0 * local_per_p;
+    var aa_padding = core::AA_PADDING * local_per_p;
+
+    // Pad our bounds by the diagonal of a square cap so that caps are never clipped
+    var local_pos = center + vertex.xy * (half_size + radius * 1.42 + aa_padding);
+
+    // Rotate the position based on our basis vectors and add the world position offset
+    let offset = (local_pos - center) * scale;
+    var world_pos = origin + offset.x * basis_vectors[0] - offset.y * basis_vectors[1];
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+    out.pos = local_pos;
+    out.radius = radius;
+    out.cap = core::f_cap(shape.flags);
+
+    out.p_0 = shape.p_0;
+    out.p_1 = shape.p_1;
+    out.p_2 = shape.p_2;
+    out.p_3 = shape.p_3;
+
+    out.color = out_color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) cap: u32,
+
+    @location(4) p_0: vec2<f32>,
+    @location(5) p_1: vec2<f32>,
+    @location(6) p_2: vec2<f32>,
+    @location(7) p_3: vec2<f32>,
+#ifdef TEXTURED
+    @location(8) texture_uv: vec2<f32>,
+#endif
+};
+
+// Number of straight segments the curve is divided into when measuring distance
+const SEGMENTS: u32 = 32u;
+
+// Evaluate the cubic bezier curve with the given control points at t
+fn bezier_point(p_0: vec2<f32>, p_1: vec2<f32>, p_2: vec2<f32>, p_3: vec2<f32>, t: f32) -> vec2<f32> {
+    let u = 1.0 - t;
+    return u * u * u * p_0 + 3.0 * u * u * t * p_1 + 3.0 * u * t * t * p_2 + t * t * t * p_3;
+}
+
+fn cross2d(a: vec2<f32>, b: vec2<f32>) -> f32 {
+    return (a.x * b.y) - (a.y * b.x);
+}
+
+// Calculate the distance between a point and the curve taking into account the cap type
+fn bezierSDF(pos: vec2<f32>, p_0: vec2<f32>, p_1: vec2<f32>, p_2: vec2<f32>, p_3: vec2<f32>, radius: f32, cap: u32) -> f32 {
+    var dist = 1e20;
+
+    // Distance past the end of the curve along it's tangent and distance from the tangent
+    // only non-zero when the closest point on the curve is one of it's end points
+    var along = 0.0;
+    var perp = 0.0;
+
+    var prev = p_0;
+    for (var i = 1u; i <= SEGMENTS; i++) {
+        let next = bezier_point(p_0, p_1, p_2, p_3, f32(i) / f32(SEGMENTS));
+        let seg = next - prev;
+        let seg_length_sq = max(dot(seg, seg), 1e-12);
+        let to_pos = pos - prev;
+
+        // Project our point onto the segment
+        let h = dot(to_pos, seg) / seg_length_sq;
+        let seg_dist = length(to_pos - seg * clamp(h, 0.0, 1.0));
+
+        if seg_dist < dist {
+            dist = seg_dist;
+            along = 0.0;
+
+            // Check if we are past either end of the curve
+            let seg_length = sqrt(seg_length_sq);
+            if i == 1u && h < 0.0 {
+                along = -h * seg_length;
+                perp = abs(cross2d(to_pos, seg)) / seg_length;
+            } else if i == SEGMENTS && h > 1.0 {
+                along = (h - 1.0) * seg_length;
+                perp = abs(cross2d(to_pos, seg)) / seg_length;
+            }
+        }
+
+        prev = next;
+    }
+
+    if along > 0.0 {
+        switch cap {
+            // Cap::None
+            default: {
+                dist = max(perp, along + radius);
+            }
+            // Cap::Square
+            case 1u: {
+                dist = max(perp, along);
+            }
+            // Cap::Round, our euclidean distance is already correct
+            case 2u: {}
+        }
+    }
+
+    return dist;
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    // Cut off points further from the curve than our radius
+    var dist = bezierSDF(f.pos, f.p_0, f.p_1, f.p_2, f.p_3, f.radius, f.cap);
+    in_shape *= core::step_aa(dist, f.radius);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
@@ -0,0 +1,157 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) start: vec2<f32>,
+    @location(8) end: vec2<f32>,
+    @location(9) radius: f32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) thickness: f32,
+
+    @location(4) start: vec2<f32>,
+    @location(5) end: vec2<f32>,
+#ifdef TEXTURED
+    @location(6) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+    let scale = core::get_scale(matrix);
+
+    // Our quad covers the bounds of the segment between our two centers
+    let bounds_min = min(shape.start, shape.end);
+    let bounds_max = max(shape.start, shape.end);
+    let center = (bounds_min + bounds_max) / 2.0;
+    let half_size = (bounds_max - bounds_min) / 2.0;
+
+    // Transform the center of our bounds into world space
+    var origin = (matrix * vec4<f32>(center, 0.0, 1.0)).xyz;
+    var basis_vectors = core::get_basis_vectors(matrix, origin, shape.flags);
+
+    // Calculate thickness data
+    var thickness_type = core::f_thickness_type(shape.flags);
+    var thickness_data = core::get_thickness_data(shape.thickness, thickness_type, origin, basis_vectors[1]);
+
+    // Convert our thickness and padding from pixels into the local space of our points
+    let local_per_p = 1.0 / (thickness_data.pixels_per_u * max(scale.x, scale.y));
+    var aa_padding = core::AA_PADDING * local_per_p;
+    if core::f_hollow(shape.flags) > 0u {
+        out.thickness = thickness_data.thickness_p * local_per_p;
+    } else {
+        // Filled capsules can never be further than their radius inside the outline
+        out.thickness = shape.radius * 2.0;
+    }
+
+    // Pad our bounds by the radius so that the semi-circles are never clipped
+    var local_pos = center + vertex.xy * (half_size + shape.radius + aa_padding);
+
+    // Rotate the position based on our basis vectors and add the world position offset
+    let offset = (local_pos - center) * scale;
+    var world_pos = origin + offset.x * basis_vectors[0] - offset.y * basis_vectors[1];
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+    out.pos = local_pos;
+    out.radius = shape.radius;
+
+    out.start = shape.start;
+    out.end = shape.end;
+
+    out.color = shape.color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) thickness: f32,
+
+    @location(4) start: vec2<f32>,
+    @location(5) end: vec2<f32>,
+#ifdef TEXTURED
+    @location(6) texture_uv: vec2<f32>,
+#endif
+};
+
+// Calculate the signed distance between a point and the outline of a capsule
+fn capsuleSDF(pos: vec2<f32>, start: vec2<f32>, end: vec2<f32>, radius: f32) -> f32 {
+    let seg = end - start;
+    let to_pos = pos - start;
+
+    // Project our point onto the segment, clamping to it's ends
+    let h = clamp(dot(to_pos, seg) / max(dot(seg, seg), 1e-12), 0.0, 1.0);
+    return length(to_pos - seg * h) - radius;
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    // Calculate our positions distance from the capsule
+    var dist = capsuleSDF(f.pos, f.start, f.end, f.radius);
+
+    // Cut off points outside the shape or within the hollow area
+    in_shape *= core::step_aa(-f.thickness, dist) * core::step_aa(dist, 0.);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
@@ -1,6 +1,7 @@
 #import bevy_vector_shapes::core
 #import bevy_vector_shapes::core::{view, image, image_sampler}
 #import bevy_vector_shapes::constants::{PI, TAU}
+#import bevy_vector_shapes::hooks
 
 struct Vertex {
     @builtin(instance_index) index: u32,
@@ -20,6 +21,14 @@ struct Shape {
     @location(7) radius: f32,
     @location(8) start_angle: f32, 
     @location(9) end_angle: f32,
+    @location(10) inner_radius: f32,
+    @location(11) dash: vec3<f32>,
+    @location(12) thickness_end: f32,
+    @location(13) gradient_color: vec4<f32>,
+    @location(14) gradient: vec4<f32>,
+    @location(15) texture_uv: vec4<f32>,
+    @location(16) stroke_color: vec4<f32>,
+    @location(17) user_data: vec4<f32>,
 };
 
 #ifdef PER_OBJECT_BUFFER_BATCH_SIZE
@@ -36,8 +45,19 @@ struct VertexOutput {
     @location(3) angle: f32,
     @location(4) delta: f32,
     @location(5) cap: u32,
+    @location(6) dash: vec3<f32>,
+    @location(7) thickness_end: f32,
+    @location(8) flags: u32,
+    @location(9) gradient_color: vec4<f32>,
+    @location(10) gradient: vec4<f32>,
+    @location(11) gradient_pos: vec2<f32>,
+    @location(12) stroke_color: vec4<f32>,
+    @location(13) stroke_offset: f32,
 #ifdef TEXTURED
-    @location(6) texture_uv: vec2<f32>,
+    @location(14) texture_uv: vec2<f32>,
+#endif
+#ifdef SHAPE_HOOK
+    @location(15) @interpolate(flat) user_data: vec4<f32>,
 #endif
 };
 
@@ -47,27 +67,60 @@ fn vertex(v: Vertex) -> VertexOutput {
 
     // Vertex positions for a basic quad
     let vertex = v.pos;
-    let shape = shapes[v.index];
+    let shape = shapes[core::get_instance_index(v.index)];
 
-    let matrix = mat4x4<f32>(
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
         shape.matrix_0,
         shape.matrix_1,
         shape.matrix_2,
         shape.matrix_3
-    );
+    ), shape.flags);
 
     var vertex_data = core::get_vertex_data(matrix, vertex.xy * shape.radius, shape.thickness, shape.flags);
+    out.thickness = core::calculate_thickness(vertex_data.thickness_data, shape.radius, shape.flags);
+
+    // Thickness types are all linear so the end thickness of a tapered arc scales with our thickness in uv space
+    out.thickness_end = out.thickness * shape.thickness_end / max(shape.thickness, 1e-6);
+
+    out.flags = shape.flags;
+
+    // Annular sectors are always filled between their inner and outer radius and are never outlined
+    if shape.inner_radius > 0.0 {
+        out.thickness = 1.0 - shape.inner_radius / shape.radius;
+        out.flags = out.flags & ~((1u << 15u) | (3u << 16u));
+    }
+
+    // Only hollow arcs taper, everything else keeps a constant thickness
+    var arc = core::f_arc(shape.flags);
+    if arc == 0u || core::f_hollow(shape.flags) == 0u || shape.inner_radius > 0.0 {
+        out.thickness_end = out.thickness;
+    }
+
+    // Grow our quad to fit outlines that are moved outwards by their stroke alignment
+    out.stroke_offset = core::stroke_offset(out.flags);
+    var grow = 1.0 + out.stroke_offset * max(out.thickness, out.thickness_end);
+    vertex_data = core::get_vertex_data(matrix, vertex.xy * shape.radius * grow, shape.thickness, shape.flags);
 
     // Multiply the world space position by the view projection matrix to convert to our clip position
     out.clip_position = vertex_data.clip_pos;
-    out.uv = vertex.xy * vertex_data.uv_ratio;
-    out.thickness = core::calculate_thickness(vertex_data.thickness_data, shape.radius, shape.flags);
+    out.uv = vertex.xy * grow * vertex_data.uv_ratio;
+
+    // Convert our dash pattern from world units into uv space
+    out.dash = shape.dash / (shape.radius * vertex_data.scale.x);
 
     // Extract cap type from flags
     out.cap = core::f_cap(shape.flags);
 
+    // Gradients are measured before our uv space is rotated for arcs
+    out.gradient_pos = out.uv;
+    out.gradient_color = shape.gradient_color;
+    out.gradient = shape.gradient;
+    out.stroke_color = shape.stroke_color;
+#ifdef SHAPE_HOOK
+    out.user_data = shape.user_data;
+#endif
+
     // Setup angles for the fragment shader if we are an arc
-    var arc = core::f_arc(shape.flags);
     if arc > 0u {
         // Transform our angles such that 0 points towards y up
         var delta = (shape.end_angle - shape.start_angle) / 2.0;
@@ -83,20 +136,32 @@ fn vertex(v: Vertex) -> VertexOutput {
 
     out.color = shape.color;
 #ifdef TEXTURED
-    out.texture_uv = core::get_texture_uv(vertex.xy);
+    out.texture_uv = core::get_tiled_texture_uv(vertex.xy * grow, shape.texture_uv);
 #endif
     return out;
 }
 
 struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) thickness: f32,
     @location(3) angle: f32,
     @location(4) delta: f32,
     @location(5) cap: u32,
+    @location(6) dash: vec3<f32>,
+    @location(7) thickness_end: f32,
+    @location(8) flags: u32,
+    @location(9) gradient_color: vec4<f32>,
+    @location(10) gradient: vec4<f32>,
+    @location(11) gradient_pos: vec2<f32>,
+    @location(12) stroke_color: vec4<f32>,
+    @location(13) stroke_offset: f32,
 #ifdef TEXTURED
-    @location(6) texture_uv: vec2<f32>,
+    @location(14) texture_uv: vec2<f32>,
+#endif
+#ifdef SHAPE_HOOK
+    @location(15) @interpolate(flat) user_data: vec4<f32>,
 #endif
 };
 
@@ -104,36 +169,80 @@ struct FragmentInput {
 #ifdef FRAGMENT
 @fragment
 fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
-    // Mask representing whether this fragment falls within the shape
-    var in_shape = f.color.a;
+    // User data is only passed to the fragment shader of shapes drawn with a hook
+#ifdef SHAPE_HOOK
+    let user_data = f.user_data;
+#else
+    let user_data = vec4<f32>(0.0);
+#endif
+
+    // Interpolate the thickness of a tapered arc from it's start to it's end
+    var angle = atan2(f.uv.y, f.uv.x);
+    var t = clamp((angle + f.delta) / (2.0 * f.delta), 0.0, 1.0);
+    var thickness = mix(f.thickness, f.thickness_end, t);
 
-    // Cut off points outside the shape or within the hollow area
-    var dist = length(f.uv) - 1.;
-    in_shape *= core::step_aa(-f.thickness, dist) * core::step_aa(dist, 0.);
+    // Conic gradients on arcs follow the sweep of the arc
+    var fill_color = core::gradient_color(f.color, f.gradient_color, f.gradient, f.gradient_pos, vec2<f32>(1.0), f.flags);
+    if core::f_gradient(f.flags) == 3u && core::f_arc(f.flags) > 0u {
+        fill_color = mix(f.color, f.gradient_color, t);
+    }
+    fill_color = hooks::fill(fill_color, f.gradient_pos, vec2<f32>(1.0), f.clip_position, user_data);
+
+    // Cut off points outside the shape, which grows outwards with our outline
+    var offset = f.stroke_offset * thickness;
+    var dist = hooks::distance(length(f.uv) - 1. - offset, f.uv, vec2<f32>(1.0), user_data);
+    var in_shape = core::step_aa(dist, 0.);
 
     // Cut off points outside the allowed range of angles
-    var angle = atan2(f.uv.y, f.uv.x);
     in_shape *= core::step_aa_pd(-f.delta, angle, abs(angle)) * core::step_aa_pd(angle, f.delta, abs(angle));
 
-    // Handle rounded caps
-    if f.cap == 2u {
+    // Our outline runs inside the edge of the shape, when hollow everything outside of it is cut off
+    var outline = core::step_aa(-thickness, dist);
+    var outlined = core::f_outlined(f.flags) > 0u;
+
+    // Filled arcs are also outlined along their straight edges
+    if outlined && core::f_arc(f.flags) > 0u {
+        var to_edge = f.delta - abs(angle);
+        var edge_dist = select(length(f.uv), length(f.uv) * sin(to_edge), to_edge < PI / 2.0) + offset;
+        outline = max(outline, core::step_aa(edge_dist, thickness));
+    }
+
+    // Dash along the center of our outline starting from the beginning of the arc
+    outline *= core::dash_mask((angle + f.delta) * (1.0 + offset - thickness / 2.0), f.dash);
+
+    var shape_color = core::outline_fill(fill_color, f.stroke_color, outline, f.flags);
+    in_shape *= shape_color.a;
+
+    // Handle rounded caps, outlined arcs keep their straight edges
+    if f.cap == 2u && !outlined {
         // Take the delta in the direction towards our point
         var nearest_angle = sign(angle) * f.delta;
 
         // With that delta find the point at the end of the arc
         // Use thickness to offset from the radius
-        var end_point = vec2<f32>(cos(nearest_angle), sin(nearest_angle)) * (1.0 - f.thickness / 2.0);
+        var end_point = vec2<f32>(cos(nearest_angle), sin(nearest_angle)) * (1.0 + offset - thickness / 2.0);
 
         // Mask in points near the end point based on our thickness
         var dist = length(end_point - f.uv);
 
-        var mask = core::step_aa(dist, f.thickness / 2.0);
-        in_shape = min(max(in_shape, mask), f.color.a);
+        var mask = core::step_aa(dist, thickness / 2.0);
+        in_shape = min(max(in_shape, mask), fill_color.a);
+    }
+
+    // Shadows fade out either side of the edge of the shape rather than being cut off by it
+    if core::f_shadow(f.flags) > 0u {
+        in_shape = core::shadow_mask(dist + offset, thickness) * fill_color.a;
+    }
+
+    // Glows fade out from the edge of the shape with their falloff packed into their gradient
+    if core::f_glow(f.flags) > 0u {
+        in_shape = core::glow_mask(dist + offset, thickness, f.gradient.x) * fill_color.a;
     }
 
-    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(shape_color.rgb, in_shape));
 #ifdef TEXTURED
-    color = color * textureSample(image, image_sampler, f.texture_uv);
+    color = color * core::texture_color(textureSample(image, image_sampler, f.texture_uv), f.flags);
 #endif
 
     // Discard fragments no longer in the shape
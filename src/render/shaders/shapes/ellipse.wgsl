@@ -0,0 +1,138 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) radii: vec2<f32>,
+}
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) radii: vec2<f32>,
+    @location(3) thickness: f32,
+#ifdef TEXTURED
+    @location(4) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+    // Shortest of the two radii for the ellipse
+    var shortest_radius = min(shape.radii.x, shape.radii.y);
+
+    var vertex_data = core::get_vertex_data(matrix, vertex.xy * shape.radii, shape.thickness, shape.flags);
+    out.clip_position = vertex_data.clip_pos;
+
+    // Our vertex outputs should all be in uv space so scale our uv space such that the shortest radius is of length 1
+    out.radii = shape.radii / shortest_radius;
+    out.uv = vertex.xy * out.radii * vertex_data.uv_ratio;
+    out.thickness = core::calculate_thickness(vertex_data.thickness_data, shortest_radius, shape.flags);
+
+    out.color = shape.color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) radii: vec2<f32>,
+    @location(3) thickness: f32,
+#ifdef TEXTURED
+    @location(4) texture_uv: vec2<f32>,
+#endif
+};
+
+// Number of newton iterations used to find the closest point on the ellipse
+const ITERATIONS: i32 = 5;
+
+// Given a position and the radii of an ellipse determine the signed distance between the point and the ellipse
+fn ellipseSDF(position: vec2<f32>, radii: vec2<f32>) -> f32 {
+    // Ellipses are symmetrical across both axis so we can mirror our point
+    // into the positive x and y axis by taking the absolute value
+    var pos = abs(position);
+
+    // Start our search for the angle of the closest point on whichever axis we are nearest to
+    var q = radii * (pos - radii);
+    var w = select(0.0, PI / 2.0, q.x < q.y);
+
+    // Refine the angle using newton's method on the dot product of the tangent and the vector to our point
+    for (var i = 0; i < ITERATIONS; i++) {
+        var cs = vec2<f32>(cos(w), sin(w));
+        var u = radii * cs;
+        var v = radii * vec2<f32>(-cs.y, cs.x);
+        w = w + dot(pos - u, v) / (dot(pos - u, u) + dot(v, v));
+    }
+
+    var dist = length(pos - radii * vec2<f32>(cos(w), sin(w)));
+
+    // Points inside the ellipse have a negative distance
+    var scaled = pos / radii;
+    return select(-dist, dist, dot(scaled, scaled) > 1.0);
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    // Calculate our positions distance from the ellipse
+    var dist = ellipseSDF(f.uv, f.radii);
+
+    // Cut off points outside the shape or within the hollow area
+    in_shape *= core::step_aa(-f.thickness, dist) * core::step_aa(dist, 0.);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
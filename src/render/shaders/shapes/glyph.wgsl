@@ -0,0 +1,102 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) position: vec2<f32>,
+    @location(8) size: vec2<f32>,
+    @location(9) uv_min: vec2<f32>,
+    @location(10) uv_max: vec2<f32>,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) atlas_uv: vec2<f32>,
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+    let scale = core::get_scale(matrix);
+
+    // Transform the center of our glyph into world space
+    var origin = (matrix * vec4<f32>(shape.position, 0.0, 1.0)).xyz;
+    var basis_vectors = core::get_basis_vectors(matrix, origin, shape.flags);
+
+    // Glyphs already include the spread of their distance field so need no additional padding
+    let offset = vertex.xy * shape.size / 2.0 * scale;
+    var world_pos = origin + offset.x * basis_vectors[0] - offset.y * basis_vectors[1];
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+
+    // Rows of our atlas run from the top of the glyph down
+    let t = (vertex.xy + 1.0) / 2.0;
+    out.atlas_uv = mix(shape.uv_min, shape.uv_max, vec2<f32>(t.x, 1.0 - t.y));
+
+    out.color = shape.color;
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) atlas_uv: vec2<f32>,
+};
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    // Our atlas stores distance to the outline of each glyph with the outline at 0.5
+#ifdef TEXTURED
+    var dist = textureSample(image, image_sampler, f.atlas_uv).r;
+    in_shape *= core::step_aa(0.5, dist);
+#endif
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
@@ -0,0 +1,148 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) cells: vec2<u32>,
+    @location(8) cell_size: vec2<f32>,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) cells: vec2<f32>,
+    @location(4) cell_size: vec2<f32>,
+#ifdef TEXTURED
+    @location(5) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+    let scale = core::get_scale(matrix);
+
+    let cells = vec2<f32>(shape.cells);
+    let half_size = cells * shape.cell_size / 2.0;
+
+    // Transform the center of our grid into world space
+    var origin = (matrix * vec4<f32>(0.0, 0.0, 0.0, 1.0)).xyz;
+    var basis_vectors = core::get_basis_vectors(matrix, origin, shape.flags);
+
+    // Calculate thickness data
+    var thickness_type = core::f_thickness_type(shape.flags);
+    var thickness_data = core::get_thickness_data(shape.thickness, thickness_type, origin, basis_vectors[1]);
+
+    // If our thickness in pixels is less than 1, clamp to 1 and reduce the alpha instead
+    var out_color = shape.color;
+    if thickness_data.thickness_p < 1.0 {
+        out_color.a = out_color.a * thickness_data.thickness_p;
+        thickness_data.thickness_p = 1.;
+    }
+
+    // Convert our radius and padding from pixels into the local space of our grid
+    let local_per_p = 1.0 / (thickness_data.pixels_per_u * max(scale.x, scale.y));
+    var radius = thickness_data.thickness_p / 2.0 * local_per_p;
+    var aa_padding = core::AA_PADDING * local_per_p;
+
+    // Pad our bounds so that the outer lines are never clipped
+    var local_pos = vertex.xy * (half_size + radius + aa_padding);
+
+    // Rotate the position based on our basis vectors and add the world position offset
+    let offset = local_pos * scale;
+    var world_pos = origin + offset.x * basis_vectors[0] - offset.y * basis_vectors[1];
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+
+    // Measure our position from the corner of the grid so that lines fall on multiples of the cell size
+    out.pos = local_pos + half_size;
+    out.radius = radius;
+    out.cells = cells;
+    out.cell_size = shape.cell_size;
+
+    out.color = out_color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) cells: vec2<f32>,
+    @location(4) cell_size: vec2<f32>,
+#ifdef TEXTURED
+    @location(5) texture_uv: vec2<f32>,
+#endif
+};
+
+// Given a position measured from the corner of the grid determine the distance to the nearest line
+fn gridSDF(pos: vec2<f32>, cells: vec2<f32>, cell_size: vec2<f32>) -> f32 {
+    // Snap to the nearest line on each axis, clamping so that no lines are drawn past the edges of the grid
+    var nearest = clamp(round(pos / cell_size), vec2<f32>(0.0), cells) * cell_size;
+    var to_line = abs(pos - nearest);
+    return min(to_line.x, to_line.y);
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    // Cut off points further from a line than our radius
+    var dist = gridSDF(f.pos, f.cells, f.cell_size);
+    in_shape *= core::step_aa(dist, f.radius);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
@@ -19,6 +19,9 @@ struct Shape {
 
     @location(7) start: vec3<f32>,
     @location(8) end: vec3<f32>,
+    @location(9) dash: vec3<f32>,
+    @location(10) thickness_end: f32,
+    @location(11) color_end: vec4<f32>,
 };
 
 #ifdef PER_OBJECT_BUFFER_BATCH_SIZE
@@ -31,9 +34,15 @@ struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
     @location(1) uv: vec2<f32>,
-    @location(2) cap_ratio: f32,
+    @location(2) cap: u32,
+    @location(3) along: f32,
+    @location(4) dash: vec3<f32>,
+    @location(5) t: f32,
+    @location(6) length_ratio: f32,
+    @location(7) taper: vec2<f32>,
+    @location(8) color_end: vec4<f32>,
 #ifdef TEXTURED
-    @location(3) texture_uv: vec2<f32>,
+    @location(9) texture_uv: vec2<f32>,
 #endif
 };
 
@@ -43,15 +52,15 @@ fn vertex(v: Vertex) -> VertexOutput {
 
     // Vertex positions for a basic quad
     let vertex = v.pos;
-    let shape = shapes[v.index];
+    let shape = shapes[core::get_instance_index(v.index)];
 
     // Reconstruct our transformation matrix
-    let matrix = mat4x4<f32>(
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
         shape.matrix_0,
         shape.matrix_1,
         shape.matrix_2,
         shape.matrix_3
-    );
+    ), shape.flags);
 
     // Vector from start -> end
     var line_vec = shape.end - shape.start;
@@ -73,18 +82,24 @@ fn vertex(v: Vertex) -> VertexOutput {
     var origin = select(world_end, world_start, vertex.y < 0.0);
 
     // Calculate the remainder of our basis vectors
-    var basis_vectors = core::get_basis_vectors_from_up(matrix, origin, y_basis, core::f_alignment(shape.flags) << 1u);
+    var basis_vectors = core::get_basis_vectors_from_up(matrix, origin, y_basis, core::f_line_alignment(shape.flags));
 
-    // Calculate thickness data
+    // Calculate thickness data for the thicker end of the line, our quad is sized to fit it along the whole length
     var thickness_type = core::f_thickness_type(shape.flags);
-    var thickness_data = core::get_thickness_data(shape.thickness, thickness_type, origin, basis_vectors[1]);
+    var max_thickness = max(shape.thickness, shape.thickness_end);
+    var thickness_data = core::get_thickness_data(max_thickness, thickness_type, origin, basis_vectors[1]);
+
+    // Thickness of each end relative to the thicker end, thickness types are all linear so this holds in pixels
+    out.taper = vec2<f32>(shape.thickness, shape.thickness_end) / max(max_thickness, 1e-6);
 
     let scale = vec3<f32>(length(matrix[0].xyz), length(matrix[1].xyz), length(matrix[2].xyz));
 
     // If our thickness in pixels is less than 1, clamp to 1 and reduce the alpha instead
     var out_color = shape.color;
+    var out_color_end = shape.color_end;
     if thickness_data.thickness_p * max(scale.x, scale.y) < 1.0 {
         out_color.a = out_color.a * thickness_data.thickness_p * max(scale.x, scale.y);
+        out_color_end.a = out_color_end.a * thickness_data.thickness_p * max(scale.x, scale.y);
         thickness_data.thickness_p = 1.;
     }
 
@@ -100,10 +115,7 @@ fn vertex(v: Vertex) -> VertexOutput {
         cap_length = radius;
     }
 
-    // If our caps are round store the ratio of the length of our caps to the entire length of the line
-    if cap_type == 2u {
-        out.cap_ratio = thickness / (line_length + thickness);
-    }
+    out.cap = cap_type;
 
     // Calculate the vertex position with scaling
     var local_pos = vertex.xy * vec2<f32>(radius, cap_length + line_length / 2.0) * scale.xy;
@@ -126,7 +138,17 @@ fn vertex(v: Vertex) -> VertexOutput {
     out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
     out.uv = vertex.xy * uv_ratio;
 
+    // Measure our distance along the line from the start in world units for dashing
+    var world_length = length(world_end - world_start);
+    out.along = select(world_length + local_offset.y, local_offset.y, vertex.y < 0.0);
+    out.dash = shape.dash;
+
+    // Our position along the body of the line from 0 to 1, and the ratio to convert it into multiples of our radius
+    out.t = out.along / max(world_length, 1e-6);
+    out.length_ratio = world_length / max(radius * scale.y, 1e-6);
+
     out.color = out_color;
+    out.color_end = out_color_end;
 #ifdef TEXTURED
     out.texture_uv = core::get_texture_uv(vertex.xy);
 #endif
@@ -134,11 +156,18 @@ fn vertex(v: Vertex) -> VertexOutput {
 }
 
 struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
     @location(1) uv: vec2<f32>,
-    @location(2) cap_ratio: f32,
+    @location(2) cap: u32,
+    @location(3) along: f32,
+    @location(4) dash: vec3<f32>,
+    @location(5) t: f32,
+    @location(6) length_ratio: f32,
+    @location(7) taper: vec2<f32>,
+    @location(8) color_end: vec4<f32>,
 #ifdef TEXTURED
-    @location(3) texture_uv: vec2<f32>,
+    @location(9) texture_uv: vec2<f32>,
 #endif
 };
 
@@ -146,37 +175,36 @@ struct FragmentInput {
 #ifdef FRAGMENT
 @fragment
 fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
-    var in_shape = f.color.a;
-
-    // If we have rounded caps mask them
-    if f.cap_ratio > 0.0 {
-        // Lines are symmetrical across both axis so we can mirror our point 
-        //  onto the positive x and y axis by taking the absolute value
-        var pos = abs(f.uv);
-
-        // Our x value already represents the x distance to our line so we now must transform our y value
-        // We want y = 0 to represent being within the body of the line, and 1 to be at the tip of our cap
-
-        // Calculate the -y distance to the end of the quad in caps
-        // The end of the quad is y = 1 so subtract to get the distance and then scale by cap length
-        var to_end_cap = (pos.y - 1.) / f.cap_ratio;
-
-        // We want y = 0 when the amount of caps until the end of the quad is 1 
-        //  and y = 1 when the number of quads is 0 so take 1 + to_end_cap
-        // If the total is negative we are within the line so clamp to > 0
-        pos.y = max(0., 1. + to_end_cap);
-
-        // We now have the shortest vector from our point to the line so take the distance
-        var dist = length(pos);
-
-        // Mask out corners
-        in_shape = min(in_shape, core::step_aa(dist, 1.));
-    } else {
-        // Simple rectangle sdf for no caps or square caps
-        in_shape = min(in_shape, core::step_aa(abs(f.uv.x), 1.) * core::step_aa(abs(f.uv.y), 1.0));
+    // Interpolate our color along the body of the line, the caps take the color of their end
+    var line_color = mix(f.color, f.color_end, clamp(f.t, 0.0, 1.0));
+    var in_shape = line_color.a;
+
+    // Half width of the line at our position relative to the thicker end, the caps take the width of their end
+    var width = mix(f.taper.x, f.taper.y, clamp(f.t, 0.0, 1.0));
+
+    // Distance past the nearest end of the body of the line in multiples of our radius
+    var past_end = max(max(-f.t, f.t - 1.0), 0.0) * f.length_ratio;
+
+    switch f.cap {
+        // Cap::None, the quad ends with the body of the line
+        default: {
+            in_shape = min(in_shape, core::step_aa(abs(f.uv.x), width) * core::step_aa(abs(f.uv.y), 1.0));
+        }
+        // Cap::Square
+        case 1u: {
+            in_shape = min(in_shape, core::step_aa(abs(f.uv.x), width) * core::step_aa(past_end, width));
+        }
+        // Cap::Round, take the distance to the nearest point on the body of the line
+        case 2u: {
+            var dist = length(vec2<f32>(f.uv.x, past_end));
+            in_shape = min(in_shape, core::step_aa(dist, width));
+        }
     }
 
-    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+    in_shape *= core::dash_mask(f.along, f.dash);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(line_color.rgb, in_shape));
 #ifdef TEXTURED
     color = color * textureSample(image, image_sampler, f.texture_uv);
 #endif
@@ -1,6 +1,7 @@
 #import bevy_vector_shapes::core
 #import bevy_vector_shapes::core::{view, image, image_sampler}
 #import bevy_vector_shapes::constants::{PI, TAU}
+#import bevy_vector_shapes::hooks
 
 struct Vertex {
     @builtin(instance_index) index: u32,
@@ -19,7 +20,12 @@ struct Shape {
   
     @location(7) sides: f32,
     @location(8) radius: f32,
-    @location(9) roundness: f32
+    @location(9) roundness: f32,
+    @location(10) gradient_color: vec4<f32>,
+    @location(11) gradient: vec4<f32>,
+    @location(12) texture_uv: vec4<f32>,
+    @location(13) stroke_color: vec4<f32>,
+    @location(14) user_data: vec4<f32>,
 };
 
 #ifdef PER_OBJECT_BUFFER_BATCH_SIZE
@@ -36,8 +42,16 @@ struct VertexOutput {
     @location(3) central_angle: f32,
     @location(4) half_side_length: f32,
     @location(5) roundness: f32,
+    @location(6) flags: u32,
+    @location(7) gradient_color: vec4<f32>,
+    @location(8) gradient: vec4<f32>,
+    @location(9) stroke_color: vec4<f32>,
+    @location(10) stroke_offset: f32,
 #ifdef TEXTURED
-    @location(6) texture_uv: vec2<f32>,
+    @location(11) texture_uv: vec2<f32>,
+#endif
+#ifdef SHAPE_HOOK
+    @location(12) @interpolate(flat) user_data: vec4<f32>,
 #endif
 };
 
@@ -47,19 +61,18 @@ fn vertex(v: Vertex) -> VertexOutput {
 
     // Vertex positions for a basic quad
     let vertex = v.pos;
-    let shape = shapes[v.index];
+    let shape = shapes[core::get_instance_index(v.index)];
 
     // Reconstruct our transformation matrix
-    let matrix = mat4x4<f32>(
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
         shape.matrix_0,
         shape.matrix_1,
         shape.matrix_2,
         shape.matrix_3
-    );
+    ), shape.flags);
 
     // Calculate vertex data shared between most shapes
     var vertex_data = core::get_vertex_data(matrix, vertex.xy * shape.radius, shape.thickness, shape.flags);
-    out.clip_position = vertex_data.clip_pos;
 
     // Here we precompute several values related to our polygon
 
@@ -80,30 +93,52 @@ fn vertex(v: Vertex) -> VertexOutput {
 
     // We want 1 unit in uv space to be the length of the apothem of our polygon 
     // so scale world to uv space using the world space apothem
-    out.uv = vertex_data.local_pos / (apothem * vertex_data.scale) * vertex_data.uv_ratio;
     out.thickness = core::calculate_thickness(vertex_data.thickness_data, apothem, shape.flags);
+
+    // Grow our quad to fit outlines that are moved outwards by their stroke alignment
+    out.stroke_offset = core::stroke_offset(shape.flags);
+    var grow = 1.0 + out.stroke_offset * out.thickness * unit_apothem;
+    vertex_data = core::get_vertex_data(matrix, vertex.xy * shape.radius * grow, shape.thickness, shape.flags);
+    out.clip_position = vertex_data.clip_pos;
+    out.uv = vertex_data.local_pos / (apothem * vertex_data.scale) * vertex_data.uv_ratio;
     out.roundness = min(shape.roundness / apothem, 1.0);
 
     // Scale our half side length to match our uv space of 1 unit per apothem
     // Precalculate our scaling by the inverse of roundness for our sdf
     out.half_side_length = half_side_length / unit_apothem * (1.0 - out.roundness);
 
+    out.flags = shape.flags;
+    out.gradient_color = shape.gradient_color;
+    out.gradient = shape.gradient;
+    out.stroke_color = shape.stroke_color;
+#ifdef SHAPE_HOOK
+    out.user_data = shape.user_data;
+#endif
     out.color = shape.color;
 #ifdef TEXTURED
-    out.texture_uv = core::get_texture_uv(vertex.xy);
+    out.texture_uv = core::get_tiled_texture_uv(vertex.xy * grow, shape.texture_uv);
 #endif
     return out;
 }
 
 struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) thickness: f32,
     @location(3) central_angle: f32,
     @location(4) half_side_length: f32,
     @location(5) roundness: f32,
+    @location(6) flags: u32,
+    @location(7) gradient_color: vec4<f32>,
+    @location(8) gradient: vec4<f32>,
+    @location(9) stroke_color: vec4<f32>,
+    @location(10) stroke_offset: f32,
 #ifdef TEXTURED
-    @location(6) texture_uv: vec2<f32>,
+    @location(11) texture_uv: vec2<f32>,
+#endif
+#ifdef SHAPE_HOOK
+    @location(12) @interpolate(flat) user_data: vec4<f32>,
 #endif
 };
 
@@ -139,18 +174,45 @@ fn ngonSDF(position: vec2<f32>, central_angle: f32, half_side_length: f32, apoth
 #ifdef FRAGMENT
 @fragment
 fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
-    // Mask representing whether this fragment falls within the shape
-    var in_shape = f.color.a;
+    // User data is only passed to the fragment shader of shapes drawn with a hook
+#ifdef SHAPE_HOOK
+    let user_data = f.user_data;
+#else
+    let user_data = vec4<f32>(0.0);
+#endif
 
-    // Calculate our positions distance from the polygon
-    var dist = ngonSDF(f.uv, f.central_angle, f.half_side_length, 1.0 - f.roundness) - f.roundness;
+    // Gradients span the circumcircle of the polygon, which has a radius of 1 / cos(central_angle / 2) in uv space
+    var half_size = vec2<f32>(1.0 / cos(f.central_angle / 2.0));
+    var fill_color = core::gradient_color(f.color, f.gradient_color, f.gradient, f.uv, half_size, f.flags);
+    fill_color = hooks::fill(fill_color, f.uv, half_size, f.clip_position, user_data);
+
+    // Calculate our positions distance from the polygon, which grows outwards with our outline
+    var offset = f.stroke_offset * f.thickness;
+    var dist = ngonSDF(f.uv, f.central_angle, f.half_side_length, 1.0 - f.roundness) - f.roundness - offset;
+    dist = hooks::distance(dist, f.uv, half_size, user_data);
     
-    // Cut off points outside the shape or within the hollow area
-    in_shape *= core::step_aa(-f.thickness, dist) * core::step_aa(dist, 0.);
+    // Cut off points outside the shape
+    var in_shape = core::step_aa(dist, 0.);
+
+    // Our outline runs inside the edge of the shape, when hollow everything outside of it is cut off
+    var outline = core::step_aa(-f.thickness, dist);
+    var shape_color = core::outline_fill(fill_color, f.stroke_color, outline, f.flags);
+    in_shape *= shape_color.a;
+
+    // Shadows fade out either side of the edge of the shape rather than being cut off by it
+    if core::f_shadow(f.flags) > 0u {
+        in_shape = core::shadow_mask(dist + offset, f.thickness) * fill_color.a;
+    }
+
+    // Glows fade out from the edge of the shape with their falloff packed into their gradient
+    if core::f_glow(f.flags) > 0u {
+        in_shape = core::glow_mask(dist + offset, f.thickness, f.gradient.x) * fill_color.a;
+    }
 
-    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(shape_color.rgb, in_shape));
 #ifdef TEXTURED
-    color = color * textureSample(image, image_sampler, f.texture_uv);
+    color = color * core::texture_color(textureSample(image, image_sampler, f.texture_uv), f.flags);
 #endif
 
     // Discard fragments no longer in the shape
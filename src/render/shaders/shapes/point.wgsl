@@ -0,0 +1,118 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) size: f32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+#ifdef TEXTURED
+    @location(2) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    );
+
+    // Transform the center of our point into world space
+    var origin = (matrix * vec4<f32>(0.0, 0.0, 0.0, 1.0)).xyz;
+    var basis_vectors = core::get_basis_vectors(matrix, origin, shape.flags);
+
+    // Our size is in pixels so convert it into world units at our origin, ignoring the scale of our transform
+    var pixels_per_u = core::pixels_per_unit(origin, basis_vectors[1]);
+    var out_color = shape.color;
+
+    // If our size in pixels is less than 1, clamp to 1 and reduce the alpha instead
+    var size_p = shape.size;
+    if size_p < 1.0 {
+        out_color.a = out_color.a * size_p;
+        size_p = 1.0;
+    }
+    var radius = size_p / 2.0 / pixels_per_u;
+    var aa_padding = core::AA_PADDING / pixels_per_u;
+
+    // Pad our quad and determine the ratio by which to scale uv such that uvs ignore the padding
+    var uv_ratio = (radius + aa_padding) / radius;
+    let offset = vertex.xy * (radius + aa_padding);
+    var world_pos = origin + offset.x * basis_vectors[0] + offset.y * basis_vectors[1];
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+    out.uv = vertex.xy * uv_ratio;
+
+    out.color = out_color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+#ifdef TEXTURED
+    @location(2) texture_uv: vec2<f32>,
+#endif
+};
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    // Cut off points outside the point's radius
+    in_shape *= core::step_aa(length(f.uv), 1.0);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
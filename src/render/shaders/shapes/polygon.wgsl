@@ -0,0 +1,205 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) p_0: vec2<f32>,
+    @location(8) p_1: vec2<f32>,
+    @location(9) p_2: vec2<f32>,
+    @location(10) p_3: vec2<f32>,
+    @location(11) edges: u32,
+    @location(12) miter_limit: f32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) flags: u32,
+    @location(4) edges: u32,
+
+    @location(5) p_0: vec2<f32>,
+    @location(6) p_1: vec2<f32>,
+    @location(7) p_2: vec2<f32>,
+    @location(8) p_3: vec2<f32>,
+    @location(9) miter_limit: f32,
+#ifdef TEXTURED
+    @location(10) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+    let scale = core::get_scale(matrix);
+    let hollow = core::f_hollow(shape.flags) > 0u;
+
+    // Filled polygons are drawn as triangles and hollow polygons as polyline segments,
+    // in both cases we work around the bounds of the instance
+    var bounds_min = min(shape.p_1, shape.p_2);
+    var bounds_max = max(shape.p_1, shape.p_2);
+    if !hollow {
+        bounds_min = min(bounds_min, shape.p_0);
+        bounds_max = max(bounds_max, shape.p_0);
+    }
+    let center = (bounds_min + bounds_max) / 2.0;
+    let half_size = (bounds_max - bounds_min) / 2.0;
+
+    // Transform the center of our bounds into world space
+    var origin = (matrix * vec4<f32>(center, 0.0, 1.0)).xyz;
+    var basis_vectors = core::get_basis_vectors(matrix, origin, shape.flags);
+
+    // Calculate thickness data
+    var thickness_type = core::f_thickness_type(shape.flags);
+    var thickness_data = core::get_thickness_data(shape.thickness, thickness_type, origin, basis_vectors[1]);
+
+    var out_color = shape.color;
+    let local_per_p = 1.0 / (thickness_data.pixels_per_u * max(scale.x, scale.y));
+    var local_pos: vec2<f32>;
+    if hollow {
+        // If our thickness in pixels is less than 1, clamp to 1 and reduce the alpha instead
+        if thickness_data.thickness_p < 1.0 {
+            out_color.a = out_color.a * thickness_data.thickness_p;
+            thickness_data.thickness_p = 1.;
+        }
+        out.radius = thickness_data.thickness_p / 2.0 * local_per_p;
+
+        // Pad our bounds by the longest possible miter so that joins are never clipped
+        local_pos = center + v.pos.xy * (half_size + out.radius * max(shape.miter_limit, 1.0) + core::AA_PADDING * local_per_p);
+    } else {
+        // Triangles are drawn unpadded so that neighbouring triangles meet exactly,
+        // instead anti-aliasing is applied inside the outline of the polygon
+        out.radius = core::AA_PADDING / 2.0 * local_per_p;
+
+        switch v.vertex_index {
+            case 1u: {
+                local_pos = shape.p_1;
+            }
+            case 2u: {
+                local_pos = shape.p_2;
+            }
+            // Any remaining vertices of the quad are collapsed onto the first
+            default: {
+                local_pos = shape.p_0;
+            }
+        }
+    }
+
+    // Rotate the position based on our basis vectors and add the world position offset
+    let offset = (local_pos - center) * scale;
+    var world_pos = origin + offset.x * basis_vectors[0] - offset.y * basis_vectors[1];
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+    out.pos = local_pos;
+    out.flags = shape.flags;
+    out.edges = shape.edges;
+
+    out.p_0 = shape.p_0;
+    out.p_1 = shape.p_1;
+    out.p_2 = shape.p_2;
+    out.p_3 = shape.p_3;
+    out.miter_limit = shape.miter_limit;
+
+    out.color = out_color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv((local_pos - center) / max(half_size, vec2<f32>(0.0001)));
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) flags: u32,
+    @location(4) edges: u32,
+
+    @location(5) p_0: vec2<f32>,
+    @location(6) p_1: vec2<f32>,
+    @location(7) p_2: vec2<f32>,
+    @location(8) p_3: vec2<f32>,
+    @location(9) miter_limit: f32,
+#ifdef TEXTURED
+    @location(10) texture_uv: vec2<f32>,
+#endif
+};
+
+// Distance from a counter-clockwise edge, positive inside the triangle
+fn edge_distance(pos: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> f32 {
+    let edge = b - a;
+    return core::cross2d(edge, pos - a) / length(edge);
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    if core::f_hollow(f.flags) > 0u {
+        // Draw the outline segment the same way as a polyline
+        var segment = core::polyline_segment_distance(f.pos, f.p_0, f.p_1, f.p_2, f.p_3, f.radius, f.miter_limit, f.flags);
+        in_shape *= segment.owned * core::step_aa(segment.dist, f.radius);
+    } else {
+        // Only the edges on the outline of the polygon are anti-aliased, the rest are shared with another triangle
+        var dist = 1e20;
+        if (f.edges & 1u) > 0u {
+            dist = min(dist, edge_distance(f.pos, f.p_0, f.p_1));
+        }
+        if (f.edges & 2u) > 0u {
+            dist = min(dist, edge_distance(f.pos, f.p_1, f.p_2));
+        }
+        if (f.edges & 4u) > 0u {
+            dist = min(dist, edge_distance(f.pos, f.p_2, f.p_0));
+        }
+        in_shape *= core::step_aa(f.radius, dist);
+    }
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
@@ -0,0 +1,177 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) prev: vec3<f32>,
+    @location(8) start: vec3<f32>,
+    @location(9) end: vec3<f32>,
+    @location(10) next: vec3<f32>,
+    @location(11) miter_limit: f32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) flags: u32,
+
+    @location(4) prev: vec2<f32>,
+    @location(5) start: vec2<f32>,
+    @location(6) end: vec2<f32>,
+    @location(7) next: vec2<f32>,
+    @location(8) miter_limit: f32,
+#ifdef TEXTURED
+    @location(9) texture_uv: vec2<f32>,
+#endif
+};
+
+// Project a point in world space into the 2d space of a segment starting at origin
+fn to_segment_space(p: vec3<f32>, origin: vec3<f32>, dir: vec3<f32>, side: vec3<f32>) -> vec2<f32> {
+    let offset = p - origin;
+    return vec2<f32>(dot(offset, dir), dot(offset, side));
+}
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+
+    // Get the points of the segment in world space
+    let world_prev = (matrix * vec4<f32>(shape.prev, 1.0)).xyz;
+    let world_start = (matrix * vec4<f32>(shape.start, 1.0)).xyz;
+    let world_end = (matrix * vec4<f32>(shape.end, 1.0)).xyz;
+    let world_next = (matrix * vec4<f32>(shape.next, 1.0)).xyz;
+    let center = (world_start + world_end) / 2.0;
+
+    // The segment is drawn in the plane containing it that faces along the z axis of our transform,
+    // or like lines rotates around it's direction to face the camera when billboarded
+    var normal = normalize(matrix[2].xyz);
+    if core::f_alignment(shape.flags) != 0u {
+        normal = core::p_to_camera_dir(center);
+    }
+    let dir = normalize(world_end - world_start);
+    var side = cross(normal, dir);
+
+    // Segments running along the normal have no plane to lie in so face the camera instead
+    if length(side) < 0.0001 {
+        side = cross(core::p_to_camera_dir(center), dir);
+    }
+    side = normalize(side);
+
+    // Calculate thickness data
+    var thickness_type = core::f_thickness_type(shape.flags);
+    var thickness_data = core::get_thickness_data(shape.thickness, thickness_type, center, side);
+
+    // If our thickness in pixels is less than 1, clamp to 1 and reduce the alpha instead
+    var out_color = shape.color;
+    if thickness_data.thickness_p < 1.0 {
+        out_color.a = out_color.a * thickness_data.thickness_p;
+        thickness_data.thickness_p = 1.;
+    }
+
+    // Convert our radius and padding from pixels into world units
+    var radius = thickness_data.thickness_p / 2.0 / thickness_data.pixels_per_u;
+    var aa_padding = core::AA_PADDING / thickness_data.pixels_per_u;
+
+    // Our quad covers the segment padded by the longest possible miter so that joins are never clipped
+    let half_length = length(world_end - world_start) / 2.0;
+    let padding = radius * max(shape.miter_limit, 1.0) + aa_padding;
+    let local_pos = vec2<f32>(half_length + vertex.x * (half_length + padding), vertex.y * padding);
+    let world_pos = world_start + local_pos.x * dir + local_pos.y * side;
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+    out.pos = local_pos;
+    out.radius = radius;
+    out.flags = shape.flags;
+
+    // Neighbouring points are projected into the plane of the segment to calculate joins
+    out.prev = to_segment_space(world_prev, world_start, dir, side);
+    out.start = to_segment_space(world_start, world_start, dir, side);
+    out.end = to_segment_space(world_end, world_start, dir, side);
+    out.next = to_segment_space(world_next, world_start, dir, side);
+    out.miter_limit = shape.miter_limit;
+
+    out.color = out_color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) flags: u32,
+
+    @location(4) prev: vec2<f32>,
+    @location(5) start: vec2<f32>,
+    @location(6) end: vec2<f32>,
+    @location(7) next: vec2<f32>,
+    @location(8) miter_limit: f32,
+#ifdef TEXTURED
+    @location(9) texture_uv: vec2<f32>,
+#endif
+};
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    var segment = core::polyline_segment_distance(f.pos, f.prev, f.start, f.end, f.next, f.radius, f.miter_limit, f.flags);
+    in_shape *= segment.owned;
+
+    // Cut off points further from the segment than our radius
+    in_shape *= core::step_aa(segment.dist, f.radius);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
@@ -1,6 +1,7 @@
 #import bevy_vector_shapes::core
 #import bevy_vector_shapes::core::{view, image, image_sampler}
 #import bevy_vector_shapes::constants::{PI, TAU}
+#import bevy_vector_shapes::hooks
 
 struct Vertex {
     @builtin(instance_index) index: u32,
@@ -19,6 +20,12 @@ struct Shape {
 
     @location(7) size: vec2<f32>,
     @location(8) corner_radii: vec4<f32>,
+    @location(9) dash: vec3<f32>,
+    @location(10) gradient_color: vec4<f32>,
+    @location(11) gradient: vec4<f32>,
+    @location(12) texture_uv: vec4<f32>,
+    @location(13) stroke_color: vec4<f32>,
+    @location(14) user_data: vec4<f32>,
 }
 
 #ifdef PER_OBJECT_BUFFER_BATCH_SIZE
@@ -34,8 +41,17 @@ struct VertexOutput {
     @location(2) size: vec2<f32>,
     @location(3) corner_radii: vec4<f32>,
     @location(4) thickness: f32,
+    @location(5) dash: vec3<f32>,
+    @location(6) flags: u32,
+    @location(7) gradient_color: vec4<f32>,
+    @location(8) gradient: vec4<f32>,
+    @location(9) stroke_color: vec4<f32>,
+    @location(10) stroke_offset: f32,
 #ifdef TEXTURED
-    @location(5) texture_uv: vec2<f32>,
+    @location(11) texture_uv: vec2<f32>,
+#endif
+#ifdef SHAPE_HOOK
+    @location(12) @interpolate(flat) user_data: vec4<f32>,
 #endif
 };
 
@@ -45,44 +61,79 @@ fn vertex(v: Vertex) -> VertexOutput {
 
     // Vertex positions for a basic quad
     let vertex = v.pos;
-    let shape = shapes[v.index];
+    let shape = shapes[core::get_instance_index(v.index)];
 
     // Reconstruct our transformation matrix
-    let matrix = mat4x4<f32>(
+    var matrix = core::apply_constant_screen_size(mat4x4<f32>(
         shape.matrix_0,
         shape.matrix_1,
         shape.matrix_2,
         shape.matrix_3
-    );
+    ), shape.flags);
+    var size = shape.size;
+
+    // Move the scale of 9-slice rectangles into their size so that corners and outlines are not stretched
+    if core::f_nine_slice(shape.flags) > 0u {
+        var scale = vec2<f32>(length(matrix[0].xyz), length(matrix[1].xyz));
+        size *= scale;
+        matrix[0] /= max(scale.x, 1e-6);
+        matrix[1] /= max(scale.y, 1e-6);
+    }
+
     // Shortest of the two side lengths for the rectangle
-    var shortest_side = min(shape.size.x, shape.size.y);
+    var shortest_side = min(size.x, size.y);
 
-    var vertex_data = core::get_vertex_data(matrix, vertex.xy * shape.size / 2.0, shape.thickness, shape.flags);
-    out.clip_position = vertex_data.clip_pos;
+    var vertex_data = core::get_vertex_data(matrix, vertex.xy * size / 2.0, shape.thickness, shape.flags);
 
     // Our vertex outputs should all be in uv space so scale our uv space such that the shortest side is of length 1
-    out.size = shape.size / shortest_side;
-    out.uv = vertex.xy * out.size * vertex_data.uv_ratio;
+    out.size = size / shortest_side;
     out.thickness = core::calculate_thickness(vertex_data.thickness_data, shortest_side / 2.0, shape.flags);
 
+    // Grow our quad to fit outlines that are moved outwards by their stroke alignment
+    out.stroke_offset = core::stroke_offset(shape.flags);
+    var grown_size = out.size + out.stroke_offset * out.thickness;
+    vertex_data = core::get_vertex_data(matrix, vertex.xy * grown_size * shortest_side / 2.0, shape.thickness, shape.flags);
+    out.clip_position = vertex_data.clip_pos;
+    out.uv = vertex.xy * grown_size * vertex_data.uv_ratio;
+
     // Our corner radii cannot be more than half the shortest side so cap them
     out.corner_radii = 2.0 * min(shape.corner_radii / shortest_side, vec4<f32>(0.5));
 
+    // Convert our dash pattern from world units into uv space
+    out.dash = shape.dash / (shortest_side / 2.0 * vertex_data.scale.x);
+
+    out.flags = shape.flags;
+    out.gradient_color = shape.gradient_color;
+    out.gradient = shape.gradient;
+    out.stroke_color = shape.stroke_color;
+#ifdef SHAPE_HOOK
+    out.user_data = shape.user_data;
+#endif
     out.color = shape.color;
 #ifdef TEXTURED
-    out.texture_uv = core::get_texture_uv(vertex.xy);
+    out.texture_uv = core::get_tiled_texture_uv(vertex.xy * grown_size / out.size, shape.texture_uv);
 #endif
     return out;
 }
 
 struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) size: vec2<f32>,
     @location(3) corner_radii: vec4<f32>,
     @location(4) thickness: f32,
+    @location(5) dash: vec3<f32>,
+    @location(6) flags: u32,
+    @location(7) gradient_color: vec4<f32>,
+    @location(8) gradient: vec4<f32>,
+    @location(9) stroke_color: vec4<f32>,
+    @location(10) stroke_offset: f32,
 #ifdef TEXTURED
-    @location(5) texture_uv: vec2<f32>,
+    @location(11) texture_uv: vec2<f32>,
+#endif
+#ifdef SHAPE_HOOK
+    @location(12) @interpolate(flat) user_data: vec4<f32>,
 #endif
 };
 
@@ -107,6 +158,29 @@ fn rectSDF(position: vec2<f32>, size: vec2<f32>) -> f32 {
     return length(outside_to_edge) + inside_length;
 }
 
+// Distance from a sharp cornered rectangle grown outwards by offset, with it's corners shaped by the given join type
+// Round joins match growing the rectangle evenly in every direction
+fn grownRectSDF(position: vec2<f32>, size: vec2<f32>, offset: f32, join: u32) -> f32 {
+    var sharp = rectSDF(position, size + offset);
+    switch join {
+        // Join::Miter
+        default: {
+            return sharp;
+        }
+        // Join::Bevel
+        case 1u: {
+            // Clip the corner with a straight edge between the ends of the two grown sides
+            var pos = abs(position);
+            var bevel = (pos.x + pos.y - size.x - size.y - offset) / sqrt(2.0);
+            return max(sharp, bevel);
+        }
+        // Join::Round
+        case 2u: {
+            return rectSDF(position, size) - offset;
+        }
+    }
+}
+
 // Given a uv position get which quadrant that position is in
 // Return an integer from 0 to 3
 fn quadrant(in: vec2<f32>) -> i32 {
@@ -114,28 +188,109 @@ fn quadrant(in: vec2<f32>) -> i32 {
     return -uv.y + (-uv.x * uv.y + 3) / 2;
 }
 
+// Length of the outline of a single quadrant of the rectangle
+fn quadrant_length(size: vec2<f32>, radius: f32, center_radius: f32) -> f32 {
+    return size.x + size.y - 2.0 * radius + center_radius * PI / 2.0;
+}
+
+// Distance along the outline of a single quadrant measured from the x axis towards the y axis
+// Corners are treated as arcs of at least our thickness so that the position is continuous
+fn quadrant_position(pos: vec2<f32>, size: vec2<f32>, radius: f32, center_radius: f32) -> f32 {
+    var corner = size - radius;
+    if pos.x > corner.x && pos.y > corner.y {
+        var to_pos = pos - corner;
+        return corner.y + atan2(to_pos.y, to_pos.x) * center_radius;
+    }
+    if size.x - pos.x < size.y - pos.y {
+        return pos.y;
+    }
+    return corner.y + center_radius * PI / 2.0 + corner.x - pos.x;
+}
+
+// Distance along the outline of the rectangle, starting from the middle of the positive x edge
+fn outline_position(uv: vec2<f32>, size: vec2<f32>, corner_radii: vec4<f32>, thickness: f32) -> f32 {
+    var radii = max(corner_radii, vec4<f32>(thickness));
+    var center_radii = max(radii - thickness / 2.0, vec4<f32>(0.0));
+    var lengths = vec4<f32>(
+        quadrant_length(size, radii[0], center_radii[0]),
+        quadrant_length(size, radii[1], center_radii[1]),
+        quadrant_length(size, radii[2], center_radii[2]),
+        quadrant_length(size, radii[3], center_radii[3])
+    );
+
+    // Mirrored quadrants run in the opposite direction so measure them back from their end
+    var pos = abs(uv);
+    var quadrant = quadrant(uv);
+    var position = quadrant_position(pos, size, radii[quadrant], center_radii[quadrant]);
+    switch quadrant {
+        default: {}
+        case 1: {
+            position = lengths[0] + lengths[1] - position;
+        }
+        case 2: {
+            position = lengths[0] + lengths[1] + position;
+        }
+        case 3: {
+            position = lengths[0] + lengths[1] + lengths[2] + lengths[3] - position;
+        }
+    }
+    return position;
+}
+
 // Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
 #ifdef FRAGMENT
 @fragment
 fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
-    // Mask representing whether this fragment falls within the shape
-    var in_shape = f.color.a;
+    // User data is only passed to the fragment shader of shapes drawn with a hook
+#ifdef SHAPE_HOOK
+    let user_data = f.user_data;
+#else
+    let user_data = vec4<f32>(0.0);
+#endif
+
+    var fill_color = core::gradient_color(f.color, f.gradient_color, f.gradient, f.uv, f.size, f.flags);
+    fill_color = hooks::fill(fill_color, f.uv, f.size, f.clip_position, user_data);
 
     // Use quadrant to determine which corner radii to use
     var quadrant = quadrant(f.uv);
     var radii = f.corner_radii[quadrant];
 
-    // Calculate our positions distance from the rectangle
-    var dist = rectSDF(f.uv, f.size - radii) - radii;
-    
-    // Cut off points outside the shape or within the hollow area
-    in_shape *= core::step_aa(-f.thickness, dist) * core::step_aa(dist, 0.);
+    // Calculate our positions distance from the rectangle, which grows outwards with our outline
+    var offset = f.stroke_offset * f.thickness;
+    var dist = rectSDF(f.uv, f.size - radii) - radii - offset;
+
+    // Sharp corners keep their shape as they grow based on our join type
+    if offset > 0.0 && radii <= 0.0 && (core::f_shadow(f.flags) | core::f_glow(f.flags)) == 0u {
+        dist = grownRectSDF(f.uv, f.size, offset, core::f_join(f.flags));
+    }
+    dist = hooks::distance(dist, f.uv, f.size, user_data);
+
+    // Cut off points outside the shape
+    var in_shape = core::step_aa(dist, 0.);
+
+    // Our outline runs inside the edge of the shape, when hollow everything outside of it is cut off
+    var outline = core::step_aa(-f.thickness, dist);
 
+    // Dash along the center of our outline
+    outline *= core::dash_mask(outline_position(f.uv, f.size, f.corner_radii, f.thickness), f.dash);
 
+    var shape_color = core::outline_fill(fill_color, f.stroke_color, outline, f.flags);
+    in_shape *= shape_color.a;
+
+    // Shadows fade out either side of the edge of the shape rather than being cut off by it
+    if core::f_shadow(f.flags) > 0u {
+        in_shape = core::shadow_mask(dist + offset, f.thickness) * fill_color.a;
+    }
+
+    // Glows fade out from the edge of the shape with their falloff packed into their gradient
+    if core::f_glow(f.flags) > 0u {
+        in_shape = core::glow_mask(dist + offset, f.thickness, f.gradient.x) * fill_color.a;
+    }
 
-    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(shape_color.rgb, in_shape));
 #ifdef TEXTURED
-    color = color * textureSample(image, image_sampler, f.texture_uv);
+    color = color * core::texture_color(textureSample(image, image_sampler, f.texture_uv), f.flags);
 #endif
 
     // Discard fragments no longer in the shape
@@ -0,0 +1,180 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) cell_size: f32,
+    @location(8) fade_distance: f32,
+    @location(9) minor_color: vec4<f32>,
+    @location(10) x_axis_color: vec4<f32>,
+    @location(11) y_axis_color: vec4<f32>,
+    @location(12) major_every: u32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) offset: vec2<f32>,
+    @location(3) @interpolate(flat) thickness: f32,
+    @location(4) @interpolate(flat) flags: u32,
+    @location(5) cell_size: f32,
+    @location(6) fade_distance: f32,
+    @location(7) minor_color: vec4<f32>,
+    @location(8) x_axis_color: vec4<f32>,
+    @location(9) y_axis_color: vec4<f32>,
+    @location(10) @interpolate(flat) major_every: u32,
+#ifdef TEXTURED
+    @location(11) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix, the grid is infinite so constant screen size does not apply
+    let matrix = mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    );
+    let origin = matrix[3].xyz;
+    let x_axis = matrix[0].xyz;
+    let y_axis = matrix[1].xyz;
+
+    // Center the quad on the point of the grid beneath the camera so that it appears to extend forever
+    let to_camera = view.world_position - origin;
+    let camera_pos = vec2<f32>(
+        dot(to_camera, x_axis) / dot(x_axis, x_axis),
+        dot(to_camera, y_axis) / dot(y_axis, y_axis)
+    );
+    let offset = vertex.xy * shape.fade_distance;
+    let local_pos = camera_pos + offset;
+    let world_pos = origin + local_pos.x * x_axis + local_pos.y * y_axis;
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+
+    out.pos = local_pos;
+    out.offset = offset;
+    out.thickness = shape.thickness;
+    out.flags = shape.flags;
+    out.cell_size = shape.cell_size;
+    out.fade_distance = shape.fade_distance;
+    out.minor_color = shape.minor_color;
+    out.x_axis_color = shape.x_axis_color;
+    out.y_axis_color = shape.y_axis_color;
+    out.major_every = shape.major_every;
+
+    out.color = shape.color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) offset: vec2<f32>,
+    @location(3) @interpolate(flat) thickness: f32,
+    @location(4) @interpolate(flat) flags: u32,
+    @location(5) cell_size: f32,
+    @location(6) fade_distance: f32,
+    @location(7) minor_color: vec4<f32>,
+    @location(8) x_axis_color: vec4<f32>,
+    @location(9) y_axis_color: vec4<f32>,
+    @location(10) @interpolate(flat) major_every: u32,
+#ifdef TEXTURED
+    @location(11) texture_uv: vec2<f32>,
+#endif
+};
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+// Distance in pixels to the nearest of the lines spaced `spacing` apart on each axis
+fn to_lines(pos: vec2<f32>, spacing: f32, local_per_p: vec2<f32>) -> vec2<f32> {
+    return abs(pos - round(pos / spacing) * spacing) / local_per_p;
+}
+
+// Coverage of the nearest line on each axis given the distance to it in pixels,
+// lines thinner than a pixel are drawn a pixel wide and faded instead
+fn line_coverage(to_line_p: vec2<f32>, thickness_p: vec2<f32>) -> vec2<f32> {
+    let coverage = saturate(max(thickness_p, vec2<f32>(1.0)) / 2.0 + 0.5 - to_line_p);
+    return coverage * min(thickness_p, vec2<f32>(1.0));
+}
+
+// Fade out lines as the space between them shrinks towards a few pixels to avoid moire patterns
+fn density_fade(spacing: f32, local_per_p: vec2<f32>) -> f32 {
+    let spacing_p = spacing / max(local_per_p.x, local_per_p.y);
+    return saturate(spacing_p / 8.0 - 0.5);
+}
+
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Size of a pixel in the local space of the grid along each axis
+    let local_per_p = max(fwidth(f.pos), vec2<f32>(0.00001));
+
+    let thickness_type = core::f_thickness_type(f.flags);
+    let thickness_p = vec2<f32>(
+        core::get_thickness_pixels(f.thickness, thickness_type, 1.0 / local_per_p.x),
+        core::get_thickness_pixels(f.thickness, thickness_type, 1.0 / local_per_p.y)
+    );
+
+    // Layer the minor lines, major lines and then the axes on top of each other
+    let minor = line_coverage(to_lines(f.pos, f.cell_size, local_per_p), thickness_p);
+    var color = f.minor_color;
+    color.a *= max(minor.x, minor.y) * density_fade(f.cell_size, local_per_p);
+
+    if f.major_every > 0u {
+        let major_spacing = f.cell_size * f32(f.major_every);
+        let major = line_coverage(to_lines(f.pos, major_spacing, local_per_p), thickness_p);
+        color = mix(color, f.color, max(major.x, major.y) * density_fade(major_spacing, local_per_p));
+    }
+
+    let axes = line_coverage(abs(f.pos) / local_per_p, thickness_p);
+    color = mix(color, f.x_axis_color, axes.y);
+    color = mix(color, f.y_axis_color, axes.x);
+
+    // Fade out towards the edge of the quad
+    color.a *= 1.0 - smoothstep(f.fade_distance * 0.5, f.fade_distance, length(f.offset));
+
+    var in_shape = color.a * core::clip_mask(f.clip_position);
+    color = core::color_output(vec4<f32>(color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
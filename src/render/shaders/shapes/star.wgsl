@@ -0,0 +1,153 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) points: f32,
+    @location(8) inner_radius: f32,
+    @location(9) outer_radius: f32,
+    @location(10) roundness: f32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) thickness: f32,
+    @location(3) half_angle: f32,
+    @location(4) tip: vec2<f32>,
+    @location(5) inner: vec2<f32>,
+    @location(6) roundness: f32,
+#ifdef TEXTURED
+    @location(7) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+
+    // Calculate vertex data shared between most shapes
+    var vertex_data = core::get_vertex_data(matrix, vertex.xy * shape.outer_radius, shape.thickness, shape.flags);
+    out.clip_position = vertex_data.clip_pos;
+
+    // We want 1 unit in uv space to be the outer radius of our star
+    out.uv = vertex_data.local_pos / (shape.outer_radius * vertex_data.scale) * vertex_data.uv_ratio;
+    out.thickness = core::calculate_thickness(vertex_data.thickness_data, shape.outer_radius, shape.flags);
+
+    // Half of the angle at the center of the star between two adjacent tips
+    out.half_angle = PI / shape.points;
+
+    // The edge between a tip and the following inner corner in uv space
+    var tip = vec2<f32>(1.0, 0.0);
+    var inner = shape.inner_radius / shape.outer_radius * vec2<f32>(cos(out.half_angle), sin(out.half_angle));
+
+    // Every edge is the same distance from the center so shrinking the star by our roundness is a uniform scale
+    var edge_distance = abs(core::cross2d(tip, inner)) / length(inner - tip);
+    out.roundness = min(shape.roundness / shape.outer_radius, edge_distance);
+    var shrink = (edge_distance - out.roundness) / edge_distance;
+    out.tip = tip * shrink;
+    out.inner = inner * shrink;
+
+    out.color = shape.color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) thickness: f32,
+    @location(3) half_angle: f32,
+    @location(4) tip: vec2<f32>,
+    @location(5) inner: vec2<f32>,
+    @location(6) roundness: f32,
+#ifdef TEXTURED
+    @location(7) texture_uv: vec2<f32>,
+#endif
+};
+
+// Given a position, half the angle between two tips and the edge between a tip and an inner corner
+// determine the distance between the point and a star with the given properties
+fn starSDF(position: vec2<f32>, half_angle: f32, tip: vec2<f32>, inner: vec2<f32>) -> f32 {
+    // Rotate our position so that the first tip points up
+    var pos = vec2<f32>(-position.y, position.x);
+
+    // Fold our point into the half of a single point between it's tip and the following inner corner
+    var angle = atan2(pos.y, pos.x);
+    var sector_angle = angle - 2.0 * half_angle * floor((angle + half_angle) / (2.0 * half_angle));
+    pos = length(pos) * vec2<f32>(cos(sector_angle), abs(sin(sector_angle)));
+
+    // Find the nearest point along the edge
+    var edge = inner - tip;
+    var to_pos = pos - tip;
+    var h = clamp(dot(to_pos, edge) / dot(edge, edge), 0.0, 1.0);
+    var dist = length(to_pos - edge * h);
+
+    // Within our folded section the star is the triangle between the center, tip and inner corner
+    // so we fall inside the shape when on the same side of the edge as the center
+    return select(dist, -dist, core::cross2d(edge, to_pos) > 0.0);
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    // Calculate our positions distance from the star
+    var dist = starSDF(f.uv, f.half_angle, f.tip, f.inner) - f.roundness;
+
+    // Cut off points outside the shape or within the hollow area
+    in_shape *= core::step_aa(-f.thickness, dist) * core::step_aa(dist, 0.);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
@@ -0,0 +1,132 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) size: vec2<f32>,
+    @location(8) exponent: f32,
+}
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) radii: vec2<f32>,
+    @location(3) thickness: f32,
+    @location(4) exponent: f32,
+#ifdef TEXTURED
+    @location(5) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+    let radii = shape.size / 2.0;
+
+    // Shortest of the two radii for the superellipse
+    var shortest_radius = min(radii.x, radii.y);
+
+    var vertex_data = core::get_vertex_data(matrix, vertex.xy * radii, shape.thickness, shape.flags);
+    out.clip_position = vertex_data.clip_pos;
+
+    // Our vertex outputs should all be in uv space so scale our uv space such that the shortest radius is of length 1
+    out.radii = radii / shortest_radius;
+    out.uv = vertex.xy * out.radii * vertex_data.uv_ratio;
+    out.thickness = core::calculate_thickness(vertex_data.thickness_data, shortest_radius, shape.flags);
+    out.exponent = shape.exponent;
+
+    out.color = shape.color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) radii: vec2<f32>,
+    @location(3) thickness: f32,
+    @location(4) exponent: f32,
+#ifdef TEXTURED
+    @location(5) texture_uv: vec2<f32>,
+#endif
+};
+
+// Given a position, the radii and exponent of a superellipse approximate the signed distance between the point and the superellipse
+fn superellipseSDF(position: vec2<f32>, radii: vec2<f32>, exponent: f32) -> f32 {
+    // Superellipses are symmetrical across both axis so we can mirror our point
+    // into the positive x and y axis by taking the absolute value
+    var pos = max(abs(position), vec2<f32>(1e-6)) / radii;
+
+    // Value of the implicit function, 1 on the outline and increasing outwards
+    var terms = pow(pos, vec2<f32>(exponent));
+    var sum = terms.x + terms.y;
+    var value = pow(sum, 1.0 / exponent);
+
+    // Divide by the length of the gradient to turn our value into an approximate distance
+    var gradient = value / sum * terms / pos / radii;
+    return (value - 1.0) / max(length(gradient), 1e-6);
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    // Calculate our positions distance from the superellipse
+    var dist = superellipseSDF(f.uv, f.radii, f.exponent);
+
+    // Cut off points outside the shape or within the hollow area
+    in_shape *= core::step_aa(-f.thickness, dist) * core::step_aa(dist, 0.);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
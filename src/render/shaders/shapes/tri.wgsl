@@ -51,7 +51,7 @@ fn vertex(v: Vertex) -> VertexOutput {
     var out: VertexOutput;
 
     // Vertex positions for a basic quad
-    let shape = shapes[v.index];
+    let shape = shapes[core::get_instance_index(v.index)];
     var vertex: vec2<f32>;
     switch v.vertex_index {
         default: {
@@ -66,12 +66,12 @@ fn vertex(v: Vertex) -> VertexOutput {
     }
 
     // Reconstruct our transformation matrix
-    let matrix = mat4x4<f32>(
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
         shape.matrix_0,
         shape.matrix_1,
         shape.matrix_2,
         shape.matrix_3
-    );
+    ), shape.flags);
 
     let l_s_0 = length(shape.v_1 - shape.v_2);
     let l_s_1 = length(shape.v_2 - shape.v_0);
@@ -140,6 +140,7 @@ fn vertex(v: Vertex) -> VertexOutput {
 }
 
 struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec4<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) thickness: f32,
@@ -203,6 +204,7 @@ fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
     // Cut off points outside the shape or within the hollow area
     in_shape *= core::step_aa(-f.thickness, dist) * core::step_aa(dist, 0.);
 
+    in_shape *= core::clip_mask(f.clip_position);
     var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
 #ifdef TEXTURED
     color = color * textureSample(image, image_sampler, f.texture_uv);
@@ -0,0 +1,254 @@
+#import bevy_vector_shapes::core
+#import bevy_vector_shapes::core::{view, image, image_sampler}
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct Vertex {
+    @builtin(instance_index) index: u32,
+    @location(0) pos: vec3<f32>
+};
+
+struct Shape {
+    @location(0) matrix_0: vec4<f32>,
+    @location(1) matrix_1: vec4<f32>,
+    @location(2) matrix_2: vec4<f32>,
+    @location(3) matrix_3: vec4<f32>,
+
+    @location(4) color: vec4<f32>,
+    @location(5) thickness: f32,
+    @location(6) flags: u32,
+
+    @location(7) start: vec3<f32>,
+    @location(8) end: vec3<f32>,
+    @location(9) amplitude: f32,
+    @location(10) frequency: f32,
+};
+
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+@group(1) @binding(0) var<uniform> shapes: array<Shape, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
+#else
+@group(1) @binding(0) var<storage> shapes: array<Shape>;
+#endif
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) length: f32,
+    @location(4) amplitude: f32,
+    @location(5) wave_number: f32,
+    @location(6) flags: u32,
+#ifdef TEXTURED
+    @location(7) texture_uv: vec2<f32>,
+#endif
+};
+
+@vertex
+fn vertex(v: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Vertex positions for a basic quad
+    let vertex = v.pos;
+    let shape = shapes[core::get_instance_index(v.index)];
+
+    // Reconstruct our transformation matrix
+    let matrix = core::apply_constant_screen_size(mat4x4<f32>(
+        shape.matrix_0,
+        shape.matrix_1,
+        shape.matrix_2,
+        shape.matrix_3
+    ), shape.flags);
+
+    // Get our start and end in world space
+    var world_start = (matrix * vec4<f32>(shape.start, 1.0)).xyz;
+    var world_end = (matrix * vec4<f32>(shape.end, 1.0)).xyz;
+
+    // The y basis is the normalized vector along the wave
+    var y_basis = normalize(world_start - world_end);
+
+    // Choose which point we will work in reference to based on our y position
+    var origin = select(world_end, world_start, vertex.y < 0.0);
+
+    // Calculate the remainder of our basis vectors
+    var basis_vectors = core::get_basis_vectors_from_up(matrix, origin, y_basis, core::f_line_alignment(shape.flags));
+
+    // Calculate thickness data
+    var thickness_type = core::f_thickness_type(shape.flags);
+    var thickness_data = core::get_thickness_data(shape.thickness, thickness_type, origin, basis_vectors[1]);
+
+    let scale = core::get_scale(matrix);
+
+    // If our thickness in pixels is less than 1, clamp to 1 and reduce the alpha instead
+    var out_color = shape.color;
+    if thickness_data.thickness_p * scale.x < 1.0 {
+        out_color.a = out_color.a * thickness_data.thickness_p * scale.x;
+        thickness_data.thickness_p = 1.;
+    }
+
+    // We work in world units with x across the wave and y along it starting from our start point
+    var thickness = thickness_data.thickness_p / thickness_data.pixels_per_u * scale.x;
+    out.radius = thickness / 2.0;
+    out.length = length(world_end - world_start);
+
+    // Amplitude and frequency are given in local units so convert them to world units
+    var world_per_local = out.length / max(length(shape.end - shape.start), 0.0001);
+    out.amplitude = shape.amplitude * world_per_local;
+    out.wave_number = TAU * shape.frequency / world_per_local;
+
+    // Pad each side by the amplitude and each end by our radius to fit the wave and it's caps
+    var extent = vec2<f32>(out.amplitude + out.radius, out.radius);
+    var aa_padding_u = core::AA_PADDING / thickness_data.pixels_per_u;
+
+    // Calculate the offset from our origin point
+    var local_offset = vertex.xy * (extent + aa_padding_u);
+
+    // Determine final world position by offsetting by the origin we chose and rotating by our basis vectors
+    var world_pos = origin + local_offset.x * basis_vectors[0] + local_offset.y * basis_vectors[1];
+
+    out.clip_position = view.view_proj * vec4<f32>(world_pos, 1.0);
+    out.pos = vec2<f32>(local_offset.x, select(out.length + local_offset.y, local_offset.y, vertex.y < 0.0));
+    out.flags = shape.flags;
+
+    out.color = out_color;
+#ifdef TEXTURED
+    out.texture_uv = core::get_texture_uv(vertex.xy);
+#endif
+    return out;
+}
+
+struct FragmentInput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) pos: vec2<f32>,
+    @location(2) radius: f32,
+    @location(3) length: f32,
+    @location(4) amplitude: f32,
+    @location(5) wave_number: f32,
+    @location(6) flags: u32,
+#ifdef TEXTURED
+    @location(7) texture_uv: vec2<f32>,
+#endif
+};
+
+const SAMPLES: i32 = 8;
+const ITERATIONS: i32 = 3;
+
+// Distance from the curve x = amplitude * sin(k * y), optionally restricting y to [0, length]
+fn sineSDF(pos: vec2<f32>, amplitude: f32, k: f32, len: f32, clamped: bool) -> f32 {
+    // The nearest point is no further along y than the point on the curve directly across from us
+    var across = abs(pos.x - amplitude * sin(k * pos.y));
+    var lo = pos.y - across;
+    var hi = pos.y + across;
+    if clamped {
+        lo = clamp(lo, 0.0, len);
+        hi = clamp(hi, 0.0, len);
+    }
+
+    // Sample that range to find a starting guess near the global minimum
+    var t = lo;
+    var best = 1e20;
+    for (var i = 0; i <= SAMPLES; i++) {
+        var s = mix(lo, hi, f32(i) / f32(SAMPLES));
+        var d = pos - vec2<f32>(amplitude * sin(k * s), s);
+        if dot(d, d) < best {
+            best = dot(d, d);
+            t = s;
+        }
+    }
+
+    // Refine with newton's method on the derivative of the squared distance
+    for (var i = 0; i < ITERATIONS; i++) {
+        var s = sin(k * t);
+        var c = cos(k * t);
+        var dx = amplitude * s - pos.x;
+        var g = (t - pos.y) + dx * amplitude * k * c;
+        var dg = 1.0 + amplitude * amplitude * k * k * c * c - dx * amplitude * k * k * s;
+        if dg > 0.0 {
+            t = t - g / dg;
+        }
+        if clamped {
+            t = clamp(t, 0.0, len);
+        }
+    }
+
+    return length(pos - vec2<f32>(amplitude * sin(k * t), t));
+}
+
+// Triangle wave matching the peaks and zero crossings of sin(k * y)
+fn tri(y: f32, k: f32) -> f32 {
+    return asin(sin(k * y)) * 2.0 / PI;
+}
+
+// Distance from a segment of the zigzag between y = a and y = b
+fn segmentSDF(pos: vec2<f32>, amplitude: f32, k: f32, a: f32, b: f32) -> f32 {
+    var pa = vec2<f32>(amplitude * tri(a, k), a);
+    var pb = vec2<f32>(amplitude * tri(b, k), b);
+    var ba = pb - pa;
+    var h = clamp(dot(pos - pa, ba) / max(dot(ba, ba), 0.0001), 0.0, 1.0);
+    return length(pos - pa - ba * h);
+}
+
+// Distance from the zigzag through the peaks of x = amplitude * sin(k * y), optionally restricting y to [0, length]
+fn zigzagSDF(pos: vec2<f32>, amplitude: f32, k: f32, len: f32, clamped: bool) -> f32 {
+    // Corners sit at each peak and trough, half a period apart
+    var half_period = PI / k;
+    var corner = (round((pos.y - half_period / 2.0) / half_period) + 0.5) * half_period;
+
+    var a = corner - half_period;
+    var b = corner;
+    var c = corner + half_period;
+    if clamped {
+        a = clamp(a, 0.0, len);
+        b = clamp(b, 0.0, len);
+        c = clamp(c, 0.0, len);
+    }
+
+    return min(segmentSDF(pos, amplitude, k, a, b), segmentSDF(pos, amplitude, k, b, c));
+}
+
+// Due to https://github.com/gfx-rs/naga/issues/1743 this cannot be compiled into the vertex shader on web
+#ifdef FRAGMENT
+@fragment
+fn fragment(f: FragmentInput) -> @location(0) vec4<f32> {
+    // Mask representing whether this fragment falls within the shape
+    var in_shape = f.color.a;
+
+    var cap = core::f_cap(f.flags);
+    var round_cap = cap == 2u;
+
+    // Round caps come from restricting the curve to it's ends, other caps cut it off flat
+    var dist: f32;
+    switch core::f_wave_type(f.flags) {
+        // WaveType::Sine
+        default: {
+            dist = sineSDF(f.pos, f.amplitude, f.wave_number, f.length, round_cap);
+        }
+        // WaveType::Zigzag
+        case 1u: {
+            dist = zigzagSDF(f.pos, f.amplitude, f.wave_number, f.length, round_cap);
+        }
+    }
+    dist = dist - f.radius;
+
+    if !round_cap {
+        // Square caps extend by our radius
+        var cut = max(-f.pos.y, f.pos.y - f.length) - select(0.0, f.radius, cap == 1u);
+        dist = max(dist, cut);
+    }
+
+    in_shape *= core::step_aa(dist, 0.0);
+
+    in_shape *= core::clip_mask(f.clip_position);
+    var color = core::color_output(vec4<f32>(f.color.rgb, in_shape));
+#ifdef TEXTURED
+    color = color * textureSample(image, image_sampler, f.texture_uv);
+#endif
+
+    // Discard fragments no longer in the shape
+    if in_shape < 0.0001 {
+        discard;
+    }
+
+    return color;
+}
+#endif
@@ -0,0 +1,88 @@
+#import bevy_ui::ui_vertex_output::UiVertexOutput
+#import bevy_vector_shapes::constants::{PI, TAU}
+
+struct ShapeNode {
+    color: vec4<f32>,
+    corner_radii: vec4<f32>,
+    thickness: f32,
+    kind: u32,
+    start_angle: f32,
+    end_angle: f32,
+    sides: f32,
+    roundness: f32,
+};
+
+@group(1) @binding(0) var<uniform> node: ShapeNode;
+
+const KIND_RECT: u32 = 0u;
+const KIND_CIRCLE: u32 = 1u;
+const KIND_ARC: u32 = 2u;
+const KIND_NGON: u32 = 3u;
+
+// Signed distance to a rectangle with per corner radii ordered top-right, top-left, bottom-left, bottom-right
+fn rect_sdf(pos: vec2<f32>, half_size: vec2<f32>, corner_radii: vec4<f32>) -> f32 {
+    var radii = select(corner_radii.yz, corner_radii.xw, pos.x > 0.0);
+    var radius = clamp(select(radii.y, radii.x, pos.y > 0.0), 0.0, min(half_size.x, half_size.y));
+    var q = abs(pos) - half_size + radius;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+// Signed distance to a regular polygon with it's first vertex pointing up, matching the ngon shader
+fn ngon_sdf(position: vec2<f32>, radius: f32, sides: f32, roundness: f32) -> f32 {
+    var central_angle = TAU / max(sides, 3.0);
+    var apothem = radius * cos(central_angle / 2.0);
+    var rounding = clamp(roundness, 0.0, apothem);
+    var half_side_length = radius * sin(central_angle / 2.0) * (1.0 - rounding / apothem);
+
+    var pos = position.yx;
+    var angle = atan2(pos.y, pos.x);
+    var nearest_angle = central_angle * floor((angle + 0.5 * central_angle) / central_angle);
+    var c = cos(nearest_angle);
+    var s = sin(nearest_angle);
+    pos = vec2<f32>(c * pos.x + s * pos.y, -s * pos.x + c * pos.y);
+    var nearest = vec2<f32>(apothem - rounding, clamp(pos.y, -half_side_length, half_side_length));
+    return length(pos - nearest) * sign(pos.x - nearest.x) - rounding;
+}
+
+@fragment
+fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
+    // Position in logical pixels relative to the center of the node with y up
+    var pos = (in.uv - 0.5) * in.size * vec2<f32>(1.0, -1.0);
+    var half_size = in.size / 2.0;
+    var radius = min(half_size.x, half_size.y);
+
+    var dist: f32;
+    switch node.kind {
+        case KIND_CIRCLE, KIND_ARC: {
+            dist = length(pos) - radius;
+        }
+        case KIND_NGON: {
+            dist = ngon_sdf(pos, radius, node.sides, node.roundness);
+        }
+        default: {
+            dist = rect_sdf(pos, half_size, node.corner_radii);
+        }
+    }
+
+    // Outlines are drawn inside the edge so that they stay within the node
+    if node.thickness > 0.0 {
+        dist = abs(dist + node.thickness / 2.0) - node.thickness / 2.0;
+    }
+
+    // Cut off points outside the arc, angles start from y up and increase clockwise
+    if node.kind == KIND_ARC {
+        var start = min(node.start_angle, node.end_angle);
+        var sweep = abs(node.end_angle - node.start_angle);
+        if sweep < TAU {
+            var angle = atan2(pos.x, pos.y) - start;
+            angle = angle - floor(angle / TAU) * TAU;
+            var outside = angle - sweep;
+            // Distance to the nearest straight edge of the arc in pixels
+            var to_edge = min(outside, TAU - angle) * length(pos);
+            dist = max(dist, select(-1e6, to_edge, outside > 0.0));
+        }
+    }
+
+    var alpha = clamp(0.5 - dist, 0.0, 1.0);
+    return vec4<f32>(node.color.rgb, node.color.a * alpha);
+}
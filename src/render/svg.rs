@@ -0,0 +1,174 @@
+use std::fmt::{self, Display, Write};
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// A single SVG element describing a shape instance, see [`ShapeData::svg`](super::ShapeData::svg).
+///
+/// Coordinates are in world space with the y axis pointing up, [`ShapeRecording::to_svg`] flips the document to match.
+#[derive(Clone, Debug)]
+pub struct SvgElement {
+    tag: &'static str,
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl SvgElement {
+    pub fn new(tag: &'static str) -> Self {
+        Self {
+            tag,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Add an attribute to the element.
+    pub fn attr(mut self, name: &'static str, value: impl Display) -> Self {
+        self.attributes.push((name, value.to_string()));
+        self
+    }
+
+    /// Place the element with the xy plane of the given transform, the z axis is ignored.
+    pub fn transform(self, transform: Mat4) -> Self {
+        let (x, y, t) = (transform.x_axis, transform.y_axis, transform.w_axis);
+        self.attr(
+            "transform",
+            format_args!("matrix({} {} {} {} {} {})", x.x, x.y, y.x, y.y, t.x, t.y),
+        )
+    }
+
+    /// Fill the element with a linear rgba color as stored in shape data.
+    pub fn fill(self, color: [f32; 4]) -> Self {
+        let (hex, alpha) = svg_color(color);
+        self.attr("fill", hex).attr("fill-opacity", alpha)
+    }
+
+    /// Outline the element with a linear rgba color as stored in shape data, leaving it unfilled.
+    pub fn stroke(self, color: [f32; 4], width: f32) -> Self {
+        let (hex, alpha) = svg_color(color);
+        self.attr("fill", "none")
+            .attr("stroke", hex)
+            .attr("stroke-opacity", alpha)
+            .attr("stroke-width", width)
+    }
+
+    pub fn cap(self, cap: Cap) -> Self {
+        let cap = match cap {
+            Cap::None => "butt",
+            Cap::Square => "square",
+            Cap::Round => "round",
+        };
+        self.attr("stroke-linecap", cap)
+    }
+
+    /// Dash the outline of the element with a dash pattern as stored in shape data.
+    pub fn dash(self, dash: Vec3) -> Self {
+        if dash.x <= 0.0 {
+            return self;
+        }
+        self.attr("stroke-dasharray", format_args!("{} {}", dash.x, dash.y))
+            .attr("stroke-dashoffset", -dash.z)
+    }
+}
+
+impl Display for SvgElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}", self.tag)?;
+        for (name, value) in &self.attributes {
+            write!(f, " {name}=\"{value}\"")?;
+        }
+        write!(f, "/>")
+    }
+}
+
+/// Convert a linear rgba color into an sRGB hex color and an opacity.
+fn svg_color(color: [f32; 4]) -> (String, f32) {
+    let [r, g, b, a] = Color::rgba_linear(color[0], color[1], color[2], color[3]).as_rgba_u8();
+    (format!("#{r:02x}{g:02x}{b:02x}"), a as f32 / 255.0)
+}
+
+/// Point on a circle of the given radius at an angle in the convention used by arcs, 0 is up and angles increase clockwise.
+fn arc_point(radius: f32, angle: f32) -> Vec2 {
+    Vec2::new(angle.sin(), angle.cos()) * radius
+}
+
+/// Path data for an arc from `start_angle` to `end_angle` continuing the current path, see [`arc_point`].
+fn arc_to(path: &mut String, radius: f32, start_angle: f32, end_angle: f32) {
+    let end = arc_point(radius, end_angle);
+    let large_arc = ((end_angle - start_angle).abs() > std::f32::consts::PI) as u8;
+    // Increasing angles run clockwise which is the negative direction in y up coordinates
+    let sweep = (end_angle < start_angle) as u8;
+    let _ = write!(
+        path,
+        "A {radius} {radius} 0 {large_arc} {sweep} {} {}",
+        end.x, end.y
+    );
+}
+
+/// Path data for the outline of an arc, see [`arc_point`].
+pub(crate) fn arc_path(radius: f32, start_angle: f32, end_angle: f32) -> String {
+    let start = arc_point(radius, start_angle);
+    let mut path = format!("M {} {} ", start.x, start.y);
+    arc_to(&mut path, radius, start_angle, end_angle);
+    path
+}
+
+/// Path data for a filled arc, a pie slice when `inner_radius` is 0 and an annular sector otherwise.
+pub(crate) fn sector_path(
+    inner_radius: f32,
+    outer_radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+) -> String {
+    let mut path = arc_path(outer_radius, start_angle, end_angle);
+    if inner_radius > 0.0 {
+        let inner = arc_point(inner_radius, end_angle);
+        let _ = write!(path, " L {} {} ", inner.x, inner.y);
+        arc_to(&mut path, inner_radius, end_angle, start_angle);
+    } else {
+        path.push_str(" L 0 0");
+    }
+    path.push_str(" Z");
+    path
+}
+
+/// Path data for a rectangle centered on the origin with corner radii ordered top-right, top-left, bottom-left, bottom-right.
+pub(crate) fn rounded_rect_path(size: Vec2, corner_radii: Vec4) -> String {
+    let half = size / 2.0;
+    let max_radius = half.min_element();
+    let [top_right, top_left, bottom_left, bottom_right] = corner_radii
+        .to_array()
+        .map(|radius| radius.clamp(0.0, max_radius));
+
+    // Trace the outline clockwise from the top edge, with each corner turning clockwise
+    let mut path = format!("M {} {} ", -half.x + top_left, half.y);
+    let corner = |path: &mut String, radius: f32, to: Vec2| {
+        let _ = write!(path, "A {radius} {radius} 0 0 0 {} {} ", to.x, to.y);
+    };
+    let _ = write!(path, "L {} {} ", half.x - top_right, half.y);
+    corner(&mut path, top_right, Vec2::new(half.x, half.y - top_right));
+    let _ = write!(path, "L {} {} ", half.x, -half.y + bottom_right);
+    corner(
+        &mut path,
+        bottom_right,
+        Vec2::new(half.x - bottom_right, -half.y),
+    );
+    let _ = write!(path, "L {} {} ", -half.x + bottom_left, -half.y);
+    corner(
+        &mut path,
+        bottom_left,
+        Vec2::new(-half.x, -half.y + bottom_left),
+    );
+    let _ = write!(path, "L {} {} ", -half.x, half.y - top_left);
+    corner(&mut path, top_left, Vec2::new(-half.x + top_left, half.y));
+    path.push('Z');
+    path
+}
+
+/// Offset of the center of an outline from the edge of a shape for the given alignment.
+pub(crate) fn stroke_offset(alignment: StrokeAlignment, thickness: f32) -> f32 {
+    match alignment {
+        StrokeAlignment::Inside => -thickness / 2.0,
+        StrokeAlignment::Center => 0.0,
+        StrokeAlignment::Outside => thickness / 2.0,
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::{
+    asset::load_internal_asset,
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef, ShaderType},
+};
+
+use crate::prelude::*;
+
+/// Handler to shader for drawing shapes in bevy_ui nodes.
+pub const UI_NODE_HANDLE: Handle<Shader> = Handle::weak_from_u128(12816207465093862711);
+
+/// Shape drawn by a [`ShapeNode`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeShape {
+    /// Rectangle filling the node, with the corner radii of the [`ShapeNode`].
+    #[default]
+    Rect,
+    /// Circle with a diameter of the smallest dimension of the node.
+    Circle,
+    /// Arc of a circle with a diameter of the smallest dimension of the node, angles follow the convention of [`DiscPainter::arc`].
+    ///
+    /// Filled arcs are drawn as pie slices.
+    Arc { start_angle: f32, end_angle: f32 },
+    /// Regular polygon with a circumradius of half the smallest dimension of the node, rounded by the roundness of the [`ShapeNode`].
+    Ngon { sides: f32 },
+}
+
+/// Component that draws a shape sized and positioned by the bevy_ui layout, requires the `bevy_ui` feature.
+///
+/// Shapes are drawn by the UI renderer so they are ordered and clipped like any other node.
+/// Thickness is always in logical pixels and outlines are drawn inside the edge of the shape so that they
+/// stay within the node.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapeNode {
+    pub shape: NodeShape,
+    pub color: Color,
+    pub hollow: bool,
+    pub thickness: f32,
+    /// Corner radii of rectangles ordered top-right, top-left, bottom-left, bottom-right, see [`ShapeConfig::corner_radii`].
+    pub corner_radii: Vec4,
+    /// Corner rounding radius of regular polygons, see [`ShapeConfig::roundness`].
+    pub roundness: f32,
+}
+
+impl ShapeNode {
+    pub fn new(config: &ShapeConfig, shape: NodeShape) -> Self {
+        Self {
+            shape,
+            color: config.color,
+            hollow: config.hollow,
+            thickness: config.thickness,
+            corner_radii: config.corner_radii,
+            roundness: config.roundness,
+        }
+    }
+}
+
+impl Default for ShapeNode {
+    fn default() -> Self {
+        Self {
+            shape: default(),
+            color: Color::WHITE,
+            hollow: false,
+            thickness: 1.0,
+            corner_radii: Vec4::ZERO,
+            roundness: 0.0,
+        }
+    }
+}
+
+/// Raw data sent to the UI node shader
+#[derive(Clone, Copy, Default, Debug, ShaderType)]
+struct ShapeNodeData {
+    color: Vec4,
+    corner_radii: Vec4,
+    thickness: f32,
+    kind: u32,
+    start_angle: f32,
+    end_angle: f32,
+    sides: f32,
+    roundness: f32,
+}
+
+impl From<&ShapeNode> for ShapeNodeData {
+    fn from(node: &ShapeNode) -> Self {
+        let (kind, start_angle, end_angle, sides) = match node.shape {
+            NodeShape::Rect => (0, 0.0, 0.0, 0.0),
+            NodeShape::Circle => (1, 0.0, 0.0, 0.0),
+            NodeShape::Arc {
+                start_angle,
+                end_angle,
+            } => (2, start_angle, end_angle, 0.0),
+            NodeShape::Ngon { sides } => (3, 0.0, 0.0, sides),
+        };
+
+        Self {
+            color: Vec4::from_array(node.color.as_linear_rgba_f32()),
+            corner_radii: node.corner_radii,
+            thickness: if node.hollow { node.thickness } else { 0.0 },
+            kind,
+            start_angle,
+            end_angle,
+            sides,
+            roundness: node.roundness,
+        }
+    }
+}
+
+/// UI material generated from each [`ShapeNode`], updated automatically when the node changes.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct ShapeNodeMaterial {
+    #[uniform(0)]
+    data: ShapeNodeData,
+}
+
+impl UiMaterial for ShapeNodeMaterial {
+    fn fragment_shader() -> ShaderRef {
+        UI_NODE_HANDLE.into()
+    }
+}
+
+/// Bundle that draws a shape as a bevy_ui node, requires the `bevy_ui` feature.
+#[derive(Bundle, Default)]
+pub struct ShapeNodeBundle {
+    pub node: MaterialNodeBundle<ShapeNodeMaterial>,
+    pub shape: ShapeNode,
+}
+
+impl ShapeNodeBundle {
+    /// Create a node drawing the given shape with the color, thickness and rounding of `config` and the given style.
+    pub fn new(config: &ShapeConfig, shape: NodeShape, style: Style) -> Self {
+        Self {
+            node: MaterialNodeBundle { style, ..default() },
+            shape: ShapeNode::new(config, shape),
+        }
+    }
+}
+
+/// Creates or updates the material of each changed [`ShapeNode`].
+pub fn update_shape_node_materials(
+    mut nodes: Query<(&ShapeNode, &mut Handle<ShapeNodeMaterial>), Changed<ShapeNode>>,
+    mut materials: ResMut<Assets<ShapeNodeMaterial>>,
+) {
+    for (node, mut handle) in nodes.iter_mut() {
+        let data = ShapeNodeData::from(node);
+        match materials.get_mut(&*handle) {
+            Some(material) => material.data = data,
+            None => *handle = materials.add(ShapeNodeMaterial { data }),
+        }
+    }
+}
+
+/// Plugin that draws [`ShapeNode`]s in bevy_ui, added by [`Shape2dPlugin`] when the `bevy_ui` feature is enabled.
+pub struct ShapeNodePlugin;
+
+impl Plugin for ShapeNodePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            UI_NODE_HANDLE,
+            "shaders/ui_node.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_plugins(UiMaterialPlugin::<ShapeNodeMaterial>::default())
+            .register_type::<ShapeNode>()
+            .register_type::<NodeShape>()
+            .add_systems(
+                PostUpdate,
+                update_shape_node_materials.before(bevy::ui::UiSystem::Layout),
+            );
+    }
+}
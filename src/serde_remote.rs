@@ -0,0 +1,30 @@
+use bevy::{prelude::*, sprite::Anchor};
+use serde::{Deserialize, Serialize};
+
+/// Mirror of [`Anchor`] used to serialize it with `#[serde(with = "AnchorDef")]`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Anchor")]
+pub(crate) enum AnchorDef {
+    Center,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    CenterLeft,
+    CenterRight,
+    TopLeft,
+    TopCenter,
+    TopRight,
+    Custom(Vec2),
+}
+
+/// Mirror of [`AlphaMode`] used to serialize it with `#[serde(with = "AlphaModeDef")]`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AlphaMode")]
+pub(crate) enum AlphaModeDef {
+    Opaque,
+    Mask(f32),
+    Blend,
+    Premultiplied,
+    Add,
+    Multiply,
+}
@@ -0,0 +1,246 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// Easing curve applied to the progress of a shape animation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShapeEasing {
+    Linear,
+    QuadraticIn,
+    QuadraticOut,
+    QuadraticInOut,
+    CubicInOut,
+    #[default]
+    SineInOut,
+}
+
+impl ShapeEasing {
+    /// Map the progress `t`, from 0.0 to 1.0, along the easing curve.
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::QuadraticIn => t * t,
+            Self::QuadraticOut => t * (2.0 - t),
+            Self::QuadraticInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Self::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Self::SineInOut => (1.0 - (PI * t).cos()) / 2.0,
+        }
+    }
+}
+
+/// Eased progress of an animation that goes from 0.0 to 1.0 and back again once every `period` seconds.
+fn ping_pong(elapsed: f32, period: f32, easing: ShapeEasing) -> f32 {
+    if period <= 0.0 {
+        return 0.0;
+    }
+    let t = (elapsed / period).fract() * 2.0;
+    easing.ease(if t > 1.0 { 2.0 - t } else { t })
+}
+
+/// Component that scales a shape entity back and forth between two factors of it's transform's scale.
+///
+/// The scale is multiplied by the current factor each frame after undoing the previous one, so the transform may
+/// still be scaled by other systems. Factors should therefore never reach zero.
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapePulse {
+    /// Factor the scale is multiplied by at the start of each pulse.
+    pub from: f32,
+    /// Factor the scale is multiplied by half way through each pulse.
+    pub to: f32,
+    /// Seconds taken to grow and shrink back again.
+    pub period: f32,
+    pub easing: ShapeEasing,
+    /// Seconds since the animation started, may be set to offset animations from each other.
+    pub elapsed: f32,
+    /// Factor applied to the scale by the previous frame.
+    applied: f32,
+}
+
+impl ShapePulse {
+    pub fn new(from: f32, to: f32, period: f32) -> Self {
+        Self {
+            from,
+            to,
+            period,
+            ..default()
+        }
+    }
+
+    pub fn with_easing(mut self, easing: ShapeEasing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl Default for ShapePulse {
+    fn default() -> Self {
+        Self {
+            from: 1.0,
+            to: 1.2,
+            period: 1.0,
+            easing: default(),
+            elapsed: 0.0,
+            applied: 1.0,
+        }
+    }
+}
+
+/// Component that rotates a shape entity around an axis of it's transform at a constant speed.
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapeSpin {
+    /// Speed of the rotation in radians per second, negative speeds spin clockwise.
+    pub speed: f32,
+    /// Local axis to rotate around, the Z axis spins shapes within their own plane.
+    pub axis: Vec3,
+}
+
+impl ShapeSpin {
+    pub fn new(speed: f32) -> Self {
+        Self { speed, ..default() }
+    }
+
+    pub fn with_axis(mut self, axis: Vec3) -> Self {
+        self.axis = axis;
+        self
+    }
+}
+
+impl Default for ShapeSpin {
+    fn default() -> Self {
+        Self {
+            speed: PI,
+            axis: Vec3::Z,
+        }
+    }
+}
+
+/// Component that fades a shape entity and it's descendants back and forth between two alphas.
+///
+/// Drives the [`ShapeAlpha`] of the entity, inserting it if missing and replacing any value set on it.
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapeBlink {
+    /// Alpha at the start of each blink.
+    pub from: f32,
+    /// Alpha half way through each blink.
+    pub to: f32,
+    /// Seconds taken to fade out and back in again.
+    pub period: f32,
+    pub easing: ShapeEasing,
+    /// Seconds since the animation started, may be set to offset animations from each other.
+    pub elapsed: f32,
+}
+
+impl ShapeBlink {
+    pub fn new(from: f32, to: f32, period: f32) -> Self {
+        Self {
+            from,
+            to,
+            period,
+            ..default()
+        }
+    }
+
+    pub fn with_easing(mut self, easing: ShapeEasing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+impl Default for ShapeBlink {
+    fn default() -> Self {
+        Self {
+            from: 1.0,
+            to: 0.0,
+            period: 1.0,
+            easing: default(),
+            elapsed: 0.0,
+        }
+    }
+}
+
+pub fn animate_shape_pulses(time: Res<Time>, mut query: Query<(&mut ShapePulse, &mut Transform)>) {
+    for (mut pulse, mut tf) in &mut query {
+        pulse.elapsed += time.delta_seconds();
+        let t = ping_pong(pulse.elapsed, pulse.period, pulse.easing);
+        let factor = pulse.from + (pulse.to - pulse.from) * t;
+        tf.scale *= factor / pulse.applied;
+        pulse.applied = factor;
+    }
+}
+
+pub fn animate_shape_spins(time: Res<Time>, mut query: Query<(&ShapeSpin, &mut Transform)>) {
+    for (spin, mut tf) in &mut query {
+        let axis = spin.axis.try_normalize().unwrap_or(Vec3::Z);
+        tf.rotate_local(Quat::from_axis_angle(
+            axis,
+            spin.speed * time.delta_seconds(),
+        ));
+    }
+}
+
+pub fn animate_shape_blinks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ShapeBlink, Option<&mut ShapeAlpha>)>,
+) {
+    for (entity, mut blink, alpha) in &mut query {
+        blink.elapsed += time.delta_seconds();
+        let t = ping_pong(blink.elapsed, blink.period, blink.easing);
+        let value = blink.from + (blink.to - blink.from) * t;
+        match alpha {
+            Some(mut alpha) => alpha.0 = value,
+            None => {
+                commands.entity(entity).insert(ShapeAlpha(value));
+            }
+        }
+    }
+}
+
+/// Plugin that animates retained shapes with a [`ShapePulse`], [`ShapeSpin`], [`ShapeBlink`] or [`ShapeMorph`], added by [`Shape2dPlugin`].
+///
+/// Animations advance with Bevy's [`Time`] so they pause along with virtual time.
+pub struct ShapeAnimationPlugin;
+
+impl Plugin for ShapeAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ShapeEasing>()
+            .register_type::<ShapePulse>()
+            .register_type::<ShapeSpin>()
+            .register_type::<ShapeBlink>()
+            .add_systems(
+                Update,
+                (
+                    animate_shape_pulses,
+                    animate_shape_spins,
+                    animate_shape_blinks,
+                    morph_shapes::<ShapeFill>,
+                    morph_shapes::<DiscComponent>,
+                    morph_shapes::<RectangleComponent>,
+                ),
+            );
+        #[cfg(feature = "ngon")]
+        app.add_systems(Update, morph_shapes::<RegularPolygonComponent>);
+    }
+}
@@ -0,0 +1,245 @@
+use std::f32::consts::SQRT_2;
+
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, ARROW_HANDLE},
+};
+
+/// Component containing the data for drawing an arrow.
+///
+/// Arrows are drawn as a line from start to end with a head at the end and optionally at the start.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArrowComponent {
+    pub alignment: Alignment,
+    /// Cap type for the tail of a single headed arrow.
+    pub cap: Cap,
+    /// Style of the arrow heads.
+    pub head: ArrowHead,
+    /// Whether to also draw a head at the start of the arrow.
+    pub double_headed: bool,
+
+    /// Position to draw the start of the arrow in world space relative to it's transform.
+    pub start: Vec3,
+    /// Position to draw the end of the arrow in world space relative to it's transform.
+    pub end: Vec3,
+    /// Length of the arrow heads as a multiple of the arrow's thickness.
+    pub head_size: f32,
+}
+
+impl ArrowComponent {
+    pub fn new(config: &ShapeConfig, start: Vec3, end: Vec3, double_headed: bool) -> Self {
+        Self {
+            alignment: config.alignment,
+            cap: config.cap,
+            head: config.arrow_head,
+            double_headed,
+
+            start,
+            end,
+            head_size: config.arrow_head_size,
+        }
+    }
+}
+
+impl Default for ArrowComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+            cap: default(),
+            head: default(),
+            double_headed: false,
+
+            start: default(),
+            end: default(),
+            head_size: 4.0,
+        }
+    }
+}
+
+impl ShapeComponent for ArrowComponent {
+    type Data = ArrowData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        // Heads are as wide as they are long, measured in multiples of the thickness
+        stroke_aabb(
+            self.start.min(self.end),
+            self.start.max(self.end),
+            Alignment::Flat,
+            fill,
+            |thickness| thickness * (1.0 + self.head_size.max(0.0)) / 2.0 * SQRT_2,
+        )
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> ArrowData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+        flags.set_cap(self.cap);
+        flags.set_arrow_head(self.head);
+        flags.set_double_headed(self.double_headed as u32);
+
+        ArrowData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            start: self.start,
+            end: self.end,
+            head_size: self.head_size,
+        }
+    }
+}
+
+/// Raw data sent to the arrow shader to draw an arrow
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct ArrowData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    start: Vec3,
+    end: Vec3,
+    head_size: f32,
+}
+
+impl ArrowData {
+    pub fn new(config: &ShapeConfig, start: Vec3, end: Vec3, double_headed: bool) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+        flags.set_cap(config.cap);
+        flags.set_arrow_head(config.arrow_head);
+        flags.set_double_headed(double_headed as u32);
+
+        ArrowData {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            start,
+            end,
+            head_size: config.arrow_head_size,
+        }
+    }
+}
+
+impl ShapeData for ArrowData {
+    type Component = ArrowComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+            7 => Float32x3,
+            8 => Float32x3,
+            9 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        ARROW_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw arrows.
+pub trait ArrowPainter {
+    fn arrow(&mut self, start: Vec3, end: Vec3) -> &mut Self;
+    fn double_arrow(&mut self, start: Vec3, end: Vec3) -> &mut Self;
+}
+
+impl<P: ShapeSender> ArrowPainter for P {
+    fn arrow(&mut self, start: Vec3, end: Vec3) -> &mut Self {
+        self.send(ArrowData::new(self.config(), start, end, false))
+    }
+
+    fn double_arrow(&mut self, start: Vec3, end: Vec3) -> &mut Self {
+        self.send(ArrowData::new(self.config(), start, end, true))
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of arrow bundles.
+pub trait ArrowBundle {
+    fn arrow(config: &ShapeConfig, start: Vec3, end: Vec3) -> Self;
+    fn double_arrow(config: &ShapeConfig, start: Vec3, end: Vec3) -> Self;
+}
+
+impl ArrowBundle for ShapeBundle<ArrowComponent> {
+    fn arrow(config: &ShapeConfig, start: Vec3, end: Vec3) -> Self {
+        let mut bundle = Self::new(config, ArrowComponent::new(config, start, end, false));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+
+    fn double_arrow(config: &ShapeConfig, start: Vec3, end: Vec3) -> Self {
+        let mut bundle = Self::new(config, ArrowComponent::new(config, start, end, true));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of arrow entities.
+pub trait ArrowSpawner<'w> {
+    fn arrow(&mut self, start: Vec3, end: Vec3) -> ShapeEntityCommands;
+    fn double_arrow(&mut self, start: Vec3, end: Vec3) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> ArrowSpawner<'w> for T {
+    fn arrow(&mut self, start: Vec3, end: Vec3) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::arrow(self.config(), start, end))
+    }
+
+    fn double_arrow(&mut self, start: Vec3, end: Vec3) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::double_arrow(self.config(), start, end))
+    }
+}
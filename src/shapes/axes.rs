@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// Direction, color and label of each axis drawn by [`AxesPainter`].
+const AXES: [(Vec3, Color, &str); 3] = [
+    (Vec3::X, Color::RED, "X"),
+    (Vec3::Y, Color::GREEN, "Y"),
+    (Vec3::Z, Color::BLUE, "Z"),
+];
+
+/// Extension trait for [`ShapePainter`] to enable it to draw coordinate axes.
+///
+/// Axes are drawn from the origin of the painter's transform colored red, green and blue for x, y and z,
+/// the painter's config is left unchanged.
+pub trait AxesPainter {
+    /// Draw a line of length `size` along each axis.
+    fn axes(&mut self, size: f32) -> &mut Self;
+    /// Draw an arrow of length `size` along each axis, see [`ShapeConfig::arrow_head`].
+    fn axes_arrows(&mut self, size: f32) -> &mut Self;
+    /// Draw an arrow of length `size` along each axis with it's name past the tip, see [`TextPainter`].
+    #[cfg(feature = "text")]
+    fn axes_labeled(&mut self, size: f32, font: &Handle<Font>) -> &mut Self;
+}
+
+fn draw_axes(painter: &mut ShapePainter, size: f32, arrows: bool) {
+    for (dir, color, _) in AXES {
+        painter.with_children(|painter| {
+            painter.color = color;
+            if arrows {
+                painter.arrow(Vec3::ZERO, dir * size);
+            } else {
+                painter.line(Vec3::ZERO, dir * size);
+            }
+        });
+    }
+}
+
+impl<'w, 's> AxesPainter for ShapePainter<'w, 's> {
+    fn axes(&mut self, size: f32) -> &mut Self {
+        draw_axes(self, size, false);
+        self
+    }
+
+    fn axes_arrows(&mut self, size: f32) -> &mut Self {
+        draw_axes(self, size, true);
+        self
+    }
+
+    #[cfg(feature = "text")]
+    fn axes_labeled(&mut self, size: f32, font: &Handle<Font>) -> &mut Self {
+        draw_axes(self, size, true);
+        for (dir, color, label) in AXES {
+            self.with_children(|painter| {
+                painter.color = color;
+                let offset = size + painter.font_size;
+                painter.translate(dir * offset);
+                painter.text(label, font);
+            });
+        }
+        self
+    }
+}
@@ -0,0 +1,238 @@
+use std::f32::consts::SQRT_2;
+
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, BEZIER_HANDLE},
+};
+
+/// Component containing the data for drawing a bézier curve.
+///
+/// Quadratic curves are stored as their equivalent cubic curve.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct BezierComponent {
+    pub alignment: Alignment,
+    pub cap: Cap,
+
+    /// Control points of the cubic curve in the local space of it's transform.
+    pub control_points: [Vec2; 4],
+}
+
+impl BezierComponent {
+    pub fn new(config: &ShapeConfig, control_points: [Vec2; 4]) -> Self {
+        Self {
+            alignment: config.alignment,
+            cap: config.cap,
+
+            control_points,
+        }
+    }
+
+    pub fn quadratic(config: &ShapeConfig, p0: Vec2, p1: Vec2, p2: Vec2) -> Self {
+        Self::new(config, quadratic_to_cubic(p0, p1, p2))
+    }
+
+    pub fn cubic(config: &ShapeConfig, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Self {
+        Self::new(config, [p0, p1, p2, p3])
+    }
+}
+
+impl Default for BezierComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+            cap: default(),
+
+            control_points: [Vec2::ZERO, Vec2::Y, Vec2::ONE, Vec2::X],
+        }
+    }
+}
+
+impl ShapeComponent for BezierComponent {
+    type Data = BezierData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        // Curves lie within the hull of their control points
+        let [a, b, c, d] = self.control_points;
+        stroke_aabb(
+            a.min(b).min(c).min(d).extend(0.0),
+            a.max(b).max(c).max(d).extend(0.0),
+            self.alignment,
+            fill,
+            |thickness| thickness / 2.0 * SQRT_2,
+        )
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> BezierData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+        flags.set_cap(self.cap);
+
+        BezierData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            control_points: self.control_points.map(Into::into),
+
+            padding: default(),
+        }
+    }
+}
+
+/// Raw data sent to the bézier shader to draw a curve
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct BezierData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    control_points: [[f32; 2]; 4],
+
+    padding: [f32; 2],
+}
+
+impl BezierData {
+    pub fn new(config: &ShapeConfig, control_points: [Vec2; 4]) -> BezierData {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+        flags.set_cap(config.cap);
+
+        BezierData {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            control_points: control_points.map(Into::into),
+
+            padding: default(),
+        }
+    }
+}
+
+impl ShapeData for BezierData {
+    type Component = BezierComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32x2,
+            8 => Float32x2,
+            9 => Float32x2,
+            10 => Float32x2,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        BEZIER_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw bézier curves.
+pub trait BezierPainter {
+    fn bezier_quadratic(&mut self, p0: Vec2, p1: Vec2, p2: Vec2) -> &mut Self;
+    fn bezier_cubic(&mut self, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> &mut Self;
+}
+
+impl<P: ShapeSender> BezierPainter for P {
+    fn bezier_quadratic(&mut self, p0: Vec2, p1: Vec2, p2: Vec2) -> &mut Self {
+        self.send(BezierData::new(
+            self.config(),
+            quadratic_to_cubic(p0, p1, p2),
+        ))
+    }
+
+    fn bezier_cubic(&mut self, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> &mut Self {
+        self.send(BezierData::new(self.config(), [p0, p1, p2, p3]))
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of bézier curve bundles.
+pub trait BezierBundle {
+    fn bezier_quadratic(config: &ShapeConfig, p0: Vec2, p1: Vec2, p2: Vec2) -> Self;
+    fn bezier_cubic(config: &ShapeConfig, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Self;
+}
+
+impl BezierBundle for ShapeBundle<BezierComponent> {
+    fn bezier_quadratic(config: &ShapeConfig, p0: Vec2, p1: Vec2, p2: Vec2) -> Self {
+        let mut bundle = Self::new(config, BezierComponent::quadratic(config, p0, p1, p2));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+
+    fn bezier_cubic(config: &ShapeConfig, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Self {
+        let mut bundle = Self::new(config, BezierComponent::cubic(config, p0, p1, p2, p3));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of bézier curve entities.
+pub trait BezierSpawner<'w> {
+    fn bezier_quadratic(&mut self, p0: Vec2, p1: Vec2, p2: Vec2) -> ShapeEntityCommands;
+    fn bezier_cubic(&mut self, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> BezierSpawner<'w> for T {
+    fn bezier_quadratic(&mut self, p0: Vec2, p1: Vec2, p2: Vec2) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::bezier_quadratic(self.config(), p0, p1, p2))
+    }
+
+    fn bezier_cubic(&mut self, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::bezier_cubic(self.config(), p0, p1, p2, p3))
+    }
+}
@@ -0,0 +1,212 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, CAPSULE_HANDLE},
+};
+
+/// Component containing the data for drawing a capsule.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct CapsuleComponent {
+    pub alignment: Alignment,
+
+    /// Center of the first semi-circle in the local space of it's transform.
+    pub start: Vec2,
+    /// Center of the second semi-circle in the local space of it's transform.
+    pub end: Vec2,
+    /// Distance from the segment between start and end to the outline of the capsule.
+    pub radius: f32,
+}
+
+impl CapsuleComponent {
+    pub fn new(config: &ShapeConfig, start: Vec2, end: Vec2, radius: f32) -> Self {
+        Self {
+            alignment: config.alignment,
+
+            start,
+            end,
+            radius,
+        }
+    }
+}
+
+impl Default for CapsuleComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+
+            start: Vec2::new(-0.5, 0.0),
+            end: Vec2::new(0.5, 0.0),
+            radius: 0.5,
+        }
+    }
+}
+
+impl ShapeComponent for CapsuleComponent {
+    type Data = CapsuleData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let radius = self.radius.abs();
+        shape_aabb(
+            self.start.min(self.end) - radius,
+            self.start.max(self.end) + radius,
+            self.alignment,
+            fill,
+            |thickness| thickness,
+        )
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> CapsuleData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+
+        CapsuleData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            start: self.start.into(),
+            end: self.end.into(),
+            radius: self.radius,
+
+            padding: default(),
+        }
+    }
+}
+
+/// Raw data sent to the capsule shader to draw a capsule
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct CapsuleData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    start: [f32; 2],
+    end: [f32; 2],
+    radius: f32,
+
+    padding: f32,
+}
+
+impl CapsuleData {
+    pub fn new(config: &ShapeConfig, start: Vec2, end: Vec2, radius: f32) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+        flags.set_hollow(config.hollow as u32);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            start: start.into(),
+            end: end.into(),
+            radius,
+
+            padding: default(),
+        }
+    }
+}
+
+impl ShapeData for CapsuleData {
+    type Component = CapsuleComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32x2,
+            8 => Float32x2,
+            9 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        CAPSULE_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw capsules.
+pub trait CapsulePainter {
+    fn capsule(&mut self, start: Vec2, end: Vec2, radius: f32) -> &mut Self;
+}
+
+impl<P: ShapeSender> CapsulePainter for P {
+    fn capsule(&mut self, start: Vec2, end: Vec2, radius: f32) -> &mut Self {
+        self.send(CapsuleData::new(self.config(), start, end, radius))
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of capsule bundles.
+pub trait CapsuleBundle {
+    fn capsule(config: &ShapeConfig, start: Vec2, end: Vec2, radius: f32) -> Self;
+}
+
+impl CapsuleBundle for ShapeBundle<CapsuleComponent> {
+    fn capsule(config: &ShapeConfig, start: Vec2, end: Vec2, radius: f32) -> Self {
+        Self::new(config, CapsuleComponent::new(config, start, end, radius))
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of capsule entities.
+pub trait CapsuleSpawner<'w> {
+    fn capsule(&mut self, start: Vec2, end: Vec2, radius: f32) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> CapsuleSpawner<'w> for T {
+    fn capsule(&mut self, start: Vec2, end: Vec2, radius: f32) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::capsule(self.config(), start, end, radius))
+    }
+}
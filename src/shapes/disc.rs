@@ -1,21 +1,33 @@
+use std::f32::consts::TAU;
+
 use bevy::{
     prelude::*,
     reflect::Reflect,
-    render::render_resource::{ShaderRef, ShaderType},
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
 };
 use wgpu::vertex_attr_array;
 
 use crate::{
     prelude::*,
-    render::{Flags, ShapeComponent, ShapeData, DISC_HANDLE},
+    render::{
+        arc_path, sector_path, stroke_offset, tint_color, Flags, ShapeComponent, ShapeData,
+        SvgElement, DISC_HANDLE,
+    },
 };
 
 /// Component containing the data for drawing a disc.
 ///
-/// Discs include both arcs and circles
-#[derive(Component, Reflect)]
+/// Discs include arcs, circles and annular sectors
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct DiscComponent {
     pub alignment: Alignment,
+    /// Placement of the outline relative to the edge of the shape.
+    pub stroke_alignment: StrokeAlignment,
     /// Cap type for an arc, only supports None or Round
     pub cap: Cap,
     /// Whether to treat this disc like an arc
@@ -23,10 +35,23 @@ pub struct DiscComponent {
 
     /// External radius of the disc
     pub radius: f32,
+    /// Internal radius of an annular sector, when greater than 0 the area between the two radii is filled
+    /// regardless of thickness
+    pub inner_radius: f32,
     /// Starting angle for an arc
     pub start_angle: f32,
     /// Ending angle for an arc
     pub end_angle: f32,
+    /// Pattern of dashes along the circumference.
+    pub dash_pattern: Option<DashPattern>,
+    /// Thickness at the end of an arc, see [`ShapeConfig::thickness_end`].
+    pub thickness_end: Option<f32>,
+    /// Gradient blending from the fill color, see [`ShapeConfig::gradient`].
+    pub gradient: Option<Gradient>,
+    /// Number of times the texture repeats across the shape, see [`ShapeConfig::texture_tiling`].
+    pub texture_tiling: Vec2,
+    /// Offset of the texture across the shape, see [`ShapeConfig::texture_offset`].
+    pub texture_offset: Vec2,
 }
 
 impl DiscComponent {
@@ -40,12 +65,19 @@ impl DiscComponent {
     ) -> Self {
         Self {
             alignment: config.alignment,
+            stroke_alignment: config.stroke_alignment,
             cap,
             arc,
 
             radius,
+            inner_radius: 0.0,
             start_angle,
             end_angle,
+            dash_pattern: config.dash_pattern,
+            thickness_end: config.thickness_end,
+            gradient: config.without_alpha().faded_gradient(),
+            texture_tiling: config.texture_tiling,
+            texture_offset: config.texture_offset,
         }
     }
 
@@ -56,14 +88,55 @@ impl DiscComponent {
     pub fn arc(config: &ShapeConfig, radius: f32, start_angle: f32, end_angle: f32) -> Self {
         Self::new(config, radius, true, start_angle, end_angle, config.cap)
     }
+
+    pub fn sector(
+        config: &ShapeConfig,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> Self {
+        Self {
+            inner_radius,
+            ..Self::arc(config, outer_radius, start_angle, end_angle)
+        }
+    }
 }
 
 impl ShapeComponent for DiscComponent {
     type Data = DiscData;
 
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let radius = Vec2::splat(self.radius);
+        shape_aabb(-radius, radius, self.alignment, fill, |thickness| {
+            let thickness = thickness.max(self.thickness_end.unwrap_or(0.0));
+            stroke_offset(self.stroke_alignment, thickness) + thickness / 2.0
+        })
+    }
+
+    fn contains(&self, point: Vec2, thickness: Option<f32>) -> bool {
+        // Arc angles start from y up and increase clockwise
+        let sweep = (self.end_angle - self.start_angle).abs();
+        if self.arc && sweep < TAU {
+            let start = self.start_angle.min(self.end_angle);
+            if (point.x.atan2(point.y) - start).rem_euclid(TAU) > sweep {
+                return false;
+            }
+        }
+
+        let dist = point.length();
+        match thickness {
+            Some(thickness) => {
+                let center = self.radius + stroke_offset(self.stroke_alignment, thickness);
+                (dist - center).abs() <= thickness / 2.0
+            }
+            None => dist <= self.radius && dist >= self.inner_radius,
+        }
+    }
+
     fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> DiscData {
         let mut flags = Flags(0);
-        let thickness = match fill.ty {
+        let mut thickness = match fill.ty {
             FillType::Stroke(thickness, thickness_type) => {
                 flags.set_thickness_type(thickness_type);
                 flags.set_hollow(1);
@@ -72,8 +145,12 @@ impl ShapeComponent for DiscComponent {
             FillType::Fill => 1.0,
         };
         flags.set_alignment(self.alignment);
+        flags.set_stroke_alignment(self.stroke_alignment);
         flags.set_cap(self.cap);
         flags.set_arc(self.arc as u32);
+        let (gradient_type, gradient_color, gradient) = gradient_data(self.gradient);
+        flags.set_gradient(gradient_type);
+        let stroke_color = stroke_data(fill.outline(), &mut flags, &mut thickness);
 
         DiscData {
             transform: tf.compute_matrix().to_cols_array_2d(),
@@ -85,8 +162,14 @@ impl ShapeComponent for DiscComponent {
             radius: self.radius,
             start_angle: self.start_angle,
             end_angle: self.end_angle,
-
-            padding: default(),
+            inner_radius: self.inner_radius,
+            dash: dash_data(self.dash_pattern),
+            thickness_end: self.thickness_end.unwrap_or(thickness),
+            gradient_color,
+            gradient,
+            texture_uv: texture_data(self.texture_tiling, self.texture_offset),
+            stroke_color,
+            user_data: fill.user_data,
         }
     }
 }
@@ -95,12 +178,19 @@ impl Default for DiscComponent {
     fn default() -> Self {
         Self {
             alignment: default(),
+            stroke_alignment: default(),
             cap: Cap::None,
             arc: false,
 
             radius: 1.0,
+            inner_radius: 0.0,
             start_angle: 0.0,
             end_angle: 0.0,
+            dash_pattern: None,
+            thickness_end: None,
+            gradient: None,
+            texture_tiling: Vec2::ONE,
+            texture_offset: Vec2::ZERO,
         }
     }
 }
@@ -118,8 +208,14 @@ pub struct DiscData {
     radius: f32,
     start_angle: f32,
     end_angle: f32,
-
-    padding: [f32; 3],
+    inner_radius: f32,
+    dash: Vec3,
+    thickness_end: f32,
+    gradient_color: Vec4,
+    gradient: Vec4,
+    texture_uv: Vec4,
+    stroke_color: Vec4,
+    user_data: Vec4,
 }
 
 impl DiscData {
@@ -127,22 +223,33 @@ impl DiscData {
         let mut flags = Flags(0);
         flags.set_thickness_type(config.thickness_type);
         flags.set_alignment(config.alignment);
+        flags.set_stroke_alignment(config.stroke_alignment);
         flags.set_hollow(config.hollow as u32);
         flags.set_arc(false as u32);
+        let (gradient_type, gradient_color, gradient) = gradient_data(config.faded_gradient());
+        flags.set_gradient(gradient_type);
+        let mut thickness = config.thickness;
+        let stroke_color = stroke_data(config.outline(), &mut flags, &mut thickness);
 
         DiscData {
             transform: config.transform.compute_matrix().to_cols_array_2d(),
 
-            color: config.color.as_linear_rgba_f32(),
-            thickness: config.thickness,
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness,
             flags: flags.0,
 
             radius,
 
             start_angle: 0.0,
             end_angle: 0.0,
-
-            padding: default(),
+            inner_radius: 0.0,
+            dash: dash_data(config.dash_pattern),
+            thickness_end: config.thickness,
+            gradient_color,
+            gradient,
+            texture_uv: texture_data(config.texture_tiling, config.texture_offset),
+            stroke_color,
+            user_data: config.user_data,
         }
     }
 
@@ -150,27 +257,65 @@ impl DiscData {
         let mut flags = Flags(0);
         flags.set_thickness_type(config.thickness_type);
         flags.set_alignment(config.alignment);
+        flags.set_stroke_alignment(config.stroke_alignment);
         flags.set_hollow(config.hollow as u32);
         flags.set_cap(config.cap);
         flags.set_arc(true as u32);
+        let (gradient_type, gradient_color, gradient) = gradient_data(config.faded_gradient());
+        flags.set_gradient(gradient_type);
+        let mut thickness = config.thickness;
+        let stroke_color = stroke_data(config.outline(), &mut flags, &mut thickness);
 
         DiscData {
             transform: config.transform.compute_matrix().to_cols_array_2d(),
 
-            color: config.color.as_linear_rgba_f32(),
-            thickness: config.thickness,
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness,
             flags: flags.0,
 
             radius,
 
             start_angle,
             end_angle,
+            inner_radius: 0.0,
+            dash: dash_data(config.dash_pattern),
+            thickness_end: config.thickness_end.unwrap_or(config.thickness),
+            gradient_color,
+            gradient,
+            texture_uv: texture_data(config.texture_tiling, config.texture_offset),
+            stroke_color,
+            user_data: config.user_data,
+        }
+    }
+
+    /// Circle lying flat in the plane with the given normal relative to the config's transform, ignoring alignment.
+    pub fn circle_oriented(config: &ShapeConfig, radius: f32, normal: Vec3) -> DiscData {
+        DiscData::circle(&oriented_config(config, normal), radius)
+    }
 
-            padding: default(),
+    pub fn sector(
+        config: &ShapeConfig,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> DiscData {
+        DiscData {
+            inner_radius,
+            ..DiscData::arc(config, outer_radius, start_angle, end_angle)
         }
     }
 }
 
+/// Rotate a config such that shapes drawn in it's xy plane face along `normal` and disable billboarding.
+fn oriented_config(config: &ShapeConfig, normal: Vec3) -> ShapeConfig {
+    let mut config = config.clone();
+    let normal = normal.try_normalize().unwrap_or(Vec3::Z);
+    config.rotate(Quat::from_rotation_arc(Vec3::Z, normal));
+    config.alignment = Alignment::Flat;
+    config
+}
+
 impl ShapeData for DiscData {
     type Component = DiscComponent;
 
@@ -187,6 +332,14 @@ impl ShapeData for DiscData {
             7 => Float32,
             8 => Float32,
             9 => Float32,
+            10 => Float32,
+            11 => Float32x3,
+            12 => Float32,
+            13 => Float32x4,
+            14 => Float32x4,
+            15 => Float32x4,
+            16 => Float32x4,
+            17 => Float32x4,
         ]
         .to_vec()
     }
@@ -198,29 +351,149 @@ impl ShapeData for DiscData {
     fn transform(&self) -> Mat4 {
         Mat4::from_cols_array_2d(&self.transform)
     }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+        self.gradient_color = tint_color(self.gradient_color.into(), color).into();
+        self.stroke_color = tint_color(self.stroke_color.into(), color).into();
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+
+    fn shadow(&self, shadow: &Shadow, thickness_type: ThicknessType) -> Option<Self> {
+        if Flags(self.flags).hollow() > 0 || Flags(self.flags).arc() > 0 || self.inner_radius > 0.0
+        {
+            return None;
+        }
+        Some(Self {
+            transform: shadow_transform(self.transform, shadow.offset),
+            color: shadow.color.as_linear_rgba_f32(),
+            thickness: shadow.blur,
+            flags: shadow_flags(self.flags, thickness_type),
+            ..*self
+        })
+    }
+
+    fn glow(&self, glow: &Glow, thickness_type: ThicknessType) -> Option<Self> {
+        if Flags(self.flags).hollow() > 0 || Flags(self.flags).arc() > 0 || self.inner_radius > 0.0
+        {
+            return None;
+        }
+        Some(Self {
+            color: glow.color.as_linear_rgba_f32(),
+            thickness: glow.radius,
+            flags: glow_flags(self.flags, thickness_type),
+            gradient: Vec4::new(glow.falloff, 0.0, 0.0, 0.0),
+            ..*self
+        })
+    }
+
+    fn svg(&self) -> Option<SvgElement> {
+        let flags = Flags(self.flags);
+        if flags.shadow() > 0 || flags.glow() > 0 {
+            return None;
+        }
+
+        let is_circle = flags.arc() == 0 || (self.end_angle - self.start_angle).abs() >= TAU;
+        let element = if flags.hollow() > 0 {
+            let radius = self.radius + stroke_offset(flags.stroke_alignment(), self.thickness);
+            let element = if is_circle {
+                SvgElement::new("circle").attr("r", radius)
+            } else {
+                SvgElement::new("path")
+                    .attr("d", arc_path(radius, self.start_angle, self.end_angle))
+                    .cap(flags.cap())
+            };
+            element.stroke(self.color, self.thickness).dash(self.dash)
+        } else if is_circle && self.inner_radius <= 0.0 {
+            SvgElement::new("circle")
+                .attr("r", self.radius)
+                .fill(self.color)
+        } else {
+            let (start_angle, end_angle) = if is_circle {
+                (0.0, TAU - f32::EPSILON)
+            } else {
+                (self.start_angle, self.end_angle)
+            };
+            let path = sector_path(self.inner_radius, self.radius, start_angle, end_angle);
+            SvgElement::new("path").attr("d", path).fill(self.color)
+        };
+        Some(element.transform(self.transform()))
+    }
 }
 
 /// Extension trait for [`ShapePainter`] to enable it to draw disc type shapes.
 pub trait DiscPainter {
     fn circle(&mut self, radius: f32) -> &mut Self;
+    /// Draw a circle at each position relative to the painter's transform, see [`ShapePainter::send_many`].
+    fn circles(&mut self, radius: f32, positions: impl IntoIterator<Item = Vec3>) -> &mut Self;
+    /// Draw a circle lying flat in the plane with the given normal, regardless of the painter's alignment.
+    fn circle_oriented(&mut self, radius: f32, normal: Vec3) -> &mut Self;
     fn arc(&mut self, radius: f32, start_angle: f32, end_angle: f32) -> &mut Self;
+    fn sector(
+        &mut self,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> &mut Self;
 }
 
-impl<'w, 's> DiscPainter for ShapePainter<'w, 's> {
+impl<P: ShapeSender> DiscPainter for P {
     fn circle(&mut self, radius: f32) -> &mut Self {
         self.send(DiscData::circle(self.config(), radius))
     }
 
+    fn circles(&mut self, radius: f32, positions: impl IntoIterator<Item = Vec3>) -> &mut Self {
+        self.send_many(DiscData::circle(self.config(), radius), positions)
+    }
+
+    fn circle_oriented(&mut self, radius: f32, normal: Vec3) -> &mut Self {
+        self.send(DiscData::circle_oriented(self.config(), radius, normal))
+    }
+
     fn arc(&mut self, radius: f32, start_angle: f32, end_angle: f32) -> &mut Self {
         self.send(DiscData::arc(self.config(), radius, start_angle, end_angle));
         self
     }
+
+    fn sector(
+        &mut self,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> &mut Self {
+        self.send(DiscData::sector(
+            self.config(),
+            inner_radius,
+            outer_radius,
+            start_angle,
+            end_angle,
+        ))
+    }
 }
 
 /// Extension trait for [`ShapeBundle`] to enable creation of bundles for disc type shapes.
 pub trait DiscBundle {
     fn circle(config: &ShapeConfig, radius: f32) -> Self;
+    fn circle_oriented(config: &ShapeConfig, radius: f32, normal: Vec3) -> Self;
     fn arc(config: &ShapeConfig, radius: f32, start_angle: f32, end_angle: f32) -> Self;
+    fn sector(
+        config: &ShapeConfig,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> Self;
 }
 
 impl DiscBundle for ShapeBundle<DiscComponent> {
@@ -228,18 +501,43 @@ impl DiscBundle for ShapeBundle<DiscComponent> {
         Self::new(config, DiscComponent::circle(config, radius))
     }
 
+    fn circle_oriented(config: &ShapeConfig, radius: f32, normal: Vec3) -> Self {
+        Self::circle(&oriented_config(config, normal), radius)
+    }
+
     fn arc(config: &ShapeConfig, radius: f32, start_angle: f32, end_angle: f32) -> Self {
         Self::new(
             config,
             DiscComponent::arc(config, radius, start_angle, end_angle),
         )
     }
+
+    fn sector(
+        config: &ShapeConfig,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> Self {
+        Self::new(
+            config,
+            DiscComponent::sector(config, inner_radius, outer_radius, start_angle, end_angle),
+        )
+    }
 }
 
 /// Extension trait for [`ShapeSpawner`] to enable spawning of entities for disc type shapes.
 pub trait DiscSpawner<'w> {
     fn circle(&mut self, radius: f32) -> ShapeEntityCommands;
+    fn circle_oriented(&mut self, radius: f32, normal: Vec3) -> ShapeEntityCommands;
     fn arc(&mut self, radius: f32, start_angle: f32, end_angle: f32) -> ShapeEntityCommands;
+    fn sector(
+        &mut self,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> ShapeEntityCommands;
 }
 
 impl<'w, T: ShapeSpawner<'w>> DiscSpawner<'w> for T {
@@ -247,6 +545,10 @@ impl<'w, T: ShapeSpawner<'w>> DiscSpawner<'w> for T {
         self.spawn_shape(ShapeBundle::circle(self.config(), radius))
     }
 
+    fn circle_oriented(&mut self, radius: f32, normal: Vec3) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::circle_oriented(self.config(), radius, normal))
+    }
+
     fn arc(&mut self, radius: f32, start_angle: f32, end_angle: f32) -> ShapeEntityCommands {
         self.spawn_shape(ShapeBundle::arc(
             self.config(),
@@ -255,4 +557,78 @@ impl<'w, T: ShapeSpawner<'w>> DiscSpawner<'w> for T {
             end_angle,
         ))
     }
+    fn sector(
+        &mut self,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::sector(
+            self.config(),
+            inner_radius,
+            outer_radius,
+            start_angle,
+            end_angle,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn filled() {
+        let disc = DiscComponent::default();
+        assert!(disc.contains(Vec2::new(0.5, 0.0), None));
+        assert!(disc.contains(Vec2::new(0.0, 1.0), None));
+        assert!(!disc.contains(Vec2::new(1.01, 0.0), None));
+
+        let annulus = DiscComponent {
+            inner_radius: 0.5,
+            ..default()
+        };
+        assert!(!annulus.contains(Vec2::new(0.25, 0.0), None));
+        assert!(annulus.contains(Vec2::new(0.75, 0.0), None));
+    }
+
+    #[test]
+    fn hollow() {
+        let inside = DiscComponent {
+            stroke_alignment: StrokeAlignment::Inside,
+            ..default()
+        };
+        assert!(inside.contains(Vec2::new(0.0, 0.9), Some(0.2)));
+        assert!(!inside.contains(Vec2::new(0.0, 0.5), Some(0.2)));
+        assert!(!inside.contains(Vec2::new(0.0, 1.05), Some(0.2)));
+
+        let center = DiscComponent {
+            stroke_alignment: StrokeAlignment::Center,
+            ..default()
+        };
+        assert!(center.contains(Vec2::new(0.0, 1.05), Some(0.2)));
+
+        let outside = DiscComponent {
+            stroke_alignment: StrokeAlignment::Outside,
+            ..default()
+        };
+        assert!(outside.contains(Vec2::new(0.0, 1.1), Some(0.2)));
+        assert!(!outside.contains(Vec2::new(0.0, 0.9), Some(0.2)));
+    }
+
+    #[test]
+    fn arc() {
+        let arc = DiscComponent {
+            arc: true,
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            ..default()
+        };
+        assert!(arc.contains(Vec2::new(0.5, 0.5), None));
+        assert!(!arc.contains(Vec2::new(-0.5, 0.5), None));
+        assert!(!arc.contains(Vec2::new(0.5, -0.5), None));
+    }
 }
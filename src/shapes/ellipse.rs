@@ -0,0 +1,184 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, ELLIPSE_HANDLE},
+};
+
+/// Component containing the data for drawing an ellipse.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct EllipseComponent {
+    pub alignment: Alignment,
+
+    /// Radius of the ellipse along the x and y axis.
+    pub radii: Vec2,
+}
+
+impl EllipseComponent {
+    pub fn new(config: &ShapeConfig, radii: Vec2) -> Self {
+        Self {
+            alignment: config.alignment,
+
+            radii,
+        }
+    }
+}
+
+impl ShapeComponent for EllipseComponent {
+    type Data = EllipseData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let radii = self.radii.abs();
+        shape_aabb(-radii, radii, self.alignment, fill, |thickness| thickness)
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> EllipseData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+
+        EllipseData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            radii: self.radii.into(),
+        }
+    }
+}
+
+impl Default for EllipseComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+
+            radii: Vec2::ONE,
+        }
+    }
+}
+
+/// Raw data sent to the ellipse shader to draw an ellipse
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct EllipseData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    radii: [f32; 2],
+}
+
+impl EllipseData {
+    pub fn new(config: &ShapeConfig, radii: Vec2) -> Self {
+        let mut flags = Flags(0);
+        flags.set_alignment(config.alignment);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_hollow(config.hollow as u32);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            radii: radii.into(),
+        }
+    }
+}
+
+impl ShapeData for EllipseData {
+    type Component = EllipseComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32x2,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        ELLIPSE_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw ellipses.
+pub trait EllipsePainter {
+    fn ellipse(&mut self, radii: Vec2) -> &mut Self;
+}
+
+impl<P: ShapeSender> EllipsePainter for P {
+    fn ellipse(&mut self, radii: Vec2) -> &mut Self {
+        self.send(EllipseData::new(self.config(), radii))
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of ellipse bundles.
+pub trait EllipseBundle {
+    fn ellipse(config: &ShapeConfig, radii: Vec2) -> Self;
+}
+
+impl EllipseBundle for ShapeBundle<EllipseComponent> {
+    fn ellipse(config: &ShapeConfig, radii: Vec2) -> Self {
+        Self::new(config, EllipseComponent::new(config, radii))
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of ellipse entities.
+pub trait EllipseSpawner<'w> {
+    fn ellipse(&mut self, radii: Vec2) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> EllipseSpawner<'w> for T {
+    fn ellipse(&mut self, radii: Vec2) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::ellipse(self.config(), radii))
+    }
+}
@@ -0,0 +1,218 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, GRID_HANDLE},
+};
+
+/// Component containing the data for drawing a grid of lines.
+///
+/// The whole grid is drawn as a single instance centered on it's transform, with lines of the current thickness
+/// along the edges of every cell.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridComponent {
+    pub alignment: Alignment,
+
+    /// Number of cells along the x and y axis.
+    pub cells: UVec2,
+    /// Size of a single cell.
+    pub cell_size: Vec2,
+}
+
+impl GridComponent {
+    pub fn new(config: &ShapeConfig, cells: UVec2, cell_size: Vec2) -> Self {
+        Self {
+            alignment: config.alignment,
+
+            cells,
+            cell_size,
+        }
+    }
+}
+
+impl Default for GridComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+
+            cells: UVec2::ONE,
+            cell_size: Vec2::ONE,
+        }
+    }
+}
+
+impl ShapeComponent for GridComponent {
+    type Data = GridData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let half_size = (self.cells.as_vec2() * self.cell_size / 2.0)
+            .abs()
+            .extend(0.0);
+        stroke_aabb(-half_size, half_size, self.alignment, fill, |thickness| {
+            thickness / 2.0
+        })
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> GridData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+
+        GridData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            cells: self.cells.into(),
+            cell_size: self.cell_size.into(),
+
+            padding: default(),
+        }
+    }
+}
+
+/// Raw data sent to the grid shader to draw a grid
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct GridData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    cells: [u32; 2],
+    cell_size: [f32; 2],
+
+    padding: [f32; 2],
+}
+
+impl GridData {
+    pub fn new(config: &ShapeConfig, cells: UVec2, cell_size: Vec2) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            cells: cells.into(),
+            cell_size: cell_size.into(),
+
+            padding: default(),
+        }
+    }
+}
+
+impl ShapeData for GridData {
+    type Component = GridComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Uint32x2,
+            8 => Float32x2,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        GRID_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw grids.
+pub trait GridPainter {
+    fn grid(&mut self, cells_x: u32, cells_y: u32, cell_size: Vec2) -> &mut Self;
+}
+
+impl<P: ShapeSender> GridPainter for P {
+    fn grid(&mut self, cells_x: u32, cells_y: u32, cell_size: Vec2) -> &mut Self {
+        self.send(GridData::new(
+            self.config(),
+            UVec2::new(cells_x, cells_y),
+            cell_size,
+        ))
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of grid bundles.
+pub trait GridBundle {
+    fn grid(config: &ShapeConfig, cells_x: u32, cells_y: u32, cell_size: Vec2) -> Self;
+}
+
+impl GridBundle for ShapeBundle<GridComponent> {
+    fn grid(config: &ShapeConfig, cells_x: u32, cells_y: u32, cell_size: Vec2) -> Self {
+        let mut bundle = Self::new(
+            config,
+            GridComponent::new(config, UVec2::new(cells_x, cells_y), cell_size),
+        );
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of grid entities.
+pub trait GridSpawner<'w> {
+    fn grid(&mut self, cells_x: u32, cells_y: u32, cell_size: Vec2) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> GridSpawner<'w> for T {
+    fn grid(&mut self, cells_x: u32, cells_y: u32, cell_size: Vec2) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::grid(
+            self.config(),
+            cells_x,
+            cells_y,
+            cell_size,
+        ))
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::prelude::*;
+
+use super::morph::lerp_color;
+
+/// Layout and coloring of a grid of cells drawn by [`HeatmapPainter`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heatmap {
+    /// Number of cells along the x and y axis.
+    pub cells: UVec2,
+    /// Size of a single cell.
+    pub cell_size: Vec2,
+    /// Values mapped to the first and last colors of the palette, values outside of the range are clamped.
+    pub range: Vec2,
+    /// Colors evenly spaced across the range, values between them blend between their colors.
+    pub palette: Vec<Color>,
+}
+
+impl Heatmap {
+    pub fn new(cells: UVec2, cell_size: Vec2) -> Self {
+        Self {
+            cells,
+            cell_size,
+            ..default()
+        }
+    }
+
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.range = Vec2::new(min, max);
+        self
+    }
+
+    pub fn with_palette(mut self, palette: impl Into<Vec<Color>>) -> Self {
+        self.palette = palette.into();
+        self
+    }
+
+    /// Returns the color of the palette for the given value.
+    pub fn color(&self, value: f32) -> Color {
+        let Some(&last) = self.palette.last() else {
+            return Color::NONE;
+        };
+        let t = ((value - self.range.x) / (self.range.y - self.range.x)).clamp(0.0, 1.0);
+        if !t.is_finite() {
+            return self.palette[0];
+        }
+        let position = t * (self.palette.len() - 1) as f32;
+        let index = position.floor() as usize;
+        match self.palette.get(index + 1) {
+            Some(&next) => lerp_color(self.palette[index], next, position.fract()),
+            None => last,
+        }
+    }
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Self {
+            cells: UVec2::ONE,
+            cell_size: Vec2::ONE,
+            range: Vec2::new(0.0, 1.0),
+            palette: vec![Color::MIDNIGHT_BLUE, Color::TEAL, Color::YELLOW, Color::RED],
+        }
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw heatmaps of colored cells.
+pub trait HeatmapPainter {
+    /// Draw a rectangle colored by the heatmap's palette for each value, centered on the painter's transform.
+    ///
+    /// Values are read row by row starting from the bottom left cell, cells whose value is missing or NaN are left
+    /// empty. Every cell is a rectangle of the same instanced shape, so the whole heatmap is drawn in a single batch.
+    /// Cells use the painter's config other than their color and anchor, local anti-aliasing is disabled so that
+    /// neighbouring cells meet without seams.
+    fn heatmap(&mut self, heatmap: &Heatmap, values: &[f32]) -> &mut Self;
+}
+
+impl<P: ShapeSender> HeatmapPainter for P {
+    fn heatmap(&mut self, heatmap: &Heatmap, values: &[f32]) -> &mut Self {
+        let mut config = self.config().clone();
+        config.anchor = Anchor::Center;
+        config.disable_laa = true;
+        let base = config.transform;
+        let corner = -heatmap.cells.as_vec2() * heatmap.cell_size / 2.0;
+
+        let cells =
+            (0..heatmap.cells.y).flat_map(|y| (0..heatmap.cells.x).map(move |x| UVec2::new(x, y)));
+        for (cell, &value) in cells.zip(values) {
+            if value.is_nan() {
+                continue;
+            }
+            let center = corner + (cell.as_vec2() + 0.5) * heatmap.cell_size;
+            config.transform = base * Transform::from_translation(center.extend(0.0));
+            config.color = heatmap.color(value);
+            self.send_with_config(&config, RectData::new(&config, heatmap.cell_size));
+        }
+        self
+    }
+}
@@ -0,0 +1,214 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+const SQRT_3: f32 = 1.732_050_8;
+
+/// Defines which way the corners of a hexagon point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum HexOrientation {
+    /// Flat edges at the top and bottom, columns of hexagons are offset vertically.
+    Flat,
+    /// Corners at the top and bottom, rows of hexagons are offset horizontally.
+    #[default]
+    Pointy,
+}
+
+impl HexOrientation {
+    /// Rotation around the z axis from a regular polygon, which has it's first corner pointing up.
+    fn angle(self) -> f32 {
+        match self {
+            HexOrientation::Flat => PI / 6.0,
+            HexOrientation::Pointy => 0.0,
+        }
+    }
+}
+
+/// Describes a grid of hexagons addressed by axial coordinates, with `q` along x and `r` along y of an [`IVec2`].
+///
+/// Cells are laid out in the xy plane with the cell `(0, 0)` centered on the origin and `r` increasing upwards,
+/// see [`HexPainter::hex_grid`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct HexLayout {
+    pub orientation: HexOrientation,
+    /// Radius from the center of a cell to the tip of a corner.
+    pub radius: f32,
+}
+
+impl HexLayout {
+    pub fn new(orientation: HexOrientation, radius: f32) -> Self {
+        Self {
+            orientation,
+            radius,
+        }
+    }
+
+    /// Returns the center of the cell at the given axial coordinates.
+    pub fn center(&self, cell: IVec2) -> Vec2 {
+        let cell = cell.as_vec2();
+        let center = match self.orientation {
+            HexOrientation::Flat => {
+                Vec2::new(1.5 * cell.x, SQRT_3 / 2.0 * cell.x + SQRT_3 * cell.y)
+            }
+            HexOrientation::Pointy => {
+                Vec2::new(SQRT_3 * cell.x + SQRT_3 / 2.0 * cell.y, 1.5 * cell.y)
+            }
+        };
+        center * self.radius
+    }
+
+    /// Returns the axial coordinates of the cell containing the given point.
+    pub fn cell(&self, point: Vec2) -> IVec2 {
+        let point = point / self.radius;
+        let (q, r) = match self.orientation {
+            HexOrientation::Flat => (
+                2.0 / 3.0 * point.x,
+                -1.0 / 3.0 * point.x + SQRT_3 / 3.0 * point.y,
+            ),
+            HexOrientation::Pointy => (
+                SQRT_3 / 3.0 * point.x - 1.0 / 3.0 * point.y,
+                2.0 / 3.0 * point.y,
+            ),
+        };
+
+        // Round in cube coordinates, where q + r + s = 0, fixing up the component that moved the most
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        IVec2::new(rq as i32, rr as i32)
+    }
+
+    /// Returns the number of steps between two cells.
+    pub fn distance(a: IVec2, b: IVec2) -> i32 {
+        let d = a - b;
+        (d.x.abs() + d.y.abs() + (d.x + d.y).abs()) / 2
+    }
+
+    /// Returns the cells within `distance` steps of `center`, including `center` itself.
+    pub fn range(center: IVec2, distance: i32) -> impl Iterator<Item = IVec2> {
+        let distance = distance.max(0);
+        (-distance..=distance).flat_map(move |q| {
+            let min = (-distance).max(-q - distance);
+            let max = distance.min(-q + distance);
+            (min..=max).map(move |r| center + IVec2::new(q, r))
+        })
+    }
+}
+
+impl Default for HexLayout {
+    fn default() -> Self {
+        Self::new(HexOrientation::Pointy, 1.0)
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw hexagons and hexagonal grids.
+///
+/// Hexagons are regular polygons with their corners rounded by the painter's roundness.
+pub trait HexPainter {
+    /// Draw a hexagon with the given radius to the tip of a corner.
+    fn hex(&mut self, radius: f32, orientation: HexOrientation) -> &mut Self;
+    /// Draw a hexagon filling each of the cells relative to the painter's transform.
+    ///
+    /// Every cell is a copy of the same instanced shape, see [`ShapePainter::send_many`], so large regions are cheap
+    /// to draw. Hollow hexagons share their edges with their neighbours, reduce the radius of the layout to leave gaps.
+    fn hex_grid(&mut self, layout: HexLayout, cells: impl IntoIterator<Item = IVec2>) -> &mut Self;
+}
+
+/// Returns the data of a hexagon at the painter's transform, rotated to the given orientation.
+fn hex_data(config: &ShapeConfig, radius: f32, orientation: HexOrientation) -> NgonData {
+    let mut config = config.clone();
+    config.rotate_z(orientation.angle());
+    NgonData::new(&config, 6.0, radius)
+}
+
+impl<P: ShapeSender> HexPainter for P {
+    fn hex(&mut self, radius: f32, orientation: HexOrientation) -> &mut Self {
+        self.send(hex_data(self.config(), radius, orientation))
+    }
+
+    fn hex_grid(&mut self, layout: HexLayout, cells: impl IntoIterator<Item = IVec2>) -> &mut Self {
+        let data = hex_data(self.config(), layout.radius, layout.orientation);
+        self.send_many(
+            data,
+            cells
+                .into_iter()
+                .map(|cell| layout.center(cell).extend(0.0)),
+        )
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of hexagon entities.
+pub trait HexSpawner<'w> {
+    fn hex(&mut self, radius: f32, orientation: HexOrientation) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> HexSpawner<'w> for T {
+    fn hex(&mut self, radius: f32, orientation: HexOrientation) -> ShapeEntityCommands {
+        let mut config = self.config().clone();
+        config.rotate_z(orientation.angle());
+        self.spawn_shape(ShapeBundle::ngon(&config, 6.0, radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::*;
+
+    const ORIENTATIONS: [HexOrientation; 2] = [HexOrientation::Flat, HexOrientation::Pointy];
+
+    #[test]
+    fn round_trip() {
+        for orientation in ORIENTATIONS {
+            let layout = HexLayout::new(orientation, 2.0);
+            let apothem = layout.radius * SQRT_3 / 2.0;
+            for cell in HexLayout::range(IVec2::ZERO, 3) {
+                let center = layout.center(cell);
+                assert_eq!(layout.cell(center), cell);
+
+                // Any point within the inscribed circle of a cell belongs to it
+                for i in 0..12 {
+                    let offset = Vec2::from_angle(i as f32 * TAU / 12.0) * apothem * 0.8;
+                    assert_eq!(layout.cell(center + offset), cell);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn neighbours() {
+        for orientation in ORIENTATIONS {
+            let layout = HexLayout::new(orientation, 2.0);
+            for neighbour in [IVec2::new(1, 0), IVec2::new(0, 1), IVec2::new(-1, 1)] {
+                assert_eq!(HexLayout::distance(IVec2::ZERO, neighbour), 1);
+
+                // Neighbouring cells share an edge, so their centers are two apothems apart
+                let center = layout.center(neighbour);
+                assert!((center.length() - layout.radius * SQRT_3).abs() < 1e-5);
+                assert_eq!(layout.cell(center * 0.45), IVec2::ZERO);
+                assert_eq!(layout.cell(center * 0.55), neighbour);
+            }
+        }
+    }
+
+    #[test]
+    fn range() {
+        for distance in 0..4 {
+            let cells: Vec<_> = HexLayout::range(IVec2::new(2, -1), distance).collect();
+            assert_eq!(cells.len() as i32, 1 + 3 * distance * (distance + 1));
+            assert!(cells
+                .iter()
+                .all(|&cell| HexLayout::distance(IVec2::new(2, -1), cell) <= distance));
+        }
+    }
+}
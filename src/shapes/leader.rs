@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// Path taken by a leader line from it's anchor to it's label, see [`LeaderPainter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LeaderStyle {
+    /// A single straight line from the anchor to the label.
+    Straight,
+    /// A diagonal line at 45 degrees on screen from the anchor followed by a horizontal shelf into the label.
+    #[default]
+    Elbow,
+    /// A curve leaving the anchor towards the elbow and arriving at the label horizontally.
+    Curved,
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw leader lines connecting 3D objects to HUD labels.
+///
+/// Leader lines are drawn in the plane facing the camera at the depth of their anchor so that their label end stays
+/// on the same screen position as the camera moves. They are drawn with the painter's color, thickness and cap,
+/// [`ThicknessType::Pixels`] keeps their width constant on screen. The painter's config is left unchanged.
+pub trait LeaderPainter {
+    /// Draw a leader line from `anchor` in world space to `label` in viewport coordinates of `camera`.
+    ///
+    /// Nothing is drawn when the anchor is not in front of the camera.
+    fn leader_line(
+        &mut self,
+        camera: (&Camera, &GlobalTransform),
+        anchor: Vec3,
+        label: Vec2,
+        style: LeaderStyle,
+    ) -> &mut Self;
+}
+
+impl<'w, 's> LeaderPainter for ShapePainter<'w, 's> {
+    fn leader_line(
+        &mut self,
+        (camera, camera_tf): (&Camera, &GlobalTransform),
+        anchor: Vec3,
+        label: Vec2,
+        style: LeaderStyle,
+    ) -> &mut Self {
+        let Some(anchor_screen) = camera.world_to_viewport(camera_tf, anchor) else {
+            return self;
+        };
+
+        // Leave the anchor at 45 degrees, stopping short of the label if it is closer horizontally than vertically
+        let delta = label - anchor_screen;
+        let elbow = Vec2::new(
+            anchor_screen.x + delta.x.signum() * delta.x.abs().min(delta.y.abs()),
+            label.y,
+        );
+
+        // Project viewport points onto the plane facing the camera through the anchor
+        let rotation = camera_tf.compute_transform().rotation;
+        let forward = rotation * Vec3::NEG_Z;
+        let project = |point: Vec2| {
+            let ray = camera.viewport_to_world(camera_tf, point)?;
+            let distance = (anchor - ray.origin).dot(forward) / ray.direction.dot(forward);
+            let world = ray.get_point(distance);
+            Some((rotation.inverse() * (world - anchor)).truncate())
+        };
+        let (Some(elbow), Some(label)) = (project(elbow), project(label)) else {
+            return self;
+        };
+
+        self.with_children(|painter| {
+            painter.transform = Transform::from_translation(anchor).with_rotation(rotation);
+            painter.alignment = Alignment::Flat;
+            match style {
+                LeaderStyle::Straight => {
+                    painter.line(Vec3::ZERO, label.extend(0.0));
+                }
+                LeaderStyle::Elbow => {
+                    painter.polyline(&[Vec3::ZERO, elbow.extend(0.0), label.extend(0.0)]);
+                }
+                LeaderStyle::Curved => {
+                    #[cfg(feature = "bezier")]
+                    painter.bezier_quadratic(Vec2::ZERO, elbow, label);
+                    // Flatten the curve into a polyline when bézier curves are compiled out
+                    #[cfg(not(feature = "bezier"))]
+                    painter
+                        .begin_path()
+                        .move_to(Vec2::ZERO)
+                        .quad_to(elbow, label)
+                        .stroke();
+                }
+            }
+        })
+    }
+}
@@ -1,17 +1,24 @@
+use std::f32::consts::SQRT_2;
+
 use bevy::{
     prelude::*,
     reflect::Reflect,
-    render::render_resource::{ShaderRef, ShaderType},
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
 };
 use wgpu::vertex_attr_array;
 
 use crate::{
     prelude::*,
-    render::{Flags, ShapeComponent, ShapeData, LINE_HANDLE},
+    render::{tint_color, Flags, ShapeComponent, ShapeData, SvgElement, LINE_HANDLE},
 };
 
 /// Component containing the data for drawing a line.
 #[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct LineComponent {
     pub alignment: Alignment,
     pub cap: Cap,
@@ -20,6 +27,12 @@ pub struct LineComponent {
     pub start: Vec3,
     /// Position to draw the end of the line in world space relative to it's transform.
     pub end: Vec3,
+    /// Pattern of dashes along the line.
+    pub dash_pattern: Option<DashPattern>,
+    /// Thickness at the end of the line, see [`ShapeConfig::thickness_end`].
+    pub thickness_end: Option<f32>,
+    /// Color at the end of the line, when set the color of the line is interpolated from it's fill color to this value.
+    pub color_end: Option<Color>,
 }
 
 impl LineComponent {
@@ -30,6 +43,9 @@ impl LineComponent {
 
             start,
             end,
+            dash_pattern: config.dash_pattern,
+            thickness_end: config.thickness_end,
+            color_end: None,
         }
     }
 }
@@ -42,6 +58,9 @@ impl Default for LineComponent {
 
             start: default(),
             end: default(),
+            dash_pattern: None,
+            thickness_end: None,
+            color_end: None,
         }
     }
 }
@@ -49,6 +68,37 @@ impl Default for LineComponent {
 impl ShapeComponent for LineComponent {
     type Data = LineData;
 
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        // Caps extend past each end by half the thickness, billboarded lines turn around their direction
+        stroke_aabb(
+            self.start.min(self.end),
+            self.start.max(self.end),
+            Alignment::Flat,
+            fill,
+            |thickness| thickness.max(self.thickness_end.unwrap_or(0.0)) / 2.0 * SQRT_2,
+        )
+    }
+
+    fn contains(&self, point: Vec2, thickness: Option<f32>) -> bool {
+        let (start, end) = (self.start.truncate(), self.end.truncate());
+        let thickness = thickness.unwrap_or(1.0);
+        let along = end - start;
+        let t = (point - start).dot(along) / along.length_squared().max(f32::EPSILON);
+
+        match self.cap {
+            // Square caps extend the line by half its thickness at each end
+            Cap::None | Cap::Square => {
+                let extend = match self.cap {
+                    Cap::Square => thickness / 2.0 / along.length().max(f32::EPSILON),
+                    _ => 0.0,
+                };
+                let nearest = start + along * t;
+                (-extend..=1.0 + extend).contains(&t) && point.distance(nearest) <= thickness / 2.0
+            }
+            Cap::Round => point.distance(start + along * t.clamp(0.0, 1.0)) <= thickness / 2.0,
+        }
+    }
+
     fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> LineData {
         let mut flags = Flags(0);
         let thickness = match fill.ty {
@@ -71,6 +121,9 @@ impl ShapeComponent for LineComponent {
 
             start: self.start,
             end: self.end,
+            dash: dash_data(self.dash_pattern),
+            thickness_end: self.thickness_end.unwrap_or(thickness),
+            color_end: self.color_end.unwrap_or(fill.color).as_linear_rgba_f32(),
         }
     }
 }
@@ -87,6 +140,9 @@ pub struct LineData {
 
     start: Vec3,
     end: Vec3,
+    dash: Vec3,
+    thickness_end: f32,
+    color_end: [f32; 4],
 }
 
 impl LineData {
@@ -99,12 +155,29 @@ impl LineData {
         LineData {
             transform: config.transform.compute_matrix().to_cols_array_2d(),
 
-            color: config.color.as_linear_rgba_f32(),
+            color: config.faded_color().as_linear_rgba_f32(),
             thickness: config.thickness,
             flags: flags.0,
 
             start,
             end,
+            dash: dash_data(config.dash_pattern),
+            thickness_end: config.thickness_end.unwrap_or(config.thickness),
+            color_end: config.faded_color().as_linear_rgba_f32(),
+        }
+    }
+
+    pub fn gradient(
+        config: &ShapeConfig,
+        start: Vec3,
+        end: Vec3,
+        start_color: Color,
+        end_color: Color,
+    ) -> Self {
+        LineData {
+            color: config.fade(start_color).as_linear_rgba_f32(),
+            color_end: config.fade(end_color).as_linear_rgba_f32(),
+            ..LineData::new(config, start, end)
         }
     }
 }
@@ -124,6 +197,9 @@ impl ShapeData for LineData {
             6 => Uint32,
             7 => Float32x3,
             8 => Float32x3,
+            9 => Float32x3,
+            10 => Float32,
+            11 => Float32x4,
         ]
         .to_vec()
     }
@@ -135,22 +211,80 @@ impl ShapeData for LineData {
     fn transform(&self) -> Mat4 {
         Mat4::from_cols_array_2d(&self.transform)
     }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+        self.color_end = tint_color(self.color_end, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+
+    fn svg(&self) -> Option<SvgElement> {
+        let element = SvgElement::new("line")
+            .attr("x1", self.start.x)
+            .attr("y1", self.start.y)
+            .attr("x2", self.end.x)
+            .attr("y2", self.end.y)
+            .stroke(self.color, self.thickness)
+            .cap(Flags(self.flags).cap())
+            .dash(self.dash);
+        Some(element.transform(self.transform()))
+    }
 }
 
 /// Extension trait for [`ShapePainter`] to enable it to draw lines.
 pub trait LinePainter {
     fn line(&mut self, start: Vec3, end: Vec3) -> &mut Self;
+    /// Draw a line with it's color interpolated from `start_color` to `end_color`, ignoring the color of the painter.
+    fn line_gradient(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        start_color: Color,
+        end_color: Color,
+    ) -> &mut Self;
 }
 
-impl<'w, 's> LinePainter for ShapePainter<'w, 's> {
+impl<P: ShapeSender> LinePainter for P {
     fn line(&mut self, start: Vec3, end: Vec3) -> &mut Self {
         self.send(LineData::new(self.config(), start, end))
     }
+
+    fn line_gradient(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        start_color: Color,
+        end_color: Color,
+    ) -> &mut Self {
+        self.send(LineData::gradient(
+            self.config(),
+            start,
+            end,
+            start_color,
+            end_color,
+        ))
+    }
 }
 
 /// Extension trait for [`ShapeBundle`] to enable creation of line bundles.
 pub trait LineBundle {
     fn line(config: &ShapeConfig, start: Vec3, end: Vec3) -> Self;
+    fn line_gradient(
+        config: &ShapeConfig,
+        start: Vec3,
+        end: Vec3,
+        start_color: Color,
+        end_color: Color,
+    ) -> Self;
 }
 
 impl LineBundle for ShapeBundle<LineComponent> {
@@ -159,15 +293,89 @@ impl LineBundle for ShapeBundle<LineComponent> {
         bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
         bundle
     }
+
+    fn line_gradient(
+        config: &ShapeConfig,
+        start: Vec3,
+        end: Vec3,
+        start_color: Color,
+        end_color: Color,
+    ) -> Self {
+        let mut bundle = Self::line(config, start, end);
+        let opaque = config.without_alpha();
+        bundle.fill.color = opaque.fade(start_color);
+        bundle.shape_type.color_end = Some(opaque.fade(end_color));
+        bundle
+    }
 }
 
 /// Extension trait for [`ShapeSpawner`] to enable spawning of line entities.
 pub trait LineSpawner<'w>: ShapeSpawner<'w> {
     fn line(&mut self, start: Vec3, end: Vec3) -> ShapeEntityCommands;
+    fn line_gradient(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        start_color: Color,
+        end_color: Color,
+    ) -> ShapeEntityCommands;
 }
 
 impl<'w, T: ShapeSpawner<'w>> LineSpawner<'w> for T {
     fn line(&mut self, start: Vec3, end: Vec3) -> ShapeEntityCommands {
         self.spawn_shape(ShapeBundle::line(self.config(), start, end))
     }
+
+    fn line_gradient(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        start_color: Color,
+        end_color: Color,
+    ) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::line_gradient(
+            self.config(),
+            start,
+            end,
+            start_color,
+            end_color,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(cap: Cap) -> LineComponent {
+        LineComponent {
+            cap,
+            start: Vec3::ZERO,
+            end: Vec3::new(2.0, 0.0, 0.0),
+            ..default()
+        }
+    }
+
+    #[test]
+    fn no_cap() {
+        let line = line(Cap::None);
+        assert!(line.contains(Vec2::new(1.0, 0.05), Some(0.2)));
+        assert!(line.contains(Vec2::new(1.0, 0.1), Some(0.2)));
+        assert!(!line.contains(Vec2::new(1.0, 0.15), Some(0.2)));
+        assert!(!line.contains(Vec2::new(-0.05, 0.0), Some(0.2)));
+    }
+
+    #[test]
+    fn square_cap() {
+        let line = line(Cap::Square);
+        assert!(line.contains(Vec2::new(-0.05, 0.0), Some(0.2)));
+        assert!(!line.contains(Vec2::new(-0.15, 0.0), Some(0.2)));
+    }
+
+    #[test]
+    fn round_cap() {
+        let line = line(Cap::Round);
+        assert!(line.contains(Vec2::new(-0.05, 0.05), Some(0.2)));
+        assert!(!line.contains(Vec2::new(-0.09, 0.09), Some(0.2)));
+    }
 }
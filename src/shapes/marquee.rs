@@ -0,0 +1,59 @@
+use bevy::{prelude::*, sprite::Anchor};
+
+use crate::prelude::*;
+
+/// Extension trait for [`ShapePainter`] to enable it to draw animated selection marquees with marching ants.
+///
+/// Points are given in the xy plane of the painter's transform, screen points can be converted with
+/// [`Camera::viewport_to_world_2d`] for a 2D camera. Outlines are drawn with the painter's color, thickness and dash
+/// pattern, using dashes four times as long as the thickness when the painter has no dash pattern, and march one
+/// length of the pattern every second of `time`. The painter's config is left unchanged.
+pub trait MarqueePainter {
+    /// Draw a dashed rectangle with opposite corners at `start` and `end`, such as a drag selection.
+    fn marquee(&mut self, start: Vec2, end: Vec2, time: f32) -> &mut Self;
+    /// Draw a dashed closed loop through each of the points, such as a freehand lasso selection.
+    fn lasso(&mut self, points: &[Vec2], time: f32) -> &mut Self;
+}
+
+/// The painter's dash pattern shifted along the outline by `time`.
+fn marching_dashes(config: &ShapeConfig, time: f32) -> DashPattern {
+    let mut dashes = config
+        .dash_pattern
+        .unwrap_or_else(|| DashPattern::new(config.thickness * 4.0, config.thickness * 4.0));
+    dashes.offset += time * (dashes.on + dashes.off);
+    dashes
+}
+
+impl<'w, 's> MarqueePainter for ShapePainter<'w, 's> {
+    fn marquee(&mut self, start: Vec2, end: Vec2, time: f32) -> &mut Self {
+        let dashes = marching_dashes(self.config(), time);
+        self.with_children(|painter| {
+            painter.translate(((start + end) / 2.0).extend(0.0));
+            painter.hollow = true;
+            painter.anchor = Anchor::Center;
+            painter.dash_pattern = Some(dashes);
+            painter.rect((end - start).abs());
+        })
+    }
+
+    fn lasso(&mut self, points: &[Vec2], time: f32) -> &mut Self {
+        let dashes = marching_dashes(self.config(), time);
+        let Some(&last) = points.last() else {
+            return self;
+        };
+        self.with_children(|painter| {
+            // Continue the pattern from one edge to the next by shifting it back by the length of the loop so far
+            let mut along = 0.0;
+            let mut previous = last;
+            for &point in points {
+                painter.dash_pattern = Some(DashPattern {
+                    offset: dashes.offset - along,
+                    ..dashes
+                });
+                painter.line(previous.extend(0.0), point.extend(0.0));
+                along += previous.distance(point);
+                previous = point;
+            }
+        })
+    }
+}
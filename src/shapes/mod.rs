@@ -1,51 +1,196 @@
-use bevy::prelude::*;
+use std::{f32::consts::SQRT_2, time::Duration};
 
-use crate::{prelude::*, render::ShapePipelineType};
+use bevy::{prelude::*, render::primitives::Aabb, sprite::Anchor};
+
+use crate::{
+    prelude::*,
+    render::{Flags, ShapeComponent, ShapePipelineType},
+};
+
+mod animation;
+pub use animation::*;
+
+mod arrow;
+pub use arrow::*;
+
+mod axes;
+pub use axes::*;
+
+#[cfg(feature = "bezier")]
+mod bezier;
+#[cfg(feature = "bezier")]
+pub use bezier::*;
+
+mod capsule;
+pub use capsule::*;
 
 mod disc;
 pub use disc::*;
 
+#[cfg(feature = "ellipse")]
+mod ellipse;
+#[cfg(feature = "ellipse")]
+pub use ellipse::*;
+
+#[cfg(feature = "grid")]
+mod grid;
+#[cfg(feature = "grid")]
+pub use grid::*;
+
+mod heatmap;
+pub use heatmap::*;
+
+#[cfg(feature = "ngon")]
+mod hex;
+#[cfg(feature = "ngon")]
+pub use hex::*;
+
+mod leader;
+pub use leader::*;
+
 mod line;
 pub use line::*;
 
+mod marquee;
+pub use marquee::*;
+
+mod morph;
+pub use morph::*;
+
+mod path;
+pub use path::*;
+
+#[cfg(feature = "point")]
+mod point;
+#[cfg(feature = "point")]
+pub use point::*;
+
+mod polygon;
+pub use polygon::*;
+
+mod polyline;
+pub use polyline::*;
+
 mod rectangle;
 pub use rectangle::*;
 
+#[cfg(feature = "grid")]
+mod reference_grid;
+#[cfg(feature = "grid")]
+pub use reference_grid::*;
+
+#[cfg(feature = "ngon")]
 mod regular_polygon;
+#[cfg(feature = "ngon")]
 pub use regular_polygon::*;
 
+mod reticle;
+pub use reticle::*;
+
+mod spline;
+pub use spline::*;
+
+#[cfg(feature = "star")]
+mod star;
+#[cfg(feature = "star")]
+pub use star::*;
+
+#[cfg(feature = "superellipse")]
+mod superellipse;
+#[cfg(feature = "superellipse")]
+pub use superellipse::*;
+
+#[cfg(feature = "text")]
+mod text;
+#[cfg(feature = "text")]
+pub use text::*;
+
+#[cfg(feature = "triangle")]
 mod triangle;
+#[cfg(feature = "triangle")]
 pub use triangle::*;
 
+mod wave;
+pub use wave::*;
+
+mod wire_box;
+pub use wire_box::*;
+
+mod wire_sphere;
+pub use wire_sphere::*;
+
 /// Component that holds data related to a shape to be used during rendering,
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShapeMaterial {
     /// Alpha mode to use when rendering, Opaque, Blend, Add and Multiply are explicitly supported.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(with = "crate::serde_remote::AlphaModeDef")
+    )]
     pub alpha_mode: AlphaMode,
+    /// Use screen blending instead of the blending of a transparent alpha mode.
+    pub screen_blend: bool,
+    /// Use order independent transparency in 3D, see [`ShapeConfig::oit`].
+    pub oit: bool,
     /// Forcibly disable local anti-aliasing.
     pub disable_laa: bool,
+    /// Width in pixels over which edges are smoothed by local anti-aliasing, see [`ShapeConfig::aa_width`].
+    pub aa_width: f32,
+    /// Constant bias added to the depth of the shape, see [`ShapeConfig::depth_bias`].
+    pub depth_bias: f32,
+    /// Draw order of the shape in 2D, see [`ShapeConfig::z_index`].
+    pub z_index: Option<f32>,
+    /// Disable depth testing of the shape, see [`ShapeConfig::always_on_top`].
+    pub always_on_top: bool,
+    /// Tint of the shape where it is hidden behind other geometry, see [`ShapeConfig::occluded_tint`].
+    pub occluded_tint: Option<Color>,
+    /// Keep the shape the same size on screen, see [`ShapeConfig::constant_screen_size`].
+    pub constant_screen_size: bool,
+    /// Mask the shape is clipped to, see [`ShapeConfig::clip`].
+    pub clip: Option<ClipMask>,
+    /// Rectangle in pixels the shape is clipped to, see [`ShapeConfig::scissor`].
+    pub scissor: Option<Rect>,
     /// Target pipeline draw the shape.
     pub pipeline: ShapePipelineType,
     /// [`Canvas`] to draw the shape to.
     pub canvas: Option<Entity>,
     /// Texture to apply to the shape.
+    #[cfg_attr(feature = "serialize", serde(skip))]
     pub texture: Option<Handle<Image>>,
+    /// Custom WGSL hook to draw the shape with, see [`ShapeConfig::shader_hook`].
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    pub shader_hook: Option<ShapeShaderHook>,
 }
 
 impl Default for ShapeMaterial {
     fn default() -> Self {
         Self {
             alpha_mode: AlphaMode::Blend,
+            screen_blend: false,
+            oit: false,
             disable_laa: false,
+            aa_width: 1.0,
+            depth_bias: 0.0,
+            z_index: None,
+            always_on_top: false,
+            occluded_tint: None,
+            constant_screen_size: false,
+            clip: None,
+            scissor: None,
             pipeline: ShapePipelineType::Shape2d,
             texture: None,
             canvas: None,
+            shader_hook: None,
         }
     }
 }
 
 /// Used in [`ShapeFill`] to determine how a shape is rendered.
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum FillType {
     /// Fully colored shape
     #[default]
@@ -57,29 +202,235 @@ pub enum FillType {
 }
 
 /// Component attached to each shape to determine how it is rendered.
-#[derive(Default, Component, Clone, Copy)]
+#[derive(Default, Component, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShapeFill {
     pub color: Color,
     pub ty: FillType,
+    /// Outline drawn over a filled shape along with the [`ThicknessType`] of it's thickness, ignored for [`FillType::Stroke`].
+    pub stroke: Option<(Stroke, ThicknessType)>,
+    /// Shadow drawn beneath a filled shape along with the [`ThicknessType`] of it's blur, ignored for [`FillType::Stroke`].
+    pub shadow: Option<(Shadow, ThicknessType)>,
+    /// Glow drawn around a filled shape along with the [`ThicknessType`] of it's radius, ignored for [`FillType::Stroke`].
+    pub glow: Option<(Glow, ThicknessType)>,
+    /// Values passed to shader hooks with the shape, see [`ShapeConfig::user_data`].
+    pub user_data: Vec4,
 }
 
 impl ShapeFill {
     pub fn new(config: &ShapeConfig) -> Self {
         Self {
-            color: config.color,
+            color: config.faded_color(),
             ty: if config.hollow {
                 FillType::Stroke(config.thickness, config.thickness_type)
             } else {
                 FillType::Fill
             },
+            stroke: config.outline(),
+            shadow: config.drop_shadow(),
+            glow: config.halo(),
+            user_data: config.user_data,
+        }
+    }
+
+    /// Returns the outline drawn over the shape, only filled shapes have an outline.
+    pub fn outline(&self) -> Option<(Stroke, ThicknessType)> {
+        match self.ty {
+            FillType::Fill => self.stroke,
+            FillType::Stroke(..) => None,
+        }
+    }
+
+    /// Returns the shadow drawn beneath the shape, only filled shapes have a shadow.
+    pub fn drop_shadow(&self) -> Option<(Shadow, ThicknessType)> {
+        match self.ty {
+            FillType::Fill => self.shadow,
+            FillType::Stroke(..) => None,
+        }
+    }
+
+    /// Returns the glow drawn around the shape, only filled shapes glow.
+    pub fn halo(&self) -> Option<(Glow, ThicknessType)> {
+        match self.ty {
+            FillType::Fill => self.glow,
+            FillType::Stroke(..) => None,
         }
     }
 }
 
+/// Returns the transform that moves a shape of the given size such that it's anchor sits at the origin.
+pub(crate) fn anchor_transform(anchor: &Anchor, size: Vec2) -> Transform {
+    Transform::from_translation((-anchor.as_vec() * size).extend(0.0))
+}
+
 /// Marker component for entities that should be drawn by the 3D pipeline.
-#[derive(Component)]
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shape3d;
 
+/// Component that despawns a shape entity and it's children once the given time has passed.
+///
+/// The remaining time is reduced each frame, see [`ShapeEntityCommands::with_lifetime`].
+#[derive(Component, Reflect, Clone, Copy, Debug, Default)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapeLifetime(pub Duration);
+
+/// Component that multiplies the alpha of a shape entity's color, inherited by all of it's descendants.
+///
+/// May also be placed on non-shape entities to fade every shape beneath them in the hierarchy.
+/// Inserted on retained shapes spawned with a [`ShapeConfig::alpha`] other than 1.0.
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapeAlpha(pub f32);
+
+impl Default for ShapeAlpha {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Returns the product of each [`ShapeAlpha`] on the given entity and it's ancestors.
+pub fn inherited_alpha(
+    entity: Entity,
+    query: &Query<(Option<&ShapeAlpha>, Option<&Parent>)>,
+) -> f32 {
+    let mut alpha = 1.0;
+    let mut current = Some(entity);
+    while let Some((shape_alpha, parent)) = current.and_then(|entity| query.get(entity).ok()) {
+        alpha *= shape_alpha.map_or(1.0, |shape_alpha| shape_alpha.0);
+        current = parent.map(Parent::get);
+    }
+    alpha
+}
+
+/// Returns the bounds of a shape whose filled area spans from `min` to `max` in the xy plane of it's transform, grown
+/// to fit it's outline, shadow and glow.
+///
+/// `outline` returns how far an outline of the given thickness extends past the edge of the shape.
+/// Returns None when any of them is not measured in world units, as their size then depends on the camera.
+pub(crate) fn shape_aabb(
+    min: Vec2,
+    max: Vec2,
+    alignment: Alignment,
+    fill: &ShapeFill,
+    outline: impl Fn(f32) -> f32,
+) -> Option<Aabb> {
+    let world = |value: f32, thickness_type: ThicknessType| {
+        (thickness_type == ThicknessType::World).then_some(value)
+    };
+
+    let mut margin = 0.0f32;
+    let (mut shadow_min, mut shadow_max) = (min, max);
+    match fill.ty {
+        FillType::Stroke(thickness, thickness_type) => {
+            margin = outline(world(thickness, thickness_type)?);
+        }
+        FillType::Fill => {
+            if let Some((stroke, thickness_type)) = fill.stroke {
+                margin = margin.max(outline(world(stroke.thickness, thickness_type)?));
+            }
+            if let Some((glow, thickness_type)) = fill.glow {
+                margin = margin.max(world(glow.radius, thickness_type)?);
+            }
+            if let Some((shadow, thickness_type)) = fill.shadow {
+                let blur = world(shadow.blur, thickness_type)? / 2.0;
+                shadow_min = min + shadow.offset - blur;
+                shadow_max = max + shadow.offset + blur;
+            }
+        }
+    }
+
+    let min = (min - margin.max(0.0)).min(shadow_min);
+    let max = (max + margin.max(0.0)).max(shadow_max);
+    Some(aligned_aabb(min.extend(0.0), max.extend(0.0), alignment))
+}
+
+/// Returns the bounds of a shape drawn as strokes spanning from `min` to `max` in the space of it's transform, grown
+/// in every direction by `margin` for it's thickness.
+///
+/// Returns None when the thickness is not measured in world units.
+pub(crate) fn stroke_aabb(
+    min: Vec3,
+    max: Vec3,
+    alignment: Alignment,
+    fill: &ShapeFill,
+    margin: impl Fn(f32) -> f32,
+) -> Option<Aabb> {
+    let thickness = match fill.ty {
+        FillType::Stroke(thickness, ThicknessType::World) => thickness,
+        FillType::Stroke(..) => return None,
+        FillType::Fill => 1.0,
+    };
+    let margin = margin(thickness).max(0.0);
+    Some(aligned_aabb(min - margin, max + margin, alignment))
+}
+
+/// Returns the smallest and largest coordinates of a set of points, or None if there are no points.
+pub(crate) fn points_bounds(points: &[Vec2]) -> Option<(Vec2, Vec2)> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), &point| {
+        (min.min(point), max.max(point))
+    }))
+}
+
+/// How far the corners and caps of a stroke of the given thickness extend past it's points.
+pub(crate) fn join_margin(join: Join, miter_limit: f32, thickness: f32) -> f32 {
+    match join {
+        Join::Miter => thickness / 2.0 * miter_limit.max(SQRT_2),
+        _ => thickness / 2.0 * SQRT_2,
+    }
+}
+
+fn aligned_aabb(min: Vec3, max: Vec3, alignment: Alignment) -> Aabb {
+    match alignment {
+        Alignment::Flat => Aabb::from_min_max(min, max),
+        // Billboards turn to face the camera so could extend in any direction from their origin
+        Alignment::Billboard | Alignment::BillboardY => {
+            let radius = min.abs().max(max.abs()).length();
+            Aabb::from_min_max(Vec3::splat(-radius), Vec3::splat(radius))
+        }
+    }
+}
+
+/// Keeps the [`Aabb`] of each shape up to date so that shapes outside of every camera's frustum are culled.
+///
+/// Shapes without bounds, see [`ShapeComponent::aabb`], and shapes with a constant screen size are never culled.
+pub fn update_shape_aabbs<T: ShapeComponent>(
+    mut commands: Commands,
+    shapes: Query<
+        (Entity, &T, &ShapeFill, Option<&ShapeMaterial>),
+        Or<(Changed<T>, Changed<ShapeFill>, Changed<ShapeMaterial>)>,
+    >,
+) {
+    for (entity, shape, fill, material) in &shapes {
+        let aabb = shape
+            .aabb(fill)
+            .filter(|_| !material.is_some_and(|material| material.constant_screen_size));
+        match aabb {
+            Some(aabb) => commands.entity(entity).insert(aabb),
+            None => commands.entity(entity).remove::<Aabb>(),
+        };
+    }
+}
+
+/// Counts down each [`ShapeLifetime`] and despawns entities whose time has run out.
+pub fn despawn_expired_shapes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ShapeLifetime)>,
+) {
+    for (entity, mut lifetime) in &mut query {
+        lifetime.0 = lifetime.0.saturating_sub(time.delta());
+        if lifetime.0.is_zero() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
 /// Bundle that is required to render a shape.
 ///
 /// Shape specific methods will additionally add the component representing the corresponding shape.
@@ -92,17 +443,29 @@ pub struct ShapeBundle<T: Component> {
 }
 
 impl<T: Component> ShapeBundle<T> {
+    /// Note: the config's alpha is left out of the bundle, [`ShapeSpawner::spawn_shape`] inserts it as a [`ShapeAlpha`].
     pub fn new(config: &ShapeConfig, component: T) -> Self {
         Self {
             spatial_bundle: SpatialBundle::from_transform(config.transform),
             shape: ShapeMaterial {
                 alpha_mode: config.alpha_mode,
+                screen_blend: config.screen_blend,
+                oit: config.oit,
                 disable_laa: config.disable_laa,
+                aa_width: config.aa_width,
+                depth_bias: config.depth_bias,
+                z_index: config.z_index,
+                always_on_top: config.always_on_top,
+                occluded_tint: config.occluded_tint,
+                constant_screen_size: config.constant_screen_size,
+                clip: config.clip,
+                scissor: config.scissor,
                 pipeline: config.pipeline,
                 canvas: config.canvas,
                 texture: config.texture.clone(),
+                shader_hook: config.shader_hook,
             },
-            fill: ShapeFill::new(config),
+            fill: ShapeFill::new(&config.without_alpha()),
             shape_type: component,
         }
     }
@@ -115,14 +478,19 @@ impl<T: Component> ShapeBundle<T> {
 
 /// Defines the way in which the thickness value of shape is interpreted.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum ThicknessType {
     /// 1.0 thickness corresponds to 1.0 world unit.
     #[default]
     World,
-    /// 1.0 thickness corresponds to 1 pixel.
+    /// 1.0 thickness corresponds to 1 physical pixel.
     Pixels,
     /// 1.0 thickness corresponds to 1% of the screen size along the shortest axis.
     Screen,
+    /// 1.0 thickness corresponds to the full height of the viewport, so thicknesses look the same at any resolution.
+    ///
+    /// For example 0.002 is roughly 2 pixels at 1080p and 4 pixels at 4K.
+    ViewportHeight,
 }
 
 impl From<ThicknessType> for u32 {
@@ -131,8 +499,38 @@ impl From<ThicknessType> for u32 {
     }
 }
 
+/// Defines where the thickness of an outline is placed relative to the edge of a supported shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeAlignment {
+    /// The outline grows inwards from the edge of the shape
+    #[default]
+    Inside,
+    /// The outline is centered on the edge of the shape
+    Center,
+    /// The outline grows outwards from the edge of the shape
+    Outside,
+}
+
+impl From<StrokeAlignment> for u32 {
+    fn from(value: StrokeAlignment) -> Self {
+        value as u32
+    }
+}
+
+impl From<u32> for StrokeAlignment {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => StrokeAlignment::Inside,
+            1 => StrokeAlignment::Center,
+            _ => StrokeAlignment::Outside,
+        }
+    }
+}
+
 /// Defines the way in which caps will be rendered on a supported shape.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cap {
     /// No caps
     None,
@@ -149,15 +547,325 @@ impl From<Cap> for u32 {
     }
 }
 
+impl From<u32> for Cap {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Cap::None,
+            1 => Cap::Square,
+            _ => Cap::Round,
+        }
+    }
+}
+
+/// Defines the way in which the corners between connected segments will be rendered on a supported shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Join {
+    /// Segments are extended until their outer edges meet, falling back to `Bevel` for very sharp corners
+    Miter,
+    /// The outer edges of the segments are connected by a straight edge
+    Bevel,
+    /// Segments are connected by a circular arc
+    #[default]
+    Round,
+}
+
+impl From<Join> for u32 {
+    fn from(value: Join) -> Self {
+        value as u32
+    }
+}
+
+/// Defines the style of the heads drawn at the ends of an arrow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum ArrowHead {
+    /// A filled triangle with it's tip at the end of the arrow
+    #[default]
+    Triangle,
+    /// Two strokes forming an open V with it's tip at the end of the arrow
+    Open,
+    /// A filled circle centered on the end of the arrow
+    Dot,
+}
+
+impl From<ArrowHead> for u32 {
+    fn from(value: ArrowHead) -> Self {
+        value as u32
+    }
+}
+
+/// Describes a repeating pattern of dashes along the outline of a supported shape.
+///
+/// Lengths are measured in world units along the outline, dotted outlines can be created with short dashes.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct DashPattern {
+    /// Length of each dash.
+    pub on: f32,
+    /// Length of the gap between dashes.
+    pub off: f32,
+    /// Distance to shift the pattern along the outline, animate this for marching ants.
+    pub offset: f32,
+}
+
+impl DashPattern {
+    pub fn new(on: f32, off: f32) -> Self {
+        Self {
+            on,
+            off,
+            offset: 0.0,
+        }
+    }
+}
+
+/// Describes an outline drawn along the edge of a filled shape in the same instance as it's fill.
+///
+/// Supported by discs, rectangles and regular polygons, the outline is drawn inside the edge of the shape
+/// and is dashed by the shape's dash pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stroke {
+    pub color: Color,
+    /// Thickness of the outline, measured with the [`ThicknessType`] of the shape.
+    pub thickness: f32,
+}
+
+impl Stroke {
+    pub fn new(color: Color, thickness: f32) -> Self {
+        Self { color, thickness }
+    }
+}
+
+/// Describes a soft shadow drawn beneath a filled shape.
+///
+/// Supported by discs, rectangles and regular polygons, the shadow follows the outline of the shape so that
+/// rounded corners stay true to the shape as it is blurred. Arcs and hollow shapes do not cast a shadow.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shadow {
+    pub color: Color,
+    /// Offset of the shadow from the shape in the xy plane of the shape's transform.
+    pub offset: Vec2,
+    /// Width of the soft edge of the shadow centered on the edge of the shape, measured with the [`ThicknessType`] of the shape.
+    pub blur: f32,
+}
+
+impl Shadow {
+    pub fn new(color: Color, offset: Vec2, blur: f32) -> Self {
+        Self {
+            color,
+            offset,
+            blur,
+        }
+    }
+}
+
+/// Describes a soft halo drawn around the outside of a filled shape, useful for highlighting selected shapes.
+///
+/// Supported by discs, rectangles and regular polygons, like [`Shadow`] arcs and hollow shapes do not glow.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Glow {
+    pub color: Color,
+    /// Distance the glow extends outwards from the edge of the shape, measured with the [`ThicknessType`] of the shape.
+    pub radius: f32,
+    /// Exponent of the fade from the edge of the shape to the end of the glow, 1.0 fades linearly and larger values fade faster.
+    pub falloff: f32,
+}
+
+impl Glow {
+    pub fn new(color: Color, radius: f32, falloff: f32) -> Self {
+        Self {
+            color,
+            radius,
+            falloff,
+        }
+    }
+}
+
+/// Describes a rounded rectangle that shapes are clipped to.
+///
+/// Masks lie in the xy plane of their transform and clip shapes to their outline as seen from the camera,
+/// the transform is in world space so retained shapes should not use the transform of their parent.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClipMask {
+    pub transform: Transform,
+    pub size: Vec2,
+    /// Radius of the corners of the mask, clamped to half of it's shortest side.
+    pub corner_radius: f32,
+}
+
+impl ClipMask {
+    pub fn rect(transform: Transform, size: Vec2) -> Self {
+        Self::rounded_rect(transform, size, 0.0)
+    }
+
+    pub fn rounded_rect(transform: Transform, size: Vec2, corner_radius: f32) -> Self {
+        Self {
+            transform,
+            size,
+            corner_radius,
+        }
+    }
+
+    pub fn circle(transform: Transform, radius: f32) -> Self {
+        Self::rounded_rect(transform, Vec2::splat(radius * 2.0), radius)
+    }
+}
+
+/// Describes a blend from a shape's color to a second color across supported shapes.
+///
+/// Supported by discs, rectangles and regular polygons, gradients are stretched to fit the shape.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gradient {
+    /// Blend across the whole shape in the direction given by `angle`, in radians counter-clockwise from the x axis.
+    Linear { color: Color, angle: f32 },
+    /// Blend outwards from the center of the shape, reaching `color` at it's edge.
+    ///
+    /// The gradient starts from `focal`, an offset from the center relative to the shape's half size
+    /// that should lie within the unit circle.
+    Radial { color: Color, focal: Vec2 },
+    /// Blend counter-clockwise around the center of the shape starting from `start_angle`,
+    /// in radians counter-clockwise from the x axis.
+    ///
+    /// Arcs instead blend along their sweep from their start angle to their end angle.
+    Conic { color: Color, start_angle: f32 },
+}
+
+impl Gradient {
+    pub fn linear(color: Color, angle: f32) -> Self {
+        Self::Linear { color, angle }
+    }
+
+    pub fn conic(color: Color, start_angle: f32) -> Self {
+        Self::Conic { color, start_angle }
+    }
+
+    pub fn radial(color: Color) -> Self {
+        Self::Radial {
+            color,
+            focal: Vec2::ZERO,
+        }
+    }
+
+    /// Color blended to at the end of the gradient.
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Linear { color, .. } | Self::Radial { color, .. } | Self::Conic { color, .. } => {
+                *color
+            }
+        }
+    }
+
+    /// Returns the gradient with it's end color replaced.
+    pub fn with_color(mut self, new_color: Color) -> Self {
+        match &mut self {
+            Self::Linear { color, .. } | Self::Radial { color, .. } | Self::Conic { color, .. } => {
+                *color = new_color
+            }
+        }
+        self
+    }
+}
+
+/// Pack an optional gradient for use in shaders as it's type flag, end color and parameters.
+pub(crate) fn gradient_data(gradient: Option<Gradient>) -> (u32, Vec4, Vec4) {
+    match gradient {
+        None => (0, Vec4::ZERO, Vec4::ZERO),
+        Some(Gradient::Linear { color, angle }) => (
+            1,
+            color.as_linear_rgba_f32().into(),
+            Vec2::from_angle(angle).extend(0.0).extend(0.0),
+        ),
+        Some(Gradient::Radial { color, focal }) => (
+            2,
+            color.as_linear_rgba_f32().into(),
+            focal.extend(0.0).extend(0.0),
+        ),
+        Some(Gradient::Conic { color, start_angle }) => (
+            3,
+            color.as_linear_rgba_f32().into(),
+            Vec4::new(start_angle, 0.0, 0.0, 0.0),
+        ),
+    }
+}
+
+/// Pack an optional outline for use in shaders as it's color, setting the flags and thickness of an outlined shape.
+pub(crate) fn stroke_data(
+    stroke: Option<(Stroke, ThicknessType)>,
+    flags: &mut Flags,
+    thickness: &mut f32,
+) -> Vec4 {
+    let Some((stroke, thickness_type)) = stroke else {
+        return Vec4::ZERO;
+    };
+    flags.set_outlined(1);
+    flags.set_thickness_type(thickness_type);
+    *thickness = stroke.thickness;
+    stroke.color.as_linear_rgba_f32().into()
+}
+
+/// Clear the flags of a shape for an effect drawn beneath it, keeping only the alignment of the shape.
+fn underlay_flags(flags: u32, thickness_type: ThicknessType) -> Flags {
+    let mut flags = Flags(flags);
+    flags.set_thickness_type(thickness_type);
+    flags.set_hollow(0);
+    flags.set_cap(Cap::None);
+    flags.set_arc(0);
+    flags.set_gradient(0);
+    flags.set_outlined(0);
+    flags.set_stroke_alignment(StrokeAlignment::Inside);
+    flags
+}
+
+/// Pack the flags of the shadow cast by a shape.
+pub(crate) fn shadow_flags(flags: u32, thickness_type: ThicknessType) -> u32 {
+    let mut flags = underlay_flags(flags, thickness_type);
+    flags.set_shadow(1);
+    flags.0
+}
+
+/// Pack the flags of the glow around a shape.
+pub(crate) fn glow_flags(flags: u32, thickness_type: ThicknessType) -> u32 {
+    let mut flags = underlay_flags(flags, thickness_type);
+    flags.set_glow(1);
+    flags.0
+}
+
+/// Offset the transform of a shape in it's own xy plane to position it's shadow.
+pub(crate) fn shadow_transform(transform: [[f32; 4]; 4], offset: Vec2) -> [[f32; 4]; 4] {
+    (Mat4::from_cols_array_2d(&transform) * Mat4::from_translation(offset.extend(0.0)))
+        .to_cols_array_2d()
+}
+
+/// Pack the tiling and offset of a texture for use in shaders.
+pub(crate) fn texture_data(tiling: Vec2, offset: Vec2) -> Vec4 {
+    Vec4::new(tiling.x, tiling.y, offset.x, offset.y)
+}
+
+/// Pack an optional dash pattern for use in shaders, a zero length pattern disables dashing.
+pub(crate) fn dash_data(pattern: Option<DashPattern>) -> Vec3 {
+    pattern.map_or(Vec3::ZERO, |pattern| {
+        Vec3::new(pattern.on, pattern.off, pattern.offset)
+    })
+}
+
 /// Defines how a shape will orient itself in relation to it's transform and the camera
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Alignment {
     /// Shapes will respect the rotation in their transform.
     #[default]
     Flat,
     /// Shapes will always orient themselves towards the camera.
-    /// Note that lines rotate around their direction while all other shapes will fully face the camera at all times.
+    /// Note that lines, polylines and splines rotate around their direction while all other shapes will fully face the camera at all times.
     Billboard,
+    /// Shapes will only rotate around the world Y axis to face the camera, staying upright as the camera pitches.
+    /// Useful for health bars and markers above characters, lines, polylines and splines behave the same as with [`Alignment::Billboard`].
+    BillboardY,
 }
 
 impl From<Alignment> for u32 {
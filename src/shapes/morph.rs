@@ -0,0 +1,255 @@
+#[cfg(feature = "ngon")]
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// Interpolation between two configurations of the same shape, used by [`ShapeMorph`] and [`ShapePainter::morph`].
+///
+/// Continuous properties such as sizes, radii, angles and colors are interpolated, while discrete properties
+/// such as alignment or caps switch from the first configuration to the second half way through.
+pub trait Morph: Sized {
+    /// Returns the shape `t` of the way from `self` to `to`, where 0.0 is `self` and 1.0 is `to`.
+    fn morph(&self, to: &Self, t: f32) -> Self;
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+pub(crate) fn lerp_color(from: Color, to: Color, t: f32) -> Color {
+    let from = Vec4::from(from.as_linear_rgba_f32());
+    let to = Vec4::from(to.as_linear_rgba_f32());
+    Color::rgba_linear_from_array(from.lerp(to, t))
+}
+
+/// Interpolate two optional values when both are present, otherwise switch between them half way through.
+fn lerp_option<T: Copy>(
+    from: Option<T>,
+    to: Option<T>,
+    t: f32,
+    f: impl Fn(T, T) -> T,
+) -> Option<T> {
+    match (from, to) {
+        (Some(from), Some(to)) => Some(f(from, to)),
+        _ if t < 0.5 => from,
+        _ => to,
+    }
+}
+
+fn lerp_gradient(from: Option<Gradient>, to: Option<Gradient>, t: f32) -> Option<Gradient> {
+    lerp_option(from, to, t, |from, to| match (from, to) {
+        (
+            Gradient::Linear { color, angle },
+            Gradient::Linear {
+                color: to_color,
+                angle: to_angle,
+            },
+        ) => Gradient::Linear {
+            color: lerp_color(color, to_color, t),
+            angle: lerp(angle, to_angle, t),
+        },
+        (
+            Gradient::Radial { color, focal },
+            Gradient::Radial {
+                color: to_color,
+                focal: to_focal,
+            },
+        ) => Gradient::Radial {
+            color: lerp_color(color, to_color, t),
+            focal: focal.lerp(to_focal, t),
+        },
+        (
+            Gradient::Conic { color, start_angle },
+            Gradient::Conic {
+                color: to_color,
+                start_angle: to_start_angle,
+            },
+        ) => Gradient::Conic {
+            color: lerp_color(color, to_color, t),
+            start_angle: lerp(start_angle, to_start_angle, t),
+        },
+        _ if t < 0.5 => from,
+        _ => to,
+    })
+}
+
+impl Morph for ShapeFill {
+    fn morph(&self, to: &Self, t: f32) -> Self {
+        let ty = match (self.ty, to.ty) {
+            (FillType::Stroke(thickness, thickness_type), FillType::Stroke(to_thickness, _)) => {
+                FillType::Stroke(lerp(thickness, to_thickness, t), thickness_type)
+            }
+            _ if t < 0.5 => self.ty,
+            _ => to.ty,
+        };
+        Self {
+            color: lerp_color(self.color, to.color, t),
+            ty,
+            stroke: lerp_option(self.stroke, to.stroke, t, |(from, ty), (to, _)| {
+                let stroke = Stroke::new(
+                    lerp_color(from.color, to.color, t),
+                    lerp(from.thickness, to.thickness, t),
+                );
+                (stroke, ty)
+            }),
+            shadow: lerp_option(self.shadow, to.shadow, t, |(from, ty), (to, _)| {
+                let shadow = Shadow::new(
+                    lerp_color(from.color, to.color, t),
+                    from.offset.lerp(to.offset, t),
+                    lerp(from.blur, to.blur, t),
+                );
+                (shadow, ty)
+            }),
+            glow: lerp_option(self.glow, to.glow, t, |(from, ty), (to, _)| {
+                let glow = Glow::new(
+                    lerp_color(from.color, to.color, t),
+                    lerp(from.radius, to.radius, t),
+                    lerp(from.falloff, to.falloff, t),
+                );
+                (glow, ty)
+            }),
+            user_data: self.user_data.lerp(to.user_data, t),
+        }
+    }
+}
+
+impl Morph for DiscComponent {
+    fn morph(&self, to: &Self, t: f32) -> Self {
+        let step = if t < 0.5 { self } else { to };
+        Self {
+            radius: lerp(self.radius, to.radius, t),
+            inner_radius: lerp(self.inner_radius, to.inner_radius, t),
+            start_angle: lerp(self.start_angle, to.start_angle, t),
+            end_angle: lerp(self.end_angle, to.end_angle, t),
+            thickness_end: lerp_option(self.thickness_end, to.thickness_end, t, |from, to| {
+                lerp(from, to, t)
+            }),
+            gradient: lerp_gradient(self.gradient, to.gradient, t),
+            texture_tiling: self.texture_tiling.lerp(to.texture_tiling, t),
+            texture_offset: self.texture_offset.lerp(to.texture_offset, t),
+            ..step.clone()
+        }
+    }
+}
+
+impl Morph for RectangleComponent {
+    fn morph(&self, to: &Self, t: f32) -> Self {
+        let step = if t < 0.5 { self } else { to };
+        Self {
+            miter_limit: lerp(self.miter_limit, to.miter_limit, t),
+            size: self.size.lerp(to.size, t),
+            corner_radii: self.corner_radii.lerp(to.corner_radii, t),
+            gradient: lerp_gradient(self.gradient, to.gradient, t),
+            texture_tiling: self.texture_tiling.lerp(to.texture_tiling, t),
+            texture_offset: self.texture_offset.lerp(to.texture_offset, t),
+            ..step.clone()
+        }
+    }
+}
+
+#[cfg(feature = "ngon")]
+impl Morph for RegularPolygonComponent {
+    /// Polygons with a different number of sides are rounded into a circle, switch sides half way through and
+    /// are then sharpened back to their final roundness, rather than drawing fractional numbers of sides.
+    fn morph(&self, to: &Self, t: f32) -> Self {
+        let step = if t < 0.5 { self } else { to };
+        let radius = lerp(self.radius, to.radius, t);
+        let roundness = if self.sides == to.sides {
+            lerp(self.roundness, to.roundness, t)
+        } else {
+            // Rounding each corner by the polygon's apothem turns it into a circle
+            let circle = radius * (PI / step.sides).cos();
+            match t < 0.5 {
+                true => lerp(self.roundness, circle, t * 2.0),
+                false => lerp(circle, to.roundness, t * 2.0 - 1.0),
+            }
+        };
+        Self {
+            color: lerp_color(self.color, to.color, t),
+            thickness: lerp(self.thickness, to.thickness, t),
+            radius,
+            roundness,
+            gradient: lerp_gradient(self.gradient, to.gradient, t),
+            texture_tiling: self.texture_tiling.lerp(to.texture_tiling, t),
+            texture_offset: self.texture_offset.lerp(to.texture_offset, t),
+            ..step.clone()
+        }
+    }
+}
+
+/// Component that morphs a retained shape's component `T` from one configuration to another over time.
+///
+/// Add a `ShapeMorph<ShapeFill>` alongside to also morph the color and thickness of the shape. Morphs of
+/// [`DiscComponent`], [`RectangleComponent`], [`RegularPolygonComponent`] and [`ShapeFill`] are driven by
+/// [`ShapeAnimationPlugin`], other types implementing [`Morph`] can be driven by adding [`morph_shapes`] for them.
+#[derive(Component, Clone)]
+pub struct ShapeMorph<T: Morph + Component> {
+    pub from: T,
+    pub to: T,
+    /// Seconds taken to morph from `from` to `to`.
+    pub duration: f32,
+    pub easing: ShapeEasing,
+    /// Seconds since the morph started.
+    pub elapsed: f32,
+    /// Whether to morph back and forth between the two configurations rather than stopping at `to`.
+    pub repeat: bool,
+}
+
+impl<T: Morph + Component> ShapeMorph<T> {
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            easing: default(),
+            elapsed: 0.0,
+            repeat: false,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: ShapeEasing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn repeating(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    /// Whether the morph has reached `to`, never true for repeating morphs.
+    pub fn finished(&self) -> bool {
+        !self.repeat && self.elapsed >= self.duration
+    }
+
+    /// Eased progress of the morph from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        let t = self.elapsed / self.duration;
+        let t = match self.repeat {
+            true => 1.0 - ((t % 2.0) - 1.0).abs(),
+            false => t.min(1.0),
+        };
+        self.easing.ease(t)
+    }
+}
+
+/// Advances each [`ShapeMorph<T>`] and writes the morphed configuration to the entity's `T`.
+///
+/// Finished morphs are left in place, their component is no longer written to so may be changed freely.
+pub fn morph_shapes<T: Morph + Component>(
+    time: Res<Time>,
+    mut query: Query<(&mut ShapeMorph<T>, &mut T)>,
+) {
+    for (mut morph, mut shape) in &mut query {
+        if morph.finished() {
+            continue;
+        }
+        morph.elapsed += time.delta_seconds();
+        *shape = morph.from.morph(&morph.to, morph.progress());
+    }
+}
@@ -0,0 +1,209 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// A run of connected points within a path, closed subpaths join their last point back to their first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubPath {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+/// Elevate a quadratic bézier curve to the cubic curve that traces the same path.
+pub fn quadratic_to_cubic(p0: Vec2, p1: Vec2, p2: Vec2) -> [Vec2; 4] {
+    [
+        p0,
+        p0 + (p1 - p0) * 2.0 / 3.0,
+        p2 + (p1 - p2) * 2.0 / 3.0,
+        p2,
+    ]
+}
+
+/// Builds a path out of lines and curves in the style of a 2d canvas, see [`PathPainter`].
+///
+/// Curves are flattened into straight segments as they are added, using [`ShapeConfig::spline_subdivisions`]
+/// segments for each bézier curve and for each quarter turn of an arc.
+pub struct PathBuilder<'a, 'w, 's> {
+    painter: &'a mut ShapePainter<'w, 's>,
+    subpaths: Vec<SubPath>,
+    current: Option<Vec2>,
+}
+
+impl<'a, 'w, 's> PathBuilder<'a, 'w, 's> {
+    /// Returns each subpath added so far.
+    pub fn subpaths(&self) -> &[SubPath] {
+        &self.subpaths
+    }
+
+    /// Start a new subpath at the given point.
+    pub fn move_to(&mut self, point: Vec2) -> &mut Self {
+        self.subpaths.push(SubPath {
+            points: vec![point],
+            closed: false,
+        });
+        self.current = Some(point);
+        self
+    }
+
+    /// Add a straight line from the current point to the given point.
+    ///
+    /// If there is no current point this behaves as [`PathBuilder::move_to`].
+    pub fn line_to(&mut self, point: Vec2) -> &mut Self {
+        let Some(current) = self.current else {
+            return self.move_to(point);
+        };
+
+        // Drawing after a subpath has been closed continues from it's start in a new subpath
+        if self.subpaths.last().map_or(true, |subpath| subpath.closed) {
+            self.move_to(current);
+        }
+
+        let subpath = self.subpaths.last_mut().unwrap();
+        if subpath.points.last() != Some(&point) {
+            subpath.points.push(point);
+        }
+        self.current = Some(point);
+        self
+    }
+
+    /// Add a quadratic bézier curve from the current point to `end`.
+    pub fn quad_to(&mut self, control: Vec2, end: Vec2) -> &mut Self {
+        let start = self.current.unwrap_or(control);
+        let [p0, p1, p2, p3] = quadratic_to_cubic(start, control, end);
+        self.cubic_from(p0, p1, p2, p3)
+    }
+
+    /// Add a cubic bézier curve from the current point to `end`.
+    pub fn cubic_to(&mut self, control_a: Vec2, control_b: Vec2, end: Vec2) -> &mut Self {
+        let start = self.current.unwrap_or(control_a);
+        self.cubic_from(start, control_a, control_b, end)
+    }
+
+    fn cubic_from(&mut self, p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> &mut Self {
+        self.line_to(p0);
+        let subdivisions = self.painter.spline_subdivisions.max(1);
+        for i in 1..=subdivisions {
+            let t = i as f32 / subdivisions as f32;
+            let u = 1.0 - t;
+            let point =
+                p0 * u * u * u + p1 * 3.0 * u * u * t + p2 * 3.0 * u * t * t + p3 * t * t * t;
+            self.line_to(point);
+        }
+        self
+    }
+
+    /// Add an arc around `center` from `start_angle` to `end_angle`, connected to the current point by a straight line.
+    ///
+    /// Angles are in radians counter-clockwise from the x axis, the arc runs clockwise if `end_angle` is less than `start_angle`.
+    pub fn arc(
+        &mut self,
+        center: Vec2,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+    ) -> &mut Self {
+        let sweep = end_angle - start_angle;
+        let quarter_turns = (sweep.abs() / FRAC_PI_2).ceil().max(1.0);
+        let segments = quarter_turns as u32 * self.painter.spline_subdivisions.max(1);
+
+        for i in 0..=segments {
+            let angle = start_angle + sweep * i as f32 / segments as f32;
+            self.line_to(center + Vec2::from_angle(angle) * radius);
+        }
+        self
+    }
+
+    /// Add a line towards `corner` ending in an arc of the given radius that turns to face `end`, like `arcTo` on a 2d canvas.
+    ///
+    /// The path finishes where the arc meets the line from `corner` to `end`, if the points are collinear
+    /// a straight line is drawn to `corner` instead.
+    pub fn arc_to(&mut self, corner: Vec2, end: Vec2, radius: f32) -> &mut Self {
+        let Some(start) = self.current else {
+            return self.move_to(corner);
+        };
+
+        let to_start = (start - corner).normalize_or_zero();
+        let to_end = (end - corner).normalize_or_zero();
+        let turn = to_start.perp_dot(to_end);
+        if radius <= 0.0 || turn.abs() < f32::EPSILON {
+            return self.line_to(corner);
+        }
+
+        // Distance from the corner to where the arc touches each line, and to the arc's center
+        let half_angle = to_start.angle_between(to_end).abs() / 2.0;
+        let tangent = radius / half_angle.tan();
+        let center = corner + (to_start + to_end).normalize() * radius / half_angle.sin();
+
+        let from = corner + to_start * tangent - center;
+        let to = corner + to_end * tangent - center;
+        let start_angle = from.y.atan2(from.x);
+        self.arc(
+            center,
+            radius,
+            start_angle,
+            start_angle + from.angle_between(to),
+        )
+    }
+
+    /// Close the current subpath, joining it's last point back to it's first.
+    pub fn close(&mut self) -> &mut Self {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            // Drop a final point that already returns to the start to avoid a zero length segment
+            if subpath.points.len() > 1 && subpath.points.first() == subpath.points.last() {
+                subpath.points.pop();
+            }
+            subpath.closed = true;
+            self.current = subpath.points.first().copied();
+        }
+        self
+    }
+
+    /// Fill each subpath as a separate polygon, see [`PolygonPainter`].
+    ///
+    /// Subpaths are always treated as closed and do not cut holes in one another.
+    pub fn fill(&mut self) -> &mut Self {
+        let subpaths = &self.subpaths;
+        self.painter.with_children(|painter| {
+            painter.hollow = false;
+            for subpath in subpaths.iter().filter(|subpath| subpath.points.len() > 2) {
+                painter.polygon(&subpath.points);
+            }
+        });
+        self
+    }
+
+    /// Draw the outline of each subpath with the painter's thickness, join and cap, see [`PolylinePainter`].
+    pub fn stroke(&mut self) -> &mut Self {
+        for subpath in self
+            .subpaths
+            .iter()
+            .filter(|subpath| subpath.points.len() > 1)
+        {
+            let points: Vec<Vec3> = subpath.points.iter().map(|p| p.extend(0.0)).collect();
+            if subpath.closed {
+                self.painter.closed_polyline(&points);
+            } else {
+                self.painter.polyline(&points);
+            }
+        }
+        self
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw paths built from lines and curves.
+pub trait PathPainter<'w, 's> {
+    /// Start building an empty path, see [`PathBuilder`].
+    fn begin_path(&mut self) -> PathBuilder<'_, 'w, 's>;
+}
+
+impl<'w, 's> PathPainter<'w, 's> for ShapePainter<'w, 's> {
+    fn begin_path(&mut self) -> PathBuilder<'_, 'w, 's> {
+        PathBuilder {
+            painter: self,
+            subpaths: Vec::new(),
+            current: None,
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::render_resource::{ShaderRef, ShaderType},
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, POINT_HANDLE},
+};
+
+/// Component containing the data for drawing a point.
+///
+/// Points are always billboarded and keep the same size on screen regardless of camera distance or scale.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointComponent {
+    /// Diameter of the point in pixels.
+    pub size: f32,
+}
+
+impl PointComponent {
+    pub fn new(size: f32) -> Self {
+        Self { size }
+    }
+}
+
+impl Default for PointComponent {
+    fn default() -> Self {
+        Self { size: 4.0 }
+    }
+}
+
+impl ShapeComponent for PointComponent {
+    type Data = PointData;
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> PointData {
+        let mut flags = Flags(0);
+        flags.set_alignment(Alignment::Billboard);
+
+        PointData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness: 1.0,
+            flags: flags.0,
+
+            size: self.size,
+
+            padding: default(),
+        }
+    }
+}
+
+/// Raw data sent to the point shader to draw a point
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct PointData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    size: f32,
+
+    padding: [f32; 3],
+}
+
+impl PointData {
+    pub fn new(config: &ShapeConfig, size: f32) -> Self {
+        let mut flags = Flags(0);
+        flags.set_alignment(Alignment::Billboard);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: 1.0,
+            flags: flags.0,
+
+            size,
+
+            padding: default(),
+        }
+    }
+}
+
+impl ShapeData for PointData {
+    type Component = PointComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        POINT_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, _constant_screen_size: bool) {
+        // Points always keep the same size on screen
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw points.
+pub trait PointPainter {
+    /// Draw a point at the painter's position with a diameter of `size_px` pixels.
+    fn point(&mut self, size_px: f32) -> &mut Self;
+    /// Draw a point at each position relative to the painter's transform, see [`ShapePainter::send_many`].
+    fn points(&mut self, size_px: f32, positions: impl IntoIterator<Item = Vec3>) -> &mut Self;
+}
+
+impl<P: ShapeSender> PointPainter for P {
+    fn point(&mut self, size_px: f32) -> &mut Self {
+        self.send(PointData::new(self.config(), size_px))
+    }
+
+    fn points(&mut self, size_px: f32, positions: impl IntoIterator<Item = Vec3>) -> &mut Self {
+        self.send_many(PointData::new(self.config(), size_px), positions)
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of point bundles.
+pub trait PointBundle {
+    fn point(config: &ShapeConfig, size_px: f32) -> Self;
+}
+
+impl PointBundle for ShapeBundle<PointComponent> {
+    fn point(config: &ShapeConfig, size_px: f32) -> Self {
+        Self::new(config, PointComponent::new(size_px))
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of point entities.
+pub trait PointSpawner<'w> {
+    fn point(&mut self, size_px: f32) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> PointSpawner<'w> for T {
+    fn point(&mut self, size_px: f32) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::point(self.config(), size_px))
+    }
+}
@@ -0,0 +1,428 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, POLYGON_HANDLE},
+};
+
+/// Component containing the data for drawing an arbitrary polygon.
+///
+/// Filled polygons are triangulated on the CPU, hollow polygons are drawn as a closed polyline
+/// centered on the outline of the polygon.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolygonComponent {
+    pub alignment: Alignment,
+    /// Join type for the corners of a hollow polygon.
+    pub join: Join,
+    /// Ratio of miter length to half thickness past which miter joins are drawn as bevel joins.
+    pub miter_limit: f32,
+
+    /// Vertices of the polygon in the local space of it's transform, may be concave but should not self intersect.
+    pub points: Vec<Vec2>,
+}
+
+impl PolygonComponent {
+    pub fn new(config: &ShapeConfig, points: &[Vec2]) -> Self {
+        Self {
+            alignment: config.alignment,
+            join: config.join,
+            miter_limit: config.miter_limit,
+
+            points: points.to_vec(),
+        }
+    }
+}
+
+impl Default for PolygonComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+            join: default(),
+            miter_limit: 4.0,
+
+            points: Vec::new(),
+        }
+    }
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    (0..points.len())
+        .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+        .sum::<f32>()
+        / 2.0
+}
+
+fn in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(p - a) >= 0.0
+        && (c - b).perp_dot(p - b) >= 0.0
+        && (a - c).perp_dot(p - c) >= 0.0
+}
+
+/// Triangulate a simple polygon by ear clipping.
+///
+/// Returns triangles as indices into `points` with counter-clockwise winding.
+pub fn triangulate_polygon(points: &[Vec2]) -> Vec<[usize; 3]> {
+    let count = points.len();
+    if count < 3 {
+        return Vec::new();
+    }
+
+    let mut remaining: Vec<usize> = if signed_area(points) >= 0.0 {
+        (0..count).collect()
+    } else {
+        (0..count).rev().collect()
+    };
+    let mut triangles = Vec::with_capacity(count - 2);
+
+    let mut i = 0;
+    let mut since_last_ear = 0;
+    while remaining.len() > 3 && since_last_ear < remaining.len() {
+        let len = remaining.len();
+        let prev = remaining[(i + len - 1) % len];
+        let curr = remaining[i];
+        let next = remaining[(i + 1) % len];
+        let (a, b, c) = (points[prev], points[curr], points[next]);
+
+        // An ear is a convex vertex whose triangle contains no other remaining vertex
+        let is_ear = (b - a).perp_dot(c - b) > 0.0
+            && remaining
+                .iter()
+                .filter(|&&j| j != prev && j != curr && j != next)
+                .all(|&j| !in_triangle(points[j], a, b, c));
+
+        if is_ear {
+            triangles.push([prev, curr, next]);
+            remaining.remove(i);
+            if i >= remaining.len() {
+                i = 0;
+            }
+            since_last_ear = 0;
+        } else {
+            i = (i + 1) % len;
+            since_last_ear += 1;
+        }
+    }
+
+    if remaining.len() == 3 {
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+    }
+
+    triangles
+}
+
+/// Remove consecutive duplicate points and any duplicate closing point.
+fn clean_points(points: &[Vec2]) -> Vec<Vec2> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if points.len() > 2 && points.first() == points.last() {
+        points.pop();
+    }
+    points
+}
+
+/// Calls `f` with the points and boundary edge mask for each instance required to draw a polygon.
+fn polygon_instances(points: &[Vec2], hollow: bool, mut f: impl FnMut([Vec2; 4], u32)) {
+    let points = clean_points(points);
+    if hollow {
+        for segment in polyline_segments(&points, true) {
+            f(segment, 0);
+        }
+        return;
+    }
+
+    let count = points.len();
+    let is_boundary = |a: usize, b: usize| (a + 1) % count == b || (b + 1) % count == a;
+    for [a, b, c] in triangulate_polygon(&points) {
+        let edges = is_boundary(a, b) as u32
+            | (is_boundary(b, c) as u32) << 1
+            | (is_boundary(c, a) as u32) << 2;
+        f([points[a], points[b], points[c], Vec2::ZERO], edges);
+    }
+}
+
+impl ShapeComponent for PolygonComponent {
+    type Data = PolygonData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let (min, max) = points_bounds(&self.points)?;
+        shape_aabb(min, max, self.alignment, fill, |thickness| {
+            join_margin(self.join, self.miter_limit, thickness)
+        })
+    }
+
+    /// Returns the data for the first triangle or segment of the polygon, see [`ShapeComponent::get_instances`] for the full set.
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> PolygonData {
+        let mut first = None;
+        self.get_instances(tf, fill, |data| {
+            first.get_or_insert(data);
+        });
+        first.unwrap_or_default()
+    }
+
+    fn get_instances(
+        &self,
+        tf: &GlobalTransform,
+        fill: &ShapeFill,
+        mut f: impl FnMut(PolygonData),
+    ) {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+        flags.set_join(self.join);
+
+        let transform = tf.compute_matrix().to_cols_array_2d();
+        let color = fill.color.as_linear_rgba_f32();
+        let hollow = matches!(fill.ty, FillType::Stroke(..));
+        polygon_instances(&self.points, hollow, |points, edges| {
+            f(PolygonData {
+                transform,
+
+                color,
+                thickness,
+                flags: flags.0,
+
+                points: points.map(Into::into),
+                edges,
+
+                miter_limit: self.miter_limit,
+            })
+        });
+    }
+}
+
+/// Raw data sent to the polygon shader to draw a single triangle or outline segment of a polygon
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct PolygonData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    /// Triangle vertices when filled, previous, start, end and next points of a segment when hollow.
+    points: [[f32; 2]; 4],
+    /// Bitmask of the triangle edges that lie on the outline of the polygon.
+    edges: u32,
+
+    miter_limit: f32,
+}
+
+impl PolygonData {
+    pub fn new(config: &ShapeConfig, points: [Vec2; 4], edges: u32) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+        flags.set_hollow(config.hollow as u32);
+        flags.set_join(config.join);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            points: points.map(Into::into),
+            edges,
+
+            miter_limit: config.miter_limit,
+        }
+    }
+}
+
+impl ShapeData for PolygonData {
+    type Component = PolygonComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32x2,
+            8 => Float32x2,
+            9 => Float32x2,
+            10 => Float32x2,
+            11 => Uint32,
+            12 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        POLYGON_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw arbitrary polygons.
+pub trait PolygonPainter {
+    fn polygon(&mut self, points: &[Vec2]) -> &mut Self;
+}
+
+impl<P: ShapeSender> PolygonPainter for P {
+    fn polygon(&mut self, points: &[Vec2]) -> &mut Self {
+        let mut instances = Vec::new();
+        polygon_instances(points, self.hollow, |points, edges| {
+            instances.push(PolygonData::new(self.config(), points, edges))
+        });
+        for data in instances {
+            self.send(data);
+        }
+        self
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of polygon bundles.
+pub trait PolygonBundle {
+    fn polygon(config: &ShapeConfig, points: &[Vec2]) -> Self;
+}
+
+impl PolygonBundle for ShapeBundle<PolygonComponent> {
+    fn polygon(config: &ShapeConfig, points: &[Vec2]) -> Self {
+        Self::new(config, PolygonComponent::new(config, points))
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of polygon entities.
+pub trait PolygonSpawner<'w> {
+    fn polygon(&mut self, points: &[Vec2]) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> PolygonSpawner<'w> for T {
+    fn polygon(&mut self, points: &[Vec2]) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::polygon(self.config(), points))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the triangles of a filled polygon along with the number of outline edges they draw.
+    fn triangulate(points: &[Vec2]) -> (Vec<[Vec2; 4]>, u32) {
+        let mut triangles = Vec::new();
+        let mut edges = 0;
+        polygon_instances(points, false, |triangle, mask| {
+            triangles.push(triangle);
+            edges += mask.count_ones();
+        });
+        (triangles, edges)
+    }
+
+    fn area([a, b, c, _]: [Vec2; 4]) -> f32 {
+        (b - a).perp_dot(c - a) / 2.0
+    }
+
+    fn assert_triangulation(points: &[Vec2], triangles: usize, total_area: f32, edges: u32) {
+        let (result, result_edges) = triangulate(points);
+        assert_eq!(result.len(), triangles);
+        assert!(result.iter().all(|&triangle| area(triangle) >= 0.0));
+        let result_area: f32 = result.iter().map(|&triangle| area(triangle)).sum();
+        assert!((result_area - total_area).abs() < 1e-5);
+        assert_eq!(result_edges, edges);
+    }
+
+    #[test]
+    fn convex() {
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        assert_triangulation(&square, 2, 1.0, 4);
+
+        let mut clockwise = square;
+        clockwise.reverse();
+        assert_triangulation(&clockwise, 2, 1.0, 4);
+    }
+
+    #[test]
+    fn concave() {
+        let l_shape = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ];
+        assert_triangulation(&l_shape, 4, 3.0, 6);
+
+        let mut clockwise = l_shape;
+        clockwise.reverse();
+        assert_triangulation(&clockwise, 4, 3.0, 6);
+    }
+
+    #[test]
+    fn degenerate() {
+        assert_triangulation(&[], 0, 0.0, 0);
+        assert_triangulation(&[Vec2::ZERO, Vec2::X], 0, 0.0, 0);
+
+        // Duplicate and closing points are removed
+        let duplicates = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(0.0, 0.0),
+        ];
+        assert_triangulation(&duplicates, 2, 1.0, 4);
+
+        // A point along an edge still leaves every edge of the outline drawn once
+        let midpoint = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.5, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        assert_triangulation(&midpoint, 3, 1.0, 5);
+
+        // Collinear points have no area to fill
+        let (triangles, _) = triangulate(&[Vec2::ZERO, Vec2::X, Vec2::X * 2.0]);
+        assert!(triangles.iter().all(|&triangle| area(triangle) == 0.0));
+    }
+}
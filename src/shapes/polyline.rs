@@ -0,0 +1,313 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, POLYLINE_HANDLE},
+};
+
+/// Component containing the data for drawing a polyline.
+///
+/// Each segment of the polyline is drawn as it's own instance, segments own half of each join so
+/// that no area is drawn twice. Segments lie in the plane of their transform when flat and otherwise rotate around
+/// their direction to face the camera like lines, so points off the plane can be used to draw paths in 3D.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolylineComponent {
+    pub alignment: Alignment,
+    /// Cap type for the ends of an open polyline.
+    pub cap: Cap,
+    /// Join type for the corners between segments.
+    pub join: Join,
+    /// Ratio of miter length to half thickness past which miter joins are drawn as bevel joins.
+    pub miter_limit: f32,
+    /// Whether to connect the last point back to the first.
+    pub closed: bool,
+
+    /// Points of the polyline in the local space of it's transform.
+    pub points: Vec<Vec3>,
+}
+
+impl PolylineComponent {
+    pub fn new(config: &ShapeConfig, points: &[Vec3], closed: bool) -> Self {
+        Self {
+            alignment: config.alignment,
+            cap: config.cap,
+            join: config.join,
+            miter_limit: config.miter_limit,
+            closed,
+
+            points: points.to_vec(),
+        }
+    }
+}
+
+impl Default for PolylineComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+            cap: default(),
+            join: default(),
+            miter_limit: 4.0,
+            closed: false,
+
+            points: Vec::new(),
+        }
+    }
+}
+
+/// Split a list of points into segments, each returned as `[previous, start, end, next]`.
+///
+/// When a segment has no neighbour on a given side the previous or next point will equal the start or end respectively.
+pub fn polyline_segments<T: Copy + PartialEq>(points: &[T], closed: bool) -> Vec<[T; 4]> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 2 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let count = points.len();
+    if count < 2 {
+        return Vec::new();
+    }
+
+    let closed = closed && count > 2;
+    let segments = if closed { count } else { count - 1 };
+    (0..segments)
+        .map(|i| {
+            let start = points[i];
+            let end = points[(i + 1) % count];
+            let prev = if closed || i > 0 {
+                points[(i + count - 1) % count]
+            } else {
+                start
+            };
+            let next = if closed || i + 2 < count {
+                points[(i + 2) % count]
+            } else {
+                end
+            };
+            [prev, start, end, next]
+        })
+        .collect()
+}
+
+impl ShapeComponent for PolylineComponent {
+    type Data = PolylineData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let first = *self.points.first()?;
+        let (min, max) = self
+            .points
+            .iter()
+            .fold((first, first), |(min, max), &point| {
+                (min.min(point), max.max(point))
+            });
+        stroke_aabb(min, max, self.alignment, fill, |thickness| {
+            join_margin(self.join, self.miter_limit, thickness)
+        })
+    }
+
+    /// Returns the data for the first segment of the polyline, see [`ShapeComponent::get_instances`] for the full set.
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> PolylineData {
+        let mut first = None;
+        self.get_instances(tf, fill, |data| {
+            first.get_or_insert(data);
+        });
+        first.unwrap_or_default()
+    }
+
+    fn get_instances(
+        &self,
+        tf: &GlobalTransform,
+        fill: &ShapeFill,
+        mut f: impl FnMut(PolylineData),
+    ) {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+        flags.set_cap(self.cap);
+        flags.set_join(self.join);
+
+        let transform = tf.compute_matrix().to_cols_array_2d();
+        let color = fill.color.as_linear_rgba_f32();
+        for [prev, start, end, next] in polyline_segments(&self.points, self.closed) {
+            f(PolylineData {
+                transform,
+
+                color,
+                thickness,
+                flags: flags.0,
+
+                prev,
+                start,
+                end,
+                next,
+
+                miter_limit: self.miter_limit,
+            })
+        }
+    }
+}
+
+/// Raw data sent to the polyline shader to draw a single segment of a polyline
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct PolylineData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    prev: Vec3,
+    start: Vec3,
+    end: Vec3,
+    next: Vec3,
+
+    miter_limit: f32,
+}
+
+impl PolylineData {
+    pub fn new(config: &ShapeConfig, prev: Vec3, start: Vec3, end: Vec3, next: Vec3) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+        flags.set_cap(config.cap);
+        flags.set_join(config.join);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            prev,
+            start,
+            end,
+            next,
+
+            miter_limit: config.miter_limit,
+        }
+    }
+}
+
+impl ShapeData for PolylineData {
+    type Component = PolylineComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32x3,
+            8 => Float32x3,
+            9 => Float32x3,
+            10 => Float32x3,
+            11 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        POLYLINE_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw polylines.
+pub trait PolylinePainter {
+    fn polyline(&mut self, points: &[Vec3]) -> &mut Self;
+    fn closed_polyline(&mut self, points: &[Vec3]) -> &mut Self;
+}
+
+impl<P: ShapeSender> PolylinePainter for P {
+    fn polyline(&mut self, points: &[Vec3]) -> &mut Self {
+        for [prev, start, end, next] in polyline_segments(points, false) {
+            self.send(PolylineData::new(self.config(), prev, start, end, next));
+        }
+        self
+    }
+
+    fn closed_polyline(&mut self, points: &[Vec3]) -> &mut Self {
+        for [prev, start, end, next] in polyline_segments(points, true) {
+            self.send(PolylineData::new(self.config(), prev, start, end, next));
+        }
+        self
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of polyline bundles.
+pub trait PolylineBundle {
+    fn polyline(config: &ShapeConfig, points: &[Vec3]) -> Self;
+    fn closed_polyline(config: &ShapeConfig, points: &[Vec3]) -> Self;
+}
+
+impl PolylineBundle for ShapeBundle<PolylineComponent> {
+    fn polyline(config: &ShapeConfig, points: &[Vec3]) -> Self {
+        let mut bundle = Self::new(config, PolylineComponent::new(config, points, false));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+
+    fn closed_polyline(config: &ShapeConfig, points: &[Vec3]) -> Self {
+        let mut bundle = Self::new(config, PolylineComponent::new(config, points, true));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of polyline entities.
+pub trait PolylineSpawner<'w> {
+    fn polyline(&mut self, points: &[Vec3]) -> ShapeEntityCommands;
+    fn closed_polyline(&mut self, points: &[Vec3]) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> PolylineSpawner<'w> for T {
+    fn polyline(&mut self, points: &[Vec3]) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::polyline(self.config(), points))
+    }
+
+    fn closed_polyline(&mut self, points: &[Vec3]) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::closed_polyline(self.config(), points))
+    }
+}
@@ -1,43 +1,137 @@
+use std::f32::consts::SQRT_2;
+
 use bevy::{
     prelude::*,
     reflect::Reflect,
-    render::render_resource::{ShaderRef, ShaderType},
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+    sprite::Anchor,
 };
 use wgpu::vertex_attr_array;
 
 use crate::{
     prelude::*,
-    render::{Flags, ShapeComponent, ShapeData, RECT_HANDLE},
+    render::{
+        rounded_rect_path, stroke_offset, tint_color, Flags, ShapeComponent, ShapeData, SvgElement,
+        RECT_HANDLE,
+    },
 };
 
 /// Component containing the data for drawing a rectangle.
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct RectangleComponent {
     pub alignment: Alignment,
+    /// Placement of the outline relative to the edge of the shape.
+    pub stroke_alignment: StrokeAlignment,
+    /// Join type for the sharp corners of an outline that extends past the edge of the rectangle.
+    pub join: Join,
+    /// Ratio of miter length to half thickness past which miter joins are drawn as bevel joins.
+    pub miter_limit: f32,
+    /// Point on the rectangle placed at the origin of it's transform.
+    #[cfg_attr(feature = "serialize", serde(with = "crate::serde_remote::AnchorDef"))]
+    pub anchor: Anchor,
 
     /// Size of the rectangle on the x and y axis.
     pub size: Vec2,
     /// Corner rounding radius for each corner in world units.
+    ///
+    /// Ordered top-right, top-left, bottom-left, bottom-right, see [`rounded_corners`] to build from clockwise order.
     pub corner_radii: Vec4,
+    /// Whether the scale of the transform stretches only the size, see [`ShapeConfig::nine_slice`].
+    pub nine_slice: bool,
+    /// Pattern of dashes along the outline, starting from the middle of the right edge.
+    pub dash_pattern: Option<DashPattern>,
+    /// Gradient blending from the fill color, see [`ShapeConfig::gradient`].
+    pub gradient: Option<Gradient>,
+    /// Number of times the texture repeats across the shape, see [`ShapeConfig::texture_tiling`].
+    pub texture_tiling: Vec2,
+    /// Offset of the texture across the shape, see [`ShapeConfig::texture_offset`].
+    pub texture_offset: Vec2,
 }
 
 impl RectangleComponent {
     pub fn new(config: &ShapeConfig, size: Vec2) -> Self {
         Self {
             alignment: config.alignment,
+            stroke_alignment: config.stroke_alignment,
+            join: config.join,
+            miter_limit: config.miter_limit,
+            anchor: config.anchor,
 
             size,
             corner_radii: config.corner_radii,
+            nine_slice: config.nine_slice,
+            dash_pattern: config.dash_pattern,
+            gradient: config.without_alpha().faded_gradient(),
+            texture_tiling: config.texture_tiling,
+            texture_offset: config.texture_offset,
         }
     }
 }
 
+/// Convert corner radii given clockwise from the top-left corner into the order expected by [`RectangleComponent::corner_radii`].
+pub fn rounded_corners([top_left, top_right, bottom_right, bottom_left]: [f32; 4]) -> Vec4 {
+    Vec4::new(top_right, top_left, bottom_left, bottom_right)
+}
+
+/// Returns the join used for the right angled corners of a rectangle, whose miters are `sqrt(2)` times half the thickness.
+fn corner_join(join: Join, miter_limit: f32) -> Join {
+    if join == Join::Miter && miter_limit < SQRT_2 {
+        Join::Bevel
+    } else {
+        join
+    }
+}
+
 impl ShapeComponent for RectangleComponent {
     type Data = RectData;
 
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        // Nine sliced outlines ignore the scale of the transform so can't be bounded in it's space
+        if self.nine_slice {
+            return None;
+        }
+        let center = -self.anchor.as_vec() * self.size;
+        let half_size = self.size.abs() / 2.0;
+        shape_aabb(
+            center - half_size,
+            center + half_size,
+            self.alignment,
+            fill,
+            |thickness| stroke_offset(self.stroke_alignment, thickness) + thickness / 2.0,
+        )
+    }
+
+    fn contains(&self, point: Vec2, thickness: Option<f32>) -> bool {
+        let half_size = self.size / 2.0;
+        let point = point + self.anchor.as_vec() * self.size;
+
+        // Corner radii are ordered by quadrant as in the shader: top-right, top-left, bottom-left, bottom-right
+        let radius = match (point.x > 0.0, point.y > 0.0) {
+            (true, true) => self.corner_radii.x,
+            (false, true) => self.corner_radii.y,
+            (false, false) => self.corner_radii.z,
+            (true, false) => self.corner_radii.w,
+        }
+        .clamp(0.0, half_size.min_element());
+        let q = point.abs() - half_size + radius;
+        let dist = q.max(Vec2::ZERO).length() + q.max_element().min(0.0) - radius;
+
+        match thickness {
+            Some(thickness) => {
+                (dist - stroke_offset(self.stroke_alignment, thickness)).abs() <= thickness / 2.0
+            }
+            None => dist <= 0.0,
+        }
+    }
+
     fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> RectData {
         let mut flags = Flags(0);
-        let thickness = match fill.ty {
+        let mut thickness = match fill.ty {
             FillType::Stroke(thickness, thickness_type) => {
                 flags.set_thickness_type(thickness_type);
                 flags.set_hollow(1);
@@ -46,6 +140,13 @@ impl ShapeComponent for RectangleComponent {
             FillType::Fill => 1.0,
         };
         flags.set_alignment(self.alignment);
+        flags.set_stroke_alignment(self.stroke_alignment);
+        flags.set_join(corner_join(self.join, self.miter_limit));
+        flags.set_nine_slice(self.nine_slice as u32);
+        let tf = tf.mul_transform(anchor_transform(&self.anchor, self.size));
+        let (gradient_type, gradient_color, gradient) = gradient_data(self.gradient);
+        flags.set_gradient(gradient_type);
+        let stroke_color = stroke_data(fill.outline(), &mut flags, &mut thickness);
 
         RectData {
             transform: tf.compute_matrix().to_cols_array_2d(),
@@ -56,6 +157,12 @@ impl ShapeComponent for RectangleComponent {
 
             size: self.size.into(),
             corner_radii: self.corner_radii.into(),
+            dash: dash_data(self.dash_pattern),
+            gradient_color,
+            gradient,
+            texture_uv: texture_data(self.texture_tiling, self.texture_offset),
+            stroke_color,
+            user_data: fill.user_data,
         }
     }
 }
@@ -64,9 +171,18 @@ impl Default for RectangleComponent {
     fn default() -> Self {
         Self {
             alignment: default(),
+            stroke_alignment: default(),
+            join: default(),
+            miter_limit: 4.0,
+            anchor: default(),
 
             size: Vec2::ONE,
             corner_radii: default(),
+            nine_slice: false,
+            dash_pattern: None,
+            gradient: None,
+            texture_tiling: Vec2::ONE,
+            texture_offset: Vec2::ZERO,
         }
     }
 }
@@ -83,24 +199,44 @@ pub struct RectData {
 
     size: [f32; 2],
     corner_radii: [f32; 4],
+    dash: Vec3,
+    gradient_color: Vec4,
+    gradient: Vec4,
+    texture_uv: Vec4,
+    stroke_color: Vec4,
+    user_data: Vec4,
 }
 
 impl RectData {
     pub fn new(config: &ShapeConfig, size: Vec2) -> Self {
         let mut flags = Flags(0);
         flags.set_alignment(config.alignment);
+        flags.set_stroke_alignment(config.stroke_alignment);
+        flags.set_join(corner_join(config.join, config.miter_limit));
         flags.set_thickness_type(config.thickness_type);
         flags.set_hollow(config.hollow as u32);
+        flags.set_nine_slice(config.nine_slice as u32);
+        let transform = config.transform * anchor_transform(&config.anchor, size);
+        let (gradient_type, gradient_color, gradient) = gradient_data(config.faded_gradient());
+        flags.set_gradient(gradient_type);
+        let mut thickness = config.thickness;
+        let stroke_color = stroke_data(config.outline(), &mut flags, &mut thickness);
 
         Self {
-            transform: config.transform.compute_matrix().to_cols_array_2d(),
+            transform: transform.compute_matrix().to_cols_array_2d(),
 
-            color: config.color.as_linear_rgba_f32(),
-            thickness: config.thickness,
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness,
             flags: flags.0,
 
             size: size.into(),
             corner_radii: config.corner_radii.into(),
+            dash: dash_data(config.dash_pattern),
+            gradient_color,
+            gradient,
+            texture_uv: texture_data(config.texture_tiling, config.texture_offset),
+            stroke_color,
+            user_data: config.user_data,
         }
     }
 }
@@ -119,7 +255,13 @@ impl ShapeData for RectData {
             5 => Float32,
             6 => Uint32,
             7 => Float32x2,
-            8 => Float32x4
+            8 => Float32x4,
+            9 => Float32x3,
+            10 => Float32x4,
+            11 => Float32x4,
+            12 => Float32x4,
+            13 => Float32x4,
+            14 => Float32x4,
         ]
         .to_vec()
     }
@@ -131,20 +273,102 @@ impl ShapeData for RectData {
     fn transform(&self) -> Mat4 {
         Mat4::from_cols_array_2d(&self.transform)
     }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+        self.gradient_color = tint_color(self.gradient_color.into(), color).into();
+        self.stroke_color = tint_color(self.stroke_color.into(), color).into();
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+
+    fn shadow(&self, shadow: &Shadow, thickness_type: ThicknessType) -> Option<Self> {
+        if Flags(self.flags).hollow() > 0 {
+            return None;
+        }
+        Some(Self {
+            transform: shadow_transform(self.transform, shadow.offset),
+            color: shadow.color.as_linear_rgba_f32(),
+            thickness: shadow.blur,
+            flags: shadow_flags(self.flags, thickness_type),
+            ..*self
+        })
+    }
+
+    fn glow(&self, glow: &Glow, thickness_type: ThicknessType) -> Option<Self> {
+        if Flags(self.flags).hollow() > 0 {
+            return None;
+        }
+        Some(Self {
+            color: glow.color.as_linear_rgba_f32(),
+            thickness: glow.radius,
+            flags: glow_flags(self.flags, thickness_type),
+            gradient: Vec4::new(glow.falloff, 0.0, 0.0, 0.0),
+            ..*self
+        })
+    }
+
+    fn svg(&self) -> Option<SvgElement> {
+        let flags = Flags(self.flags);
+        if flags.shadow() > 0 || flags.glow() > 0 {
+            return None;
+        }
+
+        let size = Vec2::from(self.size);
+        let corner_radii = Vec4::from(self.corner_radii);
+        let element = if flags.hollow() > 0 {
+            let offset = stroke_offset(flags.stroke_alignment(), self.thickness);
+            let outline = (size + 2.0 * offset).max(Vec2::ZERO);
+            let radii = (corner_radii + offset).max(Vec4::ZERO);
+            SvgElement::new("path")
+                .attr("d", rounded_rect_path(outline, radii))
+                .stroke(self.color, self.thickness)
+                .dash(self.dash)
+        } else {
+            SvgElement::new("path")
+                .attr("d", rounded_rect_path(size, corner_radii))
+                .fill(self.color)
+        };
+        Some(element.transform(self.transform()))
+    }
 }
 
 /// Extension trait for [`ShapePainter`] to enable it to draw rectangles.
 pub trait RectPainter {
     fn rect(&mut self, size: Vec2) -> &mut Self;
 
+    /// Draw a rectangle at each position relative to the painter's transform, see [`ShapePainter::send_many`].
+    fn rects(&mut self, size: Vec2, positions: impl IntoIterator<Item = Vec3>) -> &mut Self;
+
+    /// Draw a rectangle with the given corner radii ordered top-left, top-right, bottom-right, bottom-left.
+    fn rect_rounded(&mut self, size: Vec2, corner_radii: [f32; 4]) -> &mut Self;
+
     fn image(&mut self, image: Handle<Image>, size: Vec2) -> &mut Self;
 }
 
-impl<'w, 's> RectPainter for ShapePainter<'w, 's> {
+impl<P: ShapeSender> RectPainter for P {
     fn rect(&mut self, size: Vec2) -> &mut Self {
         self.send(RectData::new(self.config(), size))
     }
 
+    fn rects(&mut self, size: Vec2, positions: impl IntoIterator<Item = Vec3>) -> &mut Self {
+        self.send_many(RectData::new(self.config(), size), positions)
+    }
+
+    fn rect_rounded(&mut self, size: Vec2, corner_radii: [f32; 4]) -> &mut Self {
+        let mut data = RectData::new(self.config(), size);
+        data.corner_radii = rounded_corners(corner_radii).into();
+        self.send(data)
+    }
+
     fn image(&mut self, image: Handle<Image>, size: Vec2) -> &mut Self {
         let mut config = self.config().clone();
         config.texture = Some(image);
@@ -157,21 +381,95 @@ impl<'w, 's> RectPainter for ShapePainter<'w, 's> {
 /// Extension trait for [`ShapeBundle`] to enable creation of rectangle bundles.
 pub trait RectangleBundle {
     fn rect(config: &ShapeConfig, size: Vec2) -> Self;
+
+    fn rect_rounded(config: &ShapeConfig, size: Vec2, corner_radii: [f32; 4]) -> Self;
 }
 
 impl RectangleBundle for ShapeBundle<RectangleComponent> {
     fn rect(config: &ShapeConfig, size: Vec2) -> Self {
         Self::new(config, RectangleComponent::new(config, size))
     }
+
+    fn rect_rounded(config: &ShapeConfig, size: Vec2, corner_radii: [f32; 4]) -> Self {
+        let mut component = RectangleComponent::new(config, size);
+        component.corner_radii = rounded_corners(corner_radii);
+        Self::new(config, component)
+    }
 }
 
 /// Extension trait for [`ShapeSpawner`] to enable spawning of rectangle entities.
 pub trait RectangleSpawner<'w> {
     fn rect(&mut self, size: Vec2) -> ShapeEntityCommands;
+
+    fn rect_rounded(&mut self, size: Vec2, corner_radii: [f32; 4]) -> ShapeEntityCommands;
 }
 
 impl<'w, T: ShapeSpawner<'w>> RectangleSpawner<'w> for T {
     fn rect(&mut self, size: Vec2) -> ShapeEntityCommands {
         self.spawn_shape(ShapeBundle::rect(self.config(), size))
     }
+
+    fn rect_rounded(&mut self, size: Vec2, corner_radii: [f32; 4]) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::rect_rounded(self.config(), size, corner_radii))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filled() {
+        let rect = RectangleComponent {
+            size: Vec2::splat(2.0),
+            ..default()
+        };
+        assert!(rect.contains(Vec2::new(0.9, 0.9), None));
+        assert!(rect.contains(Vec2::new(1.0, 0.0), None));
+        assert!(!rect.contains(Vec2::new(1.1, 0.0), None));
+    }
+
+    #[test]
+    fn rounded() {
+        let rounded = RectangleComponent {
+            size: Vec2::splat(2.0),
+            corner_radii: Vec4::splat(0.5),
+            ..default()
+        };
+        assert!(!rounded.contains(Vec2::new(0.95, 0.95), None));
+        assert!(rounded.contains(Vec2::new(0.5, 0.9), None));
+
+        // Only the top-right corner is rounded, given clockwise from the top-left corner
+        let top_right = RectangleComponent {
+            size: Vec2::splat(2.0),
+            corner_radii: rounded_corners([0.0, 0.5, 0.0, 0.0]),
+            ..default()
+        };
+        assert!(!top_right.contains(Vec2::new(0.95, 0.95), None));
+        assert!(top_right.contains(Vec2::new(-0.95, 0.95), None));
+        assert!(top_right.contains(Vec2::new(-0.95, -0.95), None));
+        assert!(top_right.contains(Vec2::new(0.95, -0.95), None));
+    }
+
+    #[test]
+    fn anchored() {
+        let rect = RectangleComponent {
+            size: Vec2::splat(2.0),
+            anchor: Anchor::BottomLeft,
+            ..default()
+        };
+        assert!(rect.contains(Vec2::new(1.5, 1.5), None));
+        assert!(!rect.contains(Vec2::new(-0.5, -0.5), None));
+    }
+
+    #[test]
+    fn hollow() {
+        let rect = RectangleComponent {
+            size: Vec2::splat(2.0),
+            stroke_alignment: StrokeAlignment::Inside,
+            ..default()
+        };
+        assert!(rect.contains(Vec2::new(0.95, 0.0), Some(0.2)));
+        assert!(!rect.contains(Vec2::ZERO, Some(0.2)));
+    }
 }
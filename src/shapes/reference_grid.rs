@@ -0,0 +1,272 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::render_resource::{ShaderRef, ShaderType},
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, REFERENCE_GRID_HANDLE},
+};
+
+/// Describes the lines of an editor style reference grid, see [`ReferenceGridPainter`].
+///
+/// Every line is drawn with the shape's thickness. Major lines are drawn with the shape's color every `major_every`
+/// cells, the lines of the remaining cells are drawn with `minor_color` and fade out as they become too dense to tell
+/// apart.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReferenceGrid {
+    /// Spacing between minor lines.
+    pub cell_size: f32,
+    /// Number of cells between major lines, 0 draws no major lines.
+    pub major_every: u32,
+    pub minor_color: Color,
+    /// Color of the line along the x axis of the grid's transform.
+    pub x_axis_color: Color,
+    /// Color of the line along the y axis of the grid's transform.
+    pub y_axis_color: Color,
+    /// Distance from the point beneath the camera at which the grid has completely faded out.
+    pub fade_distance: f32,
+}
+
+impl ReferenceGrid {
+    pub fn new(cell_size: f32, major_every: u32) -> Self {
+        Self {
+            cell_size,
+            major_every,
+            ..default()
+        }
+    }
+
+    pub fn with_minor_color(mut self, color: Color) -> Self {
+        self.minor_color = color;
+        self
+    }
+
+    pub fn with_axis_colors(mut self, x_axis_color: Color, y_axis_color: Color) -> Self {
+        self.x_axis_color = x_axis_color;
+        self.y_axis_color = y_axis_color;
+        self
+    }
+
+    pub fn with_fade_distance(mut self, fade_distance: f32) -> Self {
+        self.fade_distance = fade_distance;
+        self
+    }
+
+    /// Returns the grid with the alpha and emissive intensity of the config applied to it's colors.
+    fn faded(&self, config: &ShapeConfig) -> Self {
+        Self {
+            minor_color: config.fade(self.minor_color),
+            x_axis_color: config.fade(self.x_axis_color),
+            y_axis_color: config.fade(self.y_axis_color),
+            ..*self
+        }
+    }
+}
+
+impl Default for ReferenceGrid {
+    fn default() -> Self {
+        Self {
+            cell_size: 1.0,
+            major_every: 10,
+            minor_color: Color::rgba(0.5, 0.5, 0.5, 0.3),
+            x_axis_color: Color::RED,
+            y_axis_color: Color::GREEN,
+            fade_distance: 100.0,
+        }
+    }
+}
+
+/// Component containing the data for drawing an infinite reference grid.
+///
+/// The grid lies in the xy plane of it's transform and is drawn as a single quad that follows the camera, so it
+/// appears to extend forever while costing the same as any other shape. Rotate the transform to lay it on the ground
+/// of a 3D scene, for example with `Quat::from_rotation_x(-FRAC_PI_2)` the grid's y axis runs along world -z.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReferenceGridComponent {
+    pub grid: ReferenceGrid,
+}
+
+impl ReferenceGridComponent {
+    pub fn new(config: &ShapeConfig, grid: &ReferenceGrid) -> Self {
+        Self {
+            grid: grid.faded(&config.without_alpha()),
+        }
+    }
+}
+
+impl ShapeComponent for ReferenceGridComponent {
+    type Data = ReferenceGridData;
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> ReferenceGridData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+
+        ReferenceGridData::from_grid(
+            tf.compute_matrix(),
+            fill.color,
+            thickness,
+            flags,
+            &self.grid,
+        )
+    }
+}
+
+/// Raw data sent to the reference grid shader to draw an infinite grid
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct ReferenceGridData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    cell_size: f32,
+    fade_distance: f32,
+    minor_color: [f32; 4],
+    x_axis_color: [f32; 4],
+    y_axis_color: [f32; 4],
+    major_every: u32,
+
+    padding: [f32; 3],
+}
+
+impl ReferenceGridData {
+    pub fn new(config: &ShapeConfig, grid: &ReferenceGrid) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+
+        Self::from_grid(
+            config.transform.compute_matrix(),
+            config.faded_color(),
+            config.thickness,
+            flags,
+            &grid.faded(config),
+        )
+    }
+
+    fn from_grid(
+        transform: Mat4,
+        color: Color,
+        thickness: f32,
+        flags: Flags,
+        grid: &ReferenceGrid,
+    ) -> Self {
+        Self {
+            transform: transform.to_cols_array_2d(),
+
+            color: color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            cell_size: grid.cell_size,
+            fade_distance: grid.fade_distance,
+            minor_color: grid.minor_color.as_linear_rgba_f32(),
+            x_axis_color: grid.x_axis_color.as_linear_rgba_f32(),
+            y_axis_color: grid.y_axis_color.as_linear_rgba_f32(),
+            major_every: grid.major_every,
+
+            padding: default(),
+        }
+    }
+}
+
+impl ShapeData for ReferenceGridData {
+    type Component = ReferenceGridComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32,
+            8 => Float32,
+            9 => Float32x4,
+            10 => Float32x4,
+            11 => Float32x4,
+            12 => Uint32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        REFERENCE_GRID_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+        self.minor_color = tint_color(self.minor_color, color);
+        self.x_axis_color = tint_color(self.x_axis_color, color);
+        self.y_axis_color = tint_color(self.y_axis_color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, _constant_screen_size: bool) {
+        // The grid is infinite, so it has no size to keep constant
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw infinite reference grids.
+pub trait ReferenceGridPainter {
+    /// Draw an infinite grid in the xy plane of the painter's transform, see [`ReferenceGridComponent`].
+    ///
+    /// Lines are drawn with the painter's thickness, [`ThicknessType::Pixels`] keeps them the same width at any
+    /// distance.
+    fn reference_grid(&mut self, grid: &ReferenceGrid) -> &mut Self;
+}
+
+impl<P: ShapeSender> ReferenceGridPainter for P {
+    fn reference_grid(&mut self, grid: &ReferenceGrid) -> &mut Self {
+        self.send(ReferenceGridData::new(self.config(), grid))
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of reference grid bundles.
+pub trait ReferenceGridBundle {
+    fn reference_grid(config: &ShapeConfig, grid: &ReferenceGrid) -> Self;
+}
+
+impl ReferenceGridBundle for ShapeBundle<ReferenceGridComponent> {
+    fn reference_grid(config: &ShapeConfig, grid: &ReferenceGrid) -> Self {
+        let mut bundle = Self::new(config, ReferenceGridComponent::new(config, grid));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of reference grid entities.
+pub trait ReferenceGridSpawner<'w> {
+    fn reference_grid(&mut self, grid: &ReferenceGrid) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> ReferenceGridSpawner<'w> for T {
+    fn reference_grid(&mut self, grid: &ReferenceGrid) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::reference_grid(self.config(), grid))
+    }
+}
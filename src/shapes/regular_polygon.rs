@@ -1,23 +1,36 @@
+use std::f32::consts::TAU;
+
 use bevy::{
     prelude::*,
     reflect::Reflect,
-    render::render_resource::{ShaderRef, ShaderType},
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+    sprite::Anchor,
 };
 use wgpu::vertex_attr_array;
 
 use crate::{
     prelude::*,
-    render::{Flags, ShapeComponent, ShapeData, NGON_HANDLE},
+    render::{stroke_offset, tint_color, Flags, ShapeComponent, ShapeData, NGON_HANDLE},
 };
 
 /// Component containing the data for drawing a regular polygon.
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Clone)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct RegularPolygonComponent {
     pub color: Color,
     pub thickness: f32,
     pub thickness_type: ThicknessType,
     pub alignment: Alignment,
+    /// Placement of the outline relative to the edge of the shape.
+    pub stroke_alignment: StrokeAlignment,
     pub hollow: bool,
+    /// Point on the square bounding the polygon's circumcircle placed at the origin of it's transform.
+    #[cfg_attr(feature = "serialize", serde(with = "crate::serde_remote::AnchorDef"))]
+    pub anchor: Anchor,
 
     /// Number of sides, non-integer values may have unexpected results.
     pub sides: f32,
@@ -25,20 +38,32 @@ pub struct RegularPolygonComponent {
     pub radius: f32,
     /// Corner rounding radius for all corner in world units.
     pub roundness: f32,
+    /// Gradient blending from the fill color, see [`ShapeConfig::gradient`].
+    pub gradient: Option<Gradient>,
+    /// Number of times the texture repeats across the shape, see [`ShapeConfig::texture_tiling`].
+    pub texture_tiling: Vec2,
+    /// Offset of the texture across the shape, see [`ShapeConfig::texture_offset`].
+    pub texture_offset: Vec2,
 }
 
 impl RegularPolygonComponent {
     pub fn new(config: &ShapeConfig, sides: f32, radius: f32) -> Self {
+        let opaque = config.without_alpha();
         Self {
-            color: config.color,
+            color: opaque.faded_color(),
             thickness: config.thickness,
             thickness_type: config.thickness_type,
             alignment: config.alignment,
+            stroke_alignment: config.stroke_alignment,
             hollow: config.hollow,
+            anchor: config.anchor,
 
             sides,
             radius,
             roundness: config.roundness,
+            gradient: opaque.faded_gradient(),
+            texture_tiling: config.texture_tiling,
+            texture_offset: config.texture_offset,
         }
     }
 }
@@ -46,9 +71,53 @@ impl RegularPolygonComponent {
 impl ShapeComponent for RegularPolygonComponent {
     type Data = NgonData;
 
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let center = -self.anchor.as_vec() * self.radius * 2.0;
+        let radius = Vec2::splat(self.radius.abs());
+        shape_aabb(
+            center - radius,
+            center + radius,
+            self.alignment,
+            fill,
+            // Outlines are offset from the edges, which are nearer to the center than the vertices
+            |thickness| stroke_offset(self.stroke_alignment, thickness) + thickness / 2.0,
+        )
+    }
+
+    fn contains(&self, point: Vec2, thickness: Option<f32>) -> bool {
+        let point = point + self.anchor.as_vec() * self.radius * 2.0;
+        let central_angle = TAU / self.sides.max(3.0);
+        let apothem = self.radius * (central_angle / 2.0).cos();
+        let half_side_length = self.radius * (central_angle / 2.0).sin();
+        let roundness = self.roundness.clamp(0.0, apothem);
+
+        // Measure from the nearest side, with the first vertex pointing up as in the shader
+        let point = point.yx();
+        let angle = point.y.atan2(point.x);
+        let nearest_angle = central_angle * ((angle + 0.5 * central_angle) / central_angle).floor();
+        let (sin, cos) = nearest_angle.sin_cos();
+        let pos = Vec2::new(
+            cos * point.x + sin * point.y,
+            -sin * point.x + cos * point.y,
+        );
+        let half_side_length = half_side_length * (1.0 - roundness / apothem);
+        let nearest = Vec2::new(
+            apothem - roundness,
+            pos.y.clamp(-half_side_length, half_side_length),
+        );
+        let dist = (pos - nearest).length() * (pos.x - nearest.x).signum() - roundness;
+
+        match thickness {
+            Some(thickness) => {
+                (dist - stroke_offset(self.stroke_alignment, thickness)).abs() <= thickness / 2.0
+            }
+            None => dist <= 0.0,
+        }
+    }
+
     fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> NgonData {
         let mut flags = Flags(0);
-        let thickness = match fill.ty {
+        let mut thickness = match fill.ty {
             FillType::Stroke(thickness, thickness_type) => {
                 flags.set_thickness_type(thickness_type);
                 flags.set_hollow(1);
@@ -57,6 +126,14 @@ impl ShapeComponent for RegularPolygonComponent {
             FillType::Fill => 1.0,
         };
         flags.set_alignment(self.alignment);
+        flags.set_stroke_alignment(self.stroke_alignment);
+        let tf = tf.mul_transform(anchor_transform(
+            &self.anchor,
+            Vec2::splat(self.radius * 2.0),
+        ));
+        let (gradient_type, gradient_color, gradient) = gradient_data(self.gradient);
+        flags.set_gradient(gradient_type);
+        let stroke_color = stroke_data(fill.outline(), &mut flags, &mut thickness);
 
         NgonData {
             transform: tf.compute_matrix().to_cols_array_2d(),
@@ -68,8 +145,11 @@ impl ShapeComponent for RegularPolygonComponent {
             sides: self.sides,
             radius: self.radius,
             roundness: self.roundness,
-
-            padding: default(),
+            gradient_color,
+            gradient,
+            texture_uv: texture_data(self.texture_tiling, self.texture_offset),
+            stroke_color,
+            user_data: fill.user_data,
         }
     }
 }
@@ -81,11 +161,16 @@ impl Default for RegularPolygonComponent {
             thickness: 1.0,
             thickness_type: default(),
             alignment: default(),
+            stroke_alignment: default(),
             hollow: false,
+            anchor: default(),
 
             sides: 3.0,
             radius: 1.0,
             roundness: 0.0,
+            gradient: None,
+            texture_tiling: Vec2::ONE,
+            texture_offset: Vec2::ZERO,
         }
     }
 }
@@ -103,8 +188,11 @@ pub struct NgonData {
     sides: f32,
     radius: f32,
     roundness: f32,
-
-    padding: [f32; 3],
+    gradient_color: Vec4,
+    gradient: Vec4,
+    texture_uv: Vec4,
+    stroke_color: Vec4,
+    user_data: Vec4,
 }
 
 impl NgonData {
@@ -112,20 +200,30 @@ impl NgonData {
         let mut flags = Flags(0);
         flags.set_thickness_type(config.thickness_type);
         flags.set_alignment(config.alignment);
+        flags.set_stroke_alignment(config.stroke_alignment);
         flags.set_hollow(config.hollow as u32);
+        let transform =
+            config.transform * anchor_transform(&config.anchor, Vec2::splat(radius * 2.0));
+        let (gradient_type, gradient_color, gradient) = gradient_data(config.faded_gradient());
+        flags.set_gradient(gradient_type);
+        let mut thickness = config.thickness;
+        let stroke_color = stroke_data(config.outline(), &mut flags, &mut thickness);
 
         NgonData {
-            transform: config.transform.compute_matrix().to_cols_array_2d(),
+            transform: transform.compute_matrix().to_cols_array_2d(),
 
-            color: config.color.as_linear_rgba_f32(),
-            thickness: config.thickness,
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness,
             flags: flags.0,
 
             sides,
             radius,
             roundness: config.roundness,
-
-            padding: default(),
+            gradient_color,
+            gradient,
+            texture_uv: texture_data(config.texture_tiling, config.texture_offset),
+            stroke_color,
+            user_data: config.user_data,
         }
     }
 }
@@ -145,7 +243,12 @@ impl ShapeData for NgonData {
             6 => Uint32,
             7 => Float32,
             8 => Float32,
-            9 => Float32
+            9 => Float32,
+            10 => Float32x4,
+            11 => Float32x4,
+            12 => Float32x4,
+            13 => Float32x4,
+            14 => Float32x4,
         ]
         .to_vec()
     }
@@ -157,17 +260,75 @@ impl ShapeData for NgonData {
     fn transform(&self) -> Mat4 {
         Mat4::from_cols_array_2d(&self.transform)
     }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+        self.gradient_color = tint_color(self.gradient_color.into(), color).into();
+        self.stroke_color = tint_color(self.stroke_color.into(), color).into();
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+
+    fn shadow(&self, shadow: &Shadow, thickness_type: ThicknessType) -> Option<Self> {
+        if Flags(self.flags).hollow() > 0 {
+            return None;
+        }
+        Some(Self {
+            transform: shadow_transform(self.transform, shadow.offset),
+            color: shadow.color.as_linear_rgba_f32(),
+            thickness: shadow.blur,
+            flags: shadow_flags(self.flags, thickness_type),
+            ..*self
+        })
+    }
+
+    fn glow(&self, glow: &Glow, thickness_type: ThicknessType) -> Option<Self> {
+        if Flags(self.flags).hollow() > 0 {
+            return None;
+        }
+        Some(Self {
+            color: glow.color.as_linear_rgba_f32(),
+            thickness: glow.radius,
+            flags: glow_flags(self.flags, thickness_type),
+            gradient: Vec4::new(glow.falloff, 0.0, 0.0, 0.0),
+            ..*self
+        })
+    }
 }
 
 /// Extension trait for [`ShapePainter`] to enable it to draw regular polygons.
 pub trait RegularPolygonPainter {
     fn ngon(&mut self, sides: f32, radius: f32) -> &mut Self;
+    /// Draw a regular polygon at each position relative to the painter's transform, see [`ShapePainter::send_many`].
+    fn ngons(
+        &mut self,
+        sides: f32,
+        radius: f32,
+        positions: impl IntoIterator<Item = Vec3>,
+    ) -> &mut Self;
 }
 
-impl<'w, 's> RegularPolygonPainter for ShapePainter<'w, 's> {
+impl<P: ShapeSender> RegularPolygonPainter for P {
     fn ngon(&mut self, sides: f32, radius: f32) -> &mut Self {
         self.send(NgonData::new(self.config(), sides, radius))
     }
+
+    fn ngons(
+        &mut self,
+        sides: f32,
+        radius: f32,
+        positions: impl IntoIterator<Item = Vec3>,
+    ) -> &mut Self {
+        self.send_many(NgonData::new(self.config(), sides, radius), positions)
+    }
 }
 
 /// Extension trait for [`ShapeBundle`] to enable creation of regular polygon bundles.
@@ -191,3 +352,69 @@ impl<'w, T: ShapeSpawner<'w>> RegularPolygonSpawner<'w> for T {
         self.spawn_shape(ShapeBundle::ngon(self.config(), sides, radius))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    fn square() -> RegularPolygonComponent {
+        RegularPolygonComponent {
+            sides: 4.0,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn filled() {
+        // Squares are axis aligned with a half extent of the apothem
+        let square = square();
+        assert!(square.contains(Vec2::new(0.45, 0.45), None));
+        assert!(square.contains(Vec2::new(0.55, 0.55), None));
+        assert!(square.contains(Vec2::new(0.68, 0.68), None));
+        assert!(square.contains(Vec2::new(0.0, 0.7), None));
+        assert!(square.contains(Vec2::new(0.0, FRAC_PI_4.cos()), None));
+        assert!(!square.contains(Vec2::new(0.0, 0.75), None));
+        assert!(!square.contains(Vec2::new(0.0, 0.9), None));
+        assert!(!square.contains(Vec2::new(0.0, 1.05), None));
+
+        let hexagon = RegularPolygonComponent {
+            sides: 6.0,
+            ..default()
+        };
+        assert!(hexagon.contains(Vec2::new(0.8, 0.0), None));
+        assert!(hexagon.contains(Vec2::new(0.9, 0.0), None));
+        assert!(!hexagon.contains(Vec2::new(0.0, 0.99), None));
+    }
+
+    #[test]
+    fn rounded() {
+        let rounded = RegularPolygonComponent {
+            roundness: 0.3,
+            ..square()
+        };
+        assert!(!rounded.contains(Vec2::new(0.68, 0.68), None));
+        assert!(rounded.contains(Vec2::new(0.0, 0.5), None));
+    }
+
+    #[test]
+    fn anchored() {
+        let anchored = RegularPolygonComponent {
+            anchor: Anchor::BottomLeft,
+            ..square()
+        };
+        assert!(anchored.contains(Vec2::ONE, None));
+        assert!(!anchored.contains(Vec2::ZERO, None));
+    }
+
+    #[test]
+    fn hollow() {
+        let hollow = RegularPolygonComponent {
+            stroke_alignment: StrokeAlignment::Inside,
+            ..square()
+        };
+        assert!(hollow.contains(Vec2::new(0.0, 0.65), Some(0.2)));
+        assert!(!hollow.contains(Vec2::ZERO, Some(0.2)));
+    }
+}
@@ -0,0 +1,61 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::prelude::*;
+
+use crate::prelude::*;
+
+/// Directions of the four arms of a crosshair or set of chevrons.
+const DIRECTIONS: [Vec2; 4] = [Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y];
+
+/// Extension trait for [`ShapePainter`] to enable it to draw HUD crosshairs and reticles.
+///
+/// Each reticle is centered on the painter's transform and drawn with the painter's thickness and cap,
+/// the painter's config is left unchanged.
+pub trait ReticlePainter {
+    /// Draw four lines of the given length along the x and y axis, leaving a gap of `gap` around the center.
+    fn crosshair(&mut self, gap: f32, length: f32) -> &mut Self;
+    /// Draw a ring with `ticks` evenly spaced tick marks pointing outwards from it, starting from the top.
+    fn reticle(&mut self, radius: f32, ticks: u32, tick_length: f32) -> &mut Self;
+    /// Draw four chevrons pointing towards the center with their tips `gap` away from it.
+    fn chevrons(&mut self, gap: f32, size: f32) -> &mut Self;
+}
+
+impl<'w, 's> ReticlePainter for ShapePainter<'w, 's> {
+    fn crosshair(&mut self, gap: f32, length: f32) -> &mut Self {
+        for dir in DIRECTIONS {
+            self.line((dir * gap).extend(0.0), (dir * (gap + length)).extend(0.0));
+        }
+        self
+    }
+
+    fn reticle(&mut self, radius: f32, ticks: u32, tick_length: f32) -> &mut Self {
+        self.with_children(|painter| {
+            painter.hollow = true;
+            painter.circle(radius);
+        });
+
+        for i in 0..ticks {
+            let angle = FRAC_PI_2 + TAU * i as f32 / ticks as f32;
+            let dir = Vec2::from_angle(angle);
+            self.line(
+                (dir * radius).extend(0.0),
+                (dir * (radius + tick_length)).extend(0.0),
+            );
+        }
+        self
+    }
+
+    fn chevrons(&mut self, gap: f32, size: f32) -> &mut Self {
+        for dir in DIRECTIONS {
+            let tip = dir * gap;
+            let back = dir * (gap + size);
+            let side = dir.perp() * size;
+            self.polyline(&[
+                (back + side).extend(0.0),
+                tip.extend(0.0),
+                (back - side).extend(0.0),
+            ]);
+        }
+        self
+    }
+}
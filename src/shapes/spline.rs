@@ -0,0 +1,380 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, POLYLINE_HANDLE},
+};
+
+/// Defines how the control points of a spline are interpolated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum SplineType {
+    /// Uniform Catmull-Rom spline, passes through every control point.
+    #[default]
+    CatmullRom,
+    /// Uniform cubic B-spline, smoother but only passes through the end points of an open spline.
+    BSpline,
+}
+
+/// Component containing the data for drawing a spline.
+///
+/// The spline is subdivided on the CPU and each resulting segment is drawn by the polyline shader.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplineComponent {
+    pub alignment: Alignment,
+    /// Cap type for the ends of an open spline.
+    pub cap: Cap,
+    /// Join type for the corners between subdivided segments.
+    pub join: Join,
+    /// Ratio of miter length to half thickness past which miter joins are drawn as bevel joins.
+    pub miter_limit: f32,
+    pub ty: SplineType,
+    /// Whether to connect the last control point back to the first.
+    pub closed: bool,
+    /// Number of straight segments drawn between each pair of control points.
+    pub subdivisions: u32,
+
+    /// Control points of the spline in the local space of it's transform.
+    pub points: Vec<Vec2>,
+}
+
+impl SplineComponent {
+    pub fn new(config: &ShapeConfig, ty: SplineType, points: &[Vec2], closed: bool) -> Self {
+        Self {
+            alignment: config.alignment,
+            cap: config.cap,
+            join: config.join,
+            miter_limit: config.miter_limit,
+            ty,
+            closed,
+            subdivisions: config.spline_subdivisions,
+
+            points: points.to_vec(),
+        }
+    }
+}
+
+impl Default for SplineComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+            cap: default(),
+            join: default(),
+            miter_limit: 4.0,
+            ty: default(),
+            closed: false,
+            subdivisions: 16,
+
+            points: Vec::new(),
+        }
+    }
+}
+
+/// Evaluate a single segment of a spline at t given it's four surrounding control points.
+fn spline_point(ty: SplineType, [p0, p1, p2, p3]: [Vec2; 4], t: f32) -> Vec2 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    match ty {
+        SplineType::CatmullRom => {
+            0.5 * (2.0 * p1
+                + (p2 - p0) * t
+                + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+        }
+        SplineType::BSpline => {
+            let u = 1.0 - t;
+            (u * u * u * p0
+                + (3.0 * t3 - 6.0 * t2 + 4.0) * p1
+                + (3.0 * t2 - 3.0 * t3 + 3.0 * t + 1.0) * p2
+                + t3 * p3)
+                / 6.0
+        }
+    }
+}
+
+/// Subdivide a spline into the points of a polyline, see [`polyline_segments`].
+///
+/// Open splines are extended past their end points so that the curve begins and ends exactly on them.
+pub fn spline_points(
+    ty: SplineType,
+    points: &[Vec2],
+    closed: bool,
+    subdivisions: u32,
+) -> Vec<Vec2> {
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 2 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let count = points.len();
+    if count < 2 {
+        return points;
+    }
+
+    let closed = closed && count > 2;
+    let windows: Vec<[Vec2; 4]> = if closed {
+        (0..count)
+            .map(|i| {
+                [
+                    points[(i + count - 1) % count],
+                    points[i],
+                    points[(i + 1) % count],
+                    points[(i + 2) % count],
+                ]
+            })
+            .collect()
+    } else {
+        let (first, last) = (points[0], points[count - 1]);
+        let extended: Vec<Vec2> = match ty {
+            // Reflect the neighbouring points so the tangent at each end follows the first and last segments
+            SplineType::CatmullRom => std::iter::once(2.0 * first - points[1])
+                .chain(points.iter().copied())
+                .chain(std::iter::once(2.0 * last - points[count - 2]))
+                .collect(),
+            // Tripling the end points clamps the curve to them
+            SplineType::BSpline => [first, first]
+                .into_iter()
+                .chain(points.iter().copied())
+                .chain([last, last])
+                .collect(),
+        };
+        extended
+            .windows(4)
+            .map(|w| [w[0], w[1], w[2], w[3]])
+            .collect()
+    };
+
+    let subdivisions = subdivisions.max(1);
+    let mut curve = Vec::with_capacity(windows.len() * subdivisions as usize + 1);
+    for window in &windows {
+        for i in 0..subdivisions {
+            curve.push(spline_point(ty, *window, i as f32 / subdivisions as f32));
+        }
+    }
+    if !closed {
+        curve.push(spline_point(ty, windows[windows.len() - 1], 1.0));
+    }
+    curve
+}
+
+impl ShapeComponent for SplineComponent {
+    type Data = SplineData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        // Catmull-Rom splines overshoot their points so bound the subdivided curve instead
+        let points = spline_points(self.ty, &self.points, self.closed, self.subdivisions);
+        let (min, max) = points_bounds(&points)?;
+        stroke_aabb(
+            min.extend(0.0),
+            max.extend(0.0),
+            self.alignment,
+            fill,
+            |thickness| join_margin(self.join, self.miter_limit, thickness),
+        )
+    }
+
+    /// Returns the data for the first segment of the spline, see [`ShapeComponent::get_instances`] for the full set.
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> SplineData {
+        let mut first = None;
+        self.get_instances(tf, fill, |data| {
+            first.get_or_insert(data);
+        });
+        first.unwrap_or_default()
+    }
+
+    fn get_instances(&self, tf: &GlobalTransform, fill: &ShapeFill, mut f: impl FnMut(SplineData)) {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+        flags.set_cap(self.cap);
+        flags.set_join(self.join);
+
+        let transform = tf.compute_matrix().to_cols_array_2d();
+        let color = fill.color.as_linear_rgba_f32();
+        let points = spline_points(self.ty, &self.points, self.closed, self.subdivisions);
+        for [prev, start, end, next] in polyline_segments(&points, self.closed) {
+            f(SplineData {
+                transform,
+
+                color,
+                thickness,
+                flags: flags.0,
+
+                prev: prev.extend(0.0),
+                start: start.extend(0.0),
+                end: end.extend(0.0),
+                next: next.extend(0.0),
+
+                miter_limit: self.miter_limit,
+            })
+        }
+    }
+}
+
+/// Raw data sent to the polyline shader to draw a single subdivided segment of a spline
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct SplineData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    prev: Vec3,
+    start: Vec3,
+    end: Vec3,
+    next: Vec3,
+
+    miter_limit: f32,
+}
+
+impl SplineData {
+    pub fn new(config: &ShapeConfig, prev: Vec2, start: Vec2, end: Vec2, next: Vec2) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+        flags.set_cap(config.cap);
+        flags.set_join(config.join);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            prev: prev.extend(0.0),
+            start: start.extend(0.0),
+            end: end.extend(0.0),
+            next: next.extend(0.0),
+
+            miter_limit: config.miter_limit,
+        }
+    }
+}
+
+impl ShapeData for SplineData {
+    type Component = SplineComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32x3,
+            8 => Float32x3,
+            9 => Float32x3,
+            10 => Float32x3,
+            11 => Float32,
+        ]
+        .to_vec()
+    }
+
+    /// Shares the polyline shader as [`SplineData`] has an identical layout to [`PolylineData`].
+    fn shader() -> ShaderRef {
+        POLYLINE_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw splines.
+pub trait SplinePainter {
+    fn spline(&mut self, ty: SplineType, points: &[Vec2]) -> &mut Self;
+    fn closed_spline(&mut self, ty: SplineType, points: &[Vec2]) -> &mut Self;
+}
+
+impl<P: ShapeSender> SplinePainter for P {
+    fn spline(&mut self, ty: SplineType, points: &[Vec2]) -> &mut Self {
+        let points = spline_points(ty, points, false, self.spline_subdivisions);
+        for [prev, start, end, next] in polyline_segments(&points, false) {
+            self.send(SplineData::new(self.config(), prev, start, end, next));
+        }
+        self
+    }
+
+    fn closed_spline(&mut self, ty: SplineType, points: &[Vec2]) -> &mut Self {
+        let points = spline_points(ty, points, true, self.spline_subdivisions);
+        for [prev, start, end, next] in polyline_segments(&points, true) {
+            self.send(SplineData::new(self.config(), prev, start, end, next));
+        }
+        self
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of spline bundles.
+pub trait SplineBundle {
+    fn spline(config: &ShapeConfig, ty: SplineType, points: &[Vec2]) -> Self;
+    fn closed_spline(config: &ShapeConfig, ty: SplineType, points: &[Vec2]) -> Self;
+}
+
+impl SplineBundle for ShapeBundle<SplineComponent> {
+    fn spline(config: &ShapeConfig, ty: SplineType, points: &[Vec2]) -> Self {
+        let mut bundle = Self::new(config, SplineComponent::new(config, ty, points, false));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+
+    fn closed_spline(config: &ShapeConfig, ty: SplineType, points: &[Vec2]) -> Self {
+        let mut bundle = Self::new(config, SplineComponent::new(config, ty, points, true));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of spline entities.
+pub trait SplineSpawner<'w> {
+    fn spline(&mut self, ty: SplineType, points: &[Vec2]) -> ShapeEntityCommands;
+    fn closed_spline(&mut self, ty: SplineType, points: &[Vec2]) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> SplineSpawner<'w> for T {
+    fn spline(&mut self, ty: SplineType, points: &[Vec2]) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::spline(self.config(), ty, points))
+    }
+
+    fn closed_spline(&mut self, ty: SplineType, points: &[Vec2]) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::closed_spline(self.config(), ty, points))
+    }
+}
@@ -0,0 +1,281 @@
+use std::f32::consts::PI;
+
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, STAR_HANDLE},
+};
+
+/// Component containing the data for drawing a star.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct StarComponent {
+    pub alignment: Alignment,
+
+    /// Number of points, non-integer values may have unexpected results.
+    pub points: f32,
+    /// Radius to the corners between two points.
+    pub inner_radius: f32,
+    /// Radius to the tip of a point.
+    pub outer_radius: f32,
+    /// Rounding radius for the tip of each point in world units.
+    pub roundness: f32,
+}
+
+impl StarComponent {
+    pub fn new(config: &ShapeConfig, points: f32, inner_radius: f32, outer_radius: f32) -> Self {
+        Self {
+            alignment: config.alignment,
+
+            points,
+            inner_radius,
+            outer_radius,
+            roundness: config.roundness,
+        }
+    }
+
+    /// Create a star with each tip rounded into a full arc, see [`burst_roundness`].
+    pub fn burst(config: &ShapeConfig, points: f32, inner_radius: f32, outer_radius: f32) -> Self {
+        Self {
+            roundness: burst_roundness(points, inner_radius, outer_radius),
+            ..Self::new(config, points, inner_radius, outer_radius)
+        }
+    }
+}
+
+impl Default for StarComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+
+            points: 5.0,
+            inner_radius: 0.5,
+            outer_radius: 1.0,
+            roundness: 0.0,
+        }
+    }
+}
+
+/// Calculate the largest tip roundness for a star such that the rounded tips reach the inner corners.
+pub fn burst_roundness(points: f32, inner_radius: f32, outer_radius: f32) -> f32 {
+    let half_angle = PI / points;
+    let tip = Vec2::new(outer_radius, 0.0);
+    let inner = inner_radius * Vec2::new(half_angle.cos(), half_angle.sin());
+    let edge = (inner - tip).normalize_or_zero();
+    if edge.x >= 0.0 {
+        return 0.0;
+    }
+
+    // The circle at the tip touches the edge at the inner corner, so moving one radius along the
+    // inward normal of the edge from the inner corner must land on the axis of the tip
+    inner.y / -edge.x
+}
+
+impl ShapeComponent for StarComponent {
+    type Data = StarData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let radius = Vec2::splat(self.outer_radius.abs().max(self.inner_radius.abs()));
+        shape_aabb(-radius, radius, self.alignment, fill, |thickness| thickness)
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> StarData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+
+        StarData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            points: self.points,
+            inner_radius: self.inner_radius,
+            outer_radius: self.outer_radius,
+            roundness: self.roundness,
+
+            padding: default(),
+        }
+    }
+}
+
+/// Raw data sent to the star shader to draw a star
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct StarData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    points: f32,
+    inner_radius: f32,
+    outer_radius: f32,
+    roundness: f32,
+
+    padding: [f32; 2],
+}
+
+impl StarData {
+    pub fn new(config: &ShapeConfig, points: f32, inner_radius: f32, outer_radius: f32) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+        flags.set_hollow(config.hollow as u32);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            points,
+            inner_radius,
+            outer_radius,
+            roundness: config.roundness,
+
+            padding: default(),
+        }
+    }
+}
+
+impl ShapeData for StarData {
+    type Component = StarComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32,
+            8 => Float32,
+            9 => Float32,
+            10 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        STAR_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw stars.
+pub trait StarPainter {
+    fn star(&mut self, points: f32, inner_radius: f32, outer_radius: f32) -> &mut Self;
+
+    /// Draw a star with each tip rounded into a full arc, ignoring the configured roundness.
+    fn burst(&mut self, points: f32, inner_radius: f32, outer_radius: f32) -> &mut Self;
+}
+
+impl<P: ShapeSender> StarPainter for P {
+    fn star(&mut self, points: f32, inner_radius: f32, outer_radius: f32) -> &mut Self {
+        self.send(StarData::new(
+            self.config(),
+            points,
+            inner_radius,
+            outer_radius,
+        ))
+    }
+
+    fn burst(&mut self, points: f32, inner_radius: f32, outer_radius: f32) -> &mut Self {
+        let mut data = StarData::new(self.config(), points, inner_radius, outer_radius);
+        data.roundness = burst_roundness(points, inner_radius, outer_radius);
+        self.send(data)
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of star bundles.
+pub trait StarBundle {
+    fn star(config: &ShapeConfig, points: f32, inner_radius: f32, outer_radius: f32) -> Self;
+
+    fn burst(config: &ShapeConfig, points: f32, inner_radius: f32, outer_radius: f32) -> Self;
+}
+
+impl StarBundle for ShapeBundle<StarComponent> {
+    fn star(config: &ShapeConfig, points: f32, inner_radius: f32, outer_radius: f32) -> Self {
+        Self::new(
+            config,
+            StarComponent::new(config, points, inner_radius, outer_radius),
+        )
+    }
+
+    fn burst(config: &ShapeConfig, points: f32, inner_radius: f32, outer_radius: f32) -> Self {
+        Self::new(
+            config,
+            StarComponent::burst(config, points, inner_radius, outer_radius),
+        )
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of star entities.
+pub trait StarSpawner<'w> {
+    fn star(&mut self, points: f32, inner_radius: f32, outer_radius: f32) -> ShapeEntityCommands;
+
+    fn burst(&mut self, points: f32, inner_radius: f32, outer_radius: f32) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> StarSpawner<'w> for T {
+    fn star(&mut self, points: f32, inner_radius: f32, outer_radius: f32) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::star(
+            self.config(),
+            points,
+            inner_radius,
+            outer_radius,
+        ))
+    }
+
+    fn burst(&mut self, points: f32, inner_radius: f32, outer_radius: f32) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::burst(
+            self.config(),
+            points,
+            inner_radius,
+            outer_radius,
+        ))
+    }
+}
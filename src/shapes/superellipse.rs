@@ -0,0 +1,222 @@
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, SUPERELLIPSE_HANDLE},
+};
+
+/// Exponent giving the continuous curvature squircle used by [`SuperellipsePainter::squircle`].
+pub const SQUIRCLE_EXPONENT: f32 = 4.0;
+
+/// Component containing the data for drawing a superellipse.
+///
+/// The outline is the set of points where `|x / a|^n + |y / b|^n = 1`, an exponent of 2 gives an ellipse
+/// while larger exponents approach a rectangle.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuperellipseComponent {
+    pub alignment: Alignment,
+
+    /// Width and height of the superellipse.
+    pub size: Vec2,
+    /// Exponent of the superellipse, higher values give flatter sides and tighter corners.
+    pub exponent: f32,
+}
+
+impl SuperellipseComponent {
+    pub fn new(config: &ShapeConfig, size: Vec2, exponent: f32) -> Self {
+        Self {
+            alignment: config.alignment,
+
+            size,
+            exponent,
+        }
+    }
+}
+
+impl ShapeComponent for SuperellipseComponent {
+    type Data = SuperellipseData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let half_size = self.size.abs() / 2.0;
+        shape_aabb(-half_size, half_size, self.alignment, fill, |thickness| {
+            thickness
+        })
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> SuperellipseData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+
+        SuperellipseData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            size: self.size.into(),
+            exponent: self.exponent,
+
+            padding: default(),
+        }
+    }
+}
+
+impl Default for SuperellipseComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+
+            size: Vec2::ONE,
+            exponent: SQUIRCLE_EXPONENT,
+        }
+    }
+}
+
+/// Raw data sent to the superellipse shader to draw a superellipse
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct SuperellipseData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    size: [f32; 2],
+    exponent: f32,
+
+    padding: f32,
+}
+
+impl SuperellipseData {
+    pub fn new(config: &ShapeConfig, size: Vec2, exponent: f32) -> Self {
+        let mut flags = Flags(0);
+        flags.set_alignment(config.alignment);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_hollow(config.hollow as u32);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            size: size.into(),
+            exponent,
+
+            padding: default(),
+        }
+    }
+}
+
+impl ShapeData for SuperellipseData {
+    type Component = SuperellipseComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32x2,
+            8 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        SUPERELLIPSE_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw superellipses.
+pub trait SuperellipsePainter {
+    fn superellipse(&mut self, size: Vec2, exponent: f32) -> &mut Self;
+    /// Draw a superellipse with [`SQUIRCLE_EXPONENT`].
+    fn squircle(&mut self, size: Vec2) -> &mut Self;
+}
+
+impl<P: ShapeSender> SuperellipsePainter for P {
+    fn superellipse(&mut self, size: Vec2, exponent: f32) -> &mut Self {
+        self.send(SuperellipseData::new(self.config(), size, exponent))
+    }
+
+    fn squircle(&mut self, size: Vec2) -> &mut Self {
+        self.superellipse(size, SQUIRCLE_EXPONENT)
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of superellipse bundles.
+pub trait SuperellipseBundle {
+    fn superellipse(config: &ShapeConfig, size: Vec2, exponent: f32) -> Self;
+    fn squircle(config: &ShapeConfig, size: Vec2) -> Self;
+}
+
+impl SuperellipseBundle for ShapeBundle<SuperellipseComponent> {
+    fn superellipse(config: &ShapeConfig, size: Vec2, exponent: f32) -> Self {
+        Self::new(config, SuperellipseComponent::new(config, size, exponent))
+    }
+
+    fn squircle(config: &ShapeConfig, size: Vec2) -> Self {
+        Self::superellipse(config, size, SQUIRCLE_EXPONENT)
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of superellipse entities.
+pub trait SuperellipseSpawner<'w> {
+    fn superellipse(&mut self, size: Vec2, exponent: f32) -> ShapeEntityCommands;
+    fn squircle(&mut self, size: Vec2) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> SuperellipseSpawner<'w> for T {
+    fn superellipse(&mut self, size: Vec2, exponent: f32) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::superellipse(self.config(), size, exponent))
+    }
+
+    fn squircle(&mut self, size: Vec2) -> ShapeEntityCommands {
+        self.superellipse(size, SQUIRCLE_EXPONENT)
+    }
+}
@@ -0,0 +1,538 @@
+use ab_glyph::{Font as _, PxScale, ScaleFont};
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, ShaderRef, ShaderType, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+    utils::HashMap,
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    painter::ShapeStorage,
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, GLYPH_HANDLE},
+};
+
+/// Height in pixels at which glyphs are rasterized into the atlas.
+const SDF_SIZE: f32 = 48.0;
+/// Distance in pixels stored on either side of the outline of each glyph.
+const SDF_SPREAD: usize = 6;
+/// Width in pixels of each atlas, atlases grow vertically as glyphs are added.
+const ATLAS_WIDTH: usize = 1024;
+
+/// Component containing the data for drawing a single glyph from an SDF font atlas.
+///
+/// Glyphs are generally drawn through [`TextPainter`] which handles layout and atlas generation,
+/// the atlas must be set as the texture of the shape for it to render.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlyphComponent {
+    pub alignment: Alignment,
+
+    /// Center of the glyph in the local space of it's transform.
+    pub position: Vec2,
+    /// Size of the glyph quad.
+    pub size: Vec2,
+    /// Top left corner of the glyph in the atlas in uv coordinates.
+    pub uv_min: Vec2,
+    /// Bottom right corner of the glyph in the atlas in uv coordinates.
+    pub uv_max: Vec2,
+}
+
+impl GlyphComponent {
+    pub fn new(
+        config: &ShapeConfig,
+        position: Vec2,
+        size: Vec2,
+        uv_min: Vec2,
+        uv_max: Vec2,
+    ) -> Self {
+        Self {
+            alignment: config.alignment,
+
+            position,
+            size,
+            uv_min,
+            uv_max,
+        }
+    }
+}
+
+impl Default for GlyphComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+
+            position: Vec2::ZERO,
+            size: Vec2::ONE,
+            uv_min: Vec2::ZERO,
+            uv_max: Vec2::ONE,
+        }
+    }
+}
+
+impl ShapeComponent for GlyphComponent {
+    type Data = GlyphData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let half_size = self.size.abs() / 2.0;
+        shape_aabb(
+            self.position - half_size,
+            self.position + half_size,
+            self.alignment,
+            fill,
+            |_| 0.0,
+        )
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> GlyphData {
+        let mut flags = Flags(0);
+        flags.set_alignment(self.alignment);
+
+        GlyphData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness: 1.0,
+            flags: flags.0,
+
+            position: self.position.into(),
+            size: self.size.into(),
+            uv_min: self.uv_min.into(),
+            uv_max: self.uv_max.into(),
+
+            padding: default(),
+        }
+    }
+}
+
+/// Raw data sent to the glyph shader to draw a single glyph
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct GlyphData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    position: [f32; 2],
+    size: [f32; 2],
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+
+    padding: [f32; 2],
+}
+
+impl GlyphData {
+    pub fn new(
+        config: &ShapeConfig,
+        position: Vec2,
+        size: Vec2,
+        uv_min: Vec2,
+        uv_max: Vec2,
+    ) -> Self {
+        let mut flags = Flags(0);
+        flags.set_alignment(config.alignment);
+
+        Self {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: 1.0,
+            flags: flags.0,
+
+            position: position.into(),
+            size: size.into(),
+            uv_min: uv_min.into(),
+            uv_max: uv_max.into(),
+
+            padding: default(),
+        }
+    }
+}
+
+impl ShapeData for GlyphData {
+    type Component = GlyphComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+
+            7 => Float32x2,
+            8 => Float32x2,
+            9 => Float32x2,
+            10 => Float32x2,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        GLYPH_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Location and metrics of a glyph within an [`SdfFontAtlas`], measured in atlas pixels.
+#[derive(Clone, Copy)]
+struct AtlasGlyph {
+    /// Top left corner of the glyph in the atlas.
+    min: UVec2,
+    /// Size of the glyph in the atlas, including the spread.
+    size: UVec2,
+    /// Offset from the pen position on the baseline to the bottom left corner of the glyph, y up.
+    offset: Vec2,
+}
+
+/// A single channel texture containing signed distance fields for the glyphs of a font.
+pub struct SdfFontAtlas {
+    /// Texture to be used when drawing glyphs from this atlas.
+    pub image: Handle<Image>,
+    /// Glyphs in the atlas, glyphs without an outline such as spaces are stored as `None`.
+    glyphs: HashMap<char, Option<AtlasGlyph>>,
+    data: Vec<u8>,
+    height: usize,
+    cursor: UVec2,
+    row_height: usize,
+    dirty: bool,
+}
+
+impl SdfFontAtlas {
+    fn new(images: &mut Assets<Image>) -> Self {
+        Self {
+            image: images.add(atlas_image(Vec::new(), 0)),
+            glyphs: HashMap::new(),
+            data: Vec::new(),
+            height: 0,
+            cursor: UVec2::ZERO,
+            row_height: 0,
+            dirty: false,
+        }
+    }
+
+    /// Rasterize a glyph into the atlas if it is not already present.
+    fn add_glyph(&mut self, font: &ab_glyph::FontArc, c: char) {
+        if self.glyphs.contains_key(&c) {
+            return;
+        }
+
+        let glyph = font
+            .glyph_id(c)
+            .with_scale_and_position(PxScale::from(SDF_SIZE), ab_glyph::point(0.0, 0.0));
+        let Some(outline) = font.outline_glyph(glyph) else {
+            self.glyphs.insert(c, None);
+            return;
+        };
+
+        let bounds = outline.px_bounds();
+        let width = bounds.width() as usize + SDF_SPREAD * 2;
+        let height = bounds.height() as usize + SDF_SPREAD * 2;
+
+        let mut coverage = vec![0.0; width * height];
+        outline.draw(|x, y, c| {
+            coverage[(y as usize + SDF_SPREAD) * width + x as usize + SDF_SPREAD] = c;
+        });
+        let sdf = signed_distance_field(&coverage, width, height);
+
+        // Start a new row when we run out of space, growing the atlas to fit
+        if self.cursor.x as usize + width > ATLAS_WIDTH {
+            self.cursor = UVec2::new(0, self.cursor.y + self.row_height as u32);
+            self.row_height = 0;
+        }
+        self.row_height = self.row_height.max(height);
+        let required_height = self.cursor.y as usize + self.row_height;
+        if required_height > self.height {
+            self.height = required_height.next_power_of_two();
+            self.data.resize(ATLAS_WIDTH * self.height, 0);
+        }
+
+        for y in 0..height {
+            let start = (self.cursor.y as usize + y) * ATLAS_WIDTH + self.cursor.x as usize;
+            self.data[start..start + width].copy_from_slice(&sdf[y * width..(y + 1) * width]);
+        }
+
+        let spread = SDF_SPREAD as f32;
+        self.glyphs.insert(
+            c,
+            Some(AtlasGlyph {
+                min: self.cursor,
+                size: UVec2::new(width as u32, height as u32),
+                offset: Vec2::new(bounds.min.x - spread, -bounds.max.y - spread),
+            }),
+        );
+        self.cursor.x += width as u32;
+        self.dirty = true;
+    }
+
+    /// Upload the atlas to it's image if any glyphs were added since the last upload.
+    fn update_image(&mut self, images: &mut Assets<Image>) {
+        if self.dirty {
+            images.insert(&self.image, atlas_image(self.data.clone(), self.height));
+            self.dirty = false;
+        }
+    }
+}
+
+fn atlas_image(data: Vec<u8>, height: usize) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width: ATLAS_WIDTH as u32,
+            height: height.max(1) as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        if data.is_empty() {
+            vec![0; ATLAS_WIDTH]
+        } else {
+            data
+        },
+        TextureFormat::R8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::linear();
+    image
+}
+
+/// Squared euclidean distance transform of a single row or column.
+///
+/// See "Distance Transforms of Sampled Functions" by Felzenszwalb and Huttenlocher.
+fn distance_transform_1d(f: &[f32], d: &mut [f32], v: &mut [usize], z: &mut [f32]) {
+    let intersect = |q: usize, p: usize| {
+        ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32)) / (2.0 * (q as f32 - p as f32))
+    };
+
+    let mut k = 0;
+    v[0] = 0;
+    z[0] = f32::NEG_INFINITY;
+    z[1] = f32::INFINITY;
+    for q in 1..f.len() {
+        let mut s = intersect(q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersect(q, v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f32::INFINITY;
+    }
+
+    k = 0;
+    for (q, d) in d.iter_mut().enumerate() {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let offset = q as f32 - v[k] as f32;
+        *d = offset * offset + f[v[k]];
+    }
+}
+
+/// Squared distance from each pixel to the nearest pixel for which `is_feature` returns true.
+fn distance_transform(
+    coverage: &[f32],
+    width: usize,
+    height: usize,
+    is_feature: impl Fn(f32) -> bool,
+) -> Vec<f32> {
+    let mut grid: Vec<f32> = coverage
+        .iter()
+        .map(|&c| if is_feature(c) { 0.0 } else { 1e20 })
+        .collect();
+
+    let n = width.max(height);
+    let (mut f, mut d) = (vec![0.0; n], vec![0.0; n]);
+    let (mut v, mut z) = (vec![0; n], vec![0.0; n + 1]);
+
+    for x in 0..width {
+        for y in 0..height {
+            f[y] = grid[y * width + x];
+        }
+        distance_transform_1d(&f[..height], &mut d[..height], &mut v, &mut z);
+        for y in 0..height {
+            grid[y * width + x] = d[y];
+        }
+    }
+
+    for y in 0..height {
+        let row = y * width..(y + 1) * width;
+        f[..width].copy_from_slice(&grid[row.clone()]);
+        distance_transform_1d(&f[..width], &mut d[..width], &mut v, &mut z);
+        grid[row].copy_from_slice(&d[..width]);
+    }
+
+    grid
+}
+
+/// Convert glyph coverage into a signed distance field with the outline at 0.5, increasing towards the inside.
+fn signed_distance_field(coverage: &[f32], width: usize, height: usize) -> Vec<u8> {
+    let to_inside = distance_transform(coverage, width, height, |c| c >= 0.5);
+    let to_outside = distance_transform(coverage, width, height, |c| c < 0.5);
+
+    to_inside
+        .iter()
+        .zip(&to_outside)
+        .map(|(&to_inside, &to_outside)| {
+            let dist = to_outside.sqrt() - to_inside.sqrt();
+            let value = 0.5 + dist / (2.0 * SDF_SPREAD as f32);
+            (value.clamp(0.0, 1.0) * 255.0) as u8
+        })
+        .collect()
+}
+
+/// Resource containing the [`SdfFontAtlas`] generated for each font used by a [`ShapePainter`].
+#[derive(Resource, Default)]
+pub struct SdfFontAtlases {
+    atlases: HashMap<AssetId<Font>, SdfFontAtlas>,
+}
+
+impl SdfFontAtlases {
+    pub fn get(&self, font: impl Into<AssetId<Font>>) -> Option<&SdfFontAtlas> {
+        self.atlases.get(&font.into())
+    }
+}
+
+/// A system param for storing text drawn by [`ShapePainter`] until it is laid out.
+///
+/// Generally should only be consumed as part of [`ShapePainter`] and not used directly.
+#[derive(Resource, Default)]
+pub struct TextStorage {
+    texts: Vec<(ShapeConfig, String, Handle<Font>)>,
+}
+
+impl TextStorage {
+    pub(crate) fn send(&mut self, config: &ShapeConfig, text: &str, font: &Handle<Font>) {
+        self.texts
+            .push((config.clone(), text.to_string(), font.clone()));
+    }
+}
+
+/// Lay out text drawn this frame and send each of it's glyphs to the [`ShapeStorage`].
+///
+/// Text with a font that has not yet loaded is skipped.
+pub fn draw_text(
+    mut texts: ResMut<TextStorage>,
+    mut storage: ResMut<ShapeStorage>,
+    mut atlases: ResMut<SdfFontAtlases>,
+    mut images: ResMut<Assets<Image>>,
+    fonts: Res<Assets<Font>>,
+) {
+    for (mut config, text, handle) in texts.texts.drain(..) {
+        let Some(font) = fonts.get(&handle).map(|font| &font.font) else {
+            continue;
+        };
+        let atlas = atlases
+            .atlases
+            .entry(handle.id())
+            .or_insert_with(|| SdfFontAtlas::new(&mut images));
+        for c in text.chars() {
+            atlas.add_glyph(font, c);
+        }
+        atlas.update_image(&mut images);
+
+        config.texture = Some(atlas.image.clone());
+        config.hollow = false;
+        let atlas_size = Vec2::new(ATLAS_WIDTH as f32, atlas.height as f32);
+        layout_text(font, &text, config.font_size, |c, pen| {
+            let Some(Some(glyph)) = atlas.glyphs.get(&c) else {
+                return;
+            };
+            let to_world = config.font_size / SDF_SIZE;
+            let size = glyph.size.as_vec2();
+            storage.send(
+                &config,
+                GlyphData::new(
+                    &config,
+                    (pen + glyph.offset + size / 2.0) * to_world,
+                    size * to_world,
+                    glyph.min.as_vec2() / atlas_size,
+                    (glyph.min + glyph.size).as_vec2() / atlas_size,
+                ),
+            );
+        });
+    }
+}
+
+/// Calls `f` with each character and it's pen position in atlas pixels, y up.
+///
+/// Each line is centered horizontally and the block of lines is centered vertically on the origin.
+fn layout_text(
+    font: &ab_glyph::FontArc,
+    text: &str,
+    font_size: f32,
+    mut f: impl FnMut(char, Vec2),
+) {
+    if font_size <= 0.0 {
+        return;
+    }
+
+    let font = font.as_scaled(PxScale::from(SDF_SIZE));
+    let line_height = font.height() + font.line_gap();
+    let line_count = text.lines().count().max(1);
+    let block_height = font.height() + line_height * (line_count - 1) as f32;
+    let mut baseline = block_height / 2.0 - font.ascent();
+
+    for line in text.lines() {
+        let mut pens = Vec::with_capacity(line.len());
+        let mut pen = 0.0;
+        let mut prev = None;
+        for c in line.chars() {
+            let id = font.glyph_id(c);
+            if let Some(prev) = prev {
+                pen += font.kern(prev, id);
+            }
+            pens.push((c, pen));
+            pen += font.h_advance(id);
+            prev = Some(id);
+        }
+
+        // Once we have laid out the line it's final pen position is it's width
+        for (c, x) in pens {
+            f(c, Vec2::new(x - pen / 2.0, baseline));
+        }
+        baseline -= line_height;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw text.
+pub trait TextPainter {
+    /// Draw text centered on the painter's transform, see [`ShapeConfig::font_size`].
+    ///
+    /// Glyphs are rendered from a signed distance field atlas generated the first time each character is drawn.
+    fn text(&mut self, text: &str, font: &Handle<Font>) -> &mut Self;
+}
+
+impl<'w, 's> TextPainter for ShapePainter<'w, 's> {
+    fn text(&mut self, text: &str, font: &Handle<Font>) -> &mut Self {
+        self.send_text(text, font)
+    }
+}
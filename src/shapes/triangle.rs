@@ -2,17 +2,22 @@ use bevy::{
     math::vec2,
     prelude::*,
     reflect::Reflect,
-    render::render_resource::{ShaderRef, ShaderType},
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
 };
 use wgpu::vertex_attr_array;
 
 use crate::{
     prelude::*,
-    render::{Flags, ShapeComponent, ShapeData, TRIANGLE_HANDLE},
+    render::{tint_color, Flags, ShapeComponent, ShapeData, TRIANGLE_HANDLE},
 };
 
 /// Component containing the data for drawing a triangle.
 #[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct TriangleComponent {
     pub color: Color,
     pub thickness: f32,
@@ -26,7 +31,7 @@ pub struct TriangleComponent {
 impl TriangleComponent {
     pub fn new(config: &ShapeConfig, v_a: Vec2, v_b: Vec2, v_c: Vec2) -> Self {
         Self {
-            color: config.color,
+            color: config.without_alpha().faded_color(),
             thickness: config.thickness,
             thickness_type: config.thickness_type,
             alignment: config.alignment,
@@ -40,6 +45,17 @@ impl TriangleComponent {
 impl ShapeComponent for TriangleComponent {
     type Data = TriangleData;
 
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let [a, b, c] = self.vertices;
+        shape_aabb(
+            a.min(b).min(c),
+            a.max(b).max(c),
+            self.alignment,
+            fill,
+            |thickness| thickness,
+        )
+    }
+
     fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> TriangleData {
         let mut flags = Flags(0);
         let thickness = match fill.ty {
@@ -112,7 +128,7 @@ impl TriangleData {
         TriangleData {
             transform: config.transform.compute_matrix().to_cols_array_2d(),
 
-            color: config.color.as_linear_rgba_f32(),
+            color: config.faded_color().as_linear_rgba_f32(),
             thickness: config.thickness,
             flags: flags.0,
             vertices: [v_a.into(), v_b.into(), v_c.into()],
@@ -153,6 +169,20 @@ impl ShapeData for TriangleData {
     fn transform(&self) -> Mat4 {
         Mat4::from_cols_array_2d(&self.transform)
     }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
 }
 
 /// Extension trait for [`ShapePainter`] to enable it to draw triangles.
@@ -160,7 +190,7 @@ pub trait TrianglePainter {
     fn triangle(&mut self, v_a: Vec2, v_b: Vec2, v_c: Vec2) -> &mut Self;
 }
 
-impl<'w, 's> TrianglePainter for ShapePainter<'w, 's> {
+impl<P: ShapeSender> TrianglePainter for P {
     fn triangle(&mut self, v_a: Vec2, v_b: Vec2, v_c: Vec2) -> &mut Self {
         self.send(TriangleData::new(self.config(), v_a, v_b, v_c))
     }
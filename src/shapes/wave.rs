@@ -0,0 +1,338 @@
+use std::f32::consts::SQRT_2;
+
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+use wgpu::vertex_attr_array;
+
+use crate::{
+    prelude::*,
+    render::{tint_color, Flags, ShapeComponent, ShapeData, WAVE_HANDLE},
+};
+
+/// Defines the shape of the oscillation of a wave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum WaveType {
+    #[default]
+    Sine,
+    /// Straight segments between each peak and trough.
+    Zigzag,
+}
+
+impl From<WaveType> for u32 {
+    fn from(value: WaveType) -> Self {
+        value as u32
+    }
+}
+
+/// Component containing the data for drawing a wave.
+///
+/// Waves oscillate perpendicular to the line from start to end, beginning and ending on it like a sine wave.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaveComponent {
+    pub alignment: Alignment,
+    pub cap: Cap,
+    pub ty: WaveType,
+
+    /// Position to draw the start of the wave in world space relative to it's transform.
+    pub start: Vec3,
+    /// Position to draw the end of the wave in world space relative to it's transform.
+    pub end: Vec3,
+    /// Distance from the center line to each peak.
+    pub amplitude: f32,
+    /// Number of full periods per unit of length between start and end.
+    pub frequency: f32,
+}
+
+impl WaveComponent {
+    pub fn new(
+        config: &ShapeConfig,
+        ty: WaveType,
+        start: Vec3,
+        end: Vec3,
+        amplitude: f32,
+        frequency: f32,
+    ) -> Self {
+        Self {
+            alignment: config.alignment,
+            cap: config.cap,
+            ty,
+
+            start,
+            end,
+            amplitude,
+            frequency,
+        }
+    }
+}
+
+impl Default for WaveComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+            cap: default(),
+            ty: default(),
+
+            start: default(),
+            end: default(),
+            amplitude: 0.5,
+            frequency: 1.0,
+        }
+    }
+}
+
+impl ShapeComponent for WaveComponent {
+    type Data = WaveData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        // Waves swing up to their amplitude to either side of the line between their ends
+        stroke_aabb(
+            self.start.min(self.end),
+            self.start.max(self.end),
+            Alignment::Flat,
+            fill,
+            |thickness| self.amplitude.abs() + thickness / 2.0 * SQRT_2,
+        )
+    }
+
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> WaveData {
+        let mut flags = Flags(0);
+        let thickness = match fill.ty {
+            FillType::Stroke(thickness, thickness_type) => {
+                flags.set_thickness_type(thickness_type);
+                flags.set_hollow(1);
+                thickness
+            }
+            FillType::Fill => 1.0,
+        };
+        flags.set_alignment(self.alignment);
+        flags.set_cap(self.cap);
+        flags.set_wave_type(self.ty);
+
+        WaveData {
+            transform: tf.compute_matrix().to_cols_array_2d(),
+
+            color: fill.color.as_linear_rgba_f32(),
+            thickness,
+            flags: flags.0,
+
+            start: self.start,
+            end: self.end,
+            amplitude: self.amplitude,
+            frequency: self.frequency,
+        }
+    }
+}
+
+/// Raw data sent to the wave shader to draw a wave
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct WaveData {
+    transform: [[f32; 4]; 4],
+
+    color: [f32; 4],
+    thickness: f32,
+    flags: u32,
+
+    start: Vec3,
+    end: Vec3,
+    amplitude: f32,
+    frequency: f32,
+}
+
+impl WaveData {
+    pub fn new(
+        config: &ShapeConfig,
+        ty: WaveType,
+        start: Vec3,
+        end: Vec3,
+        amplitude: f32,
+        frequency: f32,
+    ) -> Self {
+        let mut flags = Flags(0);
+        flags.set_thickness_type(config.thickness_type);
+        flags.set_alignment(config.alignment);
+        flags.set_cap(config.cap);
+        flags.set_wave_type(ty);
+
+        WaveData {
+            transform: config.transform.compute_matrix().to_cols_array_2d(),
+
+            color: config.faded_color().as_linear_rgba_f32(),
+            thickness: config.thickness,
+            flags: flags.0,
+
+            start,
+            end,
+            amplitude,
+            frequency,
+        }
+    }
+}
+
+impl ShapeData for WaveData {
+    type Component = WaveComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        vertex_attr_array![
+            0 => Float32x4,
+            1 => Float32x4,
+            2 => Float32x4,
+            3 => Float32x4,
+
+            4 => Float32x4,
+            5 => Float32,
+            6 => Uint32,
+            7 => Float32x3,
+            8 => Float32x3,
+            9 => Float32,
+            10 => Float32,
+        ]
+        .to_vec()
+    }
+
+    fn shader() -> ShaderRef {
+        WAVE_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.transform)
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform = transform.to_cols_array_2d();
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.color = tint_color(self.color, color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        let mut flags = Flags(self.flags);
+        flags.set_constant_screen_size(constant_screen_size as u32);
+        self.flags = flags.0;
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw waves.
+pub trait WavePainter {
+    fn wave(&mut self, start: Vec3, end: Vec3, amplitude: f32, frequency: f32) -> &mut Self;
+    fn zigzag(&mut self, start: Vec3, end: Vec3, amplitude: f32, frequency: f32) -> &mut Self;
+}
+
+impl<P: ShapeSender> WavePainter for P {
+    fn wave(&mut self, start: Vec3, end: Vec3, amplitude: f32, frequency: f32) -> &mut Self {
+        self.send(WaveData::new(
+            self.config(),
+            WaveType::Sine,
+            start,
+            end,
+            amplitude,
+            frequency,
+        ))
+    }
+
+    fn zigzag(&mut self, start: Vec3, end: Vec3, amplitude: f32, frequency: f32) -> &mut Self {
+        self.send(WaveData::new(
+            self.config(),
+            WaveType::Zigzag,
+            start,
+            end,
+            amplitude,
+            frequency,
+        ))
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of wave bundles.
+pub trait WaveBundle {
+    fn wave(config: &ShapeConfig, start: Vec3, end: Vec3, amplitude: f32, frequency: f32) -> Self;
+    fn zigzag(config: &ShapeConfig, start: Vec3, end: Vec3, amplitude: f32, frequency: f32)
+        -> Self;
+}
+
+impl WaveBundle for ShapeBundle<WaveComponent> {
+    fn wave(config: &ShapeConfig, start: Vec3, end: Vec3, amplitude: f32, frequency: f32) -> Self {
+        let mut bundle = Self::new(
+            config,
+            WaveComponent::new(config, WaveType::Sine, start, end, amplitude, frequency),
+        );
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+
+    fn zigzag(
+        config: &ShapeConfig,
+        start: Vec3,
+        end: Vec3,
+        amplitude: f32,
+        frequency: f32,
+    ) -> Self {
+        let mut bundle = Self::new(
+            config,
+            WaveComponent::new(config, WaveType::Zigzag, start, end, amplitude, frequency),
+        );
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of wave entities.
+pub trait WaveSpawner<'w> {
+    fn wave(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        amplitude: f32,
+        frequency: f32,
+    ) -> ShapeEntityCommands;
+    fn zigzag(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        amplitude: f32,
+        frequency: f32,
+    ) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> WaveSpawner<'w> for T {
+    fn wave(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        amplitude: f32,
+        frequency: f32,
+    ) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::wave(
+            self.config(),
+            start,
+            end,
+            amplitude,
+            frequency,
+        ))
+    }
+
+    fn zigzag(
+        &mut self,
+        start: Vec3,
+        end: Vec3,
+        amplitude: f32,
+        frequency: f32,
+    ) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::zigzag(
+            self.config(),
+            start,
+            end,
+            amplitude,
+            frequency,
+        ))
+    }
+}
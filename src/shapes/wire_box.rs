@@ -0,0 +1,203 @@
+use std::f32::consts::SQRT_2;
+
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+
+use crate::{
+    prelude::*,
+    render::{ShapeComponent, ShapeData, LINE_HANDLE},
+};
+
+/// Component containing the data for drawing the edges of a box.
+///
+/// Each edge is drawn as a line, generally [`Alignment::Billboard`] is preferable so that edges
+/// remain visible from every angle.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct WireBoxComponent {
+    pub alignment: Alignment,
+    pub cap: Cap,
+
+    /// Half the size of the box along each axis.
+    pub half_extents: Vec3,
+}
+
+impl WireBoxComponent {
+    pub fn new(config: &ShapeConfig, half_extents: Vec3) -> Self {
+        Self {
+            alignment: config.alignment,
+            cap: config.cap,
+
+            half_extents,
+        }
+    }
+}
+
+impl Default for WireBoxComponent {
+    fn default() -> Self {
+        Self {
+            alignment: default(),
+            cap: default(),
+
+            half_extents: Vec3::splat(0.5),
+        }
+    }
+}
+
+/// Returns the start and end of each of the 12 edges of a box centered on the origin.
+pub fn wire_box_edges(half_extents: Vec3) -> [(Vec3, Vec3); 12] {
+    let corner = |i: usize| {
+        Vec3::new(
+            if i & 1 == 0 { -1.0 } else { 1.0 },
+            if i & 2 == 0 { -1.0 } else { 1.0 },
+            if i & 4 == 0 { -1.0 } else { 1.0 },
+        ) * half_extents
+    };
+
+    // Each edge connects two corners that differ along a single axis
+    let mut edges = [(Vec3::ZERO, Vec3::ZERO); 12];
+    let mut edge = 0;
+    for i in 0..8 {
+        for axis in [1, 2, 4] {
+            if i & axis == 0 {
+                edges[edge] = (corner(i), corner(i | axis));
+                edge += 1;
+            }
+        }
+    }
+    edges
+}
+
+impl ShapeComponent for WireBoxComponent {
+    type Data = WireBoxData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let half_extents = self.half_extents.abs();
+        stroke_aabb(
+            -half_extents,
+            half_extents,
+            Alignment::Flat,
+            fill,
+            |thickness| thickness / 2.0 * SQRT_2,
+        )
+    }
+
+    /// Returns the data for the first edge of the box, see [`ShapeComponent::get_instances`] for the full set.
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> WireBoxData {
+        let mut first = None;
+        self.get_instances(tf, fill, |data| {
+            first.get_or_insert(data);
+        });
+        first.unwrap_or_default()
+    }
+
+    fn get_instances(
+        &self,
+        tf: &GlobalTransform,
+        fill: &ShapeFill,
+        mut f: impl FnMut(WireBoxData),
+    ) {
+        for (start, end) in wire_box_edges(self.half_extents) {
+            let line = LineComponent {
+                alignment: self.alignment,
+                cap: self.cap,
+
+                start,
+                end,
+                ..default()
+            };
+            f(WireBoxData {
+                line: line.get_data(tf, fill),
+            })
+        }
+    }
+}
+
+/// Raw data sent to the line shader to draw a single edge of a box
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct WireBoxData {
+    line: LineData,
+}
+
+impl WireBoxData {
+    pub fn new(config: &ShapeConfig, start: Vec3, end: Vec3) -> Self {
+        Self {
+            line: LineData::new(config, start, end),
+        }
+    }
+}
+
+impl ShapeData for WireBoxData {
+    type Component = WireBoxComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        LineData::vertex_layout()
+    }
+
+    /// Shares the line shader as [`WireBoxData`] has an identical layout to [`LineData`].
+    fn shader() -> ShaderRef {
+        LINE_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.line.transform()
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.line.set_transform(transform);
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.line.tint(color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        self.line.set_constant_screen_size(constant_screen_size);
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw the edges of boxes.
+pub trait WireBoxPainter {
+    fn wire_box(&mut self, half_extents: Vec3) -> &mut Self;
+}
+
+impl<P: ShapeSender> WireBoxPainter for P {
+    fn wire_box(&mut self, half_extents: Vec3) -> &mut Self {
+        for (start, end) in wire_box_edges(half_extents) {
+            self.send(WireBoxData::new(self.config(), start, end));
+        }
+        self
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of wire box bundles.
+pub trait WireBoxBundle {
+    fn wire_box(config: &ShapeConfig, half_extents: Vec3) -> Self;
+}
+
+impl WireBoxBundle for ShapeBundle<WireBoxComponent> {
+    fn wire_box(config: &ShapeConfig, half_extents: Vec3) -> Self {
+        let mut bundle = Self::new(config, WireBoxComponent::new(config, half_extents));
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of wire box entities.
+pub trait WireBoxSpawner<'w> {
+    fn wire_box(&mut self, half_extents: Vec3) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> WireBoxSpawner<'w> for T {
+    fn wire_box(&mut self, half_extents: Vec3) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::wire_box(self.config(), half_extents))
+    }
+}
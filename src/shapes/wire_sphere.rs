@@ -0,0 +1,245 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::{
+    prelude::*,
+    reflect::Reflect,
+    render::{
+        primitives::Aabb,
+        render_resource::{ShaderRef, ShaderType},
+    },
+};
+
+use crate::{
+    prelude::*,
+    render::{ShapeComponent, ShapeData, DISC_HANDLE},
+};
+
+/// Component containing the data for drawing a sphere as a set of rings.
+///
+/// Rings are always hollow and flat, regardless of alignment, so that they keep their orientation on the sphere.
+#[derive(Component, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct WireSphereComponent {
+    /// Radius of the sphere.
+    pub radius: f32,
+    /// Number of rings of latitude evenly spaced from pole to pole.
+    pub latitudes: u32,
+    /// Number of great circles evenly spaced around the y axis, passing through both poles.
+    pub longitudes: u32,
+}
+
+impl WireSphereComponent {
+    pub fn new(radius: f32, latitudes: u32, longitudes: u32) -> Self {
+        Self {
+            radius,
+            latitudes,
+            longitudes,
+        }
+    }
+}
+
+impl Default for WireSphereComponent {
+    fn default() -> Self {
+        Self {
+            radius: 1.0,
+            latitudes: 1,
+            longitudes: 2,
+        }
+    }
+}
+
+/// Returns the local transform and radius of each ring of a wire sphere.
+///
+/// Rings are circles in the xy plane of their transform, one latitude and two longitudes give three orthogonal circles.
+pub fn sphere_rings(radius: f32, latitudes: u32, longitudes: u32) -> Vec<(Transform, f32)> {
+    let mut rings = Vec::with_capacity((latitudes + longitudes) as usize);
+
+    for i in 1..=latitudes {
+        let polar = PI * i as f32 / (latitudes + 1) as f32;
+        let transform = Transform::from_xyz(0.0, radius * polar.cos(), 0.0)
+            .with_rotation(Quat::from_rotation_x(FRAC_PI_2));
+        rings.push((transform, radius * polar.sin()));
+    }
+
+    for i in 0..longitudes {
+        let azimuth = PI * i as f32 / longitudes as f32;
+        rings.push((
+            Transform::from_rotation(Quat::from_rotation_y(azimuth)),
+            radius,
+        ));
+    }
+
+    rings
+}
+
+impl ShapeComponent for WireSphereComponent {
+    type Data = WireSphereData;
+
+    fn aabb(&self, fill: &ShapeFill) -> Option<Aabb> {
+        let radius = Vec3::splat(self.radius.abs());
+        stroke_aabb(-radius, radius, Alignment::Flat, fill, |thickness| {
+            thickness / 2.0
+        })
+    }
+
+    /// Returns the data for the first ring of the sphere, see [`ShapeComponent::get_instances`] for the full set.
+    fn get_data(&self, tf: &GlobalTransform, fill: &ShapeFill) -> WireSphereData {
+        let mut first = None;
+        self.get_instances(tf, fill, |data| {
+            first.get_or_insert(data);
+        });
+        first.unwrap_or_default()
+    }
+
+    fn get_instances(
+        &self,
+        tf: &GlobalTransform,
+        fill: &ShapeFill,
+        mut f: impl FnMut(WireSphereData),
+    ) {
+        for (transform, radius) in sphere_rings(self.radius, self.latitudes, self.longitudes) {
+            let ring = DiscComponent {
+                alignment: Alignment::Flat,
+                radius,
+                ..default()
+            };
+            f(WireSphereData {
+                disc: ring.get_data(&tf.mul_transform(transform), fill),
+            })
+        }
+    }
+}
+
+/// Raw data sent to the disc shader to draw a single ring of a wire sphere
+#[derive(Clone, Copy, Reflect, Default, Debug, ShaderType)]
+#[repr(C)]
+pub struct WireSphereData {
+    disc: DiscData,
+}
+
+impl WireSphereData {
+    /// Creates the data for a single ring, `transform` is relative to the transform of `config`.
+    pub fn new(config: &ShapeConfig, transform: Transform, radius: f32) -> Self {
+        let mut config = config.clone();
+        config.transform = config.transform * transform;
+        config.alignment = Alignment::Flat;
+        config.hollow = true;
+
+        Self {
+            disc: DiscData::circle(&config, radius),
+        }
+    }
+}
+
+impl ShapeData for WireSphereData {
+    type Component = WireSphereComponent;
+
+    fn vertex_layout() -> Vec<wgpu::VertexAttribute> {
+        DiscData::vertex_layout()
+    }
+
+    /// Shares the disc shader as [`WireSphereData`] has an identical layout to [`DiscData`].
+    fn shader() -> ShaderRef {
+        DISC_HANDLE.into()
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.disc.transform()
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.disc.set_transform(transform);
+    }
+
+    fn tint(&mut self, color: Color) {
+        self.disc.tint(color);
+    }
+
+    fn set_constant_screen_size(&mut self, constant_screen_size: bool) {
+        self.disc.set_constant_screen_size(constant_screen_size);
+    }
+}
+
+/// Extension trait for [`ShapePainter`] to enable it to draw wire spheres.
+pub trait WireSpherePainter {
+    /// Draw a sphere as three orthogonal rings.
+    fn wire_sphere(&mut self, radius: f32) -> &mut Self;
+    /// Draw a sphere with the given number of rings of latitude and longitude, see [`WireSphereComponent`].
+    fn wire_sphere_rings(&mut self, radius: f32, latitudes: u32, longitudes: u32) -> &mut Self;
+}
+
+impl<P: ShapeSender> WireSpherePainter for P {
+    fn wire_sphere(&mut self, radius: f32) -> &mut Self {
+        self.wire_sphere_rings(radius, 1, 2)
+    }
+
+    fn wire_sphere_rings(&mut self, radius: f32, latitudes: u32, longitudes: u32) -> &mut Self {
+        for (transform, radius) in sphere_rings(radius, latitudes, longitudes) {
+            self.send(WireSphereData::new(self.config(), transform, radius));
+        }
+        self
+    }
+}
+
+/// Extension trait for [`ShapeBundle`] to enable creation of wire sphere bundles.
+pub trait WireSphereBundle {
+    fn wire_sphere(config: &ShapeConfig, radius: f32) -> Self;
+    fn wire_sphere_rings(
+        config: &ShapeConfig,
+        radius: f32,
+        latitudes: u32,
+        longitudes: u32,
+    ) -> Self;
+}
+
+impl WireSphereBundle for ShapeBundle<WireSphereComponent> {
+    fn wire_sphere(config: &ShapeConfig, radius: f32) -> Self {
+        Self::wire_sphere_rings(config, radius, 1, 2)
+    }
+
+    fn wire_sphere_rings(
+        config: &ShapeConfig,
+        radius: f32,
+        latitudes: u32,
+        longitudes: u32,
+    ) -> Self {
+        let mut bundle = Self::new(
+            config,
+            WireSphereComponent::new(radius, latitudes, longitudes),
+        );
+        bundle.fill.ty = FillType::Stroke(config.thickness, config.thickness_type);
+        bundle
+    }
+}
+
+/// Extension trait for [`ShapeSpawner`] to enable spawning of wire sphere entities.
+pub trait WireSphereSpawner<'w> {
+    fn wire_sphere(&mut self, radius: f32) -> ShapeEntityCommands;
+    fn wire_sphere_rings(
+        &mut self,
+        radius: f32,
+        latitudes: u32,
+        longitudes: u32,
+    ) -> ShapeEntityCommands;
+}
+
+impl<'w, T: ShapeSpawner<'w>> WireSphereSpawner<'w> for T {
+    fn wire_sphere(&mut self, radius: f32) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::wire_sphere(self.config(), radius))
+    }
+
+    fn wire_sphere_rings(
+        &mut self,
+        radius: f32,
+        latitudes: u32,
+        longitudes: u32,
+    ) -> ShapeEntityCommands {
+        self.spawn_shape(ShapeBundle::wire_sphere_rings(
+            self.config(),
+            radius,
+            latitudes,
+            longitudes,
+        ))
+    }
+}
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use bevy_tweening::{component_animator_system, AnimationSystem, Lens, TweeningPlugin};
+
+use crate::prelude::*;
+
+/// Lens animating the color of a retained shape, interpolated in linear space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShapeColorLens {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl Lens<ShapeFill> for ShapeColorLens {
+    fn lerp(&mut self, target: &mut ShapeFill, ratio: f32) {
+        let start = Vec4::from(self.start.as_linear_rgba_f32());
+        let end = Vec4::from(self.end.as_linear_rgba_f32());
+        target.color = Color::rgba_linear_from_array(start.lerp(end, ratio));
+    }
+}
+
+/// Lens animating the thickness of a hollow retained shape, keeping it's [`ThicknessType`].
+///
+/// Filled shapes have no thickness so are left unchanged.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShapeThicknessLens {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Lens<ShapeFill> for ShapeThicknessLens {
+    fn lerp(&mut self, target: &mut ShapeFill, ratio: f32) {
+        if let FillType::Stroke(thickness, _) = &mut target.ty {
+            *thickness = self.start + (self.end - self.start) * ratio;
+        }
+    }
+}
+
+/// Lens animating the radius of retained discs, regular polygons, capsules and wireframe spheres.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShapeRadiusLens {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl ShapeRadiusLens {
+    fn radius(&self, ratio: f32) -> f32 {
+        self.start + (self.end - self.start) * ratio
+    }
+}
+
+impl Lens<DiscComponent> for ShapeRadiusLens {
+    fn lerp(&mut self, target: &mut DiscComponent, ratio: f32) {
+        target.radius = self.radius(ratio);
+    }
+}
+
+#[cfg(feature = "ngon")]
+impl Lens<RegularPolygonComponent> for ShapeRadiusLens {
+    fn lerp(&mut self, target: &mut RegularPolygonComponent, ratio: f32) {
+        target.radius = self.radius(ratio);
+    }
+}
+
+impl Lens<CapsuleComponent> for ShapeRadiusLens {
+    fn lerp(&mut self, target: &mut CapsuleComponent, ratio: f32) {
+        target.radius = self.radius(ratio);
+    }
+}
+
+impl Lens<WireSphereComponent> for ShapeRadiusLens {
+    fn lerp(&mut self, target: &mut WireSphereComponent, ratio: f32) {
+        target.radius = self.radius(ratio);
+    }
+}
+
+/// Lens animating the start and end angles of a retained arc, `x` holds the start angle and `y` the end angle.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ArcAnglesLens {
+    pub start: Vec2,
+    pub end: Vec2,
+}
+
+impl Lens<DiscComponent> for ArcAnglesLens {
+    fn lerp(&mut self, target: &mut DiscComponent, ratio: f32) {
+        let angles = self.start.lerp(self.end, ratio);
+        target.start_angle = angles.x;
+        target.end_angle = angles.y;
+    }
+}
+
+/// Plugin that drives [`Animator`](bevy_tweening::Animator)s of the shape components animated by the lenses in this
+/// module, adding bevy_tweening's [`TweeningPlugin`] if it hasn't been already.
+pub struct ShapeTweeningPlugin;
+
+impl Plugin for ShapeTweeningPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TweeningPlugin>() {
+            app.add_plugins(TweeningPlugin);
+        }
+        app.add_systems(
+            Update,
+            (
+                component_animator_system::<ShapeFill>,
+                component_animator_system::<DiscComponent>,
+                component_animator_system::<CapsuleComponent>,
+                component_animator_system::<WireSphereComponent>,
+            )
+                .in_set(AnimationSystem::AnimationUpdate),
+        );
+        #[cfg(feature = "ngon")]
+        app.add_systems(
+            Update,
+            component_animator_system::<RegularPolygonComponent>
+                .in_set(AnimationSystem::AnimationUpdate),
+        );
+    }
+}